@@ -180,6 +180,9 @@ fn bench_callback_invocation(c: &mut Criterion) {
         timestamp: 0,
         start: 0.0,
         duration: 0.0,
+        provider: None,
+        words: Vec::new(),
+        speaker: None,
     };
 
     c.bench_function("callback_invocation", |b| {
@@ -240,6 +243,9 @@ fn bench_transcription_serialization(c: &mut Criterion) {
         timestamp: 1234567890,
         start: 0.0,
         duration: 0.0,
+        provider: None,
+        words: Vec::new(),
+        speaker: None,
     };
 
     c.bench_function("transcription_to_json", |b| {
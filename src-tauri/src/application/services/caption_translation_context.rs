@@ -0,0 +1,174 @@
+//! Окно контекста для перевода входящих субтитров.
+//!
+//! Хранит последние показанные сегменты (исходник + перевод) в пределах
+//! `max_segments` и бюджета токенов; переводчик получает их как контекст нового сегмента.
+
+use std::collections::VecDeque;
+
+use crate::domain::{
+    estimate_text_tokens, CaptionTranslationContextConfig, TranslationContextSegment,
+    MAX_CAPTION_CONTEXT_SEGMENTS, MAX_CAPTION_CONTEXT_TOKENS,
+};
+
+#[derive(Debug, Default)]
+pub struct CaptionContextWindow {
+    segments: VecDeque<TranslationContextSegment>,
+    max_segments: usize,
+    max_tokens: usize,
+}
+
+impl CaptionContextWindow {
+    pub fn new(config: &CaptionTranslationContextConfig) -> Self {
+        let (max_segments, max_tokens) = if config.enabled {
+            (
+                config.max_segments.min(MAX_CAPTION_CONTEXT_SEGMENTS),
+                config.max_tokens.min(MAX_CAPTION_CONTEXT_TOKENS),
+            )
+        } else {
+            (0, 0)
+        };
+        Self {
+            segments: VecDeque::with_capacity(max_segments),
+            max_segments,
+            max_tokens,
+        }
+    }
+
+    /// Сегменты для запроса, от старых к новым. Бюджет считается с самого нового сегмента;
+    /// сегмент, который в бюджет не влезает, обрывает окно — дыр в контексте не бывает.
+    pub fn context(&self) -> Vec<TranslationContextSegment> {
+        let mut budget = self.max_tokens;
+        let mut context: Vec<TranslationContextSegment> = Vec::new();
+        for segment in self.segments.iter().rev() {
+            let tokens = segment_tokens(segment);
+            if tokens > budget {
+                break;
+            }
+            budget -= tokens;
+            context.push(segment.clone());
+        }
+        context.reverse();
+        context
+    }
+
+    /// Показанный субтитр становится самым новым сегментом окна
+    pub fn push(&mut self, source: &str, translation: &str) {
+        if self.max_segments == 0 {
+            return;
+        }
+        while self.segments.len() >= self.max_segments {
+            self.segments.pop_front();
+        }
+        self.segments.push_back(TranslationContextSegment::new(
+            source.trim(),
+            translation.trim(),
+        ));
+    }
+
+    /// Исходник последнего сегмента (для склейки с новым сегментом)
+    pub fn last_source(&self) -> Option<&str> {
+        self.segments.back().map(|segment| segment.source.as_str())
+    }
+
+    /// Модель склеила новый сегмент с последним: заменяем его перевод и дописываем исходник.
+    /// Возвращает перевод, который был показан до склейки.
+    pub fn revise_last(&mut self, source: &str, translation: &str) -> Option<String> {
+        let last = self.segments.back_mut()?;
+        last.source = format!("{} {}", last.source, source.trim());
+        let previous = std::mem::replace(&mut last.translation, translation.trim().to_string());
+        Some(previous)
+    }
+}
+
+fn segment_tokens(segment: &TranslationContextSegment) -> usize {
+    estimate_text_tokens(&segment.source) + estimate_text_tokens(&segment.translation)
+}
+
+pub fn validate_caption_translation_context_config(
+    config: &CaptionTranslationContextConfig,
+) -> Result<(), String> {
+    if config.max_segments > MAX_CAPTION_CONTEXT_SEGMENTS {
+        return Err(format!(
+            "caption_translation_context.max_segments must be at most {}",
+            MAX_CAPTION_CONTEXT_SEGMENTS
+        ));
+    }
+    if config.max_tokens > MAX_CAPTION_CONTEXT_TOKENS {
+        return Err(format!(
+            "caption_translation_context.max_tokens must be at most {}",
+            MAX_CAPTION_CONTEXT_TOKENS
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(max_segments: usize, max_tokens: usize) -> CaptionContextWindow {
+        CaptionContextWindow::new(&CaptionTranslationContextConfig {
+            enabled: true,
+            max_segments,
+            max_tokens,
+        })
+    }
+
+    #[test]
+    fn keeps_last_segments_within_token_budget() {
+        let mut window = window(2, 6);
+        window.push("one", "раз");
+        window.push("two", "два");
+        window.push("three", "три");
+
+        assert_eq!(
+            window.context(),
+            vec![
+                TranslationContextSegment::new("two", "два"),
+                TranslationContextSegment::new("three", "три"),
+            ]
+        );
+
+        window.push("a much longer segment", "что-то длинное");
+        assert!(window.context().is_empty(), "newest segment exceeds budget");
+    }
+
+    #[test]
+    fn revise_last_merges_source_and_returns_shown_translation() {
+        let mut window = window(3, 300);
+        assert_eq!(window.revise_last("x", "y"), None);
+
+        window.push("She told me", "Она сказала мне");
+        assert_eq!(
+            window.revise_last(" that she was tired", "Она сказала мне, что устала"),
+            Some("Она сказала мне".to_string())
+        );
+        assert_eq!(
+            window.context(),
+            vec![TranslationContextSegment::new(
+                "She told me that she was tired",
+                "Она сказала мне, что устала"
+            )]
+        );
+    }
+
+    #[test]
+    fn disabled_or_oversized_config() {
+        let mut disabled = CaptionContextWindow::new(&CaptionTranslationContextConfig {
+            enabled: false,
+            ..CaptionTranslationContextConfig::default()
+        });
+        disabled.push("one", "раз");
+        assert!(disabled.context().is_empty());
+
+        let config = CaptionTranslationContextConfig {
+            max_segments: MAX_CAPTION_CONTEXT_SEGMENTS + 1,
+            ..CaptionTranslationContextConfig::default()
+        };
+        assert!(validate_caption_translation_context_config(&config).is_err());
+        assert!(validate_caption_translation_context_config(
+            &CaptionTranslationContextConfig::default()
+        )
+        .is_ok());
+    }
+}
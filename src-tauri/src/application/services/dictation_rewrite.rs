@@ -0,0 +1,170 @@
+//! LLM-переписывание продиктованного текста после остановки записи.
+//! Любая ошибка модели — откат к исходному тексту: диктовка не должна теряться.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::domain::{
+    DictationRewriteConfig, DictationRewritePreset, LlmTextClient, TextTranslationError,
+};
+use crate::infrastructure::translation::create_llm_text_client;
+
+/// Длинная диктовка и локальные модели отвечают заметно дольше, чем перевод субтитров
+const DICTATION_REWRITE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Результат переписывания: либо текст модели, либо исходный текст и причина отката
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DictationRewriteOutcome {
+    pub text: String,
+    pub rewritten: bool,
+    pub error: Option<String>,
+}
+
+impl DictationRewriteOutcome {
+    fn raw(text: &str, error: impl Into<String>) -> Self {
+        Self {
+            text: text.to_string(),
+            rewritten: false,
+            error: Some(error.into()),
+        }
+    }
+}
+
+pub struct DictationRewriteService {
+    client: Arc<dyn LlmTextClient>,
+    preset: DictationRewritePreset,
+}
+
+impl DictationRewriteService {
+    /// `openai_api_key` используется только для OpenAI; на свой `endpoint` уходит
+    /// лишь `config.api_key`.
+    pub fn from_config(
+        config: &DictationRewriteConfig,
+        openai_api_key: &str,
+    ) -> Result<Self, TextTranslationError> {
+        let llm_config = config.llm_config();
+        let api_key = llm_config.resolved_api_key(openai_api_key);
+
+        Ok(Self {
+            client: create_llm_text_client(&llm_config, api_key, DICTATION_REWRITE_TIMEOUT)?,
+            preset: config.preset,
+        })
+    }
+
+    /// Переписывает текст выбранным пресетом. Никогда не возвращает ошибку:
+    /// при сбое или пустом ответе модели отдаёт исходный текст.
+    pub async fn rewrite_or_raw(&self, text: &str) -> DictationRewriteOutcome {
+        if text.trim().is_empty() {
+            return DictationRewriteOutcome {
+                text: text.to_string(),
+                rewritten: false,
+                error: None,
+            };
+        }
+
+        match self.client.complete(self.preset.instructions(), text).await {
+            Ok(rewritten) if !rewritten.trim().is_empty() => DictationRewriteOutcome {
+                text: rewritten,
+                rewritten: true,
+                error: None,
+            },
+            Ok(_) => {
+                log::warn!("Dictation rewrite returned empty text, keeping raw dictation");
+                DictationRewriteOutcome::raw(text, "model returned empty text")
+            }
+            Err(error) => {
+                log::warn!(
+                    "Dictation rewrite failed ({}), keeping raw dictation: {}",
+                    error.error_type(),
+                    error
+                );
+                DictationRewriteOutcome::raw(text, error.to_string())
+            }
+        }
+    }
+}
+
+pub fn validate_dictation_rewrite_config(config: &DictationRewriteConfig) -> Result<(), String> {
+    if let Some(endpoint) = config.custom_endpoint() {
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err(format!(
+                "dictation_rewrite.endpoint должен начинаться с http:// или https://: {}",
+                endpoint
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct FakeRewriter(Result<String, TextTranslationError>);
+
+    #[async_trait]
+    impl LlmTextClient for FakeRewriter {
+        async fn complete(
+            &self,
+            instructions: &str,
+            _input: &str,
+        ) -> Result<String, TextTranslationError> {
+            assert_eq!(
+                instructions,
+                DictationRewritePreset::BulletList.instructions()
+            );
+            self.0.clone()
+        }
+    }
+
+    fn service(result: Result<String, TextTranslationError>) -> DictationRewriteService {
+        DictationRewriteService {
+            client: Arc::new(FakeRewriter(result)),
+            preset: DictationRewritePreset::BulletList,
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_rewritten_text_on_success() {
+        let outcome = service(Ok("- buy milk\n- call Anna".to_string()))
+            .rewrite_or_raw("buy milk and call Anna")
+            .await;
+
+        assert!(outcome.rewritten);
+        assert_eq!(outcome.text, "- buy milk\n- call Anna");
+        assert_eq!(outcome.error, None);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_raw_text_on_error_or_empty_answer() {
+        let failed = service(Err(TextTranslationError::Connection(
+            "connection refused".to_string(),
+        )))
+        .rewrite_or_raw("buy milk")
+        .await;
+        assert!(!failed.rewritten);
+        assert_eq!(failed.text, "buy milk");
+        assert!(failed.error.unwrap().contains("connection refused"));
+
+        let empty = service(Ok("  ".to_string()))
+            .rewrite_or_raw("buy milk")
+            .await;
+        assert!(!empty.rewritten);
+        assert_eq!(empty.text, "buy milk");
+    }
+
+    #[test]
+    fn validates_endpoint_scheme() {
+        let mut config = DictationRewriteConfig {
+            endpoint: Some("http://localhost:11434/v1".to_string()),
+            ..DictationRewriteConfig::default()
+        };
+        assert!(validate_dictation_rewrite_config(&config).is_ok());
+
+        config.endpoint = Some("localhost:11434".to_string());
+        assert!(validate_dictation_rewrite_config(&config).is_err());
+    }
+}
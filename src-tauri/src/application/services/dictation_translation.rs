@@ -0,0 +1,122 @@
+//! Перевод финалов диктовки в режиме dictate_translate: каждый финализированный
+//! сегмент переводится до эмита, поэтому фронт вставляет уже перевод.
+
+use std::sync::Arc;
+
+use crate::domain::{TextTranslationError, TextTranslator, Transcription};
+
+pub struct DictationTranslator {
+    translator: Arc<dyn TextTranslator>,
+    target_language: String,
+}
+
+impl DictationTranslator {
+    pub fn new(translator: Arc<dyn TextTranslator>, target_language: impl Into<String>) -> Self {
+        Self {
+            translator,
+            target_language: target_language.into(),
+        }
+    }
+
+    pub fn target_language(&self) -> &str {
+        &self.target_language
+    }
+
+    /// Переводит финал. Вторым значением — исходный текст (для "оригинал в буфер обмена").
+    /// При ошибке перевода остаётся исходный текст: лучше вставить оригинал, чем потерять фразу;
+    /// третьим значением возвращается ошибка, чтобы пользователь узнал о непереведённом сегменте.
+    pub async fn translate_final(
+        &self,
+        transcription: Transcription,
+    ) -> (Transcription, String, Option<TextTranslationError>) {
+        let source_text = transcription.text.clone();
+        if source_text.trim().is_empty() {
+            return (transcription, source_text, None);
+        }
+
+        match self
+            .translator
+            .translate_text(&source_text, &self.target_language)
+            .await
+        {
+            Ok(translated) => (
+                with_translated_text(transcription, translated),
+                source_text,
+                None,
+            ),
+            Err(error) => {
+                log::warn!(
+                    "Dictation segment translation to {} failed ({}), delivering source text: {}",
+                    self.target_language,
+                    error.error_type(),
+                    error
+                );
+                (transcription, source_text, Some(error))
+            }
+        }
+    }
+}
+
+/// Пустой перевод не заменяет исходный текст; пословные тайминги к переводу не относятся
+fn with_translated_text(mut transcription: Transcription, translated: String) -> Transcription {
+    let translated = translated.trim();
+    if translated.is_empty() || translated == transcription.text {
+        return transcription;
+    }
+    transcription.text = translated.to_string();
+    transcription.words.clear();
+    transcription
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Word;
+    use async_trait::async_trait;
+
+    struct FailingTextTranslator;
+
+    #[async_trait]
+    impl TextTranslator for FailingTextTranslator {
+        async fn translate_text(
+            &self,
+            _text: &str,
+            _target_language: &str,
+        ) -> Result<String, TextTranslationError> {
+            Err(TextTranslationError::RateLimited("slow down".to_string()))
+        }
+    }
+
+    #[test]
+    fn translated_text_replaces_source_and_drops_words() {
+        let mut source = Transcription::final_result("привет, коллеги".to_string());
+        source.words = vec![Word::new("привет", 0.0, 0.4)];
+
+        let translated = with_translated_text(source, " hello, colleagues\n".to_string());
+
+        assert_eq!(translated.text, "hello, colleagues");
+        assert!(translated.words.is_empty());
+    }
+
+    #[test]
+    fn empty_translation_keeps_source_text() {
+        let source = Transcription::final_result("привет".to_string());
+
+        let translated = with_translated_text(source, "  ".to_string());
+
+        assert_eq!(translated.text, "привет");
+    }
+
+    #[tokio::test]
+    async fn failed_translation_keeps_source_and_reports_error() {
+        let translator = DictationTranslator::new(Arc::new(FailingTextTranslator), "en");
+
+        let (transcription, source_text, error) = translator
+            .translate_final(Transcription::final_result("привет".to_string()))
+            .await;
+
+        assert_eq!(transcription.text, "привет");
+        assert_eq!(source_text, "привет");
+        assert!(matches!(error, Some(TextTranslationError::RateLimited(_))));
+    }
+}
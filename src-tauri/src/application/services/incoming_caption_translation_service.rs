@@ -3,7 +3,10 @@
 //! Pipeline:
 //! - platform system audio capture, 16 kHz mono PCM16
 //! - STT provider from current app config
-//! - finalized transcript chunks -> OpenAI text translation
+//! - finalized transcript chunks -> text translation (provider from `text_translation`)
+//! - rolling context of preceding segments; a late segment may revise the previous caption
+//! - translation memory: exact repeats are served without calling the translator
+//! - glossary post-check of translated segments
 //! - translated text -> UI events
 //!
 //! This is separate from dictation and outgoing live translation:
@@ -18,20 +21,23 @@ use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex as StdMutex, Weak};
 use std::time::Duration;
 
-use async_trait::async_trait;
 use futures_util::FutureExt;
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio::task::JoinHandle;
 
+use super::{CaptionContextWindow, TranslationGlossary};
 use crate::domain::{
     AudioCapture, AudioCaptureErrorCallback, AudioCaptureHealthProbe, AudioCaptureTarget,
-    AudioChunk, AudioChunkCallback, AudioConfig, AudioError, ConnectionQualityCallback,
-    ErrorCallback, PlatformAudioFactory, RecordingStatus, SttConfig, SttConnectionCategory,
-    SttConnectionError, SttError, SttProvider, SttProviderFactory, SttResult, Transcription,
-    TranscriptionCallback,
+    AudioChunk, AudioChunkCallback, AudioConfig, AudioError, CaptionTranslationContextConfig,
+    ConnectionQualityCallback, ContextualTranslation, ErrorCallback, PlatformAudioFactory,
+    RecordingStatus, SttConfig, SttConnectionCategory, SttConnectionError, SttError, SttProvider,
+    SttProviderFactory, SttResult, TextTranslationConfig, TextTranslationError,
+    TextTranslationProviderType, TextTranslator, TextTranslatorFactory, Transcription,
+    TranscriptionCallback, TranslationContextSegment, TranslationGlossaryConfig, TranslationMemory,
+    TranslationMemoryKey,
 };
 use crate::infrastructure::audio::DefaultPlatformAudioFactory;
-use crate::infrastructure::openai::{OpenAITextTranslationClient, OpenAITextTranslationError};
+use crate::infrastructure::translation::DefaultTextTranslatorFactory;
 use crate::infrastructure::DefaultSttProviderFactory;
 
 const TARGET_LANGUAGE_DEFAULT: &str = "ru";
@@ -62,6 +68,11 @@ const CAPTURE_HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(250);
 pub struct IncomingTranslationConfig {
     pub stt_config: SttConfig,
     pub openai_api_key: String,
+    pub text_translation: TextTranslationConfig,
+    pub glossary: TranslationGlossaryConfig,
+    pub caption_context: CaptionTranslationContextConfig,
+    /// None — память переводов выключена
+    pub translation_memory: Option<Arc<dyn TranslationMemory>>,
     pub target_language: String,
     pub playback_gain: f32,
     pub session_id: u64,
@@ -72,6 +83,10 @@ impl IncomingTranslationConfig {
         Self {
             stt_config,
             openai_api_key: std::env::var("OPENAI_API_KEY").unwrap_or_default(),
+            text_translation: TextTranslationConfig::default(),
+            glossary: TranslationGlossaryConfig::default(),
+            caption_context: CaptionTranslationContextConfig::default(),
+            translation_memory: None,
             target_language: TARGET_LANGUAGE_DEFAULT.to_string(),
             playback_gain: 1.0,
             session_id,
@@ -79,6 +94,39 @@ impl IncomingTranslationConfig {
     }
 }
 
+/// Память переводов с ключом сессии: языки и переводчик не меняются до stop
+struct SessionTranslationMemory {
+    memory: Arc<dyn TranslationMemory>,
+    source_language: String,
+    target_language: String,
+    translator: String,
+}
+
+impl SessionTranslationMemory {
+    fn new(config: &IncomingTranslationConfig, target_language: &str) -> Option<Self> {
+        let source_language = if config.stt_config.auto_detect_language {
+            "auto"
+        } else {
+            config.stt_config.language.as_str()
+        };
+        config.translation_memory.clone().map(|memory| Self {
+            memory,
+            source_language: source_language.to_string(),
+            target_language: target_language.to_string(),
+            translator: config.text_translation.translator_id(),
+        })
+    }
+
+    fn key(&self, text: &str) -> Option<TranslationMemoryKey> {
+        TranslationMemoryKey::new(
+            text,
+            &self.source_language,
+            &self.target_language,
+            &self.translator,
+        )
+    }
+}
+
 fn normalize_incoming_translation_target_language(value: &str) -> String {
     let language = value.trim();
     if language.is_empty()
@@ -91,10 +139,18 @@ fn normalize_incoming_translation_target_language(value: &str) -> String {
     }
 }
 
+/// Поздний сегмент склеен с предыдущим: `text` заменяет уже показанный `previous`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptionRevision {
+    pub previous: String,
+    pub text: String,
+}
+
 #[derive(Clone)]
 pub struct IncomingTranslationCallbacks {
     pub on_source_final: Arc<dyn Fn(String) + Send + Sync>,
     pub on_translation_delta: Arc<dyn Fn(String) + Send + Sync>,
+    pub on_translation_revision: Arc<dyn Fn(CaptionRevision) + Send + Sync>,
     pub on_error: Arc<dyn Fn(IncomingTranslationError) + Send + Sync>,
     pub on_status: Arc<dyn Fn(RecordingStatus) + Send + Sync>,
 }
@@ -278,14 +334,14 @@ impl From<SttError> for IncomingTranslationError {
     }
 }
 
-impl From<OpenAITextTranslationError> for IncomingTranslationError {
-    fn from(err: OpenAITextTranslationError) -> Self {
+impl From<TextTranslationError> for IncomingTranslationError {
+    fn from(err: TextTranslationError) -> Self {
         let message = err.to_string();
         match err {
-            OpenAITextTranslationError::Authentication(_) => Self::Authentication(message),
-            OpenAITextTranslationError::RateLimited(_) => Self::RateLimited(message),
-            OpenAITextTranslationError::Connection(_) => Self::Connection(message),
-            OpenAITextTranslationError::Protocol(_) => Self::Processing(message),
+            TextTranslationError::Authentication(_) => Self::Authentication(message),
+            TextTranslationError::RateLimited(_) => Self::RateLimited(message),
+            TextTranslationError::Connection(_) => Self::Connection(message),
+            TextTranslationError::Protocol(_) => Self::Processing(message),
         }
     }
 }
@@ -381,44 +437,6 @@ impl BoundedSegmentDedupe {
     }
 }
 
-#[async_trait]
-trait TextTranslator: Send + Sync {
-    async fn translate_text(
-        &self,
-        text: &str,
-        target_language: &str,
-    ) -> Result<String, OpenAITextTranslationError>;
-}
-
-trait TextTranslatorFactory: Send + Sync {
-    fn create(
-        &self,
-        api_key: String,
-    ) -> Result<Arc<dyn TextTranslator>, OpenAITextTranslationError>;
-}
-
-struct OpenAITextTranslatorFactory;
-
-impl TextTranslatorFactory for OpenAITextTranslatorFactory {
-    fn create(
-        &self,
-        api_key: String,
-    ) -> Result<Arc<dyn TextTranslator>, OpenAITextTranslationError> {
-        Ok(Arc::new(OpenAITextTranslationClient::new(api_key)?))
-    }
-}
-
-#[async_trait]
-impl TextTranslator for OpenAITextTranslationClient {
-    async fn translate_text(
-        &self,
-        text: &str,
-        target_language: &str,
-    ) -> Result<String, OpenAITextTranslationError> {
-        OpenAITextTranslationClient::translate_text(self, text, target_language).await
-    }
-}
-
 impl Default for IncomingCaptionTranslationService {
     fn default() -> Self {
         Self::new()
@@ -441,7 +459,7 @@ impl IncomingCaptionTranslationService {
             status: Arc::new(RwLock::new(RecordingStatus::Idle)),
             stt_factory,
             audio_factory,
-            translator_factory: Arc::new(OpenAITextTranslatorFactory),
+            translator_factory: Arc::new(DefaultTextTranslatorFactory),
             inner: Arc::new(Mutex::new(None)),
             lifecycle: Arc::new(Mutex::new(())),
         }
@@ -492,11 +510,17 @@ impl IncomingCaptionTranslationService {
             return Err(IncomingTranslationError::AlreadyActive);
         }
 
-        if config.openai_api_key.trim().is_empty() {
-            return Err(IncomingTranslationError::Configuration(
-                "OpenAI API key не задан. Укажите ключ в Settings или задайте OPENAI_API_KEY"
-                    .to_string(),
-            ));
+        let translation_api_key = config
+            .text_translation
+            .resolved_api_key(&config.openai_api_key);
+        if translation_api_key.is_empty() && config.text_translation.requires_api_key() {
+            let message = match config.text_translation.provider {
+                TextTranslationProviderType::DeepL => {
+                    "DeepL API key не задан. Укажите text_translation.api_key"
+                }
+                _ => "OpenAI API key не задан. Укажите ключ в Settings или задайте OPENAI_API_KEY",
+            };
+            return Err(IncomingTranslationError::Configuration(message.to_string()));
         }
         let target_language =
             normalize_incoming_translation_target_language(&config.target_language);
@@ -547,10 +571,11 @@ impl IncomingCaptionTranslationService {
             return Err(e.into());
         }
 
-        let translator = match self
-            .translator_factory
-            .create(config.openai_api_key.clone())
-        {
+        let translator = match self.translator_factory.create(
+            &config.text_translation,
+            translation_api_key,
+            &config.glossary,
+        ) {
             Ok(translator) => translator,
             Err(e) => {
                 abort_initialized_stt_after_start_failure(
@@ -599,6 +624,9 @@ impl IncomingCaptionTranslationService {
                 translator,
                 runtime_failure_reporter.clone(),
                 target_language.clone(),
+                TranslationGlossary::for_language(&config.glossary, &target_language),
+                CaptionContextWindow::new(&config.caption_context),
+                SessionTranslationMemory::new(&config, &target_language),
                 pending_translations.clone(),
             ),
             runtime_failure_reporter.clone(),
@@ -1019,6 +1047,9 @@ async fn run_translation_worker(
     translator: Arc<dyn TextTranslator>,
     runtime_failure_reporter: IncomingRuntimeFailureReporter,
     target_language: String,
+    glossary: TranslationGlossary,
+    mut context_window: CaptionContextWindow,
+    translation_memory: Option<SessionTranslationMemory>,
     pending_translations: Arc<AtomicUsize>,
 ) {
     let mut consecutive_failures = 0u32;
@@ -1039,30 +1070,98 @@ async fn run_translation_worker(
             job.duration
         );
 
-        match translate_text_with_retry(
-            translator.as_ref(),
-            &job.text,
-            &target_language,
-            runtime_failure_reporter.running.as_ref(),
-        )
-        .await
-        {
-            Ok(translated) => {
+        let context = context_window.context();
+        // Перевод с контекстом зависит от предыдущих сегментов: ни отдавать его из памяти,
+        // ни запоминать нельзя — память работает только для самостоятельных сегментов
+        let memory_key = translation_memory
+            .as_ref()
+            .filter(|_| context.is_empty())
+            .and_then(|memory| memory.key(&job.text));
+        let remembered = match (&translation_memory, &memory_key) {
+            (Some(memory), Some(key)) => memory.memory.lookup(key).await,
+            _ => None,
+        };
+        let from_memory = remembered.is_some();
+        let result = match remembered {
+            Some(text) => {
+                log::info!(
+                    "IncomingCaptionTranslationService: {} translation served from translation memory",
+                    job.source
+                );
+                Ok(ContextualTranslation::standalone(text))
+            }
+            None => {
+                translate_text_with_retry(
+                    translator.as_ref(),
+                    &job.text,
+                    &target_language,
+                    &context,
+                    runtime_failure_reporter.running.as_ref(),
+                )
+                .await
+            }
+        };
+        match result {
+            Ok(result) => {
                 consecutive_failures = 0;
+                // Склейка возможна только с сегментом, который модель видела в контексте
+                let revises_previous = result.revises_previous && !context.is_empty();
+                if !from_memory {
+                    if let (Some(memory), Some(key)) = (&translation_memory, memory_key) {
+                        memory.memory.remember(key, result.text.clone()).await;
+                    }
+                }
+                let checked_source = match context_window.last_source() {
+                    Some(previous) if revises_previous => format!("{} {}", previous, job.text),
+                    _ => job.text.clone(),
+                };
+                let translated = if glossary.is_empty() {
+                    result.text
+                } else {
+                    let check = glossary.enforce(&checked_source, &result.text);
+                    if !check.violations.is_empty() {
+                        log::warn!(
+                            "IncomingCaptionTranslationService: glossary terms missing in translation: {:?}",
+                            check.violations
+                        );
+                    }
+                    check.text
+                };
                 if runtime_failure_reporter.running.load(Ordering::Relaxed)
                     && !translated.trim().is_empty()
                 {
-                    call_incoming_callback("translation delta", || {
-                        (runtime_failure_reporter.callbacks.on_translation_delta)(translated)
-                    });
+                    let revision = if revises_previous {
+                        context_window.revise_last(&job.text, &translated)
+                    } else {
+                        context_window.push(&job.text, &translated);
+                        None
+                    };
+                    match revision {
+                        Some(previous) => {
+                            log::info!(
+                                "IncomingCaptionTranslationService: {} segment merged into previous caption",
+                                job.source
+                            );
+                            call_incoming_callback("translation revision", || {
+                                (runtime_failure_reporter.callbacks.on_translation_revision)(
+                                    CaptionRevision {
+                                        previous,
+                                        text: translated,
+                                    },
+                                )
+                            });
+                        }
+                        None => call_incoming_callback("translation delta", || {
+                            (runtime_failure_reporter.callbacks.on_translation_delta)(translated)
+                        }),
+                    }
                 }
             }
             Err(err) => {
                 consecutive_failures = consecutive_failures.saturating_add(1);
                 let should_emit = matches!(
                     err,
-                    OpenAITextTranslationError::Authentication(_)
-                        | OpenAITextTranslationError::RateLimited(_)
+                    TextTranslationError::Authentication(_) | TextTranslationError::RateLimited(_)
                 ) || consecutive_failures >= TRANSLATION_FAILURES_BEFORE_UI_ERROR;
 
                 log::warn!(
@@ -1083,25 +1182,32 @@ async fn run_translation_worker(
             break;
         }
     }
+
+    if let Some(memory) = &translation_memory {
+        memory.memory.flush().await;
+    }
 }
 
 async fn translate_text_with_retry(
     translator: &dyn TextTranslator,
     text: &str,
     target_language: &str,
+    context: &[TranslationContextSegment],
     running: &AtomicBool,
-) -> Result<String, OpenAITextTranslationError> {
+) -> Result<ContextualTranslation, TextTranslationError> {
     let mut attempt = 1u32;
     loop {
-        match translator.translate_text(text, target_language).await {
+        match translator
+            .translate_with_context(text, target_language, context)
+            .await
+        {
             Ok(translated) => return Ok(translated),
             Err(err)
                 if attempt < TRANSLATION_MAX_ATTEMPTS
                     && running.load(Ordering::Relaxed)
                     && matches!(
                         &err,
-                        OpenAITextTranslationError::Connection(_)
-                            | OpenAITextTranslationError::Protocol(_)
+                        TextTranslationError::Connection(_) | TextTranslationError::Protocol(_)
                     ) =>
             {
                 log::warn!(
@@ -2079,8 +2185,10 @@ mod tests {
     impl TextTranslatorFactory for SyntheticTextTranslatorFactory {
         fn create(
             &self,
+            _config: &TextTranslationConfig,
             _api_key: String,
-        ) -> Result<Arc<dyn TextTranslator>, OpenAITextTranslationError> {
+            _glossary: &TranslationGlossaryConfig,
+        ) -> Result<Arc<dyn TextTranslator>, TextTranslationError> {
             Ok(Arc::new(SyntheticTextTranslator {
                 state: self.state.clone(),
             }))
@@ -2097,7 +2205,7 @@ mod tests {
             &self,
             text: &str,
             target_language: &str,
-        ) -> Result<String, OpenAITextTranslationError> {
+        ) -> Result<String, TextTranslationError> {
             self.state
                 .requests
                 .lock()
@@ -2117,10 +2225,10 @@ mod tests {
             &self,
             text: &str,
             _target_language: &str,
-        ) -> Result<String, OpenAITextTranslationError> {
+        ) -> Result<String, TextTranslationError> {
             let call = self.calls.fetch_add(1, Ordering::SeqCst);
             if call == 0 {
-                Err(OpenAITextTranslationError::Connection(
+                Err(TextTranslationError::Connection(
                     "temporary network blip".to_string(),
                 ))
             } else {
@@ -2137,8 +2245,8 @@ mod tests {
             &self,
             _text: &str,
             _target_language: &str,
-        ) -> Result<String, OpenAITextTranslationError> {
-            Err(OpenAITextTranslationError::RateLimited(
+        ) -> Result<String, TextTranslationError> {
+            Err(TextTranslationError::RateLimited(
                 "simulated rate limit".to_string(),
             ))
         }
@@ -2149,8 +2257,10 @@ mod tests {
     impl TextTranslatorFactory for RateLimitedTextTranslatorFactory {
         fn create(
             &self,
+            _config: &TextTranslationConfig,
             _api_key: String,
-        ) -> Result<Arc<dyn TextTranslator>, OpenAITextTranslationError> {
+            _glossary: &TranslationGlossaryConfig,
+        ) -> Result<Arc<dyn TextTranslator>, TextTranslationError> {
             Ok(Arc::new(RateLimitedTextTranslator))
         }
     }
@@ -2160,9 +2270,11 @@ mod tests {
     impl TextTranslatorFactory for FailingTextTranslatorFactory {
         fn create(
             &self,
+            _config: &TextTranslationConfig,
             _api_key: String,
-        ) -> Result<Arc<dyn TextTranslator>, OpenAITextTranslationError> {
-            Err(OpenAITextTranslationError::Authentication(
+            _glossary: &TranslationGlossaryConfig,
+        ) -> Result<Arc<dyn TextTranslator>, TextTranslationError> {
+            Err(TextTranslationError::Authentication(
                 "simulated translator create failure".to_string(),
             ))
         }
@@ -2174,6 +2286,7 @@ mod tests {
         IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: Arc::new(|_| {}),
             on_status: Arc::new(move |status| {
                 statuses.lock().unwrap().push(status);
@@ -2265,6 +2378,7 @@ mod tests {
                 Arc::new(move |text| source_finals.lock().unwrap().push(text))
             },
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: Arc::new(|_| {}),
             on_status: Arc::new(|_| {}),
         };
@@ -2349,6 +2463,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |error| errors.lock().unwrap().push(error.to_string()))
@@ -2393,6 +2508,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: Arc::new(|_| panic!("simulated incoming error callback panic")),
             on_status: {
                 let status_called = status_called.clone();
@@ -2441,6 +2557,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |err| errors.lock().unwrap().push(err.to_string()))
@@ -2492,6 +2609,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: Arc::new(|_| panic!("simulated incoming on_error panic")),
             on_status: Arc::new(|_| panic!("simulated incoming on_status panic")),
         };
@@ -2565,6 +2683,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |error| errors.lock().unwrap().push(error.to_string()))
@@ -2636,6 +2755,7 @@ mod tests {
                 Arc::new(move |text| source_finals.lock().unwrap().push(text))
             },
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |error| errors.lock().unwrap().push(error.to_string()))
@@ -2872,6 +2992,50 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn start_requires_key_only_for_keyed_translation_providers() {
+        let provider_state = std::sync::Arc::new(TrackingProviderState::default());
+        let service = IncomingCaptionTranslationService::new_with_all_factories(
+            std::sync::Arc::new(TrackingSttFactory {
+                state: provider_state.clone(),
+                fail_initialize: false,
+                fail_stop: false,
+            }),
+            std::sync::Arc::new(SyntheticIncomingAudioFactory {
+                capture_state: std::sync::Arc::new(SyntheticIncomingCaptureState::default()),
+                requested_target: std::sync::Arc::new(StdMutex::new(None)),
+            }),
+            std::sync::Arc::new(FailingTextTranslatorFactory),
+        );
+        let statuses = std::sync::Arc::new(StdMutex::new(Vec::new()));
+
+        // OpenAI-ключ приложения не подменяет ключ DeepL
+        let mut config = IncomingTranslationConfig::new_with_defaults(SttConfig::default(), 80);
+        config.openai_api_key = "sk-test".to_string();
+        config.text_translation.provider = TextTranslationProviderType::DeepL;
+        let err = service
+            .start(config, test_callbacks(statuses.clone()))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, IncomingTranslationError::Configuration(msg) if msg.contains("DeepL"))
+        );
+        assert!(!provider_state.initialized.load(Ordering::SeqCst));
+
+        // Локальному LibreTranslate ключ не нужен: старт доходит до создания переводчика
+        let mut config = IncomingTranslationConfig::new_with_defaults(SttConfig::default(), 81);
+        config.openai_api_key = String::new();
+        config.text_translation.provider = TextTranslationProviderType::LibreTranslate;
+        let err = service
+            .start(config, test_callbacks(statuses))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, IncomingTranslationError::Authentication(msg) if msg.contains("simulated translator create failure"))
+        );
+        assert!(provider_state.initialized.load(Ordering::SeqCst));
+    }
+
     #[tokio::test]
     async fn duplicate_start_does_not_replace_the_active_session() {
         let capture_state = std::sync::Arc::new(SyntheticIncomingCaptureState::default());
@@ -2950,6 +3114,7 @@ mod tests {
                 let translated_text = translated_text.clone();
                 Arc::new(move |text| translated_text.lock().unwrap().push_str(&text))
             },
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |err| errors.lock().unwrap().push(err.to_string()))
@@ -3030,6 +3195,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |error| errors.lock().unwrap().push(error.to_string()))
@@ -3121,6 +3287,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |error| errors.lock().unwrap().push(error.to_string()))
@@ -3175,6 +3342,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: Arc::new(|_| {}),
             on_status: Arc::new(|_| panic!("simulated incoming status callback panic")),
         };
@@ -3214,6 +3382,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |err| errors.lock().unwrap().push(err.to_string()))
@@ -3289,6 +3458,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |err| errors.lock().unwrap().push(err.to_string()))
@@ -3363,6 +3533,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |err| errors.lock().unwrap().push(err.to_string()))
@@ -3450,6 +3621,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |err| errors.lock().unwrap().push(err.to_string()))
@@ -3520,6 +3692,7 @@ mod tests {
                 let translated_text = translated_text.clone();
                 Arc::new(move |text| translated_text.lock().unwrap().push_str(&text))
             },
+            on_translation_revision: Arc::new(|_| {}),
             on_error: Arc::new(|err| panic!("unexpected incoming translation error: {err}")),
             on_status: Arc::new(|_| {}),
         };
@@ -3560,6 +3733,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: Arc::new(|error| panic!("unexpected incoming translation error: {error}")),
             on_status: Arc::new(|_| {}),
         };
@@ -3595,6 +3769,7 @@ mod tests {
         let callbacks = || IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: Arc::new(|error| panic!("unexpected incoming translation error: {error}")),
             on_status: Arc::new(|_| {}),
         };
@@ -3652,6 +3827,7 @@ mod tests {
                 let translated = translated.clone();
                 Arc::new(move |text| translated.lock().unwrap().push(text))
             },
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |err| errors.lock().unwrap().push(err.to_string()))
@@ -3673,6 +3849,9 @@ mod tests {
             }),
             runtime_failure_reporter,
             "ru".to_string(),
+            TranslationGlossary::default(),
+            CaptionContextWindow::default(),
+            None,
             pending.clone(),
         ));
 
@@ -3709,6 +3888,281 @@ mod tests {
         assert!(cleanup_rx.try_recv().is_err());
     }
 
+    #[tokio::test]
+    async fn translation_worker_applies_glossary_to_translated_segment() {
+        let (tx, rx) = mpsc::channel::<TranslationJob>(1);
+        let pending = Arc::new(AtomicUsize::new(0));
+        let (cleanup_tx, _cleanup_rx) = mpsc::unbounded_channel();
+        let translated = Arc::new(StdMutex::new(Vec::<String>::new()));
+        let callbacks = IncomingTranslationCallbacks {
+            on_source_final: Arc::new(|_| {}),
+            on_translation_delta: {
+                let translated = translated.clone();
+                Arc::new(move |text| translated.lock().unwrap().push(text))
+            },
+            on_translation_revision: Arc::new(|_| {}),
+            on_error: Arc::new(|_| {}),
+            on_status: Arc::new(|_| {}),
+        };
+        let runtime_failure_reporter = IncomingRuntimeFailureReporter {
+            callbacks,
+            running: Arc::new(AtomicBool::new(true)),
+            status: Arc::new(RwLock::new(RecordingStatus::Recording)),
+            runtime_cleanup_tx: cleanup_tx,
+            startup_error: Arc::new(StdMutex::new(None)),
+        };
+        let mut glossary = TranslationGlossaryConfig::default();
+        glossary.glossaries.insert(
+            "ru".to_string(),
+            vec![crate::domain::GlossaryTerm::new(
+                "pull request",
+                "пулл-реквест",
+            )],
+        );
+
+        let worker = tokio::spawn(run_translation_worker(
+            rx,
+            Arc::new(FlakyTextTranslator {
+                calls: std::sync::atomic::AtomicUsize::new(1),
+            }),
+            runtime_failure_reporter,
+            "ru".to_string(),
+            TranslationGlossary::for_language(&glossary, "ru"),
+            CaptionContextWindow::default(),
+            None,
+            pending.clone(),
+        ));
+
+        pending.fetch_add(1, Ordering::SeqCst);
+        tx.send(TranslationJob {
+            text: "Anna sent a pull request".to_string(),
+            source: "test",
+            start: 0.0,
+            duration: 1.0,
+        })
+        .await
+        .unwrap();
+        drop(tx);
+        worker.await.unwrap();
+
+        assert_eq!(
+            translated.lock().unwrap().as_slice(),
+            &[String::from("Anna sent a пулл-реквест translated")]
+        );
+        assert_eq!(pending.load(Ordering::SeqCst), 0);
+    }
+
+    /// Второй сегмент видит первый в контексте и склеивается с ним
+    struct MergingTextTranslator {
+        contexts: StdMutex<Vec<Vec<TranslationContextSegment>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl TextTranslator for MergingTextTranslator {
+        async fn translate_text(
+            &self,
+            text: &str,
+            _target_language: &str,
+        ) -> Result<String, TextTranslationError> {
+            Ok(format!("<{text}>"))
+        }
+
+        async fn translate_with_context(
+            &self,
+            text: &str,
+            _target_language: &str,
+            context: &[TranslationContextSegment],
+        ) -> Result<ContextualTranslation, TextTranslationError> {
+            self.contexts.lock().unwrap().push(context.to_vec());
+            Ok(match context.last() {
+                Some(previous) if text.starts_with("that") => ContextualTranslation {
+                    text: format!("<{} {text}>", previous.source),
+                    revises_previous: true,
+                },
+                _ => ContextualTranslation::standalone(format!("<{text}>")),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn translation_worker_sends_context_and_revises_previous_caption() {
+        let (tx, rx) = mpsc::channel::<TranslationJob>(3);
+        let pending = Arc::new(AtomicUsize::new(0));
+        let (cleanup_tx, _cleanup_rx) = mpsc::unbounded_channel();
+        let deltas = Arc::new(StdMutex::new(Vec::<String>::new()));
+        let revisions = Arc::new(StdMutex::new(Vec::<CaptionRevision>::new()));
+        let callbacks = IncomingTranslationCallbacks {
+            on_source_final: Arc::new(|_| {}),
+            on_translation_delta: {
+                let deltas = deltas.clone();
+                Arc::new(move |text| deltas.lock().unwrap().push(text))
+            },
+            on_translation_revision: {
+                let revisions = revisions.clone();
+                Arc::new(move |revision| revisions.lock().unwrap().push(revision))
+            },
+            on_error: Arc::new(|_| {}),
+            on_status: Arc::new(|_| {}),
+        };
+        let runtime_failure_reporter = IncomingRuntimeFailureReporter {
+            callbacks,
+            running: Arc::new(AtomicBool::new(true)),
+            status: Arc::new(RwLock::new(RecordingStatus::Recording)),
+            runtime_cleanup_tx: cleanup_tx,
+            startup_error: Arc::new(StdMutex::new(None)),
+        };
+        let translator = Arc::new(MergingTextTranslator {
+            contexts: StdMutex::new(Vec::new()),
+        });
+
+        let worker = tokio::spawn(run_translation_worker(
+            rx,
+            translator.clone(),
+            runtime_failure_reporter,
+            "ru".to_string(),
+            TranslationGlossary::default(),
+            CaptionContextWindow::new(&CaptionTranslationContextConfig::default()),
+            None,
+            pending.clone(),
+        ));
+
+        for text in ["She told me", "that she was tired", "Next"] {
+            pending.fetch_add(1, Ordering::SeqCst);
+            tx.send(TranslationJob {
+                text: text.to_string(),
+                source: "test",
+                start: 0.0,
+                duration: 1.0,
+            })
+            .await
+            .unwrap();
+        }
+        drop(tx);
+        worker.await.unwrap();
+
+        assert_eq!(
+            deltas.lock().unwrap().as_slice(),
+            &["<She told me>".to_string(), "<Next>".to_string()]
+        );
+        assert_eq!(
+            revisions.lock().unwrap().as_slice(),
+            &[CaptionRevision {
+                previous: "<She told me>".to_string(),
+                text: "<She told me that she was tired>".to_string(),
+            }]
+        );
+        let contexts = translator.contexts.lock().unwrap();
+        assert!(contexts[0].is_empty());
+        assert_eq!(
+            contexts[2],
+            vec![TranslationContextSegment::new(
+                "She told me that she was tired",
+                "<She told me that she was tired>"
+            )]
+        );
+        assert_eq!(pending.load(Ordering::SeqCst), 0);
+    }
+
+    /// Прогоняет сегменты через worker с памятью переводов; возвращает дельты,
+    /// число запросов к модели и статистику памяти
+    async fn run_translation_worker_with_memory(
+        context: CaptionTranslationContextConfig,
+        texts: &[&str],
+    ) -> (Vec<String>, usize, crate::domain::TranslationMemoryStats) {
+        let (tx, rx) = mpsc::channel::<TranslationJob>(texts.len());
+        let pending = Arc::new(AtomicUsize::new(0));
+        let (cleanup_tx, _cleanup_rx) = mpsc::unbounded_channel();
+        let deltas = Arc::new(StdMutex::new(Vec::<String>::new()));
+        let callbacks = IncomingTranslationCallbacks {
+            on_source_final: Arc::new(|_| {}),
+            on_translation_delta: {
+                let deltas = deltas.clone();
+                Arc::new(move |text| deltas.lock().unwrap().push(text))
+            },
+            on_translation_revision: Arc::new(|_| {}),
+            on_error: Arc::new(|_| {}),
+            on_status: Arc::new(|_| {}),
+        };
+        let runtime_failure_reporter = IncomingRuntimeFailureReporter {
+            callbacks,
+            running: Arc::new(AtomicBool::new(true)),
+            status: Arc::new(RwLock::new(RecordingStatus::Recording)),
+            runtime_cleanup_tx: cleanup_tx,
+            startup_error: Arc::new(StdMutex::new(None)),
+        };
+        let translator = Arc::new(MergingTextTranslator {
+            contexts: StdMutex::new(Vec::new()),
+        });
+        let store = Arc::new(crate::infrastructure::TranslationMemoryStore::in_memory());
+        let mut config = IncomingTranslationConfig::new_with_defaults(SttConfig::default(), 1);
+        config.translation_memory = Some(store.clone());
+
+        let worker = tokio::spawn(run_translation_worker(
+            rx,
+            translator.clone(),
+            runtime_failure_reporter,
+            "ru".to_string(),
+            TranslationGlossary::default(),
+            CaptionContextWindow::new(&context),
+            SessionTranslationMemory::new(&config, "ru"),
+            pending.clone(),
+        ));
+
+        for text in texts {
+            pending.fetch_add(1, Ordering::SeqCst);
+            tx.send(TranslationJob {
+                text: text.to_string(),
+                source: "test",
+                start: 0.0,
+                duration: 1.0,
+            })
+            .await
+            .unwrap();
+        }
+        drop(tx);
+        worker.await.unwrap();
+        assert_eq!(pending.load(Ordering::SeqCst), 0);
+
+        let deltas = deltas.lock().unwrap().clone();
+        let requests = translator.contexts.lock().unwrap().len();
+        (deltas, requests, store.stats().await)
+    }
+
+    #[tokio::test]
+    async fn translation_worker_serves_repeated_segments_from_translation_memory() {
+        let context = CaptionTranslationContextConfig {
+            enabled: false,
+            ..CaptionTranslationContextConfig::default()
+        };
+
+        let (deltas, requests, stats) = run_translation_worker_with_memory(
+            context,
+            &["Thank you.", "that is all", "thank  you"],
+        )
+        .await;
+
+        assert_eq!(
+            deltas,
+            ["<Thank you.>", "<that is all>", "<Thank you.>"].map(String::from)
+        );
+        assert_eq!(requests, 2);
+        assert_eq!((stats.entries, stats.hits, stats.misses), (2, 1, 2));
+    }
+
+    #[tokio::test]
+    async fn translation_worker_bypasses_translation_memory_for_segments_with_context() {
+        // Второй сегмент переводится с контекстом: из памяти его не отдаём и не запоминаем
+        let (deltas, requests, stats) = run_translation_worker_with_memory(
+            CaptionTranslationContextConfig::default(),
+            &["Thank you.", "thank you"],
+        )
+        .await;
+
+        assert_eq!(deltas, ["<Thank you.>", "<thank you>"].map(String::from));
+        assert_eq!(requests, 2);
+        assert_eq!((stats.entries, stats.hits, stats.misses), (1, 0, 1));
+    }
+
     #[tokio::test]
     async fn translation_worker_surfaces_rate_limit_error() {
         let (tx, rx) = mpsc::channel::<TranslationJob>(2);
@@ -3721,6 +4175,7 @@ mod tests {
         let callbacks = IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let errors = errors.clone();
                 Arc::new(move |err| errors.lock().unwrap().push(err.to_string()))
@@ -3743,6 +4198,9 @@ mod tests {
             Arc::new(RateLimitedTextTranslator),
             runtime_failure_reporter,
             "ru".to_string(),
+            TranslationGlossary::default(),
+            CaptionContextWindow::default(),
+            None,
             pending.clone(),
         ));
 
@@ -1490,6 +1490,7 @@ mod tests {
         let incoming_callbacks = || IncomingTranslationCallbacks {
             on_source_final: Arc::new(|_| {}),
             on_translation_delta: Arc::new(|_| {}),
+            on_translation_revision: Arc::new(|_| {}),
             on_error: Arc::new(|_| {}),
             on_status: Arc::new(|_| {}),
         };
@@ -1,15 +1,33 @@
 mod audio_spectrum;
+mod caption_translation_context;
+mod dictation_rewrite;
+mod dictation_translation;
 mod incoming_caption_translation_service;
 mod incoming_spoken_translation_service;
 mod incoming_translation_facade;
 mod live_translation_service;
 mod realtime_interpretation;
+mod session_transcript;
+mod spoken_commands;
+mod text_translation;
+mod transcript_export;
+mod transcript_post_processing;
 mod transcription_service;
+mod translation_glossary;
+mod translation_memory;
 mod translation_runtime_shutdown;
 
 pub use audio_spectrum::*;
+pub use caption_translation_context::{
+    validate_caption_translation_context_config, CaptionContextWindow,
+};
+pub use dictation_rewrite::{
+    validate_dictation_rewrite_config, DictationRewriteOutcome, DictationRewriteService,
+};
+pub use dictation_translation::DictationTranslator;
 pub use incoming_caption_translation_service::{
-    IncomingTranslationCallbacks, IncomingTranslationConfig, IncomingTranslationError,
+    CaptionRevision, IncomingTranslationCallbacks, IncomingTranslationConfig,
+    IncomingTranslationError,
 };
 pub use incoming_spoken_translation_service::{
     IncomingPlaybackState, IncomingSpokenTranslationCallbacks, IncomingSpokenTranslationConfig,
@@ -24,5 +42,22 @@ pub use live_translation_service::{
     LiveTranslationService,
 };
 pub(crate) use realtime_interpretation::*;
+pub use session_transcript::{SessionTranscript, SessionTranscriptKind, SessionTranscripts};
+pub use spoken_commands::{
+    validate_spoken_commands_config, SpokenCommandInterpreter, SpokenCommandOutcome,
+};
+pub use text_translation::validate_text_translation_config;
+pub use transcript_export::{
+    render_transcript, segments_from_history, split_into_cues, SubtitleCue,
+};
+pub use transcript_post_processing::{
+    validate_post_processing_config, FillerWordProcessor, NormalizationProcessor,
+    ReplacementProcessor, TrailingPunctuationProcessor, TranscriptPostProcessingChain,
+};
 pub use transcription_service::*;
+pub use translation_glossary::{
+    import_glossary_csv, validate_translation_glossary_config, GlossaryCheck,
+    GlossaryImportSummary, TranslationGlossary,
+};
+pub use translation_memory::validate_translation_memory_config;
 pub use translation_runtime_shutdown::*;
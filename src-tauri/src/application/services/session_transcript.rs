@@ -0,0 +1,286 @@
+//! Буфер финалов текущей сессии (диктовка / входящие субтитры / live translation) для экспорта.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{TranscriptSegment, Transcription};
+
+/// Live translation присылает только дельты перевода; пауза длиннее этой — новый сегмент
+const LIVE_TRANSLATION_SEGMENT_GAP_MS: i64 = 1_500;
+
+/// Сессия, которую можно экспортировать
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionTranscriptKind {
+    Dictation,
+    IncomingCaptions,
+    LiveTranslation,
+}
+
+/// Финалы одной сессии в порядке получения
+#[derive(Debug, Clone)]
+pub struct SessionTranscript {
+    pub kind: SessionTranscriptKind,
+    pub session_id: u64,
+    pub started_at_ms: i64,
+    segments: Vec<TranscriptSegment>,
+    last_delta_at_ms: Option<i64>,
+}
+
+impl SessionTranscript {
+    pub fn new(kind: SessionTranscriptKind, session_id: u64, started_at_ms: i64) -> Self {
+        Self {
+            kind,
+            session_id,
+            started_at_ms,
+            segments: Vec::new(),
+            last_delta_at_ms: None,
+        }
+    }
+
+    fn offset_secs(&self, at_ms: i64) -> f64 {
+        (at_ms - self.started_at_ms).max(0) as f64 / 1000.0
+    }
+
+    /// Закрывает последний сегмент без длительности моментом `at_ms`
+    fn close_open_segment(&mut self, at_ms: i64) {
+        let end = self.offset_secs(at_ms);
+        if let Some(last) = self.segments.last_mut() {
+            if last.transcription.duration <= 0.0 {
+                last.transcription.duration = (end - last.transcription.start).max(0.0);
+            }
+        }
+    }
+
+    /// Финал STT с таймингами провайдера (диктовка)
+    pub fn push_final(&mut self, transcription: Transcription) {
+        if transcription.text.trim().is_empty() {
+            return;
+        }
+        self.segments.push(TranscriptSegment::new(transcription));
+    }
+
+    /// Финал исходной речи без таймингов (входящие субтитры): время берём по часам
+    pub fn push_source_final(&mut self, text: &str, at_ms: i64) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.close_open_segment(at_ms);
+        let mut transcription =
+            Transcription::final_result(text.to_string()).with_timing(self.offset_secs(at_ms), 0.0);
+        transcription.timestamp = at_ms;
+        self.segments.push(TranscriptSegment::new(transcription));
+    }
+
+    /// Перевод входящего сегмента. Переводы приходят по одному на финал и в том же порядке,
+    /// поэтому отдаём его самому раннему сегменту без перевода.
+    pub fn push_caption_translation(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        if let Some(segment) = self
+            .segments
+            .iter_mut()
+            .find(|segment| segment.translation.is_none())
+        {
+            segment.translation = Some(text.to_string());
+        }
+    }
+
+    /// Переводчик склеил новый сегмент с предыдущим: объединяем оба исходных сегмента
+    /// в один с общим переводом, чтобы следующие переводы не съехали на сегмент.
+    pub fn revise_caption_translation(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let Some(index) = self
+            .segments
+            .iter()
+            .position(|segment| segment.translation.is_none())
+        else {
+            return;
+        };
+        if index == 0 {
+            self.segments[0].translation = Some(text.to_string());
+            return;
+        }
+
+        let merged = self.segments.remove(index);
+        let previous = &mut self.segments[index - 1];
+        previous.transcription.text = format!(
+            "{} {}",
+            previous.transcription.text, merged.transcription.text
+        );
+        let end = merged.transcription.start + merged.transcription.duration;
+        previous.transcription.duration = (end - previous.transcription.start).max(0.0);
+        previous.translation = Some(text.to_string());
+    }
+
+    /// Дельта live translation: дописываем к текущему сегменту, после паузы начинаем новый
+    pub fn push_translation_delta(&mut self, delta: &str, at_ms: i64) {
+        if delta.is_empty() {
+            return;
+        }
+        let continues = self
+            .last_delta_at_ms
+            .is_some_and(|last| at_ms - last <= LIVE_TRANSLATION_SEGMENT_GAP_MS);
+        self.last_delta_at_ms = Some(at_ms);
+        let offset = self.offset_secs(at_ms);
+
+        match self.segments.last_mut() {
+            Some(last) if continues => {
+                last.transcription.text.push_str(delta);
+                last.transcription.duration = (offset - last.transcription.start).max(0.0);
+            }
+            _ => {
+                let text = delta.trim_start();
+                if text.is_empty() {
+                    self.last_delta_at_ms = None;
+                    return;
+                }
+                let mut transcription =
+                    Transcription::final_result(text.to_string()).with_timing(offset, 0.0);
+                transcription.timestamp = at_ms;
+                self.segments.push(TranscriptSegment::new(transcription));
+            }
+        }
+    }
+
+    /// Снимок сегментов; у последнего сегмента без длительности она оценивается экспортёром
+    pub fn segments(&self) -> Vec<TranscriptSegment> {
+        self.segments
+            .iter()
+            .filter(|segment| !segment.transcription.text.trim().is_empty())
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+/// Последняя сессия каждого вида. Синхронный Mutex: пишем из sync-callback'ов сервисов.
+#[derive(Debug, Default)]
+pub struct SessionTranscripts {
+    sessions: Mutex<HashMap<SessionTranscriptKind, SessionTranscript>>,
+}
+
+impl SessionTranscripts {
+    /// Начинает новую сессию вида `kind`, старая отбрасывается
+    pub fn begin(&self, kind: SessionTranscriptKind, session_id: u64, started_at_ms: i64) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.insert(
+                kind,
+                SessionTranscript::new(kind, session_id, started_at_ms),
+            );
+        }
+    }
+
+    /// Изменяет сессию, только если она всё ещё текущая (поздние события старой сессии игнорируем)
+    pub fn update(
+        &self,
+        kind: SessionTranscriptKind,
+        session_id: u64,
+        f: impl FnOnce(&mut SessionTranscript),
+    ) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            if let Some(session) = sessions
+                .get_mut(&kind)
+                .filter(|session| session.session_id == session_id)
+            {
+                f(session);
+            }
+        }
+    }
+
+    pub fn snapshot(&self, kind: SessionTranscriptKind) -> Option<SessionTranscript> {
+        self.sessions
+            .lock()
+            .ok()
+            .and_then(|sessions| sessions.get(&kind).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caption_segments_use_wall_clock_and_oldest_untranslated() {
+        let mut session =
+            SessionTranscript::new(SessionTranscriptKind::IncomingCaptions, 1, 10_000);
+        session.push_source_final("first", 11_000);
+        session.push_source_final("second", 13_500);
+        session.push_caption_translation("первый");
+
+        let segments = session.segments();
+        assert_eq!(segments[0].transcription.start, 1.0);
+        assert_eq!(segments[0].transcription.duration, 2.5);
+        assert_eq!(segments[0].translation.as_deref(), Some("первый"));
+        assert_eq!(segments[1].transcription.start, 3.5);
+        assert!(segments[1].translation.is_none());
+    }
+
+    #[test]
+    fn test_caption_revision_merges_source_segments() {
+        let mut session =
+            SessionTranscript::new(SessionTranscriptKind::IncomingCaptions, 1, 10_000);
+        session.push_source_final("She told me", 11_000);
+        session.push_source_final("that she was tired", 12_000);
+        session.push_source_final("Next", 14_000);
+        session.push_caption_translation("Она сказала мне");
+        session.revise_caption_translation("Она сказала мне, что устала");
+        session.push_caption_translation("Дальше");
+
+        let segments = session.segments();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(
+            segments[0].transcription.text,
+            "She told me that she was tired"
+        );
+        assert_eq!(segments[0].transcription.duration, 3.0);
+        assert_eq!(
+            segments[0].translation.as_deref(),
+            Some("Она сказала мне, что устала")
+        );
+        assert_eq!(segments[1].translation.as_deref(), Some("Дальше"));
+    }
+
+    #[test]
+    fn test_translation_deltas_group_by_pause() {
+        let mut session = SessionTranscript::new(SessionTranscriptKind::LiveTranslation, 1, 0);
+        session.push_translation_delta("Hello", 1_000);
+        session.push_translation_delta(" world", 1_400);
+        session.push_translation_delta(" Next", 5_000);
+
+        let texts: Vec<String> = session
+            .segments()
+            .into_iter()
+            .map(|segment| segment.transcription.text)
+            .collect();
+        assert_eq!(texts, vec!["Hello world", "Next"]);
+    }
+
+    #[test]
+    fn test_update_ignores_stale_session() {
+        let sessions = SessionTranscripts::default();
+        sessions.begin(SessionTranscriptKind::Dictation, 2, 0);
+        sessions.update(SessionTranscriptKind::Dictation, 1, |session| {
+            session.push_final(Transcription::final_result("stale".to_string()))
+        });
+        sessions.update(SessionTranscriptKind::Dictation, 2, |session| {
+            session.push_final(Transcription::final_result("fresh".to_string()))
+        });
+
+        let snapshot = sessions.snapshot(SessionTranscriptKind::Dictation).unwrap();
+        let segments = snapshot.segments();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].transcription.text, "fresh");
+    }
+}
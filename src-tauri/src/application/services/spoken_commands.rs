@@ -0,0 +1,438 @@
+//! Голосовые команды редактирования поверх потока финалов диктовки:
+//! "new line", "comma", "delete last word", "undo that" и их русские аналоги.
+//!
+//! Команды внутри финала превращаются в правки текста. Если команда задевает уже
+//! доставленный текст (предыдущий финал), он возвращается как `retract`: фронт убирает
+//! этот хвост (и стирает его Backspace'ами, если он уже вставлен), а исправленная версия
+//! приходит заново в `text`.
+
+use std::cmp::Reverse;
+
+use crate::domain::{SpokenCommandAction, SpokenCommandsConfig};
+
+/// Пунктуация, которую STT сам расставляет вокруг продиктованной команды
+const STT_TRAILING_PUNCTUATION: [char; 7] = ['.', ',', '!', '?', ';', ':', '…'];
+
+/// Сколько доставленных финалов держим для "undo that" / "delete last word"
+const MAX_TRACKED_SEGMENTS: usize = 64;
+
+const LINE_BREAK: &str = "\n";
+const PARAGRAPH_BREAK: &str = "\n\n";
+
+/// Результат обработки одного финала
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpokenCommandOutcome {
+    /// Текст, который доставляется вместо исходного финала
+    pub text: String,
+
+    /// Хвост уже доставленного текста, который нужно убрать перед `text`
+    pub retract: Option<String>,
+}
+
+struct CommandPhrase {
+    words: Vec<String>,
+    action: SpokenCommandAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    Word(String),
+    Break(&'static str),
+}
+
+/// Интерпретатор команд одной сессии диктовки (помнит доставленные финалы)
+pub struct SpokenCommandInterpreter {
+    phrases: Vec<CommandPhrase>,
+    escape_words: Vec<String>,
+    delivered: Vec<String>,
+    pending_break: Option<&'static str>,
+}
+
+impl SpokenCommandInterpreter {
+    /// `None`, если команды выключены в настройках
+    pub fn from_config(config: &SpokenCommandsConfig, language: Option<&str>) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let vocabularies = config.vocabularies_for(language);
+        let mut phrases: Vec<CommandPhrase> = vocabularies
+            .iter()
+            .flat_map(|vocabulary| vocabulary.commands.iter())
+            .filter_map(|command| {
+                let words = phrase_words(&command.phrase);
+                (!words.is_empty()).then_some(CommandPhrase {
+                    words,
+                    action: command.action,
+                })
+            })
+            .collect();
+        // Длинные фразы первыми: "точка с запятой" не должна распознаться как "точка"
+        phrases.sort_by_key(|phrase| Reverse(phrase.words.len()));
+
+        let escape_words = vocabularies
+            .iter()
+            .map(|vocabulary| command_core(&vocabulary.escape_word))
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        Some(Self {
+            phrases,
+            escape_words,
+            delivered: Vec::new(),
+            pending_break: None,
+        })
+    }
+
+    pub fn interpret(&mut self, text: &str) -> SpokenCommandOutcome {
+        let mut pieces = Vec::new();
+        let mut retracted = Vec::new();
+
+        // "new line" в конце прошлого финала: перенос встаёт между ним и новым текстом
+        if let Some(line_break) = self.pending_break.take() {
+            if !text.trim().is_empty() && self.pull_last_segment(&mut pieces, &mut retracted) {
+                push_break(&mut pieces, line_break);
+            }
+        }
+
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let cores: Vec<String> = tokens.iter().map(|token| command_core(token)).collect();
+        let mut escape_token: Option<&str> = None;
+        let mut index = 0;
+
+        while index < tokens.len() {
+            let matched = self.match_phrase(&cores[index..]);
+
+            if escape_token.take().is_some() {
+                let len = matched.map_or(1, |(len, _)| len);
+                pieces.extend(
+                    tokens[index..index + len]
+                        .iter()
+                        .map(|token| Piece::Word(token.to_string())),
+                );
+                index += len;
+                continue;
+            }
+
+            if self.escape_words.contains(&cores[index]) {
+                escape_token = Some(tokens[index]);
+                index += 1;
+                continue;
+            }
+
+            match matched {
+                Some((len, action)) => {
+                    self.apply(action, &mut pieces, &mut retracted);
+                    index += len;
+                }
+                None => {
+                    pieces.push(Piece::Word(tokens[index].to_string()));
+                    index += 1;
+                }
+            }
+        }
+
+        // Экран в самом конце фразы — это просто слово
+        if let Some(token) = escape_token {
+            pieces.push(Piece::Word(token.to_string()));
+        }
+
+        while let Some(Piece::Break(line_break)) = pieces.last() {
+            self.pending_break = Some(*line_break);
+            pieces.pop();
+        }
+
+        SpokenCommandOutcome {
+            text: render(&pieces),
+            retract: (!retracted.is_empty()).then(|| retracted.join(" ")),
+        }
+    }
+
+    /// Запоминает финал в том виде, в каком он ушёл во фронт (после пост-обработки)
+    pub fn record_delivered(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+
+        self.delivered.push(text.to_string());
+        if self.delivered.len() > MAX_TRACKED_SEGMENTS {
+            self.delivered.remove(0);
+        }
+    }
+
+    fn match_phrase(&self, cores: &[String]) -> Option<(usize, SpokenCommandAction)> {
+        self.phrases
+            .iter()
+            .find(|phrase| cores.starts_with(&phrase.words))
+            .map(|phrase| (phrase.words.len(), phrase.action))
+    }
+
+    fn apply(
+        &mut self,
+        action: SpokenCommandAction,
+        pieces: &mut Vec<Piece>,
+        retracted: &mut Vec<String>,
+    ) {
+        if !has_words(pieces) {
+            let needs_previous_text = !matches!(action, SpokenCommandAction::UndoLast);
+            if needs_previous_text && !self.pull_last_segment(pieces, retracted) {
+                // В начале сессии прикрепить пунктуацию или перенос не к чему
+                return;
+            }
+        }
+
+        if let Some(mark) = action.punctuation() {
+            if let Some(Piece::Word(word)) = pieces
+                .iter_mut()
+                .rev()
+                .find(|piece| matches!(piece, Piece::Word(_)))
+            {
+                let trimmed_len = word.trim_end_matches(STT_TRAILING_PUNCTUATION).len();
+                word.truncate(trimmed_len);
+                word.push(mark);
+            }
+            return;
+        }
+
+        match action {
+            SpokenCommandAction::NewLine => push_break(pieces, LINE_BREAK),
+            SpokenCommandAction::NewParagraph => push_break(pieces, PARAGRAPH_BREAK),
+            SpokenCommandAction::DeleteLastWord => {
+                while matches!(pieces.last(), Some(Piece::Break(_))) {
+                    pieces.pop();
+                }
+                pieces.pop();
+            }
+            SpokenCommandAction::UndoLast => {
+                if !has_words(pieces) {
+                    self.pull_last_segment(pieces, retracted);
+                }
+                pieces.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Забирает последний доставленный финал обратно в редактируемый текст
+    fn pull_last_segment(&mut self, pieces: &mut Vec<Piece>, retracted: &mut Vec<String>) -> bool {
+        let Some(segment) = self.delivered.pop() else {
+            return false;
+        };
+
+        let mut segment_pieces = Vec::new();
+        for (index, line) in segment.split('\n').enumerate() {
+            if index > 0 {
+                push_break(&mut segment_pieces, LINE_BREAK);
+            }
+            segment_pieces.extend(line.split_whitespace().map(|w| Piece::Word(w.to_string())));
+        }
+
+        pieces.splice(0..0, segment_pieces);
+        retracted.insert(0, segment);
+        true
+    }
+}
+
+fn has_words(pieces: &[Piece]) -> bool {
+    pieces.iter().any(|piece| matches!(piece, Piece::Word(_)))
+}
+
+/// Два переноса подряд ("new line new line") дают абзац
+fn push_break(pieces: &mut Vec<Piece>, line_break: &'static str) {
+    match pieces.last_mut() {
+        Some(Piece::Break(previous)) => *previous = PARAGRAPH_BREAK,
+        _ => pieces.push(Piece::Break(line_break)),
+    }
+}
+
+fn render(pieces: &[Piece]) -> String {
+    let mut out = String::new();
+    for piece in pieces {
+        match piece {
+            Piece::Word(word) => {
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push(' ');
+                }
+                out.push_str(word);
+            }
+            Piece::Break(line_break) => {
+                if !out.is_empty() {
+                    out.push_str(line_break);
+                }
+            }
+        }
+    }
+    out
+}
+
+fn command_core(token: &str) -> String {
+    token
+        .trim_matches(|ch: char| !ch.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn phrase_words(phrase: &str) -> Vec<String> {
+    phrase
+        .split_whitespace()
+        .map(command_core)
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Проверка словарей перед сохранением настроек
+pub fn validate_spoken_commands_config(config: &SpokenCommandsConfig) -> Result<(), String> {
+    for (language, vocabulary) in &config.vocabularies {
+        if phrase_words(&vocabulary.escape_word).len() != 1 {
+            return Err(format!(
+                "Spoken commands ({}): escape word must be a single word",
+                language
+            ));
+        }
+        if let Some(command) = vocabulary
+            .commands
+            .iter()
+            .find(|command| phrase_words(&command.phrase).is_empty())
+        {
+            return Err(format!(
+                "Spoken commands ({}): phrase for {:?} is empty",
+                language, command.action
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SpokenCommandVocabulary;
+
+    fn interpreter(language: Option<&str>) -> SpokenCommandInterpreter {
+        let config = SpokenCommandsConfig {
+            enabled: true,
+            ..SpokenCommandsConfig::default()
+        };
+        SpokenCommandInterpreter::from_config(&config, language).unwrap()
+    }
+
+    fn deliver(interpreter: &mut SpokenCommandInterpreter, text: &str) -> SpokenCommandOutcome {
+        let outcome = interpreter.interpret(text);
+        interpreter.record_delivered(&outcome.text);
+        outcome
+    }
+
+    #[test]
+    fn test_disabled_config_has_no_interpreter() {
+        assert!(
+            SpokenCommandInterpreter::from_config(&SpokenCommandsConfig::default(), None).is_none()
+        );
+    }
+
+    #[test]
+    fn test_punctuation_and_line_breaks_inside_final() {
+        let outcome = interpreter(Some("en"))
+            .interpret("hello comma world new line how are you question mark new paragraph thanks");
+
+        assert_eq!(outcome.text, "hello, world\nhow are you?\n\nthanks");
+        assert_eq!(outcome.retract, None);
+    }
+
+    #[test]
+    fn test_commands_ignore_stt_punctuation_and_case() {
+        let outcome = interpreter(Some("en-US")).interpret("Hello, Comma, world. Period.");
+        assert_eq!(outcome.text, "Hello, world.");
+    }
+
+    #[test]
+    fn test_russian_prefers_longest_phrase() {
+        let outcome = interpreter(Some("ru")).interpret("привет запятая мир точка с запятой");
+        assert_eq!(outcome.text, "привет, мир;");
+    }
+
+    #[test]
+    fn test_escape_word_dictates_command_literally() {
+        let mut interpreter = interpreter(None);
+
+        assert_eq!(
+            interpreter
+                .interpret("type literal new line and буквально точка")
+                .text,
+            "type new line and точка"
+        );
+        assert_eq!(interpreter.interpret("say literal").text, "say literal");
+    }
+
+    #[test]
+    fn test_delete_last_word_inside_final() {
+        let outcome = interpreter(Some("en")).interpret("send it tomorrow delete last word today");
+        assert_eq!(outcome.text, "send it today");
+    }
+
+    #[test]
+    fn test_delete_last_word_retracts_previous_final() {
+        let mut interpreter = interpreter(Some("en"));
+        deliver(&mut interpreter, "see you tomorrow");
+
+        let outcome = deliver(&mut interpreter, "delete last word");
+        assert_eq!(outcome.text, "see you");
+        assert_eq!(outcome.retract.as_deref(), Some("see you tomorrow"));
+
+        let outcome = deliver(&mut interpreter, "delete last word");
+        assert_eq!(outcome.text, "see");
+        assert_eq!(outcome.retract.as_deref(), Some("see you"));
+    }
+
+    #[test]
+    fn test_undo_that_removes_current_or_previous_utterance() {
+        let mut interpreter = interpreter(Some("en"));
+        deliver(&mut interpreter, "first");
+        deliver(&mut interpreter, "second");
+
+        let outcome = deliver(&mut interpreter, "oops wrong undo that");
+        assert_eq!(outcome, SpokenCommandOutcome::default());
+
+        let outcome = deliver(&mut interpreter, "scratch that");
+        assert_eq!(outcome.text, "");
+        assert_eq!(outcome.retract.as_deref(), Some("second"));
+
+        let outcome = deliver(&mut interpreter, "comma then");
+        assert_eq!(outcome.text, "first, then");
+        assert_eq!(outcome.retract.as_deref(), Some("first"));
+    }
+
+    #[test]
+    fn test_trailing_line_break_is_placed_before_next_final() {
+        let mut interpreter = interpreter(Some("en"));
+
+        let outcome = deliver(&mut interpreter, "Dear team, new line");
+        assert_eq!(outcome.text, "Dear team,");
+        assert_eq!(outcome.retract, None);
+
+        let outcome = deliver(&mut interpreter, "the release is ready");
+        assert_eq!(outcome.text, "Dear team,\nthe release is ready");
+        assert_eq!(outcome.retract.as_deref(), Some("Dear team,"));
+    }
+
+    #[test]
+    fn test_command_at_session_start_is_dropped() {
+        let mut interpreter = interpreter(Some("en"));
+        assert_eq!(interpreter.interpret("comma").text, "");
+        assert_eq!(interpreter.interpret("undo that").retract, None);
+    }
+
+    #[test]
+    fn test_validation_rejects_multi_word_escape() {
+        let mut config = SpokenCommandsConfig::default();
+        assert!(validate_spoken_commands_config(&config).is_ok());
+
+        config.vocabularies.insert(
+            "en".to_string(),
+            SpokenCommandVocabulary {
+                escape_word: "say literally".to_string(),
+                ..SpokenCommandVocabulary::english()
+            },
+        );
+        assert!(validate_spoken_commands_config(&config).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+//! Проверка настроек текстового перевода (`text_translation`) перед сохранением.
+
+use crate::domain::{TextTranslationConfig, TextTranslationProviderType};
+
+pub fn validate_text_translation_config(config: &TextTranslationConfig) -> Result<(), String> {
+    if let Some(endpoint) = config.custom_endpoint() {
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err(format!(
+                "text_translation.endpoint должен начинаться с http:// или https://: {}",
+                endpoint
+            ));
+        }
+    }
+    if config.provider == TextTranslationProviderType::OpenAICompatible
+        && config.custom_model().is_none()
+    {
+        return Err("text_translation.model обязателен для openai_compatible".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_endpoint_scheme_and_compatible_model() {
+        let mut config = TextTranslationConfig {
+            provider: TextTranslationProviderType::LibreTranslate,
+            endpoint: Some("https://lt.example.com".to_string()),
+            ..TextTranslationConfig::default()
+        };
+        assert!(validate_text_translation_config(&config).is_ok());
+
+        config.endpoint = Some("lt.example.com".to_string());
+        assert!(validate_text_translation_config(&config).is_err());
+
+        config.provider = TextTranslationProviderType::OpenAICompatible;
+        config.endpoint = None;
+        assert!(validate_text_translation_config(&config).is_err());
+
+        config.model = Some("llama3.1".to_string());
+        assert!(validate_text_translation_config(&config).is_ok());
+    }
+}
@@ -0,0 +1,631 @@
+//! Экспорт финальных сегментов в SRT / WebVTT / TXT / Markdown / JSON.
+//!
+//! Рендер чистый (без I/O): команда собирает сегменты (текущая сессия или диапазон истории),
+//! а запись файла остаётся на presentation слое.
+
+use serde::Serialize;
+
+use crate::domain::{
+    HistoryEntry, SubtitleCueOptions, TranscriptExportFormat, TranscriptSegment, Transcription,
+    Word,
+};
+
+/// Скорость чтения для оценки длительности сегментов без таймингов (символов в секунду)
+const ESTIMATED_CHARS_PER_SECOND: f64 = 15.0;
+
+const JSON_EXPORT_VERSION: u32 = 1;
+
+/// Один cue субтитров
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleCue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    pub translation: Option<String>,
+    pub speaker: Option<String>,
+}
+
+#[derive(Debug)]
+struct TimedToken {
+    text: String,
+    start: f64,
+    end: f64,
+}
+
+/// Рендерит сегменты в выбранный формат
+pub fn render_transcript(
+    segments: &[TranscriptSegment],
+    format: TranscriptExportFormat,
+    options: &SubtitleCueOptions,
+) -> Result<String, serde_json::Error> {
+    let options = options.sanitized();
+    Ok(match format {
+        TranscriptExportFormat::Srt => render_srt(&split_into_cues(segments, &options), &options),
+        TranscriptExportFormat::WebVtt => {
+            render_webvtt(&split_into_cues(segments, &options), &options)
+        }
+        TranscriptExportFormat::Txt => render_txt(segments),
+        TranscriptExportFormat::Markdown => render_markdown(segments),
+        TranscriptExportFormat::Json => render_json(segments)?,
+    })
+}
+
+/// Собирает сегменты из истории на одной временной шкале: первая запись начинается с 0,
+/// остальные сдвигаются по абсолютному времени начала (записи могут быть из разных сессий)
+pub fn segments_from_history(entries: &[HistoryEntry]) -> Vec<TranscriptSegment> {
+    let mut entries: Vec<&HistoryEntry> = entries.iter().collect();
+    entries.sort_by_key(|entry| entry.started_at_ms());
+    let Some(base_ms) = entries.first().map(|entry| entry.started_at_ms()) else {
+        return Vec::new();
+    };
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            let mut transcription = entry.to_transcription();
+            let start = (entry.started_at_ms() - base_ms) as f64 / 1000.0;
+            let offset = start - transcription.start;
+            shift_transcription(&mut transcription, offset);
+            TranscriptSegment::new(transcription)
+        })
+        .collect()
+}
+
+fn shift_transcription(transcription: &mut Transcription, offset: f64) {
+    transcription.start += offset;
+    for word in &mut transcription.words {
+        word.start += offset;
+        word.end += offset;
+    }
+}
+
+/// Режет сегменты на cue по лимитам символов и длительности.
+///
+/// Если у сегмента есть пословные тайминги — границы cue берём из них, иначе распределяем
+/// длительность сегмента пропорционально длине слов. Перевод делим между cue сегмента
+/// в той же пропорции.
+pub fn split_into_cues(
+    segments: &[TranscriptSegment],
+    options: &SubtitleCueOptions,
+) -> Vec<SubtitleCue> {
+    let options = options.sanitized();
+    let mut cues = Vec::new();
+
+    for segment in segments {
+        let tokens = segment_tokens(&segment.transcription);
+        let groups = group_tokens(&tokens, &options);
+        let translations = distribute_translation(segment.translation.as_deref(), &groups);
+
+        for (group, translation) in groups.into_iter().zip(translations) {
+            let (Some(first), Some(last)) = (group.first(), group.last()) else {
+                continue;
+            };
+            cues.push(SubtitleCue {
+                start: first.start,
+                end: last.end,
+                text: join_tokens(&group),
+                translation,
+                speaker: segment.transcription.speaker.clone(),
+            });
+        }
+    }
+
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    apply_min_duration(&mut cues, options.min_cue_duration_secs);
+    cues
+}
+
+fn segment_tokens(transcription: &Transcription) -> Vec<TimedToken> {
+    let timed_words = !transcription.words.is_empty()
+        && transcription
+            .words
+            .iter()
+            .all(|word| word.start.is_finite() && word.end >= word.start);
+    if timed_words {
+        return transcription
+            .words
+            .iter()
+            .map(|word: &Word| TimedToken {
+                text: word.display_text().to_string(),
+                start: word.start,
+                end: word.end,
+            })
+            .collect();
+    }
+
+    let words: Vec<&str> = transcription.text.split_whitespace().collect();
+    let total_chars: usize = words.iter().map(|word| word.chars().count() + 1).sum();
+    if total_chars == 0 {
+        return Vec::new();
+    }
+
+    let duration = if transcription.duration > 0.0 {
+        transcription.duration
+    } else {
+        total_chars as f64 / ESTIMATED_CHARS_PER_SECOND
+    };
+    let start = transcription.start.max(0.0);
+    let mut consumed = 0usize;
+    words
+        .into_iter()
+        .map(|word| {
+            let word_start = start + duration * consumed as f64 / total_chars as f64;
+            consumed += word.chars().count() + 1;
+            TimedToken {
+                text: word.to_string(),
+                start: word_start,
+                end: start + duration * consumed as f64 / total_chars as f64,
+            }
+        })
+        .collect()
+}
+
+fn group_tokens<'a>(
+    tokens: &'a [TimedToken],
+    options: &SubtitleCueOptions,
+) -> Vec<Vec<&'a TimedToken>> {
+    let mut groups: Vec<Vec<&TimedToken>> = Vec::new();
+    let mut current: Vec<&TimedToken> = Vec::new();
+    let mut current_chars = 0usize;
+
+    for token in tokens {
+        let token_chars = token.text.chars().count();
+        if let Some(first) = current.first() {
+            let too_long = current_chars + 1 + token_chars > options.max_chars_per_cue;
+            let too_slow = token.end - first.start > options.max_cue_duration_secs;
+            if too_long || too_slow {
+                groups.push(std::mem::take(&mut current));
+                current_chars = 0;
+            }
+        }
+        current_chars += if current.is_empty() {
+            token_chars
+        } else {
+            token_chars + 1
+        };
+        current.push(token);
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+fn join_tokens(tokens: &[&TimedToken]) -> String {
+    tokens
+        .iter()
+        .map(|token| token.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn distribute_translation(
+    translation: Option<&str>,
+    groups: &[Vec<&TimedToken>],
+) -> Vec<Option<String>> {
+    let Some(translation) = translation.map(str::trim).filter(|t| !t.is_empty()) else {
+        return vec![None; groups.len()];
+    };
+    if groups.len() <= 1 {
+        return vec![Some(translation.to_string()); groups.len()];
+    }
+
+    let words: Vec<&str> = translation.split_whitespace().collect();
+    let group_chars: Vec<usize> = groups
+        .iter()
+        .map(|group| join_tokens(group).chars().count().max(1))
+        .collect();
+    let total_chars: usize = group_chars.iter().sum();
+
+    let mut result = Vec::with_capacity(groups.len());
+    let mut taken = 0usize;
+    let mut cumulative = 0usize;
+    for (index, chars) in group_chars.iter().enumerate() {
+        cumulative += chars;
+        let until = if index + 1 == groups.len() {
+            words.len()
+        } else {
+            (words.len() * cumulative + total_chars / 2) / total_chars
+        };
+        let until = until.clamp(taken, words.len());
+        let chunk = words[taken..until].join(" ");
+        taken = until;
+        result.push(Some(chunk).filter(|chunk| !chunk.is_empty()));
+    }
+    result
+}
+
+fn apply_min_duration(cues: &mut [SubtitleCue], min_duration: f64) {
+    for index in 0..cues.len() {
+        let next_start = cues.get(index + 1).map(|next| next.start);
+        let cue = &mut cues[index];
+        if cue.end - cue.start < min_duration {
+            cue.end = cue.start + min_duration;
+        }
+        // Продлённый cue не должен наезжать на следующий
+        if let Some(next_start) = next_start {
+            if next_start > cue.start && cue.end > next_start {
+                cue.end = next_start;
+            }
+        }
+    }
+}
+
+/// Переносит текст по словам так, чтобы строки не превышали `max_chars` (слово длиннее лимита не режем)
+fn wrap_lines(text: &str, max_chars: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+fn speaker_label(speaker: &str) -> String {
+    format!("Speaker {}", speaker)
+}
+
+fn format_timestamp(seconds: f64, millis_separator: char) -> String {
+    let total_ms = if seconds.is_finite() {
+        (seconds.max(0.0) * 1000.0).round() as u64
+    } else {
+        0
+    };
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        total_ms / 3_600_000,
+        (total_ms / 60_000) % 60,
+        (total_ms / 1000) % 60,
+        millis_separator,
+        total_ms % 1000
+    )
+}
+
+fn render_srt(cues: &[SubtitleCue], options: &SubtitleCueOptions) -> String {
+    let mut out = String::new();
+    for (index, cue) in cues.iter().enumerate() {
+        let text = match cue.speaker.as_deref() {
+            Some(speaker) => format!("[{}] {}", speaker_label(speaker), cue.text),
+            None => cue.text.clone(),
+        };
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n",
+            index + 1,
+            format_timestamp(cue.start, ','),
+            format_timestamp(cue.end, ','),
+            wrap_lines(&text, options.max_chars_per_line)
+        ));
+        if let Some(translation) = cue.translation.as_deref() {
+            out.push_str(&wrap_lines(translation, options.max_chars_per_line));
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn escape_webvtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn render_webvtt(cues: &[SubtitleCue], options: &SubtitleCueOptions) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in cues {
+        out.push_str(&format!(
+            "{} --> {}\n",
+            format_timestamp(cue.start, '.'),
+            format_timestamp(cue.end, '.')
+        ));
+        if let Some(speaker) = cue.speaker.as_deref() {
+            out.push_str(&format!("<v {}>", escape_webvtt(&speaker_label(speaker))));
+        }
+        out.push_str(&escape_webvtt(&wrap_lines(
+            &cue.text,
+            options.max_chars_per_line,
+        )));
+        out.push('\n');
+        if let Some(translation) = cue.translation.as_deref() {
+            out.push_str(&escape_webvtt(&wrap_lines(
+                translation,
+                options.max_chars_per_line,
+            )));
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn segment_text_with_speaker(segment: &TranscriptSegment, speaker_format: &str) -> String {
+    let text = segment.transcription.text.trim();
+    match segment.transcription.speaker.as_deref() {
+        Some(speaker) => speaker_format
+            .replace("{speaker}", &speaker_label(speaker))
+            .replace("{text}", text),
+        None => text.to_string(),
+    }
+}
+
+fn translation_of(segment: &TranscriptSegment) -> Option<&str> {
+    segment
+        .translation
+        .as_deref()
+        .map(str::trim)
+        .filter(|translation| !translation.is_empty())
+}
+
+fn render_txt(segments: &[TranscriptSegment]) -> String {
+    let has_translations = segments.iter().any(|s| translation_of(s).is_some());
+    let blocks: Vec<String> = segments
+        .iter()
+        .map(|segment| {
+            let mut block = segment_text_with_speaker(segment, "{speaker}: {text}");
+            if let Some(translation) = translation_of(segment) {
+                block.push('\n');
+                block.push_str(translation);
+            }
+            block
+        })
+        .collect();
+    // С переводом каждая пара строк отделяется пустой строкой, иначе — сегмент на строку
+    let separator = if has_translations { "\n\n" } else { "\n" };
+    let mut out = blocks.join(separator);
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+fn render_markdown(segments: &[TranscriptSegment]) -> String {
+    let mut out = String::from("# Transcript\n\n");
+    for segment in segments {
+        let timestamp = format_timestamp(segment.transcription.start, '.');
+        out.push_str(&format!(
+            "**[{}]** {}\n",
+            &timestamp[..8],
+            segment_text_with_speaker(segment, "**{speaker}:** {text}")
+        ));
+        if let Some(translation) = translation_of(segment) {
+            out.push_str(&format!("> {}\n", translation));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[derive(Serialize)]
+struct JsonTranscript<'a> {
+    version: u32,
+    segments: Vec<JsonSegment<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonSegment<'a> {
+    start: f64,
+    end: f64,
+    text: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    translation: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speaker: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confidence: Option<f32>,
+    timestamp: i64,
+    #[serde(skip_serializing_if = "<[Word]>::is_empty")]
+    words: &'a [Word],
+}
+
+fn render_json(segments: &[TranscriptSegment]) -> Result<String, serde_json::Error> {
+    let transcript = JsonTranscript {
+        version: JSON_EXPORT_VERSION,
+        segments: segments
+            .iter()
+            .map(|segment| {
+                let transcription = &segment.transcription;
+                JsonSegment {
+                    start: transcription.start,
+                    end: segment.end(),
+                    text: &transcription.text,
+                    translation: translation_of(segment),
+                    language: transcription.language.as_deref(),
+                    speaker: transcription.speaker.as_deref(),
+                    provider: transcription.provider.as_deref(),
+                    confidence: transcription.confidence,
+                    timestamp: transcription.timestamp,
+                    words: &transcription.words,
+                }
+            })
+            .collect(),
+    };
+    let mut json = serde_json::to_string_pretty(&transcript)?;
+    json.push('\n');
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, start: f64, duration: f64) -> TranscriptSegment {
+        TranscriptSegment::new(
+            Transcription::final_result(text.to_string()).with_timing(start, duration),
+        )
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(3723.456, ','), "01:02:03,456");
+        assert_eq!(format_timestamp(-1.0, '.'), "00:00:00.000");
+    }
+
+    #[test]
+    fn test_srt_renders_numbered_cues_with_translation() {
+        let segments = vec![
+            segment("Hello world", 0.5, 1.5).with_translation("Привет, мир"),
+            segment("Second line", 3.0, 2.0),
+        ];
+
+        let srt = render_transcript(
+            &segments,
+            TranscriptExportFormat::Srt,
+            &SubtitleCueOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            srt,
+            "1\n00:00:00,500 --> 00:00:02,000\nHello world\nПривет, мир\n\n\
+             2\n00:00:03,000 --> 00:00:05,000\nSecond line\n\n"
+        );
+    }
+
+    #[test]
+    fn test_webvtt_escapes_and_tags_speaker() {
+        let mut seg = segment("a < b & c", 0.0, 2.0);
+        seg.transcription.speaker = Some("0".to_string());
+
+        let vtt = render_transcript(
+            &[seg],
+            TranscriptExportFormat::WebVtt,
+            &SubtitleCueOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n00:00:00.000 --> 00:00:02.000\n<v Speaker 0>a &lt; b &amp; c\n\n"
+        );
+    }
+
+    #[test]
+    fn test_cues_split_by_max_chars_and_duration() {
+        let options = SubtitleCueOptions {
+            max_chars_per_cue: 11,
+            max_chars_per_line: 11,
+            max_cue_duration_secs: 60.0,
+            min_cue_duration_secs: 0.0,
+        };
+        let cues = split_into_cues(&[segment("aaaa bbbb cccc dddd", 0.0, 4.0)], &options);
+        let texts: Vec<&str> = cues.iter().map(|cue| cue.text.as_str()).collect();
+        assert_eq!(texts, vec!["aaaa bbbb", "cccc dddd"]);
+        assert_eq!(cues[0].start, 0.0);
+        assert_eq!(cues[1].end, 4.0);
+
+        let words = vec![
+            Word::new("one", 0.0, 1.0),
+            Word::new("two", 1.0, 2.5),
+            Word::new("three", 2.5, 3.5),
+        ];
+        let timed = TranscriptSegment::new(
+            Transcription::final_result("one two three".to_string())
+                .with_timing(0.0, 3.5)
+                .with_words(words),
+        );
+        let options = SubtitleCueOptions {
+            max_cue_duration_secs: 2.5,
+            ..options
+        };
+        let cues = split_into_cues(&[timed], &options);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].text, "one two");
+        assert_eq!((cues[1].start, cues[1].end), (2.5, 3.5));
+    }
+
+    #[test]
+    fn test_translation_is_distributed_across_cues() {
+        let options = SubtitleCueOptions {
+            max_chars_per_cue: 9,
+            max_chars_per_line: 9,
+            max_cue_duration_secs: 60.0,
+            min_cue_duration_secs: 0.0,
+        };
+        let cues = split_into_cues(
+            &[segment("aaaa bbbb", 0.0, 2.0).with_translation("x y")],
+            &options,
+        );
+        assert_eq!(cues.len(), 1);
+
+        let cues = split_into_cues(
+            &[segment("aaaa bbbb cccc", 0.0, 3.0).with_translation("один два три четыре")],
+            &options,
+        );
+        assert_eq!(cues.len(), 2);
+        let translations: Vec<String> = cues
+            .iter()
+            .map(|cue| cue.translation.clone().unwrap_or_default())
+            .collect();
+        assert_eq!(translations.join(" "), "один два три четыре");
+    }
+
+    #[test]
+    fn test_min_duration_does_not_overlap_next_cue() {
+        let options = SubtitleCueOptions {
+            min_cue_duration_secs: 2.0,
+            ..SubtitleCueOptions::default()
+        };
+        let cues = split_into_cues(
+            &[segment("short", 0.0, 0.3), segment("next", 1.0, 1.0)],
+            &options,
+        );
+        assert_eq!(cues[0].end, 1.0);
+        assert_eq!(cues[1].end, 3.0);
+    }
+
+    #[test]
+    fn test_txt_markdown_and_json() {
+        let segments = vec![
+            segment("First", 0.0, 1.0),
+            segment("Second", 61.0, 1.0).with_translation("Второй"),
+        ];
+        let options = SubtitleCueOptions::default();
+
+        let txt = render_transcript(&segments, TranscriptExportFormat::Txt, &options).unwrap();
+        assert_eq!(txt, "First\n\nSecond\nВторой\n");
+
+        let md = render_transcript(&segments, TranscriptExportFormat::Markdown, &options).unwrap();
+        assert!(md.starts_with("# Transcript\n\n**[00:00:00]** First\n"));
+        assert!(md.contains("**[00:01:01]** Second\n> Второй\n"));
+
+        let json = render_transcript(&segments, TranscriptExportFormat::Json, &options).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["segments"][1]["end"], 62.0);
+        assert_eq!(value["segments"][1]["translation"], "Второй");
+        assert!(value["segments"][0].get("translation").is_none());
+    }
+
+    #[test]
+    fn test_segments_from_history_share_one_timeline() {
+        let first = HistoryEntry::from_transcription(
+            &Transcription::final_result("first".to_string()).with_timing(2.0, 1.0),
+            "deepgram",
+        )
+        .with_session(1, Some(10_000));
+        let second = HistoryEntry::from_transcription(
+            &Transcription::final_result("second".to_string()).with_timing(0.5, 1.0),
+            "deepgram",
+        )
+        .with_session(2, Some(20_000));
+
+        let segments = segments_from_history(&[second, first]);
+
+        assert_eq!(segments[0].transcription.text, "first");
+        assert_eq!(segments[0].transcription.start, 0.0);
+        assert_eq!(segments[1].transcription.start, 8.5);
+    }
+}
@@ -0,0 +1,589 @@
+//! Цепочка пост-обработки финальных транскрипций: выполняется на каждом финале
+//! до записи в историю, буфер обмена и автовставки.
+
+use std::collections::HashSet;
+
+use regex::{NoExpand, Regex, RegexBuilder};
+
+use crate::domain::{
+    PostProcessingStage, ReplacementRule, TrailingPunctuationPolicy,
+    TranscriptPostProcessingConfig, TranscriptPostProcessor, Transcription,
+};
+
+/// Знаки, после которых начинается новое предложение
+const SENTENCE_TERMINATORS: [char; 4] = ['.', '!', '?', '…'];
+
+/// Знаки, перед которыми не должно быть пробела
+const CLOSING_PUNCTUATION: [char; 8] = ['.', ',', '!', '?', ';', ':', '…', ')'];
+
+/// Упорядоченный набор шагов пост-обработки
+#[derive(Default)]
+pub struct TranscriptPostProcessingChain {
+    processors: Vec<Box<dyn TranscriptPostProcessor>>,
+}
+
+impl TranscriptPostProcessingChain {
+    pub fn new(processors: Vec<Box<dyn TranscriptPostProcessor>>) -> Self {
+        Self { processors }
+    }
+
+    /// Собирает цепочку из настроек. Невалидные regex-правила пропускаются с warning,
+    /// чтобы одно битое правило не отключало всю обработку.
+    pub fn from_config(config: &TranscriptPostProcessingConfig) -> Self {
+        let processors = config
+            .enabled_stages()
+            .into_iter()
+            .map(|stage| -> Box<dyn TranscriptPostProcessor> {
+                match stage {
+                    PostProcessingStage::Replacements => Box::new(
+                        ReplacementProcessor::skipping_invalid(&config.replacements.rules),
+                    ),
+                    PostProcessingStage::FillerWords => {
+                        Box::new(FillerWordProcessor::new(&config.filler_words.words))
+                    }
+                    PostProcessingStage::Normalization => Box::new(NormalizationProcessor {
+                        capitalize_sentences: config.normalization.capitalize_sentences,
+                    }),
+                    PostProcessingStage::TrailingPunctuation => Box::new(
+                        TrailingPunctuationProcessor::new(config.trailing_punctuation.policy),
+                    ),
+                }
+            })
+            .collect();
+
+        Self::new(processors)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.processors.is_empty()
+    }
+
+    pub fn process(&self, text: &str) -> String {
+        self.processors
+            .iter()
+            .fold(text.to_string(), |current, processor| {
+                let next = processor.process(&current);
+                if next != current {
+                    log::trace!("Post-processing stage '{}' changed text", processor.name());
+                }
+                next
+            })
+    }
+
+    /// Применяет цепочку к финалу. Пословные тайминги сбрасываются, если текст изменился:
+    /// они больше не соответствуют словам.
+    pub fn apply(&self, mut transcription: Transcription) -> Transcription {
+        if self.is_empty() {
+            return transcription;
+        }
+
+        let processed = self.process(&transcription.text);
+        if processed != transcription.text {
+            transcription.text = processed;
+            transcription.words.clear();
+        }
+        transcription
+    }
+}
+
+/// Проверка настроек перед сохранением: все regex-правила должны компилироваться
+pub fn validate_post_processing_config(
+    config: &TranscriptPostProcessingConfig,
+) -> Result<(), String> {
+    for (index, rule) in config.replacements.rules.iter().enumerate() {
+        compile_rule(rule).map_err(|e| format!("Replacement rule #{}: {}", index + 1, e))?;
+    }
+
+    if config
+        .filler_words
+        .words
+        .iter()
+        .any(|word| word.split_whitespace().count() != 1)
+    {
+        return Err("Filler words must be single non-empty words".to_string());
+    }
+
+    Ok(())
+}
+
+fn compile_rule(rule: &ReplacementRule) -> Result<Regex, String> {
+    if rule.pattern.is_empty() {
+        return Err("pattern is empty".to_string());
+    }
+
+    let pattern = match (rule.regex, rule.whole_word) {
+        (true, true) => format!(r"\b(?:{})\b", rule.pattern),
+        (true, false) => format!("(?:{})", rule.pattern),
+        (false, true) => whole_word_literal_pattern(&rule.pattern),
+        (false, false) => regex::escape(&rule.pattern),
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!rule.case_sensitive)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Экранированный литерал целым словом. `\b` только со стороны словесных символов:
+/// иначе "c++" никогда не совпадёт
+pub(super) fn whole_word_literal_pattern(literal: &str) -> String {
+    let mut pattern = regex::escape(literal);
+    if literal.starts_with(is_word_char) {
+        pattern.insert_str(0, r"\b");
+    }
+    if literal.ends_with(is_word_char) {
+        pattern.push_str(r"\b");
+    }
+    pattern
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Пользовательские замены: литералы и регулярные выражения
+pub struct ReplacementProcessor {
+    rules: Vec<CompiledReplacement>,
+}
+
+struct CompiledReplacement {
+    regex: Regex,
+    replacement: String,
+    expand_groups: bool,
+}
+
+impl ReplacementProcessor {
+    pub fn new(rules: &[ReplacementRule]) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .map(|rule| Ok(CompiledReplacement::new(rule, compile_rule(rule)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { rules })
+    }
+
+    fn skipping_invalid(rules: &[ReplacementRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match compile_rule(rule) {
+                Ok(regex) => Some(CompiledReplacement::new(rule, regex)),
+                Err(e) => {
+                    log::warn!("Skipping replacement rule '{}': {}", rule.pattern, e);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+}
+
+impl CompiledReplacement {
+    fn new(rule: &ReplacementRule, regex: Regex) -> Self {
+        Self {
+            regex,
+            replacement: rule.replacement.clone(),
+            expand_groups: rule.regex,
+        }
+    }
+}
+
+impl TranscriptPostProcessor for ReplacementProcessor {
+    fn name(&self) -> &'static str {
+        "replacements"
+    }
+
+    fn process(&self, text: &str) -> String {
+        self.rules.iter().fold(text.to_string(), |current, rule| {
+            if rule.expand_groups {
+                rule.regex
+                    .replace_all(&current, rule.replacement.as_str())
+                    .into_owned()
+            } else {
+                rule.regex
+                    .replace_all(&current, NoExpand(&rule.replacement))
+                    .into_owned()
+            }
+        })
+    }
+}
+
+/// Удаляет слова-паразиты ("um", "uh", "ну", "типа").
+/// Точка/вопрос после удалённого слова переезжают на предыдущее слово.
+pub struct FillerWordProcessor {
+    words: HashSet<String>,
+}
+
+impl FillerWordProcessor {
+    pub fn new(words: &[String]) -> Self {
+        Self {
+            words: words
+                .iter()
+                .map(|word| word.trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect(),
+        }
+    }
+
+    fn is_filler(&self, token: &str) -> bool {
+        let core = token.trim_matches(|ch: char| !is_word_char(ch));
+        !core.is_empty() && self.words.contains(&core.to_lowercase())
+    }
+
+    fn remove_from_line(&self, line: &str) -> String {
+        let mut kept: Vec<String> = Vec::new();
+
+        for token in line.split_whitespace() {
+            if !self.is_filler(token) {
+                kept.push(token.to_string());
+                continue;
+            }
+
+            let terminator = token
+                .trim_start_matches(|ch: char| !is_word_char(ch))
+                .trim_start_matches(is_word_char)
+                .chars()
+                .find(|ch| SENTENCE_TERMINATORS.contains(ch));
+
+            if let (Some(terminator), Some(previous)) = (terminator, kept.last_mut()) {
+                let trimmed_len = previous.trim_end_matches([',', ';', ':']).len();
+                previous.truncate(trimmed_len);
+                if !previous.ends_with(SENTENCE_TERMINATORS) {
+                    previous.push(terminator);
+                }
+            }
+        }
+
+        kept.join(" ")
+    }
+}
+
+impl TranscriptPostProcessor for FillerWordProcessor {
+    fn name(&self) -> &'static str {
+        "filler_words"
+    }
+
+    fn process(&self, text: &str) -> String {
+        text.split('\n')
+            .map(|line| self.remove_from_line(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Схлопывает пробелы, убирает пробелы перед знаками препинания и висячие запятые.
+/// Опционально делает заглавной первую букву каждого предложения и строки.
+pub struct NormalizationProcessor {
+    pub capitalize_sentences: bool,
+}
+
+impl TranscriptPostProcessor for NormalizationProcessor {
+    fn name(&self) -> &'static str {
+        "normalization"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let normalized = normalize_spacing(text);
+        if self.capitalize_sentences {
+            capitalize_sentences(&normalized)
+        } else {
+            normalized
+        }
+    }
+}
+
+fn normalize_spacing(text: &str) -> String {
+    // Переносы строк (голосовые команды "new line") сохраняются, схлопываются только пробелы
+    let collapsed = text
+        .split('\n')
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut out = String::with_capacity(collapsed.len());
+
+    for ch in collapsed.chars() {
+        if CLOSING_PUNCTUATION.contains(&ch) {
+            while out.ends_with(' ') {
+                out.pop();
+            }
+            if SENTENCE_TERMINATORS.contains(&ch) {
+                // "я думаю, ." → "я думаю."
+                while out.ends_with([',', ';', ':']) {
+                    out.pop();
+                }
+            }
+            if ch == ',' && out.ends_with(',') {
+                continue;
+            }
+        }
+        out.push(ch);
+    }
+
+    out.trim_start_matches(|ch: char| matches!(ch, ',' | ';' | ':') || ch.is_whitespace())
+        .to_string()
+}
+
+fn capitalize_sentences(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut capitalize_next = true;
+    let mut after_terminator = false;
+
+    for ch in text.chars() {
+        if capitalize_next && ch.is_alphabetic() {
+            out.extend(ch.to_uppercase());
+            capitalize_next = false;
+            after_terminator = false;
+            continue;
+        }
+
+        if SENTENCE_TERMINATORS.contains(&ch) {
+            after_terminator = true;
+        } else if ch == '\n' {
+            capitalize_next = true;
+        } else if ch.is_whitespace() {
+            // Только "точка + пробел": "file.txt" и "3.5" не считаются концом предложения
+            if after_terminator {
+                capitalize_next = true;
+            }
+        } else {
+            after_terminator = false;
+            if ch.is_alphanumeric() {
+                capitalize_next = false;
+            }
+        }
+        out.push(ch);
+    }
+
+    out
+}
+
+/// Политика завершающей пунктуации
+pub struct TrailingPunctuationProcessor {
+    policy: TrailingPunctuationPolicy,
+}
+
+impl TrailingPunctuationProcessor {
+    pub fn new(policy: TrailingPunctuationPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+impl TranscriptPostProcessor for TrailingPunctuationProcessor {
+    fn name(&self) -> &'static str {
+        "trailing_punctuation"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let trimmed = text.trim_end();
+        match self.policy {
+            TrailingPunctuationPolicy::Ensure => {
+                let base = trimmed.trim_end_matches([',', ';', ':']);
+                match base.chars().last() {
+                    Some(last) if last.is_alphanumeric() => format!("{}.", base),
+                    _ if base.is_empty() => String::new(),
+                    _ => base.to_string(),
+                }
+            }
+            TrailingPunctuationPolicy::Remove => trimmed
+                .trim_end_matches(['.', ',', ';', ':', '…'])
+                .trim_end()
+                .to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        FillerWordsConfig, NormalizationConfig, ReplacementRulesConfig, TrailingPunctuationConfig,
+        Word,
+    };
+
+    fn fillers() -> FillerWordProcessor {
+        FillerWordProcessor::new(&FillerWordsConfig::default().words)
+    }
+
+    #[test]
+    fn test_literal_replacement_is_case_insensitive_and_does_not_expand_groups() {
+        let processor = ReplacementProcessor::new(&[
+            ReplacementRule::literal("gonna", "going to"),
+            ReplacementRule::literal("dollar", "$1"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            processor.process("Gonna pay one dollar"),
+            "going to pay one $1"
+        );
+    }
+
+    #[test]
+    fn test_whole_word_literal_skips_substrings() {
+        let rule = ReplacementRule {
+            whole_word: true,
+            ..ReplacementRule::literal("ai", "AI")
+        };
+        let processor = ReplacementProcessor::new(&[rule]).unwrap();
+
+        assert_eq!(
+            processor.process("he said ai is here"),
+            "he said AI is here"
+        );
+    }
+
+    #[test]
+    fn test_whole_word_literal_with_symbols() {
+        let rule = ReplacementRule {
+            whole_word: true,
+            ..ReplacementRule::literal("си плюс плюс", "C++")
+        };
+        let processor = ReplacementProcessor::new(&[rule]).unwrap();
+
+        assert_eq!(processor.process("пишу на си плюс плюс"), "пишу на C++");
+    }
+
+    #[test]
+    fn test_regex_replacement_expands_groups() {
+        let processor =
+            ReplacementProcessor::new(&[ReplacementRule::regex(r"(\d+) percent", "$1%")]).unwrap();
+
+        assert_eq!(processor.process("about 42 percent"), "about 42%");
+    }
+
+    #[test]
+    fn test_invalid_regex_is_rejected_by_validation_and_skipped_by_chain() {
+        let mut config = TranscriptPostProcessingConfig::default();
+        config.replacements.rules = vec![
+            ReplacementRule::regex("(unclosed", "x"),
+            ReplacementRule::literal("teh", "the"),
+        ];
+
+        let error = validate_post_processing_config(&config).unwrap_err();
+        assert!(error.starts_with("Replacement rule #1"));
+
+        let chain = TranscriptPostProcessingChain::from_config(&config);
+        assert_eq!(chain.process("teh end"), "the end");
+    }
+
+    #[test]
+    fn test_filler_words_are_removed_with_their_commas() {
+        assert_eq!(
+            fillers().process("Um, I think, uh, we should go"),
+            "I think, we should go"
+        );
+        assert_eq!(fillers().process("ну типа я не знаю"), "я не знаю");
+        assert_eq!(fillers().process("hi,\num, Bob"), "hi,\nBob");
+    }
+
+    #[test]
+    fn test_filler_word_keeps_sentence_terminator() {
+        assert_eq!(
+            fillers().process("I was there, um. Then"),
+            "I was there. Then"
+        );
+        assert_eq!(fillers().process("Ты придёшь, ну?"), "Ты придёшь?");
+    }
+
+    #[test]
+    fn test_filler_words_do_not_match_inside_words() {
+        assert_eq!(
+            fillers().process("number humble нужно"),
+            "number humble нужно"
+        );
+    }
+
+    #[test]
+    fn test_normalization_fixes_spacing() {
+        let processor = NormalizationProcessor {
+            capitalize_sentences: false,
+        };
+
+        assert_eq!(
+            processor.process("  , hello   world , how are you ?  "),
+            "hello world, how are you?"
+        );
+        assert_eq!(processor.process("я думаю, ."), "я думаю.");
+        assert_eq!(
+            processor.process("Dear team ,\n\n  the  release"),
+            "Dear team,\n\nthe release"
+        );
+    }
+
+    #[test]
+    fn test_normalization_capitalizes_sentences() {
+        let processor = NormalizationProcessor {
+            capitalize_sentences: true,
+        };
+
+        assert_eq!(
+            processor.process("hello. open file.txt now! привет"),
+            "Hello. Open file.txt now! Привет"
+        );
+        assert_eq!(
+            processor.process("it costs 3.5 dollars"),
+            "It costs 3.5 dollars"
+        );
+    }
+
+    #[test]
+    fn test_trailing_punctuation_ensure() {
+        let processor = TrailingPunctuationProcessor::new(TrailingPunctuationPolicy::Ensure);
+
+        assert_eq!(processor.process("hello world"), "hello world.");
+        assert_eq!(processor.process("hello world,"), "hello world.");
+        assert_eq!(processor.process("is it?"), "is it?");
+        assert_eq!(processor.process("   "), "");
+    }
+
+    #[test]
+    fn test_trailing_punctuation_remove_keeps_questions() {
+        let processor = TrailingPunctuationProcessor::new(TrailingPunctuationPolicy::Remove);
+
+        assert_eq!(processor.process("hello world..."), "hello world");
+        assert_eq!(processor.process("hello world;"), "hello world");
+        assert_eq!(processor.process("is it?"), "is it?");
+    }
+
+    #[test]
+    fn test_chain_runs_stages_in_configured_order() {
+        let config = TranscriptPostProcessingConfig {
+            replacements: ReplacementRulesConfig {
+                enabled: true,
+                rules: vec![ReplacementRule::literal("gonna", "going to")],
+            },
+            filler_words: FillerWordsConfig {
+                enabled: true,
+                ..FillerWordsConfig::default()
+            },
+            normalization: NormalizationConfig {
+                enabled: true,
+                capitalize_sentences: true,
+            },
+            trailing_punctuation: TrailingPunctuationConfig {
+                enabled: true,
+                policy: TrailingPunctuationPolicy::Ensure,
+            },
+            ..TranscriptPostProcessingConfig::default()
+        };
+        let chain = TranscriptPostProcessingChain::from_config(&config);
+
+        assert_eq!(
+            chain.process("um, we are gonna  ship it , uh"),
+            "We are going to ship it."
+        );
+    }
+
+    #[test]
+    fn test_apply_clears_word_timings_only_when_text_changes() {
+        let chain =
+            TranscriptPostProcessingChain::from_config(&TranscriptPostProcessingConfig::default());
+        let mut transcription = Transcription::new("hello  world".to_string(), true);
+        transcription.words = vec![Word::new("hello", 0.0, 0.4)];
+
+        let processed = chain.apply(transcription.clone());
+        assert_eq!(processed.text, "hello world");
+        assert!(processed.words.is_empty());
+
+        transcription.text = "hello world".to_string();
+        assert_eq!(chain.apply(transcription).words.len(), 1);
+    }
+}
@@ -132,6 +132,9 @@ fn config_requires_new_connection(previous: &SttConfig, next: &SttConfig) -> boo
         || previous.auto_detect_language != next.auto_detect_language
         || previous.enable_punctuation != next.enable_punctuation
         || previous.filter_profanity != next.filter_profanity
+        || previous.profanity_words != next.profanity_words
+        || previous.enable_diarization != next.enable_diarization
+        || previous.compress_audio != next.compress_audio
         || previous.deepgram_api_key != next.deepgram_api_key
         || previous.assemblyai_api_key != next.assemblyai_api_key
         || previous.model != next.model
@@ -139,6 +142,7 @@ fn config_requires_new_connection(previous: &SttConfig, next: &SttConfig) -> boo
         || previous.backend_url != next.backend_url
         || previous.backend_streaming_provider != next.backend_streaming_provider
         || previous.streaming_keyterms != next.streaming_keyterms
+        || previous.failover_providers != next.failover_providers
 }
 
 /// Applies the product policy for warm backend dictation sessions.
@@ -0,0 +1,375 @@
+//! Глоссарии перевода: пост-проверка переведённых сегментов и импорт из CSV.
+//!
+//! Промпт LLM-переводчика получает те же термины (`TranslationGlossaryConfig::prompt_hint`),
+//! но модель их иногда игнорирует, а DeepL/LibreTranslate промпта не имеют вовсе.
+
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::Serialize;
+
+use super::transcript_post_processing::whole_word_literal_pattern;
+use crate::domain::{glossary_language_key, GlossaryTerm, TranslationGlossaryConfig};
+
+struct CompiledTerm {
+    source: String,
+    target: String,
+    source_re: Regex,
+    target_re: Regex,
+}
+
+impl CompiledTerm {
+    fn new(source: &str, target: &str) -> Option<Self> {
+        let (source, target) = (source.trim(), target.trim());
+        Some(Self {
+            source_re: term_regex(source)?,
+            target_re: term_regex(target)?,
+            source: source.to_string(),
+            target: target.to_string(),
+        })
+    }
+}
+
+/// Результат пост-проверки: исправленный перевод и термины, которые исправить не удалось
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlossaryCheck {
+    pub text: String,
+    pub violations: Vec<String>,
+}
+
+/// Глоссарий, собранный под один язык перевода (язык сессии субтитров не меняется)
+#[derive(Default)]
+pub struct TranslationGlossary {
+    terms: Vec<CompiledTerm>,
+    do_not_translate: Vec<CompiledTerm>,
+}
+
+impl TranslationGlossary {
+    pub fn for_language(config: &TranslationGlossaryConfig, target_language: &str) -> Self {
+        Self {
+            terms: config
+                .terms_for(target_language)
+                .iter()
+                .filter_map(|term| CompiledTerm::new(&term.source, &term.target))
+                .collect(),
+            do_not_translate: config
+                .do_not_translate
+                .iter()
+                .filter_map(|term| CompiledTerm::new(term, term))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.do_not_translate.is_empty()
+    }
+
+    /// Проверяет перевод по терминам, встретившимся в исходном тексте:
+    /// - оставленный без перевода `source` заменяется на `target` глоссария;
+    /// - do-not-translate термин приводится к каноническому написанию;
+    /// - если нужного термина в переводе нет, он попадает в `violations`.
+    pub fn enforce(&self, source_text: &str, translated: &str) -> GlossaryCheck {
+        let mut text = translated.to_string();
+        let mut violations = Vec::new();
+
+        for term in &self.terms {
+            if !term.source_re.is_match(source_text) {
+                continue;
+            }
+            if !term.target_re.is_match(&text) {
+                text = term
+                    .source_re
+                    .replace_all(&text, NoExpand(&term.target))
+                    .into_owned();
+            }
+            if !term.target_re.is_match(&text) {
+                violations.push(term.source.clone());
+            }
+        }
+
+        for term in &self.do_not_translate {
+            if !term.source_re.is_match(source_text) {
+                continue;
+            }
+            if term.target_re.is_match(&text) {
+                text = term
+                    .target_re
+                    .replace_all(&text, NoExpand(&term.target))
+                    .into_owned();
+            } else {
+                violations.push(term.source.clone());
+            }
+        }
+
+        GlossaryCheck { text, violations }
+    }
+}
+
+/// Термин целым словом, без учёта регистра
+fn term_regex(term: &str) -> Option<Regex> {
+    if term.is_empty() {
+        return None;
+    }
+    RegexBuilder::new(&whole_word_literal_pattern(term))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+/// Итог импорта CSV: сколько терминов и do-not-translate записей добавлено или обновлено
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct GlossaryImportSummary {
+    pub terms: usize,
+    pub do_not_translate: usize,
+}
+
+/// Импортирует CSV `source,target,language` в глоссарий.
+///
+/// - строка с пустым `target` (или из одной колонки) — do-not-translate термин;
+/// - пустой `language` берётся из `default_language`;
+/// - заголовок `source,...` пропускается, разделитель `,` или `;` (Excel с русской локалью);
+/// - `replace` очищает глоссарии и список перед импортом, иначе термины обновляются по `source`.
+pub fn import_glossary_csv(
+    config: &mut TranslationGlossaryConfig,
+    csv: &str,
+    default_language: Option<&str>,
+    replace: bool,
+) -> Result<GlossaryImportSummary, String> {
+    let default_language = default_language
+        .map(glossary_language_key)
+        .filter(|language| !language.is_empty());
+    let delimiter = detect_csv_delimiter(csv);
+
+    let mut terms = Vec::new();
+    let mut do_not_translate = Vec::new();
+    for (index, row) in parse_csv(csv, delimiter)?.into_iter().enumerate() {
+        let cell = |column: usize| row.get(column).map(|value| value.trim()).unwrap_or("");
+        let (source, target, language) = (cell(0), cell(1), cell(2));
+        if source.is_empty() {
+            continue;
+        }
+        if index == 0
+            && (source.eq_ignore_ascii_case("source") || source.eq_ignore_ascii_case("term"))
+        {
+            continue;
+        }
+
+        if target.is_empty() {
+            do_not_translate.push(source.to_string());
+            continue;
+        }
+        let language = if language.is_empty() {
+            default_language
+                .clone()
+                .ok_or_else(|| format!("CSV row {}: language is required", index + 1))?
+        } else {
+            glossary_language_key(language)
+        };
+        terms.push((language, GlossaryTerm::new(source, target)));
+    }
+
+    if replace {
+        *config = TranslationGlossaryConfig::default();
+    }
+
+    let summary = GlossaryImportSummary {
+        terms: terms.len(),
+        do_not_translate: do_not_translate.len(),
+    };
+    for (language, term) in terms {
+        let entries = config.glossaries.entry(language).or_default();
+        match entries
+            .iter_mut()
+            .find(|entry| entry.source.trim().eq_ignore_ascii_case(&term.source))
+        {
+            Some(entry) => *entry = term,
+            None => entries.push(term),
+        }
+    }
+    for term in do_not_translate {
+        if !config
+            .do_not_translate
+            .iter()
+            .any(|existing| existing.trim().eq_ignore_ascii_case(&term))
+        {
+            config.do_not_translate.push(term);
+        }
+    }
+
+    Ok(summary)
+}
+
+pub fn validate_translation_glossary_config(
+    config: &TranslationGlossaryConfig,
+) -> Result<(), String> {
+    for (language, terms) in &config.glossaries {
+        if glossary_language_key(language).is_empty() {
+            return Err("Glossary language must not be empty".to_string());
+        }
+        if let Some(index) = terms
+            .iter()
+            .position(|term| term.source.trim().is_empty() || term.target.trim().is_empty())
+        {
+            return Err(format!(
+                "Glossary '{}' term #{}: source and target must not be empty",
+                language,
+                index + 1
+            ));
+        }
+    }
+    if config
+        .do_not_translate
+        .iter()
+        .any(|term| term.trim().is_empty())
+    {
+        return Err("Do-not-translate terms must not be empty".to_string());
+    }
+    Ok(())
+}
+
+fn detect_csv_delimiter(csv: &str) -> char {
+    let first_line = csv.lines().next().unwrap_or_default();
+    if first_line.contains(';') && !first_line.contains(',') {
+        ';'
+    } else {
+        ','
+    }
+}
+
+/// Минимальный RFC 4180: кавычки, `""` внутри кавычек, переводы строк внутри поля
+fn parse_csv(csv: &str, delimiter: char) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = csv.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(ch),
+            }
+            continue;
+        }
+
+        match ch {
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ if ch == delimiter => row.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+
+    if in_quotes {
+        return Err(format!("CSV row {}: unclosed quote", rows.len() + 1));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glossary() -> TranslationGlossary {
+        let mut config = TranslationGlossaryConfig {
+            do_not_translate: vec!["VoicetextAI".to_string(), "Anna".to_string()],
+            ..TranslationGlossaryConfig::default()
+        };
+        config.glossaries.insert(
+            "ru".to_string(),
+            vec![GlossaryTerm::new("pull request", "пулл-реквест")],
+        );
+        TranslationGlossary::for_language(&config, "ru")
+    }
+
+    #[test]
+    fn replaces_untranslated_term_and_restores_keep_spelling() {
+        let check = glossary().enforce(
+            "Anna opened a pull request in VoicetextAI",
+            "Анна открыла Pull Request в Voicetextai",
+        );
+
+        assert_eq!(check.text, "Анна открыла пулл-реквест в VoicetextAI");
+        assert_eq!(check.violations, vec!["Anna".to_string()]);
+    }
+
+    #[test]
+    fn ignores_terms_absent_from_source() {
+        let check = glossary().enforce("hello", "pull request, привет");
+
+        assert_eq!(check.text, "pull request, привет");
+        assert!(check.violations.is_empty());
+    }
+
+    #[test]
+    fn imports_csv_with_header_quotes_and_keep_rows() {
+        let mut config = TranslationGlossaryConfig::default();
+        let summary = import_glossary_csv(
+            &mut config,
+            "source,target,language\n\"pull request\",\"Pull-Request\",DE\nVoicetextAI\r\n\"merge, squash\",\"слияние \"\"squash\"\"\",\n",
+            Some("ru"),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            summary,
+            GlossaryImportSummary {
+                terms: 2,
+                do_not_translate: 1
+            }
+        );
+        assert_eq!(config.terms_for("de")[0].target, "Pull-Request");
+        assert_eq!(config.terms_for("ru")[0].source, "merge, squash");
+        assert_eq!(config.terms_for("ru")[0].target, "слияние \"squash\"");
+        assert_eq!(config.do_not_translate, vec!["VoicetextAI".to_string()]);
+    }
+
+    #[test]
+    fn import_updates_existing_terms_and_requires_language() {
+        let mut config = TranslationGlossaryConfig::default();
+        import_glossary_csv(&mut config, "deploy;Deployment;de", None, false).unwrap();
+        import_glossary_csv(&mut config, "Deploy;Bereitstellung;de", None, false).unwrap();
+        assert_eq!(
+            config.terms_for("de"),
+            &[GlossaryTerm::new("Deploy", "Bereitstellung")]
+        );
+
+        let err = import_glossary_csv(&mut config, "deploy,Deployment", None, true).unwrap_err();
+        assert!(err.contains("row 1"));
+        assert_eq!(
+            config.terms_for("de").len(),
+            1,
+            "failed import keeps config"
+        );
+
+        assert!(import_glossary_csv(&mut config, "\"open", None, false).is_err());
+    }
+
+    #[test]
+    fn validation_rejects_empty_terms() {
+        let mut config = TranslationGlossaryConfig::default();
+        config
+            .glossaries
+            .insert("de".to_string(), vec![GlossaryTerm::new("deploy", " ")]);
+        assert!(validate_translation_glossary_config(&config).is_err());
+
+        config.glossaries.clear();
+        config.do_not_translate.push("VoicetextAI".to_string());
+        assert!(validate_translation_glossary_config(&config).is_ok());
+    }
+}
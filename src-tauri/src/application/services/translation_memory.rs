@@ -0,0 +1,35 @@
+//! Проверка настроек памяти переводов (`translation_memory`) перед сохранением.
+
+use crate::domain::{TranslationMemoryConfig, MAX_TRANSLATION_MEMORY_ENTRIES};
+
+pub fn validate_translation_memory_config(config: &TranslationMemoryConfig) -> Result<(), String> {
+    if config.max_entries == 0 {
+        return Err("translation_memory.max_entries must be greater than 0".to_string());
+    }
+    if config.max_entries > MAX_TRANSLATION_MEMORY_ENTRIES {
+        return Err(format!(
+            "translation_memory.max_entries must be at most {}",
+            MAX_TRANSLATION_MEMORY_ENTRIES
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_max_entries_bounds() {
+        assert!(validate_translation_memory_config(&TranslationMemoryConfig::default()).is_ok());
+
+        let mut config = TranslationMemoryConfig {
+            max_entries: 0,
+            ..TranslationMemoryConfig::default()
+        };
+        assert!(validate_translation_memory_config(&config).is_err());
+
+        config.max_entries = MAX_TRANSLATION_MEMORY_ENTRIES + 1;
+        assert!(validate_translation_memory_config(&config).is_err());
+    }
+}
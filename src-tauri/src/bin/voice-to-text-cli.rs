@@ -0,0 +1,739 @@
+//! Headless CLI: транскрибация файла/микрофона, субтитры системного звука, список устройств
+//! и управление моделями Whisper. Использует те же адаптеры и конфиг (ConfigStore), что и
+//! приложение, но без Tauri UI — для пайплайнов и работы по SSH.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use tokio::sync::{mpsc, watch};
+
+use app_lib::application::{TranscriptPostProcessingChain, TranscriptionService};
+use app_lib::domain::{
+    AppConfig, AudioCapture, AudioCaptureErrorCallback, AudioCaptureHealthProbe,
+    AudioCaptureTarget, AudioChunk, AudioChunkCallback, AudioConfig, AudioResult,
+    PlatformAudioFactory, SttConfig, SttError, SttProviderType, Transcription,
+};
+use app_lib::infrastructure::audio::{
+    is_supported_audio_file, keep_alive_digital_silence, DefaultPlatformAudioFactory,
+    FileAudioCapture, FileCaptureOutcome, SystemAudioCapture,
+};
+use app_lib::infrastructure::models::{
+    delete_model, download_model, get_available_models, get_model_path, is_model_downloaded,
+};
+use app_lib::infrastructure::openai::OpenAITextTranslationClient;
+use app_lib::infrastructure::{AuthStore, ConfigStore, DefaultSttProviderFactory};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "voice-to-text-cli",
+    version,
+    about = "Headless transcription and translation using the Voice to Text configuration"
+)]
+struct Cli {
+    /// Подробные логи в stderr (RUST_LOG тоже учитывается)
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Transcribe an audio file (wav, mp3, flac, ogg) or the microphone (`mic`)
+    Transcribe {
+        /// Path to an audio file, or `mic`
+        source: String,
+
+        /// Input device name for `mic` (defaults to the device selected in the app)
+        #[arg(long)]
+        device: Option<String>,
+
+        /// Playback speed for files: 1.0 is real time
+        #[arg(long, default_value_t = 1.0)]
+        speed: f32,
+
+        #[command(flatten)]
+        stream: StreamArgs,
+    },
+
+    /// Live captions for system audio (loopback)
+    CaptionSystemAudio {
+        #[command(flatten)]
+        stream: StreamArgs,
+    },
+
+    /// List audio input devices
+    ListDevices {
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// Manage local Whisper models
+    Models {
+        #[command(subcommand)]
+        command: ModelsCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ModelsCommand {
+    /// List available models and whether they are downloaded
+    List {
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Download a model (progress goes to stderr)
+    Download { name: String },
+    /// Delete a downloaded model
+    Delete { name: String },
+}
+
+#[derive(Debug, Args)]
+struct StreamArgs {
+    /// Output format for transcripts
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Do not print partial (interim) results
+    #[arg(long)]
+    no_partials: bool,
+
+    /// Override the STT provider from the saved config (e.g. deepgram, assemblyai, backend)
+    #[arg(long)]
+    provider: Option<String>,
+
+    /// Override the recognition language (e.g. en, ru, multi)
+    #[arg(long)]
+    language: Option<String>,
+
+    /// Translate final segments into this language via OpenAI
+    #[arg(long, value_name = "LANGUAGE")]
+    translate_to: Option<String>,
+
+    /// Stop after this many seconds (microphone and system audio run until Ctrl+C otherwise)
+    #[arg(long, value_name = "SECS")]
+    duration: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Строка вывода CLI (в JSON режиме — одна строка JSON на событие)
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum CliEvent<'a> {
+    Partial {
+        text: &'a str,
+        start: f64,
+        duration: f64,
+    },
+    Final {
+        text: &'a str,
+        start: f64,
+        duration: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        confidence: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        provider: Option<&'a str>,
+    },
+    Translation {
+        source: &'a str,
+        text: &'a str,
+        target_language: &'a str,
+    },
+    Error {
+        message: &'a str,
+    },
+}
+
+impl<'a> CliEvent<'a> {
+    fn from_transcription(transcription: &'a Transcription) -> Self {
+        if transcription.is_final {
+            Self::Final {
+                text: transcription.text.trim(),
+                start: transcription.start,
+                duration: transcription.duration,
+                confidence: transcription.confidence,
+                provider: transcription.provider.as_deref(),
+            }
+        } else {
+            Self::Partial {
+                text: transcription.text.trim(),
+                start: transcription.start,
+                duration: transcription.duration,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Printer {
+    format: OutputFormat,
+    partials: bool,
+}
+
+impl Printer {
+    fn render(&self, event: &CliEvent<'_>) -> Option<String> {
+        match event {
+            CliEvent::Partial { text, .. } if !self.partials || text.is_empty() => return None,
+            CliEvent::Final { text: "", .. } => return None,
+            _ => {}
+        }
+
+        match self.format {
+            OutputFormat::Json => serde_json::to_string(event).ok(),
+            OutputFormat::Text => Some(match event {
+                CliEvent::Partial { text, .. } => format!("[partial] {}", text),
+                CliEvent::Final { text, .. } => text.to_string(),
+                CliEvent::Translation {
+                    text,
+                    target_language,
+                    ..
+                } => format!("[{}] {}", target_language, text),
+                CliEvent::Error { message } => format!("[error] {}", message),
+            }),
+        }
+    }
+
+    /// Ошибки в текстовом режиме уходят в stderr, чтобы не смешиваться с транскриптом
+    fn print(&self, event: &CliEvent<'_>) {
+        let Some(line) = self.render(event) else {
+            return;
+        };
+        if self.format == OutputFormat::Text && matches!(event, CliEvent::Error { .. }) {
+            eprintln!("{}", line);
+            return;
+        }
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        // Закрытый pipe (например `| head`) не должен ронять процесс паникой
+        let _ = writeln!(stdout, "{}", line);
+        let _ = stdout.flush();
+    }
+}
+
+/// Loopback в тишине отдаёт цифровые нули — те же правила, что и для записи из файла.
+struct LoopbackSilenceGuard {
+    inner: Box<dyn AudioCapture>,
+}
+
+#[async_trait]
+impl AudioCapture for LoopbackSilenceGuard {
+    async fn initialize(&mut self, config: AudioConfig) -> AudioResult<()> {
+        self.inner.initialize(config).await
+    }
+
+    async fn start_capture(&mut self, on_chunk: AudioChunkCallback) -> AudioResult<()> {
+        self.inner
+            .start_capture(Arc::new(move |mut chunk: AudioChunk| {
+                keep_alive_digital_silence(&mut chunk.data);
+                on_chunk(chunk)
+            }))
+            .await
+    }
+
+    async fn stop_capture(&mut self) -> AudioResult<()> {
+        self.inner.stop_capture().await
+    }
+
+    fn set_terminal_error_callback(&mut self, callback: Option<AudioCaptureErrorCallback>) {
+        self.inner.set_terminal_error_callback(callback);
+    }
+
+    fn health_probe(&self) -> Option<AudioCaptureHealthProbe> {
+        self.inner.health_probe()
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.inner.is_capturing()
+    }
+
+    fn config(&self) -> AudioConfig {
+        self.inner.config()
+    }
+}
+
+fn parse_provider(value: &str) -> Result<SttProviderType> {
+    let normalized = value.trim().to_ascii_lowercase().replace(['-', ' '], "");
+    serde_json::from_value(serde_json::Value::String(normalized))
+        .with_context(|| format!("Unknown STT provider '{}'", value))
+}
+
+/// Тот же источник ключа, что и в приложении: Settings, затем OPENAI_API_KEY
+fn resolve_openai_api_key(config: &AppConfig) -> Option<String> {
+    config
+        .openai_api_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(ToString::to_string)
+        .or_else(|| std::env::var("OPENAI_API_KEY").ok())
+        .filter(|key| !key.trim().is_empty())
+}
+
+async fn load_app_config() -> AppConfig {
+    ConfigStore::load_app_config().await.unwrap_or_else(|e| {
+        log::warn!("Failed to load app config, using defaults: {}", e);
+        AppConfig::default()
+    })
+}
+
+/// STT конфиг с диска + auth token из AuthStore + переопределения из аргументов
+async fn load_stt_config(args: &StreamArgs, app_config: &AppConfig) -> Result<SttConfig> {
+    let mut config = ConfigStore::load_config()
+        .await
+        .context("Failed to load STT config")?;
+
+    if let Some(provider) = args.provider.as_deref() {
+        config.provider = parse_provider(provider)?;
+    }
+    if let Some(language) = args.language.as_deref() {
+        config.language = language.trim().to_string();
+    }
+    // CLI-процесс короткоживущий: держать соединение между сессиями незачем
+    config.keep_connection_alive = false;
+
+    match config.provider {
+        SttProviderType::Backend => {
+            let session = AuthStore::load_or_create()
+                .await
+                .context("Failed to read auth session")?
+                .session;
+            let Some(session) = session else {
+                bail!("Not signed in: sign in via the app or pass --provider with your own key");
+            };
+            config.backend_auth_token = Some(session.access_token);
+        }
+        SttProviderType::OpenAIRealtime => {
+            config.openai_api_key = resolve_openai_api_key(app_config);
+        }
+        _ => {}
+    }
+
+    Ok(config)
+}
+
+/// Последовательный перевод финалов: порядок строк в выводе совпадает с порядком речи
+fn spawn_translator(
+    client: OpenAITextTranslationClient,
+    target_language: String,
+    printer: Printer,
+) -> (mpsc::UnboundedSender<String>, tokio::task::JoinHandle<()>) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let handle = tokio::spawn(async move {
+        while let Some(source) = rx.recv().await {
+            match client.translate_text(&source, &target_language).await {
+                Ok(text) => printer.print(&CliEvent::Translation {
+                    source: &source,
+                    text: text.trim(),
+                    target_language: &target_language,
+                }),
+                Err(e) => printer.print(&CliEvent::Error {
+                    message: &format!("Translation failed: {}", e),
+                }),
+            }
+        }
+    });
+    (tx, handle)
+}
+
+async fn wait_for_file(completion: Option<watch::Receiver<Option<FileCaptureOutcome>>>) {
+    let Some(mut completion) = completion else {
+        return std::future::pending().await;
+    };
+    let _ = completion.wait_for(|outcome| outcome.is_some()).await;
+}
+
+async fn wait_for_duration(duration: Option<f64>) {
+    match duration.filter(|secs| secs.is_finite() && *secs > 0.0) {
+        Some(secs) => tokio::time::sleep(Duration::from_secs_f64(secs)).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Общий прогон: capture → TranscriptionService → stdout (+ перевод финалов)
+async fn run_stream(
+    capture: Box<dyn AudioCapture>,
+    audio_config: AudioConfig,
+    args: &StreamArgs,
+    completion: Option<watch::Receiver<Option<FileCaptureOutcome>>>,
+) -> Result<()> {
+    let app_config = load_app_config().await;
+    let stt_config = load_stt_config(args, &app_config).await?;
+    let printer = Printer {
+        format: args.format,
+        partials: !args.no_partials,
+    };
+
+    let translator = match args.translate_to.as_deref() {
+        Some(target_language) => {
+            let api_key = resolve_openai_api_key(&app_config)
+                .context("--translate-to needs an OpenAI API key (Settings or OPENAI_API_KEY)")?;
+            let client = OpenAITextTranslationClient::new(api_key)?;
+            Some(spawn_translator(
+                client,
+                target_language.to_string(),
+                printer,
+            ))
+        }
+        None => None,
+    };
+    let translate_tx = translator.as_ref().map(|(tx, _)| tx.clone());
+
+    let service = TranscriptionService::new(capture, Arc::new(DefaultSttProviderFactory::new()));
+    service
+        .update_config(stt_config)
+        .await
+        .context("Failed to configure STT")?;
+    service
+        .initialize_audio(audio_config)
+        .await
+        .context("Failed to initialize audio")?;
+
+    let (error_tx, mut error_rx) = mpsc::unbounded_channel::<SttError>();
+    let post_processing = TranscriptPostProcessingChain::from_config(&app_config.post_processing);
+    let on_transcription: Arc<dyn Fn(Transcription) + Send + Sync> =
+        Arc::new(move |transcription: Transcription| {
+            let transcription = if transcription.is_final {
+                post_processing.apply(transcription)
+            } else {
+                transcription
+            };
+            printer.print(&CliEvent::from_transcription(&transcription));
+            if transcription.is_final && !transcription.text.trim().is_empty() {
+                if let Some(tx) = translate_tx.as_ref() {
+                    let _ = tx.send(transcription.text.trim().to_string());
+                }
+            }
+        });
+
+    service
+        .start_recording(
+            on_transcription.clone(),
+            on_transcription,
+            Arc::new(|_| {}),
+            Arc::new(|_| {}),
+            Arc::new(move |error: SttError| {
+                let _ = error_tx.send(error);
+            }),
+            Arc::new(|quality: String, reason: Option<String>| {
+                log::info!("Connection quality: {} ({:?})", quality, reason);
+            }),
+        )
+        .await
+        .context("Failed to start transcription")?;
+
+    let error = tokio::select! {
+        _ = tokio::signal::ctrl_c() => None,
+        _ = wait_for_duration(args.duration) => None,
+        _ = wait_for_file(completion.clone()) => None,
+        error = error_rx.recv() => error,
+    };
+
+    // stop_recording дожидается финалов по хвосту аудио
+    if let Err(e) = service.stop_recording().await {
+        log::warn!("Failed to stop transcription cleanly: {}", e);
+    }
+
+    if let Some((tx, handle)) = translator {
+        drop(tx);
+        let _ = handle.await;
+    }
+
+    if let Some(error) = error {
+        // В текстовом режиме ошибку и так напечатает main
+        if printer.format == OutputFormat::Json {
+            printer.print(&CliEvent::Error {
+                message: &error.to_string(),
+            });
+        }
+        bail!("Transcription failed: {}", error);
+    }
+    if let Some(completion) = completion {
+        if let Some(FileCaptureOutcome::Failed(error)) = completion.borrow().clone() {
+            bail!("Failed to decode audio file: {}", error);
+        }
+    }
+    Ok(())
+}
+
+async fn transcribe(
+    source: String,
+    device: Option<String>,
+    speed: f32,
+    args: StreamArgs,
+) -> Result<()> {
+    if source.eq_ignore_ascii_case("mic") {
+        let device = match device {
+            Some(device) => Some(device),
+            None => load_app_config().await.selected_audio_device,
+        };
+        let capture = SystemAudioCapture::with_device(device)?;
+        return run_stream(Box::new(capture), AudioConfig::default(), &args, None).await;
+    }
+
+    let path = PathBuf::from(&source);
+    if !path.is_file() {
+        bail!("File not found: {}", source);
+    }
+    if !is_supported_audio_file(&path) {
+        bail!(
+            "Unsupported audio file: {} (use wav, mp3, flac or ogg)",
+            source
+        );
+    }
+    let capture = FileAudioCapture::new(path).with_speed(speed);
+    let completion = capture.completion();
+    run_stream(
+        Box::new(capture),
+        AudioConfig::default(),
+        &args,
+        Some(completion),
+    )
+    .await
+}
+
+async fn caption_system_audio(args: StreamArgs) -> Result<()> {
+    let target = AudioCaptureTarget::incoming_subtitles();
+    let capture = DefaultPlatformAudioFactory::new().create_system_loopback_capture(target)?;
+    let audio_config = AudioConfig {
+        sample_rate: target.sample_rate,
+        channels: target.channels,
+        buffer_size: AudioConfig::default().buffer_size,
+    };
+    run_stream(
+        Box::new(LoopbackSilenceGuard { inner: capture }),
+        audio_config,
+        &args,
+        None,
+    )
+    .await
+}
+
+#[derive(Debug, Serialize)]
+struct DeviceLine {
+    name: String,
+    default: bool,
+}
+
+fn list_devices(format: OutputFormat) -> Result<()> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+
+    let host = cpal::default_host();
+    let default_name = host
+        .default_input_device()
+        .and_then(|device| device.name().ok());
+    let devices = host
+        .input_devices()
+        .context("Failed to enumerate input devices")?
+        .filter_map(|device| device.name().ok());
+
+    for name in devices {
+        let line = DeviceLine {
+            default: default_name.as_deref() == Some(name.as_str()),
+            name,
+        };
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string(&line)?),
+            OutputFormat::Text => {
+                println!("{} {}", if line.default { "*" } else { " " }, line.name)
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct ModelLine {
+    name: String,
+    size_human: String,
+    downloaded: bool,
+    description: String,
+}
+
+async fn models(command: ModelsCommand) -> Result<()> {
+    match command {
+        ModelsCommand::List { format } => {
+            for model in get_available_models() {
+                let line = ModelLine {
+                    downloaded: is_model_downloaded(&model.name),
+                    name: model.name,
+                    size_human: model.size_human,
+                    description: model.description,
+                };
+                match format {
+                    OutputFormat::Json => println!("{}", serde_json::to_string(&line)?),
+                    OutputFormat::Text => println!(
+                        "{:<10} {:>8}  {:<10}  {}",
+                        line.name,
+                        line.size_human,
+                        if line.downloaded { "downloaded" } else { "-" },
+                        line.description
+                    ),
+                }
+            }
+        }
+        ModelsCommand::Download { name } => {
+            if is_model_downloaded(&name) {
+                println!("{}", get_model_path(&name)?.display());
+                return Ok(());
+            }
+            let last_percent = std::sync::atomic::AtomicU64::new(u64::MAX);
+            let path = download_model(&name, |downloaded, total| {
+                if total == 0 {
+                    return;
+                }
+                let percent = downloaded * 100 / total;
+                let previous = last_percent.swap(percent, std::sync::atomic::Ordering::Relaxed);
+                if previous != percent {
+                    eprint!("\rDownloading {}: {}%", name, percent);
+                }
+            })
+            .await
+            .with_context(|| format!("Failed to download model '{}'", name))?;
+            eprintln!();
+            println!("{}", path.display());
+        }
+        ModelsCommand::Delete { name } => {
+            delete_model(&name).with_context(|| format!("Failed to delete model '{}'", name))?;
+            eprintln!("Model '{}' deleted", name);
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let default_filter = if cli.verbose { "info" } else { "warn" };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter))
+        .target(env_logger::Target::Stderr)
+        .init();
+
+    let result = match cli.command {
+        Command::Transcribe {
+            source,
+            device,
+            speed,
+            stream,
+        } => transcribe(source, device, speed, stream).await,
+        Command::CaptionSystemAudio { stream } => caption_system_audio(stream).await,
+        Command::ListDevices { format } => list_devices(format),
+        Command::Models { command } => models(command).await,
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_provider_accepts_serde_names_and_dashes() {
+        assert_eq!(
+            parse_provider("deepgram").unwrap(),
+            SttProviderType::Deepgram
+        );
+        assert_eq!(
+            parse_provider("OpenAI-Compatible").unwrap(),
+            SttProviderType::OpenAICompatible
+        );
+        assert!(parse_provider("nope").is_err());
+    }
+
+    #[test]
+    fn test_text_printer_hides_partials_when_disabled() {
+        let printer = Printer {
+            format: OutputFormat::Text,
+            partials: false,
+        };
+        let partial = Transcription::partial("hel".to_string());
+        let final_result = Transcription::final_result(" hello ".to_string());
+
+        assert_eq!(
+            printer.render(&CliEvent::from_transcription(&partial)),
+            None
+        );
+        assert_eq!(
+            printer.render(&CliEvent::from_transcription(&final_result)),
+            Some("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn test_json_printer_emits_tagged_lines() {
+        let printer = Printer {
+            format: OutputFormat::Json,
+            partials: true,
+        };
+        let partial = Transcription::partial("hel".to_string());
+        let line = printer
+            .render(&CliEvent::from_transcription(&partial))
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["type"], "partial");
+        assert_eq!(value["text"], "hel");
+
+        let line = printer
+            .render(&CliEvent::Translation {
+                source: "hello",
+                text: "привет",
+                target_language: "ru",
+            })
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["type"], "translation");
+        assert_eq!(value["target_language"], "ru");
+    }
+
+    #[test]
+    fn test_cli_parses_transcribe_flags() {
+        let cli = Cli::try_parse_from([
+            "voice-to-text-cli",
+            "transcribe",
+            "call.mp3",
+            "--speed",
+            "4",
+            "--format",
+            "json",
+            "--translate-to",
+            "de",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Transcribe {
+                source,
+                speed,
+                stream,
+                ..
+            } => {
+                assert_eq!(source, "call.mp3");
+                assert_eq!(speed, 4.0);
+                assert_eq!(stream.format, OutputFormat::Json);
+                assert_eq!(stream.translate_to.as_deref(), Some("de"));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+/// Верхние границы окна контекста: длинный контекст замедляет каждый субтитр
+pub const MAX_CAPTION_CONTEXT_SEGMENTS: usize = 8;
+pub const MAX_CAPTION_CONTEXT_TOKENS: usize = 2_000;
+
+/// Контекст перевода входящих субтитров: последние сегменты (исходник + перевод)
+/// уходят переводчику вместе с новым сегментом, но заново не переводятся.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptionTranslationContextConfig {
+    pub enabled: bool,
+
+    /// Сколько предыдущих сегментов держать в окне
+    pub max_segments: usize,
+
+    /// Бюджет окна в токенах (оценка по символам); старые сегменты отбрасываются первыми
+    pub max_tokens: usize,
+}
+
+impl Default for CaptionTranslationContextConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_segments: 3,
+            max_tokens: 300,
+        }
+    }
+}
+
+/// Уже показанный сегмент субтитров
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationContextSegment {
+    pub source: String,
+    pub translation: String,
+}
+
+impl TranslationContextSegment {
+    pub fn new(source: impl Into<String>, translation: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            translation: translation.into(),
+        }
+    }
+}
+
+/// Перевод сегмента с учётом контекста
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextualTranslation {
+    pub text: String,
+
+    /// Модель склеила сегмент с предыдущим: `text` — новый перевод последнего сегмента
+    /// контекста вместе с текущим, он заменяет последний показанный субтитр
+    pub revises_previous: bool,
+}
+
+impl ContextualTranslation {
+    pub fn standalone(text: String) -> Self {
+        Self {
+            text,
+            revises_previous: false,
+        }
+    }
+}
+
+/// Грубая оценка токенов без токенизатора: ~4 символа на токен
+pub fn estimate_text_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_config_defaults_are_within_limits() {
+        let config: CaptionTranslationContextConfig = serde_json::from_str("{}").unwrap();
+        assert!(config.enabled);
+        assert!(config.max_segments <= MAX_CAPTION_CONTEXT_SEGMENTS);
+        assert!(config.max_tokens <= MAX_CAPTION_CONTEXT_TOKENS);
+
+        assert_eq!(estimate_text_tokens(""), 0);
+        assert_eq!(estimate_text_tokens("hello"), 2);
+        assert_eq!(estimate_text_tokens("привет"), 2);
+    }
+}
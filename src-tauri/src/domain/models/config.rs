@@ -1,13 +1,28 @@
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
+use super::{
+    CaptionTranslationContextConfig, DictationRewriteConfig, SpokenCommandsConfig,
+    SubtitleCueOptions, TextTranslationConfig, TranscriptPostProcessingConfig,
+    TranslationGlossaryConfig, TranslationMemoryConfig,
+};
+
 /// Active recording mode. Чем-то управляет hotkey: dictation = STT в текст,
-/// live_translation = OpenAI realtime translate в virtual mic + текст в popover.
+/// live_translation = OpenAI realtime translate в virtual mic + текст в popover,
+/// dictate_translate = STT в текст с переводом каждого финала перед вставкой.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum RecordingMode {
     Dictation,
     LiveTranslation,
+    DictateTranslate,
+}
+
+impl RecordingMode {
+    /// Режимы, которые идут через TranscriptionService (захват микрофона, VAD, STT)
+    pub const fn uses_dictation_pipeline(self) -> bool {
+        matches!(self, Self::Dictation | Self::DictateTranslate)
+    }
 }
 
 impl Default for RecordingMode {
@@ -49,6 +64,14 @@ pub enum SttProviderType {
     GoogleCloud,
     /// Azure Speech Services
     Azure,
+    /// Self-hosted OpenAI-compatible `/v1/audio/transcriptions` (faster-whisper, whisper.cpp, vLLM)
+    #[serde(alias = "openai_compatible")]
+    OpenAICompatible,
+    /// OpenAI Realtime API, transcription intent (gpt-4o-transcribe family)
+    #[serde(alias = "openai_realtime")]
+    OpenAIRealtime,
+    /// ElevenLabs realtime STT напрямую, с ключом пользователя
+    ElevenLabs,
     /// Backend API (через наш сервер с лицензией)
     Backend,
 }
@@ -59,6 +82,25 @@ impl Default for SttProviderType {
     }
 }
 
+impl FromStr for SttProviderType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "whisperlocal" | "whisper_local" => Ok(Self::WhisperLocal),
+            "assemblyai" => Ok(Self::AssemblyAI),
+            "deepgram" => Ok(Self::Deepgram),
+            "googlecloud" | "google_cloud" => Ok(Self::GoogleCloud),
+            "azure" => Ok(Self::Azure),
+            "openaicompatible" | "openai_compatible" => Ok(Self::OpenAICompatible),
+            "openairealtime" | "openai_realtime" => Ok(Self::OpenAIRealtime),
+            "elevenlabs" | "eleven_labs" => Ok(Self::ElevenLabs),
+            "backend" => Ok(Self::Backend),
+            other => Err(format!("Unsupported STT provider: {}", other)),
+        }
+    }
+}
+
 /// Streaming STT provider selected behind our Backend API.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -122,6 +164,21 @@ pub struct SttConfig {
     /// Enable profanity filter
     pub filter_profanity: bool,
 
+    /// Дополнительные слова для клиентского фильтра мата (к встроенному списку).
+    /// `слово*` — совпадение по префиксу (удобно для русских словоформ).
+    #[serde(default)]
+    pub profanity_words: Vec<String>,
+
+    /// Диаризация: помечать сегменты спикером (встречи, интервью). Выключено по умолчанию —
+    /// не все провайдеры поддерживают, а у некоторых увеличивает задержку.
+    #[serde(default)]
+    pub enable_diarization: bool,
+
+    /// Сжимать аудио в Opus перед отправкой (~24 кбит/с вместо 256 кбит/с PCM).
+    /// Для медленных и лимитных сетей; если сервер Opus не принимает — остаёмся на PCM.
+    #[serde(default)]
+    pub compress_audio: bool,
+
     /// API key для Deepgram (если пользователь хочет использовать свой ключ)
     /// Если None, используется встроенный ключ из embedded_keys
     pub deepgram_api_key: Option<String>,
@@ -130,6 +187,44 @@ pub struct SttConfig {
     /// Если None, используется встроенный ключ из embedded_keys
     pub assemblyai_api_key: Option<String>,
 
+    /// API key для Google Cloud Speech-to-Text v2 (передаётся как `x-goog-api-key`)
+    #[serde(default)]
+    pub google_cloud_api_key: Option<String>,
+
+    /// Google Cloud project ID, в котором живёт recognizer (`projects/{id}/...`)
+    #[serde(default)]
+    pub google_cloud_project_id: Option<String>,
+
+    /// Регион Google Cloud Speech (например "global", "us-central1", "europe-west4").
+    /// Если None, используется "global".
+    #[serde(default)]
+    pub google_cloud_location: Option<String>,
+
+    /// Subscription key ресурса Azure Speech (заголовок `Ocp-Apim-Subscription-Key`)
+    #[serde(default)]
+    pub azure_speech_key: Option<String>,
+
+    /// Регион ресурса Azure Speech (например "westeurope", "eastus")
+    #[serde(default)]
+    pub azure_speech_region: Option<String>,
+
+    /// Base URL OpenAI-совместимого сервера, включая `/v1` (например "http://localhost:8000/v1")
+    #[serde(default)]
+    pub openai_compatible_base_url: Option<String>,
+
+    /// Необязательный Bearer-ключ для OpenAI-совместимого сервера
+    #[serde(default)]
+    pub openai_compatible_api_key: Option<String>,
+
+    /// OpenAI API ключ для realtime транскрипции. Источник — `AppConfig.openai_api_key`
+    /// (подставляется перед стартом диктовки), поэтому в STT конфиг на диск не пишем.
+    #[serde(default, skip_serializing)]
+    pub openai_api_key: Option<String>,
+
+    /// Пользовательский ключ ElevenLabs для прямого подключения (без нашего Backend)
+    #[serde(default)]
+    pub elevenlabs_api_key: Option<String>,
+
     /// Model name/ID for local providers
     pub model: Option<String>,
 
@@ -160,6 +255,11 @@ pub struct SttConfig {
     /// Например: "Kubernetes, VoicetextAI"
     #[serde(default, alias = "deepgram_keyterms")]
     pub streaming_keyterms: Option<String>,
+
+    /// Резервные провайдеры по порядку: на них переключаемся, если основной недоступен
+    /// (нет сети, DNS, 5xx, кончилась квота). Пусто — failover выключен.
+    #[serde(default)]
+    pub failover_providers: Vec<SttProviderType>,
 }
 
 pub const BACKEND_KEEPALIVE_TTL_SECS: u64 = 59 * 60;
@@ -176,8 +276,20 @@ impl Default for SttConfig {
             auto_detect_language: false,
             enable_punctuation: true,
             filter_profanity: false,
+            profanity_words: Vec::new(),
+            enable_diarization: false,
+            compress_audio: false,
             deepgram_api_key: None,
             assemblyai_api_key: None,
+            google_cloud_api_key: None,
+            google_cloud_project_id: None,
+            google_cloud_location: None,
+            azure_speech_key: None,
+            azure_speech_region: None,
+            openai_compatible_base_url: None,
+            openai_compatible_api_key: None,
+            openai_api_key: None,
+            elevenlabs_api_key: None,
             model: None,
             backend_auth_token: None,
             backend_url: None,
@@ -185,6 +297,7 @@ impl Default for SttConfig {
             keep_connection_alive: false, // Безопасно по умолчанию для всех провайдеров
             keep_alive_ttl_secs: default_keep_alive_ttl_secs(),
             streaming_keyterms: None,
+            failover_providers: Vec::new(),
         }
     }
 }
@@ -206,6 +319,29 @@ impl SttConfig {
         self.model = Some(model.into());
         self
     }
+
+    /// Конфиги цепочки failover: основной провайдер и резервные, без повторов.
+    ///
+    /// В звеньях цепочки `failover_providers` очищен, а keep-alive выключен —
+    /// переключение само решает, какое соединение живо.
+    pub fn failover_chain(&self) -> Vec<SttConfig> {
+        let mut providers = vec![self.provider];
+        for provider in &self.failover_providers {
+            if !providers.contains(provider) {
+                providers.push(*provider);
+            }
+        }
+
+        providers
+            .into_iter()
+            .map(|provider| SttConfig {
+                provider,
+                keep_connection_alive: false,
+                failover_providers: Vec::new(),
+                ..self.clone()
+            })
+            .collect()
+    }
 }
 
 /// Last saved recording window position in physical screen coordinates.
@@ -276,6 +412,10 @@ pub struct AppConfig {
     /// Maximum number of history items
     pub max_history_items: usize,
 
+    /// Правила нарезки cue при экспорте в SRT/WebVTT
+    #[serde(default)]
+    pub subtitle_cues: SubtitleCueOptions,
+
     /// Активный режим записи. dictation = STT в текст, live_translation = OpenAI realtime translate.
     #[serde(default)]
     pub recording_mode: RecordingMode,
@@ -291,6 +431,42 @@ pub struct AppConfig {
     /// Local translated speech volume in the inclusive 0-100 range.
     #[serde(default = "default_incoming_translation_volume")]
     pub incoming_translation_volume: u8,
+
+    /// Локальный HTTP/WebSocket API для сторонних интеграций (Stream Deck, плагины, скрипты)
+    #[serde(default)]
+    pub control_api: ControlApiConfig,
+
+    /// Цепочка пост-обработки финальных транскрипций (замены, слова-паразиты, пунктуация)
+    #[serde(default)]
+    pub post_processing: TranscriptPostProcessingConfig,
+
+    /// Голосовые команды при диктовке ("new line", "запятая", "undo that")
+    #[serde(default)]
+    pub spoken_commands: SpokenCommandsConfig,
+
+    /// LLM-переписывание диктовки после остановки записи ("fix grammar", "formal email")
+    #[serde(default)]
+    pub dictation_rewrite: DictationRewriteConfig,
+
+    /// Язык перевода и буфер обмена для режима dictate_translate
+    #[serde(default)]
+    pub dictate_translate: DictateTranslateConfig,
+
+    /// Провайдер текстового перевода для входящих субтитров и dictate_translate
+    #[serde(default)]
+    pub text_translation: TextTranslationConfig,
+
+    /// Глоссарии и do-not-translate для текстового перевода
+    #[serde(default)]
+    pub translation_glossary: TranslationGlossaryConfig,
+
+    /// Окно предыдущих сегментов, которое уходит переводчику входящих субтитров
+    #[serde(default)]
+    pub caption_translation_context: CaptionTranslationContextConfig,
+
+    /// Персистентная память переводов входящих субтитров
+    #[serde(default)]
+    pub translation_memory: TranslationMemoryConfig,
 }
 
 impl Default for AppConfig {
@@ -313,10 +489,56 @@ impl Default for AppConfig {
             selected_audio_device: None,  // По умолчанию используем системное устройство
             keep_history: true,
             max_history_items: 20,
+            subtitle_cues: SubtitleCueOptions::default(),
             recording_mode: RecordingMode::default(),
             openai_api_key: None,
             incoming_translation_delivery: IncomingTranslationDelivery::default(),
             incoming_translation_volume: default_incoming_translation_volume(),
+            control_api: ControlApiConfig::default(),
+            post_processing: TranscriptPostProcessingConfig::default(),
+            spoken_commands: SpokenCommandsConfig::default(),
+            dictation_rewrite: DictationRewriteConfig::default(),
+            dictate_translate: DictateTranslateConfig::default(),
+            text_translation: TextTranslationConfig::default(),
+            translation_glossary: TranslationGlossaryConfig::default(),
+            caption_translation_context: CaptionTranslationContextConfig::default(),
+            translation_memory: TranslationMemoryConfig::default(),
+        }
+    }
+}
+
+/// Настройки режима dictate_translate
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DictateTranslateConfig {
+    /// Язык перевода ("en", "de"). None — как у live translation: en, а для англоязычной диктовки ru.
+    pub target_language: Option<String>,
+
+    /// После остановки положить в буфер обмена оригинал, а не перевод
+    pub copy_original_to_clipboard: bool,
+}
+
+/// Порт локального control API по умолчанию
+pub const DEFAULT_CONTROL_API_PORT: u16 = 47_823;
+
+/// Настройки локального control API. Выключен по умолчанию; слушает только 127.0.0.1.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlApiConfig {
+    pub enabled: bool,
+
+    pub port: u16,
+
+    /// Bearer-токен; генерируется при первом включении
+    pub token: Option<String>,
+}
+
+impl Default for ControlApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_CONTROL_API_PORT,
+            token: None,
         }
     }
 }
@@ -357,8 +579,20 @@ mod tests {
         assert!(!config.auto_detect_language);
         assert!(config.enable_punctuation);
         assert!(!config.filter_profanity);
+        assert!(config.profanity_words.is_empty());
+        assert!(!config.enable_diarization);
+        assert!(!config.compress_audio);
         assert!(config.deepgram_api_key.is_none());
         assert!(config.assemblyai_api_key.is_none());
+        assert!(config.google_cloud_api_key.is_none());
+        assert!(config.google_cloud_project_id.is_none());
+        assert!(config.google_cloud_location.is_none());
+        assert!(config.azure_speech_key.is_none());
+        assert!(config.azure_speech_region.is_none());
+        assert!(config.openai_compatible_base_url.is_none());
+        assert!(config.openai_compatible_api_key.is_none());
+        assert!(config.openai_api_key.is_none());
+        assert!(config.elevenlabs_api_key.is_none());
         assert!(config.model.is_none());
         assert!(config.backend_auth_token.is_none());
         assert!(config.backend_url.is_none());
@@ -384,6 +618,23 @@ mod tests {
         assert!("assemblyai".parse::<BackendStreamingProvider>().is_err());
     }
 
+    #[test]
+    fn test_stt_provider_parse_matches_config_names() {
+        assert_eq!(
+            "Backend".parse::<SttProviderType>().unwrap(),
+            SttProviderType::Backend
+        );
+        assert_eq!(
+            "openai_realtime".parse::<SttProviderType>().unwrap(),
+            SttProviderType::OpenAIRealtime
+        );
+        assert_eq!(
+            "googlecloud".parse::<SttProviderType>().unwrap(),
+            SttProviderType::GoogleCloud
+        );
+        assert!("mock".parse::<SttProviderType>().is_err());
+    }
+
     #[test]
     fn backend_streaming_provider_idle_keep_alive_capability_is_explicit() {
         assert!(BackendStreamingProvider::Deepgram.supports_reliable_idle_keep_alive());
@@ -508,6 +759,7 @@ mod tests {
         assert_eq!(config.microphone_sensitivity, 100);
         assert!(config.keep_history);
         assert_eq!(config.max_history_items, 20);
+        assert_eq!(config.subtitle_cues, SubtitleCueOptions::default());
         assert_eq!(config.recording_mode, RecordingMode::Dictation);
         assert_eq!(config.openai_api_key, None);
         assert_eq!(
@@ -515,6 +767,23 @@ mod tests {
             IncomingTranslationDelivery::CaptionsOnly
         );
         assert_eq!(config.incoming_translation_volume, 100);
+        assert!(!config.control_api.enabled);
+        assert_eq!(config.control_api.port, DEFAULT_CONTROL_API_PORT);
+        assert!(config.control_api.token.is_none());
+        assert_eq!(
+            config.post_processing,
+            TranscriptPostProcessingConfig::default()
+        );
+        assert!(!config.spoken_commands.enabled);
+        assert!(!config.dictation_rewrite.enabled);
+        assert_eq!(config.dictate_translate, DictateTranslateConfig::default());
+        assert_eq!(config.text_translation, TextTranslationConfig::default());
+        assert!(config.translation_glossary.is_empty());
+        assert!(config.caption_translation_context.enabled);
+        assert_eq!(
+            config.translation_memory,
+            TranslationMemoryConfig::default()
+        );
     }
 
     #[test]
@@ -533,6 +802,15 @@ mod tests {
         assert_eq!(parsed, RecordingMode::Dictation);
         let parsed: RecordingMode = serde_json::from_str("\"live_translation\"").unwrap();
         assert_eq!(parsed, RecordingMode::LiveTranslation);
+        let parsed: RecordingMode = serde_json::from_str("\"dictate_translate\"").unwrap();
+        assert_eq!(parsed, RecordingMode::DictateTranslate);
+    }
+
+    #[test]
+    fn test_recording_mode_dictation_pipeline() {
+        assert!(RecordingMode::Dictation.uses_dictation_pipeline());
+        assert!(RecordingMode::DictateTranslate.uses_dictation_pipeline());
+        assert!(!RecordingMode::LiveTranslation.uses_dictation_pipeline());
     }
 
     #[test]
@@ -589,6 +867,67 @@ mod tests {
         assert_ne!(SttProviderType::Deepgram, SttProviderType::AssemblyAI);
     }
 
+    #[test]
+    fn test_openai_compatible_provider_serialization() {
+        assert_eq!(
+            serde_json::to_string(&SttProviderType::OpenAICompatible).unwrap(),
+            "\"openaicompatible\""
+        );
+        let provider: SttProviderType = serde_json::from_str("\"openai_compatible\"").unwrap();
+        assert_eq!(provider, SttProviderType::OpenAICompatible);
+    }
+
+    #[test]
+    fn test_openai_realtime_key_is_not_persisted_with_stt_config() {
+        let mut config = SttConfig::new(SttProviderType::OpenAIRealtime);
+        config.openai_api_key = Some("sk-test".to_string());
+
+        let value = serde_json::to_value(&config).unwrap();
+
+        assert_eq!(value["provider"], "openairealtime");
+        assert!(value.get("openai_api_key").is_none());
+        let provider: SttProviderType = serde_json::from_str("\"openai_realtime\"").unwrap();
+        assert_eq!(provider, SttProviderType::OpenAIRealtime);
+    }
+
+    #[test]
+    fn test_failover_chain_starts_with_primary_and_skips_duplicates() {
+        let mut config = SttConfig::new(SttProviderType::Backend).with_language("en");
+        config.keep_connection_alive = true;
+        config.failover_providers = vec![
+            SttProviderType::Deepgram,
+            SttProviderType::Backend,
+            SttProviderType::WhisperLocal,
+            SttProviderType::Deepgram,
+        ];
+
+        let chain = config.failover_chain();
+
+        let providers: Vec<SttProviderType> = chain.iter().map(|c| c.provider).collect();
+        assert_eq!(
+            providers,
+            vec![
+                SttProviderType::Backend,
+                SttProviderType::Deepgram,
+                SttProviderType::WhisperLocal
+            ]
+        );
+        assert!(chain.iter().all(|c| c.failover_providers.is_empty()));
+        assert!(chain.iter().all(|c| !c.keep_connection_alive));
+        assert!(chain.iter().all(|c| c.language == "en"));
+    }
+
+    #[test]
+    fn test_stt_config_without_failover_providers_deserializes() {
+        let mut value = serde_json::to_value(SttConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("failover_providers");
+
+        let config: SttConfig = serde_json::from_value(value).unwrap();
+
+        assert!(config.failover_providers.is_empty());
+        assert_eq!(config.failover_chain().len(), 1);
+    }
+
     #[test]
     fn test_stt_config_clone() {
         let config1 = SttConfig::new(SttProviderType::Deepgram).with_language("en");
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use super::{TextTranslationConfig, TextTranslationProviderType};
+
+/// Пресет LLM-переписывания продиктованного текста
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DictationRewritePreset {
+    #[default]
+    FixGrammar,
+    MakeConcise,
+    BulletList,
+    FormalEmail,
+}
+
+impl DictationRewritePreset {
+    /// Инструкция для модели. Язык ответа = язык диктовки, без пояснений.
+    pub fn instructions(self) -> &'static str {
+        match self {
+            Self::FixGrammar => {
+                "Fix grammar, spelling, and punctuation in this dictated text. Keep the wording, meaning, and language. Return only the corrected text."
+            }
+            Self::MakeConcise => {
+                "Rewrite this dictated text to be concise. Remove repetitions and filler, keep every fact, name, and number, and keep the language. Return only the rewritten text."
+            }
+            Self::BulletList => {
+                "Format this dictated text as a bullet list with one idea per \"- \" line. Keep the meaning and language. Return only the list."
+            }
+            Self::FormalEmail => {
+                "Rewrite this dictated text as a polite, formal email body. Keep every fact, name, and number, and keep the language. Do not invent a subject, recipients, or a signature. Return only the email text."
+            }
+        }
+    }
+}
+
+/// Необязательная "чистка" продиктованного текста LLM-моделью после остановки записи.
+///
+/// Использует Responses API: по умолчанию OpenAI с общим `openai_api_key`,
+/// либо локальный OpenAI-совместимый сервер через `endpoint`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DictationRewriteConfig {
+    pub enabled: bool,
+    pub preset: DictationRewritePreset,
+
+    /// Base URL (`http://localhost:11434/v1`) или полный URL `/v1/responses`. None — OpenAI.
+    pub endpoint: Option<String>,
+
+    /// None — модель по умолчанию (как у текстового перевода)
+    pub model: Option<String>,
+
+    /// Ключ для `endpoint`. Для OpenAI по умолчанию берётся `openai_api_key`,
+    /// на свой сервер он не отправляется; локальным серверам ключ обычно не нужен.
+    pub api_key: Option<String>,
+}
+
+impl DictationRewriteConfig {
+    /// Endpoint без пробелов; пустая строка = не задан
+    pub fn custom_endpoint(&self) -> Option<&str> {
+        self.endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|endpoint| !endpoint.is_empty())
+    }
+
+    /// Тот же LLM-клиент и разрешение ключа, что у текстового перевода через OpenAI
+    pub fn llm_config(&self) -> TextTranslationConfig {
+        TextTranslationConfig {
+            provider: TextTranslationProviderType::OpenAI,
+            endpoint: self.endpoint.clone(),
+            model: self.model.clone(),
+            api_key: self.api_key.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_disabled_and_fixes_grammar() {
+        let config = DictationRewriteConfig::default();
+        assert!(!config.enabled);
+        assert_eq!(config.preset, DictationRewritePreset::FixGrammar);
+        assert_eq!(config.custom_endpoint(), None);
+    }
+
+    #[test]
+    fn test_partial_json_uses_defaults() {
+        let config: DictationRewriteConfig = serde_json::from_str(
+            r#"{"enabled":true,"preset":"bullet_list","endpoint":"  http://localhost:11434/v1 "}"#,
+        )
+        .unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.preset, DictationRewritePreset::BulletList);
+        assert_eq!(config.custom_endpoint(), Some("http://localhost:11434/v1"));
+        assert_eq!(config.model, None);
+    }
+
+    #[test]
+    fn test_llm_config_keeps_openai_key_off_custom_endpoint() {
+        let mut config = DictationRewriteConfig::default();
+        assert_eq!(config.llm_config().resolved_api_key("sk-app"), "sk-app");
+
+        config.endpoint = Some("http://localhost:11434/v1".to_string());
+        assert_eq!(config.llm_config().resolved_api_key("sk-app"), "");
+
+        config.api_key = Some("local-key".to_string());
+        assert_eq!(config.llm_config().resolved_api_key("sk-app"), "local-key");
+    }
+}
@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Transcription, Word};
+
+/// Лимит страницы по умолчанию, если фронт не передал `limit`
+pub const DEFAULT_HISTORY_PAGE_SIZE: usize = 50;
+
+/// Верхняя граница страницы, чтобы один запрос не тянул всю историю в WebView
+pub const MAX_HISTORY_PAGE_SIZE: usize = 500;
+
+/// Запись персистентной истории диктовок
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Уникальный id записи (uuid v4)
+    pub id: String,
+
+    /// Финальный текст сегмента
+    pub text: String,
+
+    /// STT провайдер, который распознал сегмент
+    pub provider: String,
+
+    /// Язык распознавания, если известен
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// Длительность сегмента в секундах
+    #[serde(default)]
+    pub duration_secs: f64,
+
+    /// Начало сегмента в секундах от старта стрима (для экспорта субтитров)
+    #[serde(default)]
+    pub start_secs: f64,
+
+    /// Recording session, в которой был получен сегмент
+    #[serde(default)]
+    pub session_id: u64,
+
+    /// Приложение, в которое диктовали (bundle id на macOS), если известно
+    #[serde(default)]
+    pub target_app: Option<String>,
+
+    /// Имя исходного аудиофайла, если сегмент получен транскрибацией файла
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_file: Option<String>,
+
+    /// Unix timestamp (ms), когда провайдер отдал финал
+    pub created_at_ms: i64,
+
+    /// Unix timestamp (ms) начала записи сессии
+    #[serde(default)]
+    pub recording_started_at_ms: Option<i64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+
+    /// Пословные тайминги, если провайдер их отдаёт
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<Word>,
+}
+
+impl HistoryEntry {
+    /// `provider` используется, только если сегмент не помечен failover-цепочкой
+    pub fn from_transcription(transcription: &Transcription, provider: impl Into<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            text: transcription.text.clone(),
+            provider: transcription
+                .provider
+                .clone()
+                .unwrap_or_else(|| provider.into()),
+            language: transcription.language.clone(),
+            duration_secs: transcription.duration,
+            start_secs: transcription.start,
+            session_id: 0,
+            target_app: None,
+            source_file: None,
+            created_at_ms: transcription.timestamp,
+            recording_started_at_ms: None,
+            confidence: transcription.confidence,
+            speaker: transcription.speaker.clone(),
+            words: transcription.words.clone(),
+        }
+    }
+
+    pub fn with_session(mut self, session_id: u64, started_at_ms: Option<i64>) -> Self {
+        self.session_id = session_id;
+        self.recording_started_at_ms = started_at_ms;
+        self
+    }
+
+    pub fn with_target_app(mut self, target_app: Option<String>) -> Self {
+        self.target_app = target_app.filter(|app| !app.is_empty());
+        self
+    }
+
+    pub fn with_source_file(mut self, source_file: impl Into<String>) -> Self {
+        self.source_file = Some(source_file.into());
+        self
+    }
+
+    /// Обратно в `Transcription` (тайминги остаются относительно старта своей сессии)
+    pub fn to_transcription(&self) -> Transcription {
+        let mut transcription = Transcription::final_result(self.text.clone())
+            .with_timing(self.start_secs, self.duration_secs)
+            .with_provider(self.provider.clone())
+            .with_words(self.words.clone());
+        transcription.timestamp = self.created_at_ms;
+        transcription.language = self.language.clone();
+        transcription.confidence = self.confidence;
+        transcription.speaker = self.speaker.clone();
+        transcription
+    }
+
+    /// Абсолютное время начала сегмента (unix ms): старт сессии + смещение в стриме,
+    /// а для записей без старта сессии — момент финала минус длительность
+    pub fn started_at_ms(&self) -> i64 {
+        match self.recording_started_at_ms {
+            Some(session_start) => session_start + (self.start_secs * 1000.0).round() as i64,
+            None => self.created_at_ms - (self.duration_secs * 1000.0).round() as i64,
+        }
+    }
+
+    /// Все термы запроса (уже в lowercase) должны встречаться в тексте или в имени приложения
+    pub fn matches_terms(&self, terms: &[String]) -> bool {
+        if terms.is_empty() {
+            return true;
+        }
+        let text = self.text.to_lowercase();
+        let app = self
+            .target_app
+            .as_deref()
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        let source_file = self
+            .source_file
+            .as_deref()
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        terms.iter().all(|term| {
+            text.contains(term.as_str())
+                || app.contains(term.as_str())
+                || source_file.contains(term.as_str())
+        })
+    }
+}
+
+/// Запрос страницы истории: полнотекстовый поиск + пагинация (новые записи первыми)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default)]
+    pub search: Option<String>,
+
+    #[serde(default)]
+    pub offset: usize,
+
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    /// Термы поиска в lowercase; пустой/пробельный запрос → без фильтра
+    pub fn terms(&self) -> Vec<String> {
+        self.search
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    pub fn effective_limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_HISTORY_PAGE_SIZE)
+            .clamp(1, MAX_HISTORY_PAGE_SIZE)
+    }
+}
+
+/// Диапазон истории для экспорта: явный список id или интервал времени (unix ms, включительно)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRange {
+    #[serde(default)]
+    pub ids: Vec<String>,
+
+    #[serde(default)]
+    pub from_ms: Option<i64>,
+
+    #[serde(default)]
+    pub to_ms: Option<i64>,
+}
+
+impl HistoryRange {
+    pub fn contains(&self, entry: &HistoryEntry) -> bool {
+        if !self.ids.is_empty() && !self.ids.contains(&entry.id) {
+            return false;
+        }
+        !matches!(self.from_ms, Some(from) if entry.created_at_ms < from)
+            && !matches!(self.to_ms, Some(to) if entry.created_at_ms > to)
+    }
+}
+
+/// Страница истории
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPage {
+    pub items: Vec<HistoryEntry>,
+
+    /// Сколько записей подходит под запрос всего (без учёта пагинации)
+    pub total: usize,
+
+    pub offset: usize,
+
+    pub limit: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_from_transcription_copies_segment_fields() {
+        let mut transcription =
+            Transcription::final_result("Hello world".to_string()).with_language("en".to_string());
+        transcription.start = 1.5;
+        transcription.duration = 2.0;
+
+        let entry = HistoryEntry::from_transcription(&transcription, "deepgram")
+            .with_session(7, Some(1_000))
+            .with_target_app(Some("com.apple.TextEdit".to_string()));
+
+        assert_eq!(entry.text, "Hello world");
+        assert_eq!(entry.provider, "deepgram");
+        assert_eq!(entry.language.as_deref(), Some("en"));
+        assert_eq!(entry.start_secs, 1.5);
+        assert_eq!(entry.duration_secs, 2.0);
+        assert_eq!(entry.session_id, 7);
+        assert_eq!(entry.target_app.as_deref(), Some("com.apple.TextEdit"));
+        assert!(!entry.id.is_empty());
+    }
+
+    #[test]
+    fn test_entry_prefers_failover_provider_label() {
+        let transcription =
+            Transcription::final_result("hi".to_string()).with_provider("Deepgram Nova-3");
+        let entry = HistoryEntry::from_transcription(&transcription, "assemblyai");
+        assert_eq!(entry.provider, "Deepgram Nova-3");
+    }
+
+    #[test]
+    fn test_range_filters_by_ids_and_time() {
+        let mut entry = HistoryEntry::from_transcription(
+            &Transcription::final_result("hi".to_string()),
+            "deepgram",
+        );
+        entry.created_at_ms = 5_000;
+
+        assert!(HistoryRange::default().contains(&entry));
+        assert!(HistoryRange {
+            from_ms: Some(5_000),
+            to_ms: Some(5_000),
+            ..Default::default()
+        }
+        .contains(&entry));
+        assert!(!HistoryRange {
+            from_ms: Some(6_000),
+            ..Default::default()
+        }
+        .contains(&entry));
+        assert!(!HistoryRange {
+            ids: vec!["other".to_string()],
+            ..Default::default()
+        }
+        .contains(&entry));
+    }
+
+    #[test]
+    fn test_query_terms_and_limit() {
+        let query = HistoryQuery {
+            search: Some("  Hello   WORLD ".to_string()),
+            offset: 0,
+            limit: Some(10_000),
+        };
+        assert_eq!(query.terms(), vec!["hello", "world"]);
+        assert_eq!(query.effective_limit(), MAX_HISTORY_PAGE_SIZE);
+        assert_eq!(
+            HistoryQuery::default().effective_limit(),
+            DEFAULT_HISTORY_PAGE_SIZE
+        );
+    }
+
+    #[test]
+    fn test_matches_terms_requires_all_terms() {
+        let entry = HistoryEntry::from_transcription(
+            &Transcription::final_result("Купить молоко и хлеб".to_string()),
+            "deepgram",
+        )
+        .with_target_app(Some("com.apple.Notes".to_string()));
+
+        assert!(entry.matches_terms(&["молоко".to_string(), "хлеб".to_string()]));
+        assert!(entry.matches_terms(&["notes".to_string()]));
+        assert!(!entry.matches_terms(&["молоко".to_string(), "сыр".to_string()]));
+    }
+}
@@ -1,12 +1,30 @@
 mod audio_chunk;
 mod audio_gain;
+mod caption_context;
 mod config;
+mod dictation_rewrite;
+mod history;
+mod post_processing;
 mod realtime_translation;
+mod spoken_commands;
+mod text_translation;
+mod transcript_export;
 /// Domain models - value objects and entities
 mod transcription;
+mod translation_glossary;
+mod translation_memory;
 
 pub use audio_chunk::*;
 pub use audio_gain::*;
+pub use caption_context::*;
 pub use config::*;
+pub use dictation_rewrite::*;
+pub use history::*;
+pub use post_processing::*;
 pub use realtime_translation::*;
+pub use spoken_commands::*;
+pub use text_translation::*;
+pub use transcript_export::*;
 pub use transcription::*;
+pub use translation_glossary::*;
+pub use translation_memory::*;
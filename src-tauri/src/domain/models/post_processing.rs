@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+
+/// Шаг цепочки пост-обработки финального текста
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostProcessingStage {
+    Replacements,
+    FillerWords,
+    Normalization,
+    TrailingPunctuation,
+}
+
+impl PostProcessingStage {
+    pub const DEFAULT_ORDER: [PostProcessingStage; 4] = [
+        Self::Replacements,
+        Self::FillerWords,
+        Self::Normalization,
+        Self::TrailingPunctuation,
+    ];
+}
+
+/// Правило замены: литерал или регулярное выражение
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplacementRule {
+    pub pattern: String,
+
+    /// Для regex поддерживаются группы `$1`, `${name}`
+    #[serde(default)]
+    pub replacement: String,
+
+    #[serde(default)]
+    pub regex: bool,
+
+    #[serde(default)]
+    pub case_sensitive: bool,
+
+    /// Только целые слова (для литералов: "ai" не заменится внутри "said")
+    #[serde(default)]
+    pub whole_word: bool,
+}
+
+impl ReplacementRule {
+    pub fn literal(pattern: impl Into<String>, replacement: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            replacement: replacement.into(),
+            regex: false,
+            case_sensitive: false,
+            whole_word: false,
+        }
+    }
+
+    pub fn regex(pattern: impl Into<String>, replacement: impl Into<String>) -> Self {
+        Self {
+            regex: true,
+            ..Self::literal(pattern, replacement)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplacementRulesConfig {
+    pub enabled: bool,
+    pub rules: Vec<ReplacementRule>,
+}
+
+impl Default for ReplacementRulesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FillerWordsConfig {
+    pub enabled: bool,
+
+    /// Слова-паразиты (по одному слову, без учёта регистра)
+    pub words: Vec<String>,
+}
+
+impl Default for FillerWordsConfig {
+    fn default() -> Self {
+        // Выключено по умолчанию: "ну" и "типа" бывают и значимыми словами
+        Self {
+            enabled: false,
+            words: [
+                "um", "uh", "uhm", "umm", "er", "erm", "hmm", "ну", "типа", "э", "эм", "ээ",
+            ]
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NormalizationConfig {
+    /// Схлопывает пробелы и убирает пробелы перед знаками препинания
+    pub enabled: bool,
+
+    /// Заглавная буква в начале текста и после . ! ?
+    pub capitalize_sentences: bool,
+}
+
+impl Default for NormalizationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            capitalize_sentences: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrailingPunctuationPolicy {
+    /// Добавить точку, если фраза не заканчивается знаком препинания
+    Ensure,
+    /// Убрать завершающие . , ; : … (вопрос и восклицание остаются)
+    Remove,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrailingPunctuationConfig {
+    pub enabled: bool,
+    pub policy: TrailingPunctuationPolicy,
+}
+
+impl Default for TrailingPunctuationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            policy: TrailingPunctuationPolicy::Ensure,
+        }
+    }
+}
+
+/// Пост-обработка финалов перед историей, буфером обмена и автовставкой
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptPostProcessingConfig {
+    /// Порядок шагов; шаги, которых нет в списке, не выполняются
+    pub order: Vec<PostProcessingStage>,
+    pub replacements: ReplacementRulesConfig,
+    pub filler_words: FillerWordsConfig,
+    pub normalization: NormalizationConfig,
+    pub trailing_punctuation: TrailingPunctuationConfig,
+}
+
+impl Default for TranscriptPostProcessingConfig {
+    fn default() -> Self {
+        Self {
+            order: PostProcessingStage::DEFAULT_ORDER.to_vec(),
+            replacements: ReplacementRulesConfig::default(),
+            filler_words: FillerWordsConfig::default(),
+            normalization: NormalizationConfig::default(),
+            trailing_punctuation: TrailingPunctuationConfig::default(),
+        }
+    }
+}
+
+impl TranscriptPostProcessingConfig {
+    /// Включённые шаги в заданном порядке, без повторов
+    pub fn enabled_stages(&self) -> Vec<PostProcessingStage> {
+        let mut stages = Vec::new();
+        for stage in &self.order {
+            if stages.contains(stage) || !self.is_stage_enabled(*stage) {
+                continue;
+            }
+            stages.push(*stage);
+        }
+        stages
+    }
+
+    fn is_stage_enabled(&self, stage: PostProcessingStage) -> bool {
+        match stage {
+            PostProcessingStage::Replacements => {
+                self.replacements.enabled && !self.replacements.rules.is_empty()
+            }
+            PostProcessingStage::FillerWords => {
+                self.filler_words.enabled && !self.filler_words.words.is_empty()
+            }
+            PostProcessingStage::Normalization => self.normalization.enabled,
+            PostProcessingStage::TrailingPunctuation => self.trailing_punctuation.enabled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_only_normalizes_whitespace() {
+        let config = TranscriptPostProcessingConfig::default();
+        assert_eq!(
+            config.enabled_stages(),
+            vec![PostProcessingStage::Normalization]
+        );
+        assert!(config.filler_words.words.iter().any(|word| word == "типа"));
+    }
+
+    #[test]
+    fn test_enabled_stages_follow_order_without_duplicates() {
+        let config = TranscriptPostProcessingConfig {
+            order: vec![
+                PostProcessingStage::TrailingPunctuation,
+                PostProcessingStage::FillerWords,
+                PostProcessingStage::TrailingPunctuation,
+            ],
+            filler_words: FillerWordsConfig {
+                enabled: true,
+                ..FillerWordsConfig::default()
+            },
+            trailing_punctuation: TrailingPunctuationConfig {
+                enabled: true,
+                policy: TrailingPunctuationPolicy::Remove,
+            },
+            ..TranscriptPostProcessingConfig::default()
+        };
+        assert_eq!(
+            config.enabled_stages(),
+            vec![
+                PostProcessingStage::TrailingPunctuation,
+                PostProcessingStage::FillerWords
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partial_json_uses_defaults() {
+        let config: TranscriptPostProcessingConfig = serde_json::from_str(
+            r#"{"replacements":{"rules":[{"pattern":"gonna","replacement":"going to"}]}}"#,
+        )
+        .unwrap();
+        assert!(config.replacements.enabled);
+        assert_eq!(
+            config.replacements.rules,
+            vec![ReplacementRule::literal("gonna", "going to")]
+        );
+        assert_eq!(config.order, PostProcessingStage::DEFAULT_ORDER.to_vec());
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Действие голосовой команды во время диктовки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpokenCommandAction {
+    NewLine,
+    NewParagraph,
+    Comma,
+    Period,
+    QuestionMark,
+    ExclamationMark,
+    Colon,
+    Semicolon,
+    /// Удалить последнее продиктованное слово
+    DeleteLastWord,
+    /// Отменить последнюю фразу (utterance)
+    UndoLast,
+}
+
+impl SpokenCommandAction {
+    /// Знак препинания, который команда приклеивает к предыдущему слову
+    pub fn punctuation(self) -> Option<char> {
+        match self {
+            Self::Comma => Some(','),
+            Self::Period => Some('.'),
+            Self::QuestionMark => Some('?'),
+            Self::ExclamationMark => Some('!'),
+            Self::Colon => Some(':'),
+            Self::Semicolon => Some(';'),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpokenCommandPhrase {
+    /// Фраза целиком, без учёта регистра и пунктуации STT ("new line", "новая строка")
+    pub phrase: String,
+    pub action: SpokenCommandAction,
+}
+
+impl SpokenCommandPhrase {
+    pub fn new(phrase: impl Into<String>, action: SpokenCommandAction) -> Self {
+        Self {
+            phrase: phrase.into(),
+            action,
+        }
+    }
+}
+
+/// Словарь команд одного языка
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpokenCommandVocabulary {
+    /// Слово-экран: следующая за ним фраза вставляется буквально ("literal comma" → "comma")
+    pub escape_word: String,
+    pub commands: Vec<SpokenCommandPhrase>,
+}
+
+impl SpokenCommandVocabulary {
+    pub fn english() -> Self {
+        use SpokenCommandAction::*;
+
+        Self {
+            escape_word: "literal".to_string(),
+            commands: vec![
+                SpokenCommandPhrase::new("new line", NewLine),
+                SpokenCommandPhrase::new("newline", NewLine),
+                SpokenCommandPhrase::new("new paragraph", NewParagraph),
+                SpokenCommandPhrase::new("comma", Comma),
+                SpokenCommandPhrase::new("period", Period),
+                SpokenCommandPhrase::new("full stop", Period),
+                SpokenCommandPhrase::new("question mark", QuestionMark),
+                SpokenCommandPhrase::new("exclamation mark", ExclamationMark),
+                SpokenCommandPhrase::new("exclamation point", ExclamationMark),
+                SpokenCommandPhrase::new("colon", Colon),
+                SpokenCommandPhrase::new("semicolon", Semicolon),
+                SpokenCommandPhrase::new("delete last word", DeleteLastWord),
+                SpokenCommandPhrase::new("undo that", UndoLast),
+                SpokenCommandPhrase::new("scratch that", UndoLast),
+            ],
+        }
+    }
+
+    pub fn russian() -> Self {
+        use SpokenCommandAction::*;
+
+        Self {
+            escape_word: "буквально".to_string(),
+            commands: vec![
+                SpokenCommandPhrase::new("новая строка", NewLine),
+                SpokenCommandPhrase::new("с новой строки", NewLine),
+                SpokenCommandPhrase::new("новый абзац", NewParagraph),
+                SpokenCommandPhrase::new("запятая", Comma),
+                SpokenCommandPhrase::new("точка", Period),
+                SpokenCommandPhrase::new("вопросительный знак", QuestionMark),
+                SpokenCommandPhrase::new("восклицательный знак", ExclamationMark),
+                SpokenCommandPhrase::new("двоеточие", Colon),
+                SpokenCommandPhrase::new("точка с запятой", Semicolon),
+                SpokenCommandPhrase::new("удалить последнее слово", DeleteLastWord),
+                SpokenCommandPhrase::new("удали последнее слово", DeleteLastWord),
+                SpokenCommandPhrase::new("отмени это", UndoLast),
+                SpokenCommandPhrase::new("отменить это", UndoLast),
+            ],
+        }
+    }
+}
+
+/// Голосовые команды редактирования при диктовке
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpokenCommandsConfig {
+    /// Выключено по умолчанию: "точка" и "comma" иначе нельзя продиктовать как слова
+    pub enabled: bool,
+
+    /// Словари по основному коду языка ("en", "ru")
+    pub vocabularies: BTreeMap<String, SpokenCommandVocabulary>,
+}
+
+impl Default for SpokenCommandsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            vocabularies: BTreeMap::from([
+                ("en".to_string(), SpokenCommandVocabulary::english()),
+                ("ru".to_string(), SpokenCommandVocabulary::russian()),
+            ]),
+        }
+    }
+}
+
+impl SpokenCommandsConfig {
+    /// Словари для языка сессии. Если язык не задан (автоопределение) или для него
+    /// нет словаря — используются все словари сразу.
+    pub fn vocabularies_for(&self, language: Option<&str>) -> Vec<&SpokenCommandVocabulary> {
+        let primary = language
+            .and_then(|language| language.split(['-', '_']).next())
+            .map(str::to_lowercase);
+
+        match primary
+            .as_deref()
+            .and_then(|code| self.vocabularies.get(code))
+        {
+            Some(vocabulary) => vec![vocabulary],
+            None => self.vocabularies.values().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_disabled_with_en_and_ru() {
+        let config = SpokenCommandsConfig::default();
+        assert!(!config.enabled);
+        assert_eq!(
+            config.vocabularies.keys().collect::<Vec<_>>(),
+            vec!["en", "ru"]
+        );
+    }
+
+    #[test]
+    fn test_vocabularies_for_uses_primary_language_code() {
+        let config = SpokenCommandsConfig::default();
+
+        let ru = config.vocabularies_for(Some("ru-RU"));
+        assert_eq!(ru.len(), 1);
+        assert_eq!(ru[0].escape_word, "буквально");
+
+        assert_eq!(config.vocabularies_for(Some("de")).len(), 2);
+        assert_eq!(config.vocabularies_for(None).len(), 2);
+    }
+
+    #[test]
+    fn test_actions_serialize_as_snake_case() {
+        let json = serde_json::to_string(&SpokenCommandPhrase::new(
+            "scratch that",
+            SpokenCommandAction::UndoLast,
+        ))
+        .unwrap();
+        assert_eq!(json, r#"{"phrase":"scratch that","action":"undo_last"}"#);
+    }
+}
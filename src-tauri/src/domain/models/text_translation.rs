@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+/// Провайдер текстового перевода (субтитры входящего звука, режим dictate_translate)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextTranslationProviderType {
+    /// OpenAI Responses API с общим `openai_api_key`
+    #[default]
+    OpenAI,
+    /// DeepL API v2 (ключи free-тарифа заканчиваются на `:fx`)
+    DeepL,
+    /// Свой или публичный LibreTranslate-совместимый сервер
+    LibreTranslate,
+    /// Любой `/v1/chat/completions` (Ollama, vLLM, LM Studio)
+    #[serde(alias = "openai_compatible")]
+    OpenAICompatible,
+}
+
+impl TextTranslationProviderType {
+    /// Без ключа провайдер не ответит; для своих серверов ключ обычно не нужен
+    pub fn requires_api_key(self) -> bool {
+        matches!(self, Self::OpenAI | Self::DeepL)
+    }
+}
+
+/// Выбор провайдера текстового перевода.
+///
+/// Для OpenAI при пустом `api_key` берётся общий `openai_api_key`;
+/// остальным провайдерам и на свой `endpoint` он не отправляется.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextTranslationConfig {
+    pub provider: TextTranslationProviderType,
+
+    /// Base URL сервера. None — публичный API провайдера (для LibreTranslate и
+    /// OpenAI-compatible — локальный сервер по умолчанию).
+    pub endpoint: Option<String>,
+
+    /// Модель для OpenAI и OpenAI-compatible; DeepL и LibreTranslate её игнорируют
+    pub model: Option<String>,
+
+    pub api_key: Option<String>,
+}
+
+impl TextTranslationConfig {
+    /// Endpoint без пробелов; пустая строка = не задан
+    pub fn custom_endpoint(&self) -> Option<&str> {
+        non_empty(self.endpoint.as_deref())
+    }
+
+    pub fn custom_model(&self) -> Option<&str> {
+        non_empty(self.model.as_deref())
+    }
+
+    /// Ключ для запросов: свой `api_key`, а для OpenAI — общий `openai_api_key` как запасной.
+    /// На сторонний сервер (`endpoint`) ключ OpenAI не уходит.
+    pub fn resolved_api_key(&self, openai_api_key: &str) -> String {
+        non_empty(self.api_key.as_deref())
+            .or_else(|| {
+                (self.provider == TextTranslationProviderType::OpenAI
+                    && self.custom_endpoint().is_none())
+                .then_some(openai_api_key.trim())
+            })
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Свой OpenAI-совместимый сервер обычно работает без ключа
+    pub fn requires_api_key(&self) -> bool {
+        match self.provider {
+            TextTranslationProviderType::OpenAI => self.custom_endpoint().is_none(),
+            provider => provider.requires_api_key(),
+        }
+    }
+
+    /// Кто переводил: перевод другого провайдера, модели или сервера в памяти переводов не годится
+    pub fn translator_id(&self) -> String {
+        let provider = match self.provider {
+            TextTranslationProviderType::OpenAI => "openai",
+            TextTranslationProviderType::DeepL => "deepl",
+            TextTranslationProviderType::LibreTranslate => "libretranslate",
+            TextTranslationProviderType::OpenAICompatible => "openaicompatible",
+        };
+        format!(
+            "{}:{}@{}",
+            provider,
+            self.custom_model().unwrap_or("default"),
+            self.custom_endpoint().unwrap_or("default")
+        )
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TextTranslationError {
+    #[error("Authentication: {0}")]
+    Authentication(String),
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+    #[error("Connection: {0}")]
+    Connection(String),
+    #[error("Protocol: {0}")]
+    Protocol(String),
+}
+
+impl TextTranslationError {
+    pub fn error_type(&self) -> &'static str {
+        match self {
+            Self::Authentication(_) => "authentication",
+            Self::RateLimited(_) => "rate_limited",
+            Self::Connection(_) => "connection",
+            Self::Protocol(_) => "processing",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_names_match_config_values() {
+        let providers: Vec<TextTranslationProviderType> = serde_json::from_str(
+            r#"["openai","deepl","libretranslate","openaicompatible","openai_compatible"]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            providers,
+            vec![
+                TextTranslationProviderType::OpenAI,
+                TextTranslationProviderType::DeepL,
+                TextTranslationProviderType::LibreTranslate,
+                TextTranslationProviderType::OpenAICompatible,
+                TextTranslationProviderType::OpenAICompatible,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_openai_key_is_fallback_only_for_openai() {
+        let mut config = TextTranslationConfig::default();
+        assert_eq!(config.resolved_api_key(" sk-app "), "sk-app");
+
+        config.api_key = Some(" sk-own ".to_string());
+        assert_eq!(config.resolved_api_key("sk-app"), "sk-own");
+
+        config.api_key = None;
+        config.endpoint = Some("https://llm.example.com/v1".to_string());
+        assert_eq!(config.resolved_api_key("sk-app"), "");
+        assert!(!config.requires_api_key());
+
+        config.api_key = Some("sk-own".to_string());
+        assert_eq!(config.resolved_api_key("sk-app"), "sk-own");
+
+        config.endpoint = None;
+        config.provider = TextTranslationProviderType::DeepL;
+        config.api_key = Some("  ".to_string());
+        assert_eq!(config.resolved_api_key("sk-app"), "");
+    }
+
+    #[test]
+    fn test_translator_id_includes_model_and_endpoint() {
+        let mut config = TextTranslationConfig::default();
+        assert_eq!(config.translator_id(), "openai:default@default");
+
+        config.provider = TextTranslationProviderType::OpenAICompatible;
+        config.model = Some(" llama3.1 ".to_string());
+        config.endpoint = Some("http://localhost:11434/v1".to_string());
+        assert_eq!(
+            config.translator_id(),
+            "openaicompatible:llama3.1@http://localhost:11434/v1"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::Transcription;
+
+/// Формат файла экспорта транскрипта
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptExportFormat {
+    Srt,
+    #[serde(alias = "vtt")]
+    WebVtt,
+    #[serde(alias = "text")]
+    Txt,
+    #[serde(alias = "md")]
+    Markdown,
+    Json,
+}
+
+impl TranscriptExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::WebVtt => "vtt",
+            Self::Txt => "txt",
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+
+    /// Формат по расширению файла (без учёта регистра)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "srt" => Some(Self::Srt),
+            "vtt" | "webvtt" => Some(Self::WebVtt),
+            "txt" | "text" => Some(Self::Txt),
+            "md" | "markdown" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Правила нарезки субтитров (SRT/WebVTT) на cue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubtitleCueOptions {
+    /// Максимум символов в одном cue (все строки вместе)
+    pub max_chars_per_cue: usize,
+
+    /// Максимум символов в строке; длинный cue переносится на две строки
+    pub max_chars_per_line: usize,
+
+    /// Максимальная длительность cue в секундах
+    pub max_cue_duration_secs: f64,
+
+    /// Минимальная длительность cue, чтобы короткие фразы успевали прочитать
+    pub min_cue_duration_secs: f64,
+}
+
+impl Default for SubtitleCueOptions {
+    fn default() -> Self {
+        // Типичные ограничения broadcast-субтитров: 2 строки по 42 символа, до 6 секунд.
+        Self {
+            max_chars_per_cue: 84,
+            max_chars_per_line: 42,
+            max_cue_duration_secs: 6.0,
+            min_cue_duration_secs: 1.0,
+        }
+    }
+}
+
+impl SubtitleCueOptions {
+    /// Защита от нулевых/отрицательных значений из конфига или с фронта
+    pub fn sanitized(&self) -> Self {
+        let defaults = Self::default();
+        let max_chars_per_line = if self.max_chars_per_line == 0 {
+            defaults.max_chars_per_line
+        } else {
+            self.max_chars_per_line
+        };
+        let max_chars_per_cue = if self.max_chars_per_cue == 0 {
+            defaults.max_chars_per_cue
+        } else {
+            self.max_chars_per_cue.max(max_chars_per_line)
+        };
+        let max_cue_duration_secs =
+            if self.max_cue_duration_secs.is_finite() && self.max_cue_duration_secs > 0.0 {
+                self.max_cue_duration_secs
+            } else {
+                defaults.max_cue_duration_secs
+            };
+        let min_cue_duration_secs = if self.min_cue_duration_secs.is_finite() {
+            self.min_cue_duration_secs.clamp(0.0, max_cue_duration_secs)
+        } else {
+            defaults.min_cue_duration_secs
+        };
+        Self {
+            max_chars_per_cue,
+            max_chars_per_line,
+            max_cue_duration_secs,
+            min_cue_duration_secs,
+        }
+    }
+}
+
+/// Финальный сегмент для экспорта, опционально с переводом
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub transcription: Transcription,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<String>,
+}
+
+impl TranscriptSegment {
+    pub fn new(transcription: Transcription) -> Self {
+        Self {
+            transcription,
+            translation: None,
+        }
+    }
+
+    pub fn with_translation(mut self, translation: impl Into<String>) -> Self {
+        self.translation = Some(translation.into());
+        self
+    }
+
+    pub fn end(&self) -> f64 {
+        self.transcription.start + self.transcription.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_path_extension() {
+        assert_eq!(
+            TranscriptExportFormat::from_path(Path::new("/tmp/a.SRT")),
+            Some(TranscriptExportFormat::Srt)
+        );
+        assert_eq!(
+            TranscriptExportFormat::from_path(Path::new("notes.md")),
+            Some(TranscriptExportFormat::Markdown)
+        );
+        assert_eq!(TranscriptExportFormat::from_path(Path::new("a.docx")), None);
+        assert_eq!(TranscriptExportFormat::from_path(Path::new("noext")), None);
+    }
+
+    #[test]
+    fn test_format_serde_aliases() {
+        let format: TranscriptExportFormat = serde_json::from_str("\"vtt\"").unwrap();
+        assert_eq!(format, TranscriptExportFormat::WebVtt);
+        assert_eq!(
+            serde_json::to_string(&TranscriptExportFormat::WebVtt).unwrap(),
+            "\"webvtt\""
+        );
+    }
+
+    #[test]
+    fn test_cue_options_sanitized_falls_back_to_defaults() {
+        let options = SubtitleCueOptions {
+            max_chars_per_cue: 0,
+            max_chars_per_line: 0,
+            max_cue_duration_secs: -1.0,
+            min_cue_duration_secs: 10.0,
+        }
+        .sanitized();
+
+        let defaults = SubtitleCueOptions::default();
+        assert_eq!(options.max_chars_per_cue, defaults.max_chars_per_cue);
+        assert_eq!(options.max_chars_per_line, defaults.max_chars_per_line);
+        assert_eq!(
+            options.max_cue_duration_secs,
+            defaults.max_cue_duration_secs
+        );
+        assert_eq!(
+            options.min_cue_duration_secs,
+            defaults.max_cue_duration_secs
+        );
+    }
+}
@@ -1,5 +1,62 @@
 use serde::{Deserialize, Serialize};
 
+/// Слово внутри сегмента с таймингами (секунды от начала стрима, как и `Transcription::start`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Word {
+    /// Слово в "сыром" виде, как его распознал провайдер
+    pub text: String,
+
+    /// Start time of the word in seconds
+    pub start: f64,
+
+    /// End time of the word in seconds
+    pub end: f64,
+
+    /// Confidence score (0.0 to 1.0), if available
+    pub confidence: Option<f32>,
+
+    /// Слово с пунктуацией и регистром (Deepgram `punctuated_word`), если провайдер его отдаёт
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub punctuated: Option<String>,
+
+    /// Метка спикера при диаризации (формат зависит от провайдера: "0", "A", ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+impl Word {
+    pub fn new(text: impl Into<String>, start: f64, end: f64) -> Self {
+        Self {
+            text: text.into(),
+            start,
+            end,
+            confidence: None,
+            punctuated: None,
+            speaker: None,
+        }
+    }
+
+    pub fn with_confidence(mut self, confidence: f32) -> Self {
+        self.confidence = Some(confidence);
+        self
+    }
+
+    pub fn with_punctuated(mut self, punctuated: impl Into<String>) -> Self {
+        self.punctuated = Some(punctuated.into());
+        self
+    }
+
+    pub fn with_speaker(mut self, speaker: impl Into<String>) -> Self {
+        self.speaker = Some(speaker.into());
+        self
+    }
+
+    /// Текст для отображения: с пунктуацией, если она есть
+    pub fn display_text(&self) -> &str {
+        self.punctuated.as_deref().unwrap_or(&self.text)
+    }
+}
+
 /// Represents the result of a speech-to-text transcription
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transcription {
@@ -23,6 +80,18 @@ pub struct Transcription {
 
     /// Duration of the audio segment in seconds (from Deepgram)
     pub duration: f64,
+
+    /// Имя STT провайдера, распознавшего сегмент (проставляет failover-цепочка)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+
+    /// Пословные тайминги и confidence (пусто, если провайдер их не отдаёт)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<Word>,
+
+    /// Спикер сегмента, если включена диаризация
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
 }
 
 impl Transcription {
@@ -35,6 +104,9 @@ impl Transcription {
             timestamp: current_unix_timestamp_ms(),
             start: 0.0,
             duration: 0.0,
+            provider: None,
+            words: Vec::new(),
+            speaker: None,
         }
     }
 
@@ -54,6 +126,41 @@ impl Transcription {
         self
     }
 
+    pub fn with_provider(mut self, provider: impl Into<String>) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    pub fn with_words(mut self, words: Vec<Word>) -> Self {
+        self.words = words;
+        self
+    }
+
+    pub fn with_speaker(mut self, speaker: impl Into<String>) -> Self {
+        self.speaker = Some(speaker.into());
+        self
+    }
+
+    /// Спикер сегмента по словам: тот, кто произнёс больше всего слов
+    /// (при равенстве — тот, кто заговорил первым).
+    pub fn dominant_speaker(words: &[Word]) -> Option<String> {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for speaker in words.iter().filter_map(|word| word.speaker.as_deref()) {
+            match counts.iter_mut().find(|(known, _)| *known == speaker) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((speaker, 1)),
+            }
+        }
+
+        let mut best: Option<(&str, usize)> = None;
+        for (speaker, count) in counts {
+            if best.map_or(true, |(_, best_count)| count > best_count) {
+                best = Some((speaker, count));
+            }
+        }
+        best.map(|(speaker, _)| speaker.to_string())
+    }
+
     /// Creates a partial transcription result
     pub fn partial(text: String) -> Self {
         Self::new(text, false)
@@ -146,6 +253,38 @@ mod tests {
         assert_eq!(t1.is_final, t2.is_final);
     }
 
+    #[test]
+    fn test_transcription_words_are_omitted_when_empty() {
+        let t = Transcription::final_result("hi".to_string());
+        let json = serde_json::to_value(&t).unwrap();
+        assert!(json.get("words").is_none());
+
+        let t = t.with_words(vec![Word::new("hi", 0.1, 0.4)
+            .with_confidence(0.9)
+            .with_punctuated("Hi.")]);
+        let json = serde_json::to_value(&t).unwrap();
+        assert_eq!(json["words"][0]["punctuated"], "Hi.");
+        assert_eq!(t.words[0].display_text(), "Hi.");
+    }
+
+    #[test]
+    fn test_dominant_speaker_prefers_most_words_then_first() {
+        let word = |speaker: &str| Word::new("w", 0.0, 0.1).with_speaker(speaker);
+
+        assert_eq!(
+            Transcription::dominant_speaker(&[word("1"), word("0"), word("0")]),
+            Some("0".to_string())
+        );
+        assert_eq!(
+            Transcription::dominant_speaker(&[word("1"), word("0")]),
+            Some("1".to_string())
+        );
+        assert_eq!(
+            Transcription::dominant_speaker(&[Word::new("w", 0.0, 0.1)]),
+            None
+        );
+    }
+
     #[test]
     fn test_recording_status_default() {
         assert_eq!(RecordingStatus::default(), RecordingStatus::Idle);
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Термин глоссария: `source` в переводе всегда передаётся как `target`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlossaryTerm {
+    pub source: String,
+    pub target: String,
+}
+
+impl GlossaryTerm {
+    pub fn new(source: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            target: target.into(),
+        }
+    }
+}
+
+/// Глоссарии по языкам перевода и список терминов, которые не переводятся.
+///
+/// Используются в промпте LLM-переводчиков и как пост-проверка переведённых субтитров.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranslationGlossaryConfig {
+    /// Целевой язык ("de", "pt-BR") → термины. Для "pt-BR" без своего глоссария берётся "pt".
+    pub glossaries: BTreeMap<String, Vec<GlossaryTerm>>,
+
+    /// Имена, продукты и жаргон, которые остаются как есть в любом языке
+    pub do_not_translate: Vec<String>,
+}
+
+impl TranslationGlossaryConfig {
+    pub fn is_empty(&self) -> bool {
+        self.do_not_translate.is_empty() && self.glossaries.values().all(Vec::is_empty)
+    }
+
+    /// Термины для языка перевода: точное совпадение кода без учёта регистра, затем базовый код
+    pub fn terms_for(&self, target_language: &str) -> &[GlossaryTerm] {
+        let language = glossary_language_key(target_language);
+        let base = language.split('-').next().unwrap_or_default();
+        self.glossaries
+            .iter()
+            .find(|(key, _)| glossary_language_key(key) == language)
+            .or_else(|| {
+                self.glossaries
+                    .iter()
+                    .find(|(key, _)| glossary_language_key(key) == base)
+            })
+            .map(|(_, terms)| terms.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Дополнение к инструкции LLM-переводчика; None, если для языка нечего требовать
+    pub fn prompt_hint(&self, target_language: &str) -> Option<String> {
+        let terms: Vec<String> = self
+            .terms_for(target_language)
+            .iter()
+            .filter(|term| !term.source.trim().is_empty() && !term.target.trim().is_empty())
+            .map(|term| format!("\"{}\" → \"{}\"", term.source.trim(), term.target.trim()))
+            .collect();
+        let keep: Vec<String> = self
+            .do_not_translate
+            .iter()
+            .map(|term| term.trim())
+            .filter(|term| !term.is_empty())
+            .map(|term| format!("\"{}\"", term))
+            .collect();
+
+        let mut parts = Vec::new();
+        if !terms.is_empty() {
+            parts.push(format!(
+                "Always translate these terms exactly as given: {}.",
+                terms.join("; ")
+            ));
+        }
+        if !keep.is_empty() {
+            parts.push(format!(
+                "Keep these terms unchanged, with the same spelling: {}.",
+                keep.join(", ")
+            ));
+        }
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+/// Ключ языка глоссария: без пробелов, в нижнем регистре, `_` как `-`
+pub fn glossary_language_key(language: &str) -> String {
+    language.trim().replace('_', "-").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TranslationGlossaryConfig {
+        let mut glossaries = BTreeMap::new();
+        glossaries.insert(
+            "de".to_string(),
+            vec![GlossaryTerm::new("pull request", "Pull-Request")],
+        );
+        glossaries.insert(
+            "pt-BR".to_string(),
+            vec![GlossaryTerm::new("deploy", "implantação")],
+        );
+        TranslationGlossaryConfig {
+            glossaries,
+            do_not_translate: vec!["VoicetextAI".to_string(), " ".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_terms_for_matches_case_insensitive_and_base_language() {
+        let config = config();
+        assert_eq!(config.terms_for("DE")[0].target, "Pull-Request");
+        assert_eq!(config.terms_for("de-AT")[0].target, "Pull-Request");
+        assert_eq!(config.terms_for("pt_br")[0].target, "implantação");
+        assert!(config.terms_for("pt").is_empty());
+        assert!(config.terms_for("ru").is_empty());
+    }
+
+    #[test]
+    fn test_prompt_hint_lists_terms_and_keep_list() {
+        let config = config();
+        assert_eq!(
+            config.prompt_hint("de").as_deref(),
+            Some(
+                "Always translate these terms exactly as given: \"pull request\" → \"Pull-Request\". Keep these terms unchanged, with the same spelling: \"VoicetextAI\"."
+            )
+        );
+        assert_eq!(TranslationGlossaryConfig::default().prompt_hint("de"), None);
+    }
+}
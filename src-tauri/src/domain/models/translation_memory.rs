@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+/// Верхняя граница памяти переводов: весь файл держится в памяти и переписывается целиком
+pub const MAX_TRANSLATION_MEMORY_ENTRIES: usize = 50_000;
+
+/// Память переводов входящих субтитров: повторяющиеся фразы не уходят в платный API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranslationMemoryConfig {
+    pub enabled: bool,
+
+    /// Сколько переводов хранить; при переполнении вытесняются давно не использованные
+    pub max_entries: usize,
+}
+
+impl Default for TranslationMemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 5_000,
+        }
+    }
+}
+
+/// Ключ точного совпадения: нормализованный исходник, языки и переводчик
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TranslationMemoryKey {
+    pub source_text: String,
+    pub source_language: String,
+    pub target_language: String,
+    /// Провайдер, модель и endpoint (`TextTranslationConfig::translator_id`)
+    pub translator: String,
+}
+
+impl TranslationMemoryKey {
+    /// None для пустого после нормализации текста
+    pub fn new(
+        source_text: &str,
+        source_language: &str,
+        target_language: &str,
+        translator: &str,
+    ) -> Option<Self> {
+        let source_text = normalize_translation_memory_text(source_text);
+        if source_text.is_empty() {
+            return None;
+        }
+        Some(Self {
+            source_text,
+            source_language: normalize_language(source_language),
+            target_language: normalize_language(target_language),
+            translator: translator.trim().to_string(),
+        })
+    }
+}
+
+/// "Let's  move on." и "let's move on" — одна фраза: регистр, пробелы и
+/// финальная точка STT от раза к разу отличаются. `?` и `!` сохраняем —
+/// вопрос и утверждение переводятся по-разному.
+pub fn normalize_translation_memory_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(|c: char| c == '.' || c == '…' || c.is_whitespace())
+        .to_lowercase()
+}
+
+fn normalize_language(language: &str) -> String {
+    let language = language.trim().replace('_', "-").to_lowercase();
+    if language.is_empty() || language == "multi" {
+        "auto".to_string()
+    } else {
+        language
+    }
+}
+
+/// Статистика памяти переводов с момента последней очистки
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct TranslationMemoryStats {
+    pub entries: usize,
+    pub capacity: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// Доля попаданий от 0.0 до 1.0; 0.0, пока запросов не было
+    pub hit_rate: f64,
+}
+
+impl TranslationMemoryStats {
+    pub fn new(entries: usize, capacity: usize, hits: u64, misses: u64) -> Self {
+        let lookups = hits + misses;
+        Self {
+            entries,
+            capacity,
+            hits,
+            misses,
+            hit_rate: if lookups == 0 {
+                0.0
+            } else {
+                hits as f64 / lookups as f64
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_normalizes_text_and_languages() {
+        let key = TranslationMemoryKey::new("  Let's   MOVE on.  ", "multi", "pt_BR", " openai ")
+            .unwrap();
+        assert_eq!(key.source_text, "let's move on");
+        assert_eq!(key.source_language, "auto");
+        assert_eq!(key.target_language, "pt-br");
+        assert_eq!(key.translator, "openai");
+
+        assert_eq!(
+            Some(key.clone()),
+            TranslationMemoryKey::new("let's move on …", "", "PT-br", "openai")
+        );
+        assert_ne!(
+            Some(key),
+            TranslationMemoryKey::new("let's move on?", "", "PT-br", "openai")
+        );
+        assert!(TranslationMemoryKey::new(" ... ", "en", "ru", "openai").is_none());
+    }
+
+    #[test]
+    fn test_stats_hit_rate() {
+        assert_eq!(TranslationMemoryStats::new(0, 10, 0, 0).hit_rate, 0.0);
+        assert_eq!(TranslationMemoryStats::new(3, 10, 3, 1).hit_rate, 0.75);
+    }
+}
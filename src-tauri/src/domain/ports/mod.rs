@@ -6,7 +6,10 @@ mod spoken_translation_capability;
 /// These abstractions allow the domain layer to remain independent of infrastructure
 mod stt_provider;
 mod system_audio_capture_factory;
+mod text_translator;
+mod transcript_post_processor;
 mod translation_audio_output;
+mod translation_memory;
 
 pub use audio_capture::*;
 pub use local_playback_output_factory::*;
@@ -14,4 +17,7 @@ pub use realtime_translation::*;
 pub use spoken_translation_capability::*;
 pub use stt_provider::*;
 pub use system_audio_capture_factory::*;
+pub use text_translator::*;
+pub use transcript_post_processor::*;
 pub use translation_audio_output::*;
+pub use translation_memory::*;
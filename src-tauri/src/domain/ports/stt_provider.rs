@@ -180,6 +180,18 @@ pub trait SttProvider: Send + Sync {
         false
     }
 
+    /// Check if provider applies `enable_punctuation` server-side
+    /// (otherwise punctuation is stripped client-side when disabled)
+    fn supports_punctuation_control(&self) -> bool {
+        false
+    }
+
+    /// Check if provider masks profanity server-side when `filter_profanity` is set
+    /// (otherwise the client-side profanity filter is applied)
+    fn supports_profanity_filter(&self) -> bool {
+        false
+    }
+
     /// Check if connection is currently alive (paused but not closed)
     fn is_connection_alive(&self) -> bool {
         false
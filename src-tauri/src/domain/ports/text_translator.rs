@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::domain::{
+    ContextualTranslation, TextTranslationConfig, TextTranslationError, TranslationContextSegment,
+    TranslationGlossaryConfig,
+};
+
+/// Перевод готового текста (финалы STT) на целевой язык.
+///
+/// `target_language` — код языка из настроек ("ru", "en", "pt-BR"); адаптер сам приводит
+/// его к формату своего API.
+#[async_trait]
+pub trait TextTranslator: Send + Sync {
+    async fn translate_text(
+        &self,
+        text: &str,
+        target_language: &str,
+    ) -> Result<String, TextTranslationError>;
+
+    /// Перевод сегмента субтитров с предыдущими сегментами как контекстом (от старых к новым).
+    ///
+    /// По умолчанию контекст игнорируется: DeepL и LibreTranslate не умеют его принимать.
+    async fn translate_with_context(
+        &self,
+        text: &str,
+        target_language: &str,
+        _context: &[TranslationContextSegment],
+    ) -> Result<ContextualTranslation, TextTranslationError> {
+        self.translate_text(text, target_language)
+            .await
+            .map(ContextualTranslation::standalone)
+    }
+}
+
+/// Один запрос к LLM: `instructions` + текст, в ответ — только текст модели.
+///
+/// Общий клиент OpenAI/OpenAI-compatible для перевода и переписывания диктовки.
+#[async_trait]
+pub trait LlmTextClient: Send + Sync {
+    async fn complete(
+        &self,
+        instructions: &str,
+        input: &str,
+    ) -> Result<String, TextTranslationError>;
+}
+
+pub trait TextTranslatorFactory: Send + Sync {
+    /// `api_key` уже разрешён вызывающим (см. `TextTranslationConfig::resolved_api_key`).
+    /// `glossary` адаптер может передать модели; соблюдение гарантирует не он, а пост-проверка.
+    fn create(
+        &self,
+        config: &TextTranslationConfig,
+        api_key: String,
+        glossary: &TranslationGlossaryConfig,
+    ) -> Result<Arc<dyn TextTranslator>, TextTranslationError>;
+}
@@ -0,0 +1,10 @@
+/// Шаг пост-обработки финального текста транскрипции.
+///
+/// Реализации должны быть чистыми функциями текста: без IO и без состояния между вызовами,
+/// чтобы их можно было свободно переставлять в цепочке и тестировать по отдельности.
+pub trait TranscriptPostProcessor: Send + Sync {
+    /// Короткое имя шага для логов
+    fn name(&self) -> &'static str;
+
+    fn process(&self, text: &str) -> String;
+}
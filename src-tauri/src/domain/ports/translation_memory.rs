@@ -0,0 +1,23 @@
+use std::fmt;
+
+use async_trait::async_trait;
+
+use crate::domain::TranslationMemoryKey;
+
+/// Персистентная память переводов (точные совпадения между сессиями)
+#[async_trait]
+pub trait TranslationMemory: Send + Sync {
+    /// Готовый перевод; каждый вызов учитывается в статистике попаданий
+    async fn lookup(&self, key: &TranslationMemoryKey) -> Option<String>;
+
+    async fn remember(&self, key: TranslationMemoryKey, translation: String);
+
+    /// Сохраняет накопленные изменения (конец сессии субтитров)
+    async fn flush(&self);
+}
+
+impl fmt::Debug for dyn TranslationMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TranslationMemory")
+    }
+}
@@ -0,0 +1,658 @@
+use async_trait::async_trait;
+use rubato::{Resampler, SincFixedIn};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tokio::sync::watch;
+
+use super::SystemAudioCapture;
+use crate::domain::{
+    AudioCapture, AudioCaptureErrorCallback, AudioChunk, AudioChunkCallback, AudioConfig,
+    AudioError, AudioResult,
+};
+
+/// Расширения, которые умеет декодировать symphonia с включёнными фичами
+pub const SUPPORTED_AUDIO_FILE_EXTENSIONS: &[&str] = &["wav", "mp3", "flac", "ogg", "oga"];
+
+/// Размер чанка, как у живого захвата (~100ms)
+const FILE_CHUNK_DURATION_MS: u64 = 100;
+
+/// Потолок ускорения: TranscriptionService дропает чанки при переполнении очереди,
+/// поэтому "сколько влезет" для стриминговых провайдеров опасно
+pub const MAX_FILE_PLAYBACK_SPEED: f32 = 8.0;
+
+/// Чем закончилось проигрывание файла
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileCaptureOutcome {
+    /// Файл отдан целиком
+    Finished { duration_secs: f64 },
+    /// Остановлен через stop_capture до конца файла
+    Stopped,
+    /// Ошибка декодирования
+    Failed(String),
+}
+
+/// Проверка расширения до открытия файла (для понятной ошибки в UI)
+pub fn is_supported_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .is_some_and(|ext| SUPPORTED_AUDIO_FILE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Моно-ресемплер поверх rubato с накоплением входа до фиксированного размера блока
+struct MonoResampler {
+    /// None → частоты совпадают, ресемплинг не нужен
+    inner: Option<SincFixedIn<f32>>,
+    pending: Vec<f32>,
+    ratio: f64,
+    consumed: u64,
+    produced: u64,
+}
+
+impl MonoResampler {
+    fn new(from_sample_rate: u32, to_sample_rate: u32) -> AudioResult<Self> {
+        let inner = if from_sample_rate == to_sample_rate {
+            None
+        } else {
+            Some(SystemAudioCapture::create_resampler(
+                from_sample_rate,
+                to_sample_rate,
+                1,
+            )?)
+        };
+        Ok(Self {
+            inner,
+            pending: Vec::new(),
+            ratio: to_sample_rate as f64 / from_sample_rate as f64,
+            consumed: 0,
+            produced: 0,
+        })
+    }
+
+    fn push(&mut self, samples: &[f32]) -> AudioResult<Vec<f32>> {
+        let Some(resampler) = self.inner.as_mut() else {
+            return Ok(samples.to_vec());
+        };
+        self.pending.extend_from_slice(samples);
+
+        let mut output = Vec::new();
+        loop {
+            let needed = resampler.input_frames_next();
+            if self.pending.len() < needed {
+                break;
+            }
+            let block: Vec<f32> = self.pending.drain(..needed).collect();
+            let resampled = resampler
+                .process(&[block], None)
+                .map_err(|e| AudioError::Internal(format!("Resampling failed: {}", e)))?;
+            self.consumed += needed as u64;
+            output.extend_from_slice(&resampled[0]);
+        }
+        self.produced += output.len() as u64;
+        Ok(output)
+    }
+
+    /// Дожимает хвост и обрезает выход до ожидаемой длины (без "звенящего" паддинга)
+    fn finish(&mut self) -> AudioResult<Vec<f32>> {
+        let Some(resampler) = self.inner.as_mut() else {
+            return Ok(Vec::new());
+        };
+        let rest = std::mem::take(&mut self.pending);
+        self.consumed += rest.len() as u64;
+
+        let mut output = resampler
+            .process_partial(Some(&[rest]), None)
+            .map_err(|e| AudioError::Internal(format!("Resampling failed: {}", e)))?
+            .swap_remove(0);
+        let flushed = resampler
+            .process_partial::<Vec<f32>>(None, None)
+            .map_err(|e| AudioError::Internal(format!("Resampling failed: {}", e)))?;
+        output.extend_from_slice(&flushed[0]);
+
+        let expected_total = (self.consumed as f64 * self.ratio).round() as u64;
+        let remaining = expected_total.saturating_sub(self.produced) as usize;
+        output.truncate(remaining);
+        self.produced += output.len() as u64;
+        Ok(output)
+    }
+}
+
+/// Потоковый декодер аудиофайла в PCM 16-bit mono целевой частоты
+pub struct FileAudioDecoder {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    target_sample_rate: u32,
+    resampler: Option<MonoResampler>,
+    duration_secs: Option<f64>,
+    finished: bool,
+}
+
+impl FileAudioDecoder {
+    pub fn open(path: &Path, target_sample_rate: u32) -> AudioResult<Self> {
+        let file = std::fs::File::open(path).map_err(|e| {
+            AudioError::DeviceNotFound(format!("Cannot open audio file {:?}: {}", path, e))
+        })?;
+        let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+        let mut hint = Hint::new();
+        if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+            hint.with_extension(extension);
+        }
+
+        let probed = symphonia::default::get_probe()
+            .format(
+                &hint,
+                stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .map_err(|e| {
+                AudioError::Configuration(format!("Unsupported audio file {:?}: {}", path, e))
+            })?;
+        let format = probed.format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or_else(|| {
+                AudioError::Configuration(format!("No audio track in file {:?}", path))
+            })?;
+        let track_id = track.id;
+        let duration_secs = match (track.codec_params.n_frames, track.codec_params.sample_rate) {
+            (Some(frames), Some(rate)) if rate > 0 => Some(frames as f64 / rate as f64),
+            _ => None,
+        };
+
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .map_err(|e| {
+                AudioError::Configuration(format!("Unsupported codec in {:?}: {}", path, e))
+            })?;
+
+        Ok(Self {
+            format,
+            decoder,
+            track_id,
+            target_sample_rate,
+            resampler: None,
+            duration_secs,
+            finished: false,
+        })
+    }
+
+    /// Длительность по метаданным контейнера, если она известна заранее
+    pub fn duration_secs(&self) -> Option<f64> {
+        self.duration_secs
+    }
+
+    /// Следующая порция PCM; `None` — файл закончился
+    pub fn next_samples(&mut self) -> AudioResult<Option<Vec<i16>>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(e))
+                    if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    self.finished = true;
+                    let tail = match self.resampler.as_mut() {
+                        Some(resampler) => resampler.finish()?,
+                        None => Vec::new(),
+                    };
+                    return Ok((!tail.is_empty()).then(|| SystemAudioCapture::f32_to_i16(&tail)));
+                }
+                Err(e) => {
+                    return Err(AudioError::Capture(format!(
+                        "Failed to read audio packet: {}",
+                        e
+                    )))
+                }
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // Битый фрейм (типично для MP3 с мусором в начале) пропускаем, как плееры
+                Err(SymphoniaError::DecodeError(e)) => {
+                    log::warn!("FileAudioDecoder: skipping undecodable packet: {}", e);
+                    continue;
+                }
+                Err(e) => {
+                    return Err(AudioError::Capture(format!(
+                        "Failed to decode audio: {}",
+                        e
+                    )))
+                }
+            };
+
+            let spec = *decoded.spec();
+            let channels = spec.channels.count().max(1);
+            let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            buffer.copy_interleaved_ref(decoded);
+
+            let mono: Vec<f32> = buffer
+                .samples()
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+                .collect();
+            if mono.is_empty() {
+                continue;
+            }
+
+            if self.resampler.is_none() {
+                self.resampler = Some(MonoResampler::new(spec.rate, self.target_sample_rate)?);
+            }
+            let resampled = self
+                .resampler
+                .as_mut()
+                .map(|resampler| resampler.push(&mono))
+                .transpose()?
+                .unwrap_or_default();
+            if !resampled.is_empty() {
+                return Ok(Some(SystemAudioCapture::f32_to_i16(&resampled)));
+            }
+        }
+    }
+
+    /// Декодирует файл целиком (для коротких файлов и тестов)
+    pub fn decode_all(path: &Path, target_sample_rate: u32) -> AudioResult<Vec<i16>> {
+        let mut decoder = Self::open(path, target_sample_rate)?;
+        let mut samples = Vec::new();
+        while let Some(chunk) = decoder.next_samples()? {
+            samples.extend_from_slice(&chunk);
+        }
+        Ok(samples)
+    }
+}
+
+/// `AudioCapture` поверх аудиофайла: декодирует WAV/MP3/FLAC/OGG, приводит к формату
+/// `AudioConfig` (16 kHz mono) и отдаёт чанки в темпе реального времени или быстрее.
+///
+/// Позиция в файле переживает stop/start: рестарт захвата (например, stall-recovery в
+/// TranscriptionService) продолжает с того же места, а не проигрывает файл заново.
+pub struct FileAudioCapture {
+    path: PathBuf,
+    config: AudioConfig,
+    speed: f32,
+    is_capturing: Arc<AtomicBool>,
+    stop_requested: Arc<AtomicBool>,
+    emitted_samples: Arc<AtomicU64>,
+    worker: Option<std::thread::JoinHandle<()>>,
+    terminal_error_callback: Option<AudioCaptureErrorCallback>,
+    completion_tx: Arc<watch::Sender<Option<FileCaptureOutcome>>>,
+}
+
+impl FileAudioCapture {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let (completion_tx, _) = watch::channel(None);
+        Self {
+            path: path.into(),
+            config: AudioConfig::default(),
+            speed: 1.0,
+            is_capturing: Arc::new(AtomicBool::new(false)),
+            stop_requested: Arc::new(AtomicBool::new(false)),
+            emitted_samples: Arc::new(AtomicU64::new(0)),
+            worker: None,
+            terminal_error_callback: None,
+            completion_tx: Arc::new(completion_tx),
+        }
+    }
+
+    /// Скорость подачи: 1.0 — реальное время, 2.0 — вдвое быстрее (не больше `MAX_FILE_PLAYBACK_SPEED`)
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = if speed.is_finite() && speed > 0.0 {
+            speed.min(MAX_FILE_PLAYBACK_SPEED)
+        } else {
+            1.0
+        };
+        self
+    }
+
+    /// Подписка на завершение проигрывания файла
+    pub fn completion(&self) -> watch::Receiver<Option<FileCaptureOutcome>> {
+        self.completion_tx.subscribe()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn chunk_samples(&self) -> usize {
+        (self.config.sample_rate as u64 * FILE_CHUNK_DURATION_MS / 1000).max(1) as usize
+    }
+}
+
+/// Полностью нулевые чанки TranscriptionService считает отвалившимся микрофоном;
+/// цифровая тишина в записи звонка — нормальна, поэтому слегка "оживляем" такой чанк.
+pub fn keep_alive_digital_silence(chunk: &mut [i16]) {
+    if chunk.iter().all(|&sample| sample == 0) {
+        if let Some(first) = chunk.first_mut() {
+            *first = 1;
+        }
+    }
+}
+
+#[async_trait]
+impl AudioCapture for FileAudioCapture {
+    async fn initialize(&mut self, config: AudioConfig) -> AudioResult<()> {
+        if !self.path.is_file() {
+            return Err(AudioError::DeviceNotFound(format!(
+                "Audio file not found: {:?}",
+                self.path
+            )));
+        }
+        log::info!(
+            "FileAudioCapture: Initializing {:?} with config: {:?}",
+            self.path,
+            config
+        );
+        // Выход всегда моно: STT pipeline рассчитан на 1 канал
+        self.config = AudioConfig {
+            channels: 1,
+            ..config
+        };
+        Ok(())
+    }
+
+    async fn start_capture(&mut self, on_chunk: AudioChunkCallback) -> AudioResult<()> {
+        if self.is_capturing.load(Ordering::SeqCst) {
+            return Err(AudioError::Capture("Already capturing".to_string()));
+        }
+        if matches!(
+            *self.completion_tx.borrow(),
+            Some(FileCaptureOutcome::Finished { .. }) | Some(FileCaptureOutcome::Failed(_))
+        ) {
+            // Файл уже отдан: рестарт не должен начинать его заново
+            return Ok(());
+        }
+
+        let mut decoder = FileAudioDecoder::open(&self.path, self.config.sample_rate)?;
+        let skip_samples = self.emitted_samples.load(Ordering::SeqCst);
+
+        self.stop_requested.store(false, Ordering::SeqCst);
+        self.is_capturing.store(true, Ordering::SeqCst);
+
+        let sample_rate = self.config.sample_rate;
+        let chunk_samples = self.chunk_samples();
+        let speed = self.speed as f64;
+        let is_capturing = self.is_capturing.clone();
+        let stop_requested = self.stop_requested.clone();
+        let emitted_samples = self.emitted_samples.clone();
+        let completion_tx = self.completion_tx.clone();
+        let on_error = self.terminal_error_callback.clone();
+
+        log::info!(
+            "FileAudioCapture: Starting {:?} at {}x (resume from sample {})",
+            self.path,
+            speed,
+            skip_samples
+        );
+
+        let worker = std::thread::Builder::new()
+            .name("file-audio-capture".to_string())
+            .spawn(move || {
+                let started_at = Instant::now();
+                let mut skipped = 0u64;
+                let mut paced_samples = 0u64;
+                let mut pending: Vec<i16> = Vec::new();
+
+                let emit = |chunk: Vec<i16>, paced_samples: &mut u64| {
+                    let mut chunk = chunk;
+                    keep_alive_digital_silence(&mut chunk);
+                    let len = chunk.len() as u64;
+                    on_chunk(AudioChunk::new(chunk, sample_rate, 1));
+                    emitted_samples.fetch_add(len, Ordering::SeqCst);
+                    *paced_samples += len;
+
+                    let target =
+                        Duration::from_secs_f64(*paced_samples as f64 / sample_rate as f64 / speed);
+                    if let Some(wait) = target.checked_sub(started_at.elapsed()) {
+                        std::thread::sleep(wait);
+                    }
+                };
+
+                let outcome = loop {
+                    if stop_requested.load(Ordering::SeqCst) {
+                        break FileCaptureOutcome::Stopped;
+                    }
+                    match decoder.next_samples() {
+                        Ok(Some(mut samples)) => {
+                            // Продолжение после рестарта: уже отданное пропускаем
+                            if skipped < skip_samples {
+                                let skip = ((skip_samples - skipped) as usize).min(samples.len());
+                                samples.drain(..skip);
+                                skipped += skip as u64;
+                            }
+                            pending.extend_from_slice(&samples);
+                            while pending.len() >= chunk_samples
+                                && !stop_requested.load(Ordering::SeqCst)
+                            {
+                                let chunk: Vec<i16> = pending.drain(..chunk_samples).collect();
+                                emit(chunk, &mut paced_samples);
+                            }
+                        }
+                        Ok(None) => {
+                            if !pending.is_empty() {
+                                emit(std::mem::take(&mut pending), &mut paced_samples);
+                            }
+                            let total = emitted_samples.load(Ordering::SeqCst);
+                            break FileCaptureOutcome::Finished {
+                                duration_secs: total as f64 / sample_rate as f64,
+                            };
+                        }
+                        Err(e) => {
+                            log::error!("FileAudioCapture: {}", e);
+                            let message = e.to_string();
+                            if let Some(on_error) = on_error.as_ref() {
+                                on_error(e);
+                            }
+                            break FileCaptureOutcome::Failed(message);
+                        }
+                    }
+                };
+
+                log::info!("FileAudioCapture: Capture loop ended: {:?}", outcome);
+                is_capturing.store(false, Ordering::SeqCst);
+                completion_tx.send_replace(Some(outcome));
+            })
+            .map_err(|e| {
+                self.is_capturing.store(false, Ordering::SeqCst);
+                AudioError::Internal(format!("Failed to spawn file capture thread: {}", e))
+            })?;
+
+        self.worker = Some(worker);
+        Ok(())
+    }
+
+    async fn stop_capture(&mut self) -> AudioResult<()> {
+        log::info!("FileAudioCapture: Stopping capture");
+        self.stop_requested.store(true, Ordering::SeqCst);
+        if let Some(worker) = self.worker.take() {
+            // Поток спит не дольше одного чанка, join не блокирует рантайм надолго
+            let _ = tokio::task::spawn_blocking(move || worker.join()).await;
+        }
+        self.is_capturing.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn set_terminal_error_callback(&mut self, callback: Option<AudioCaptureErrorCallback>) {
+        self.terminal_error_callback = callback;
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.is_capturing.load(Ordering::SeqCst)
+    }
+
+    fn config(&self) -> AudioConfig {
+        self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    struct TestAudioFile {
+        path: PathBuf,
+    }
+
+    impl TestAudioFile {
+        /// Пишет 16-bit PCM WAV с синусом 440 Hz
+        fn wav(sample_rate: u32, channels: u16, duration_secs: f64) -> Self {
+            let frames = (sample_rate as f64 * duration_secs) as usize;
+            let mut data = Vec::with_capacity(frames * channels as usize * 2);
+            for frame in 0..frames {
+                let t = frame as f64 / sample_rate as f64;
+                let sample = ((t * 440.0 * std::f64::consts::TAU).sin() * 8_000.0) as i16;
+                for _ in 0..channels {
+                    data.extend_from_slice(&sample.to_le_bytes());
+                }
+            }
+
+            let block_align = channels as u32 * 2;
+            let mut wav = Vec::new();
+            wav.extend_from_slice(b"RIFF");
+            wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+            wav.extend_from_slice(b"WAVEfmt ");
+            wav.extend_from_slice(&16u32.to_le_bytes());
+            wav.extend_from_slice(&1u16.to_le_bytes());
+            wav.extend_from_slice(&channels.to_le_bytes());
+            wav.extend_from_slice(&sample_rate.to_le_bytes());
+            wav.extend_from_slice(&(sample_rate * block_align).to_le_bytes());
+            wav.extend_from_slice(&(block_align as u16).to_le_bytes());
+            wav.extend_from_slice(&16u16.to_le_bytes());
+            wav.extend_from_slice(b"data");
+            wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            wav.extend_from_slice(&data);
+
+            let path = std::env::temp_dir().join(format!("voice-to-text-{}.wav", Uuid::new_v4()));
+            std::fs::write(&path, wav).unwrap();
+            Self { path }
+        }
+    }
+
+    impl Drop for TestAudioFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    #[test]
+    fn test_supported_extensions() {
+        assert!(is_supported_audio_file(Path::new("memo.WAV")));
+        assert!(is_supported_audio_file(Path::new("/calls/call.flac")));
+        assert!(!is_supported_audio_file(Path::new("movie.mkv")));
+        assert!(!is_supported_audio_file(Path::new("noext")));
+    }
+
+    #[test]
+    fn test_decode_resamples_stereo_48k_to_16k_mono() {
+        let file = TestAudioFile::wav(48_000, 2, 1.0);
+
+        let decoder = FileAudioDecoder::open(&file.path, 16_000).unwrap();
+        assert_eq!(decoder.duration_secs(), Some(1.0));
+
+        let samples = FileAudioDecoder::decode_all(&file.path, 16_000).unwrap();
+        assert!(
+            (15_900..=16_100).contains(&samples.len()),
+            "unexpected sample count {}",
+            samples.len()
+        );
+        assert!(samples.iter().any(|&s| s.unsigned_abs() > 4_000));
+    }
+
+    #[test]
+    fn test_decode_passthrough_when_rates_match() {
+        let file = TestAudioFile::wav(16_000, 1, 0.5);
+        let samples = FileAudioDecoder::decode_all(&file.path, 16_000).unwrap();
+        assert_eq!(samples.len(), 8_000);
+    }
+
+    #[test]
+    fn test_decode_rejects_non_audio_file() {
+        let path = std::env::temp_dir().join(format!("voice-to-text-{}.wav", Uuid::new_v4()));
+        std::fs::write(&path, b"definitely not audio").unwrap();
+        let result = FileAudioDecoder::open(&path, 16_000);
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(result, Err(AudioError::Configuration(_))));
+    }
+
+    #[tokio::test]
+    async fn test_capture_streams_whole_file_and_reports_completion() {
+        let file = TestAudioFile::wav(16_000, 1, 0.5);
+        let mut capture = FileAudioCapture::new(&file.path).with_speed(MAX_FILE_PLAYBACK_SPEED);
+        capture.initialize(AudioConfig::default()).await.unwrap();
+        let mut completion = capture.completion();
+
+        let received = Arc::new(Mutex::new(Vec::<usize>::new()));
+        let received_cb = received.clone();
+        capture
+            .start_capture(Arc::new(move |chunk: AudioChunk| {
+                assert_eq!(chunk.sample_rate, 16_000);
+                assert_eq!(chunk.channels, 1);
+                received_cb.lock().unwrap().push(chunk.data.len());
+            }))
+            .await
+            .unwrap();
+
+        let outcome = tokio::time::timeout(
+            Duration::from_secs(5),
+            completion.wait_for(|outcome| outcome.is_some()),
+        )
+        .await
+        .expect("file capture must finish")
+        .unwrap()
+        .clone();
+        capture.stop_capture().await.unwrap();
+
+        assert_eq!(
+            outcome,
+            Some(FileCaptureOutcome::Finished { duration_secs: 0.5 })
+        );
+        {
+            let received = received.lock().unwrap();
+            assert_eq!(received.len(), 5);
+            assert_eq!(received.iter().sum::<usize>(), 8_000);
+        }
+
+        // Рестарт после конца файла ничего не переигрывает
+        capture.start_capture(Arc::new(|_| {})).await.unwrap();
+        assert!(!capture.is_capturing());
+    }
+
+    #[tokio::test]
+    async fn test_initialize_fails_for_missing_file() {
+        let mut capture = FileAudioCapture::new("/definitely/missing/file.wav");
+        let result = capture.initialize(AudioConfig::default()).await;
+        assert!(matches!(result, Err(AudioError::DeviceNotFound(_))));
+    }
+
+    #[test]
+    fn test_digital_silence_is_not_all_zero() {
+        let mut chunk = vec![0i16; 4];
+        keep_alive_digital_silence(&mut chunk);
+        assert_eq!(chunk, vec![1, 0, 0, 0]);
+
+        let mut speech = vec![0i16, 5, 0];
+        keep_alive_digital_silence(&mut speech);
+        assert_eq!(speech, vec![0, 5, 0]);
+    }
+}
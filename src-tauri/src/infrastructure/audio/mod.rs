@@ -1,5 +1,6 @@
 /// Audio capture implementations
 mod cpal_output;
+mod file_capture;
 #[cfg_attr(all(test, not(target_os = "linux")), allow(dead_code))]
 #[cfg(any(target_os = "linux", test))]
 mod linux_pulse;
@@ -8,6 +9,7 @@ mod macos_spoken_translation_capability;
 #[cfg(target_os = "macos")]
 mod macos_system_audio_capture;
 mod mock_capture;
+mod opus_encoder;
 mod platform_factory;
 mod system_capture;
 mod vad_capture_wrapper;
@@ -20,11 +22,18 @@ pub use cpal_output::{
     ENV_TRANSLATION_OUTPUT_DEVICE, MACOS_BLACKHOLE_DEVICE_NAMES,
     WINDOWS_VB_CABLE_OUTPUT_DEVICE_NAMES,
 };
+pub use file_capture::{
+    is_supported_audio_file, keep_alive_digital_silence, FileAudioCapture, FileAudioDecoder,
+    FileCaptureOutcome, MAX_FILE_PLAYBACK_SPEED, SUPPORTED_AUDIO_FILE_EXTENSIONS,
+};
 pub use local_playback_factory::DefaultLocalPlaybackOutputFactory;
 pub use macos_spoken_translation_capability::DefaultSpokenTranslationCapability;
 #[cfg(target_os = "macos")]
 pub use macos_system_audio_capture::MacosSystemAudioCapture;
 pub use mock_capture::MockAudioCapture;
+pub use opus_encoder::{
+    AudioEncodeError, OggOpusStream, OpusFrameEncoder, OPUS_FRAME_SAMPLES, OPUS_SAMPLE_RATE,
+};
 pub use platform_factory::{is_macos_blackhole_device_name, DefaultPlatformAudioFactory};
 pub use system_capture::{SystemAudioCapture, SystemAudioCaptureOptions};
 pub use vad_capture_wrapper::VadCaptureWrapper;
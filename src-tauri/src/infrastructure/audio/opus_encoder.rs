@@ -0,0 +1,369 @@
+//! Opus-кодирование аудио перед отправкой в streaming STT.
+//!
+//! Сырой PCM16 16 кГц — это 256 кбит/с на сессию; Opus в режиме VoIP даёт ~24 кбит/с
+//! без заметной потери качества распознавания. Сам кодек (libopus) подключается feature
+//! `opus` — как и whisper, он требует нативной сборки. Без feature `OpusFrameEncoder::new`
+//! возвращает `Unavailable`, и провайдеры остаются на PCM.
+//!
+//! Есть два формата транспорта:
+//! - `OpusFrameEncoder` — "голые" Opus-пакеты, по одному на сообщение (наш Backend);
+//! - `OggOpusStream` — те же пакеты в Ogg-страницах (Deepgram `encoding=opus`).
+
+use thiserror::Error;
+
+/// Частота, с которой приходит аудио от захвата (см. `AudioChunk`)
+pub const OPUS_SAMPLE_RATE: u32 = 16_000;
+
+/// 40 мс на пакет: ~25 сообщений в секунду, укладываемся в лимит частоты отправки
+pub const OPUS_FRAME_SAMPLES: usize = OPUS_SAMPLE_RATE as usize * 40 / 1000;
+
+/// Целевой битрейт для речи
+#[cfg_attr(not(feature = "opus"), allow(dead_code))]
+const OPUS_BITRATE_BPS: i32 = 24_000;
+
+/// Максимальный размер одного пакета (рекомендация libopus)
+#[cfg_attr(not(feature = "opus"), allow(dead_code))]
+const OPUS_MAX_PACKET_BYTES: usize = 4000;
+
+#[derive(Debug, Error)]
+pub enum AudioEncodeError {
+    #[error("Opus support is not available in this build")]
+    Unavailable,
+    #[error("Opus encoder error: {0}")]
+    Codec(String),
+}
+
+// Реальный кодек через libopus (feature "opus")
+#[cfg(feature = "opus")]
+struct OpusCodec(opus::Encoder);
+
+#[cfg(feature = "opus")]
+impl OpusCodec {
+    fn new() -> Result<Self, AudioEncodeError> {
+        let codec_error = |e: opus::Error| AudioEncodeError::Codec(e.to_string());
+        let mut encoder = opus::Encoder::new(
+            OPUS_SAMPLE_RATE,
+            opus::Channels::Mono,
+            opus::Application::Voip,
+        )
+        .map_err(codec_error)?;
+        encoder
+            .set_bitrate(opus::Bitrate::Bits(OPUS_BITRATE_BPS))
+            .map_err(codec_error)?;
+        Ok(Self(encoder))
+    }
+
+    fn encode(&mut self, frame: &[i16]) -> Result<Vec<u8>, AudioEncodeError> {
+        self.0
+            .encode_vec(frame, OPUS_MAX_PACKET_BYTES)
+            .map_err(|e| AudioEncodeError::Codec(e.to_string()))
+    }
+
+    fn reset(&mut self) {
+        if let Err(e) = self.0.reset_state() {
+            log::warn!("Failed to reset Opus encoder state: {}", e);
+        }
+    }
+}
+
+// Заглушка когда opus feature не включен
+#[cfg(not(feature = "opus"))]
+struct OpusCodec;
+
+#[cfg(not(feature = "opus"))]
+impl OpusCodec {
+    fn new() -> Result<Self, AudioEncodeError> {
+        Err(AudioEncodeError::Unavailable)
+    }
+
+    fn encode(&mut self, _frame: &[i16]) -> Result<Vec<u8>, AudioEncodeError> {
+        Err(AudioEncodeError::Unavailable)
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Режет входящий PCM на 40 мс кадры и кодирует каждый в отдельный Opus-пакет.
+///
+/// Хвост короче кадра копится до следующего вызова; `flush` добивает его тишиной.
+pub struct OpusFrameEncoder {
+    codec: OpusCodec,
+    pending: Vec<i16>,
+}
+
+impl OpusFrameEncoder {
+    /// Собран ли бинарник с поддержкой Opus
+    pub fn is_available() -> bool {
+        cfg!(feature = "opus")
+    }
+
+    pub fn new() -> Result<Self, AudioEncodeError> {
+        Ok(Self {
+            codec: OpusCodec::new()?,
+            pending: Vec::with_capacity(OPUS_FRAME_SAMPLES),
+        })
+    }
+
+    /// Кодирует все полные кадры, которые набрались с учётом `samples`
+    pub fn encode(&mut self, samples: &[i16]) -> Result<Vec<Vec<u8>>, AudioEncodeError> {
+        self.pending.extend_from_slice(samples);
+        let full_frames = self.pending.len() / OPUS_FRAME_SAMPLES;
+        let mut packets = Vec::with_capacity(full_frames);
+        for frame in self.pending.chunks_exact(OPUS_FRAME_SAMPLES) {
+            packets.push(self.codec.encode(frame)?);
+        }
+        self.pending.drain(..full_frames * OPUS_FRAME_SAMPLES);
+        Ok(packets)
+    }
+
+    /// Досылает накопленный хвост, дополнив его тишиной до полного кадра
+    pub fn flush(&mut self) -> Result<Option<Vec<u8>>, AudioEncodeError> {
+        if self.pending.is_empty() {
+            return Ok(None);
+        }
+        self.pending.resize(OPUS_FRAME_SAMPLES, 0);
+        let packet = self.codec.encode(&self.pending)?;
+        self.pending.clear();
+        Ok(Some(packet))
+    }
+
+    /// Есть ли недокодированный хвост (меньше кадра)
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Новый поток: сбрасываем состояние кодека и недокодированный хвост
+    pub fn reset(&mut self) {
+        self.codec.reset();
+        self.pending.clear();
+    }
+}
+
+/// Pre-skip декодера в 48 кГц отсчётах (стандартный lookahead libopus)
+const OGG_OPUS_PRE_SKIP: u16 = 312;
+
+/// Opus всегда считает granule position в 48 кГц
+const OGG_GRANULE_PER_INPUT_SAMPLE: u64 = 48_000 / OPUS_SAMPLE_RATE as u64;
+
+const OGG_HEADER_BOS: u8 = 0x02;
+
+/// Opus-пакеты в Ogg-контейнере (RFC 7845): каждый вызов отдаёт готовые страницы,
+/// первый — вместе с заголовками `OpusHead`/`OpusTags`.
+pub struct OggOpusStream {
+    encoder: OpusFrameEncoder,
+    serial: u32,
+    page_sequence: u32,
+    granule_position: u64,
+    headers_written: bool,
+}
+
+impl OggOpusStream {
+    pub fn new() -> Result<Self, AudioEncodeError> {
+        Ok(Self {
+            encoder: OpusFrameEncoder::new()?,
+            serial: new_stream_serial(),
+            page_sequence: 0,
+            granule_position: 0,
+            headers_written: false,
+        })
+    }
+
+    pub fn encode(&mut self, samples: &[i16]) -> Result<Vec<u8>, AudioEncodeError> {
+        let packets = self.encoder.encode(samples)?;
+        Ok(self.write_pages(packets))
+    }
+
+    pub fn flush(&mut self) -> Result<Vec<u8>, AudioEncodeError> {
+        let packets: Vec<Vec<u8>> = self.encoder.flush()?.into_iter().collect();
+        Ok(self.write_pages(packets))
+    }
+
+    pub fn has_pending(&self) -> bool {
+        self.encoder.has_pending()
+    }
+
+    /// Новое соединение — новый логический Ogg-поток с заголовками
+    pub fn reset(&mut self) {
+        self.encoder.reset();
+        self.serial = new_stream_serial();
+        self.page_sequence = 0;
+        self.granule_position = 0;
+        self.headers_written = false;
+    }
+
+    fn write_pages(&mut self, packets: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut out = Vec::new();
+        if packets.is_empty() {
+            return out;
+        }
+        if !self.headers_written {
+            self.headers_written = true;
+            out.extend(self.next_page(OGG_HEADER_BOS, 0, &opus_head()));
+            out.extend(self.next_page(0, 0, &opus_tags()));
+        }
+        for packet in packets {
+            self.granule_position += OPUS_FRAME_SAMPLES as u64 * OGG_GRANULE_PER_INPUT_SAMPLE;
+            out.extend(self.next_page(0, self.granule_position, &packet));
+        }
+        out
+    }
+
+    fn next_page(&mut self, header_type: u8, granule: u64, packet: &[u8]) -> Vec<u8> {
+        let page = ogg_page(
+            header_type,
+            granule,
+            self.serial,
+            self.page_sequence,
+            packet,
+        );
+        self.page_sequence += 1;
+        page
+    }
+}
+
+fn new_stream_serial() -> u32 {
+    uuid::Uuid::new_v4().as_u128() as u32
+}
+
+fn opus_head() -> Vec<u8> {
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(1); // channels
+    head.extend_from_slice(&OGG_OPUS_PRE_SKIP.to_le_bytes());
+    head.extend_from_slice(&OPUS_SAMPLE_RATE.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family
+    head
+}
+
+fn opus_tags() -> Vec<u8> {
+    const VENDOR: &[u8] = b"voice-to-text";
+    let mut tags = Vec::with_capacity(16 + VENDOR.len());
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(VENDOR.len() as u32).to_le_bytes());
+    tags.extend_from_slice(VENDOR);
+    tags.extend_from_slice(&0u32.to_le_bytes()); // user comments
+    tags
+}
+
+/// Одна Ogg-страница с одним пакетом (пакеты у нас заведомо < 255 * 255 байт)
+fn ogg_page(header_type: u8, granule: u64, serial: u32, sequence: u32, packet: &[u8]) -> Vec<u8> {
+    let mut lacing = vec![255u8; packet.len() / 255];
+    lacing.push((packet.len() % 255) as u8);
+
+    let mut page = Vec::with_capacity(27 + lacing.len() + packet.len());
+    page.extend_from_slice(b"OggS");
+    page.push(0); // stream structure version
+    page.push(header_type);
+    page.extend_from_slice(&granule.to_le_bytes());
+    page.extend_from_slice(&serial.to_le_bytes());
+    page.extend_from_slice(&sequence.to_le_bytes());
+    page.extend_from_slice(&0u32.to_le_bytes()); // CRC, заполняем ниже
+    page.push(lacing.len() as u8);
+    page.extend_from_slice(&lacing);
+    page.extend_from_slice(packet);
+
+    let crc = ogg_crc32(&page);
+    page[22..26].copy_from_slice(&crc.to_le_bytes());
+    page
+}
+
+/// CRC-32 из спецификации Ogg: полином 0x04c11db7, без отражения и финального XOR
+fn ogg_crc32(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |mut crc, &byte| {
+        crc ^= (byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04c1_1db7
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ogg_page_has_valid_header_lacing_and_crc() {
+        let packet = vec![7u8; 300];
+        let page = ogg_page(OGG_HEADER_BOS, 1920, 42, 3, &packet);
+
+        assert_eq!(&page[..4], b"OggS");
+        assert_eq!(page[5], OGG_HEADER_BOS);
+        assert_eq!(u64::from_le_bytes(page[6..14].try_into().unwrap()), 1920);
+        assert_eq!(u32::from_le_bytes(page[14..18].try_into().unwrap()), 42);
+        assert_eq!(u32::from_le_bytes(page[18..22].try_into().unwrap()), 3);
+        assert_eq!(page[26], 2);
+        assert_eq!(&page[27..29], &[255, 45]);
+        assert_eq!(&page[29..], packet.as_slice());
+
+        let mut zeroed = page.clone();
+        zeroed[22..26].fill(0);
+        assert_eq!(
+            u32::from_le_bytes(page[22..26].try_into().unwrap()),
+            ogg_crc32(&zeroed)
+        );
+    }
+
+    #[test]
+    fn ogg_lacing_terminates_packets_that_are_multiples_of_255() {
+        let page = ogg_page(0, 0, 1, 0, &[0u8; 255]);
+        assert_eq!(page[26], 2);
+        assert_eq!(&page[27..29], &[255, 0]);
+    }
+
+    #[test]
+    fn ogg_crc_matches_reference_value() {
+        // CRC-32/POSIX "check" (0x765e7680) без финального XOR
+        assert_eq!(ogg_crc32(b"123456789"), 0x89a1_897f);
+    }
+
+    #[test]
+    fn opus_head_describes_16khz_mono_stream() {
+        let head = opus_head();
+        assert_eq!(&head[..8], b"OpusHead");
+        assert_eq!(head[9], 1);
+        assert_eq!(u32::from_le_bytes(head[12..16].try_into().unwrap()), 16_000);
+    }
+
+    #[cfg(not(feature = "opus"))]
+    #[test]
+    fn encoder_reports_unavailable_without_opus_feature() {
+        assert!(!OpusFrameEncoder::is_available());
+        assert!(matches!(
+            OpusFrameEncoder::new(),
+            Err(AudioEncodeError::Unavailable)
+        ));
+    }
+
+    #[cfg(feature = "opus")]
+    #[test]
+    fn encoder_emits_one_packet_per_40ms_and_pads_tail_on_flush() {
+        let mut encoder = OpusFrameEncoder::new().unwrap();
+        let packets = encoder
+            .encode(&vec![1000; OPUS_FRAME_SAMPLES * 2 + 100])
+            .unwrap();
+        assert_eq!(packets.len(), 2);
+        assert!(packets.iter().all(|p| !p.is_empty() && p.len() < 640));
+
+        assert!(encoder.flush().unwrap().is_some());
+        assert!(encoder.flush().unwrap().is_none());
+    }
+
+    #[cfg(feature = "opus")]
+    #[test]
+    fn ogg_stream_writes_headers_once() {
+        let mut stream = OggOpusStream::new().unwrap();
+        let first = stream.encode(&vec![0; OPUS_FRAME_SAMPLES]).unwrap();
+        let second = stream.encode(&vec![0; OPUS_FRAME_SAMPLES]).unwrap();
+
+        assert_eq!(first.windows(8).filter(|w| *w == b"OpusHead").count(), 1);
+        assert_eq!(second.windows(8).filter(|w| *w == b"OpusHead").count(), 0);
+        assert_eq!(first.windows(4).filter(|w| *w == b"OggS").count(), 3);
+    }
+}
@@ -91,7 +91,7 @@ impl ConfigStore {
         }
     }
 
-    async fn write_file_atomic(path: &Path, contents: &str) -> Result<()> {
+    pub(crate) async fn write_file_atomic(path: &Path, contents: &str) -> Result<()> {
         // Пишем во временный файл и только потом атомарно подменяем.
         // На Windows rename может падать, если цель уже существует, поэтому делаем best-effort remove.
         // Важно: tmp-файл должен быть уникальным, иначе параллельные save() будут конфликтовать.
@@ -1,8 +1,10 @@
-use crate::domain::{
-    SttConfig, SttError, SttProvider, SttProviderFactory, SttProviderType, SttResult,
-};
+use std::sync::Arc;
+
+use crate::domain::{SttConfig, SttProvider, SttProviderFactory, SttProviderType, SttResult};
 use crate::infrastructure::stt::{
-    AssemblyAIProvider, BackendProvider, DeepgramProvider, WhisperLocalProvider,
+    AssemblyAIProvider, AzureProvider, BackendProvider, DeepgramProvider, ElevenLabsProvider,
+    FailoverProvider, GoogleCloudProvider, OpenAICompatibleProvider, OpenAIRealtimeProvider,
+    TextFilterProvider, WhisperLocalProvider,
 };
 
 /// Factory for creating STT providers based on configuration
@@ -26,23 +28,41 @@ impl SttProviderFactory for DefaultSttProviderFactory {
     fn create(&self, config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
         log::info!("Creating STT provider: {:?}", config.provider);
 
-        match config.provider {
-            SttProviderType::WhisperLocal => Ok(Box::new(WhisperLocalProvider::new())),
+        // Звенья цепочки приходят сюда же, но уже с пустым failover_providers
+        if !config.failover_providers.is_empty() {
+            log::info!(
+                "Wrapping STT provider into failover chain: {:?}",
+                config.failover_providers
+            );
+            return Ok(Box::new(FailoverProvider::new(Arc::new(Self::new()))));
+        }
+
+        let provider: Box<dyn SttProvider> = match config.provider {
+            SttProviderType::WhisperLocal => Box::new(WhisperLocalProvider::new()),
+
+            SttProviderType::AssemblyAI => Box::new(AssemblyAIProvider::new()),
 
-            SttProviderType::AssemblyAI => Ok(Box::new(AssemblyAIProvider::new())),
+            SttProviderType::Deepgram => Box::new(DeepgramProvider::new()),
 
-            SttProviderType::Deepgram => Ok(Box::new(DeepgramProvider::new())),
+            SttProviderType::Backend => Box::new(BackendProvider::new()),
 
-            SttProviderType::Backend => Ok(Box::new(BackendProvider::new())),
+            SttProviderType::GoogleCloud => Box::new(GoogleCloudProvider::new()),
 
-            SttProviderType::GoogleCloud => Err(SttError::Unsupported(
-                "Google Cloud STT provider not yet implemented".to_string(),
-            )),
+            SttProviderType::Azure => Box::new(AzureProvider::new()),
 
-            SttProviderType::Azure => Err(SttError::Unsupported(
-                "Azure STT provider not yet implemented".to_string(),
-            )),
+            SttProviderType::OpenAICompatible => Box::new(OpenAICompatibleProvider::new()),
+
+            SttProviderType::OpenAIRealtime => Box::new(OpenAIRealtimeProvider::new()),
+
+            SttProviderType::ElevenLabs => Box::new(ElevenLabsProvider::new()),
+        };
+
+        // Пунктуация/мат: что не умеет провайдер, доделываем на клиенте
+        if TextFilterProvider::is_needed(config) {
+            return Ok(Box::new(TextFilterProvider::new(provider)));
         }
+
+        Ok(provider)
     }
 }
 
@@ -94,18 +114,69 @@ mod tests {
     }
 
     #[test]
-    fn test_create_google_cloud_unsupported() {
+    fn test_create_google_cloud() {
         let factory = DefaultSttProviderFactory::new();
         let config = SttConfig::new(SttProviderType::GoogleCloud);
-        let result = factory.create(&config);
-        assert!(result.is_err());
+        let provider = factory.create(&config).expect("google cloud provider");
+        assert_eq!(provider.name(), "Google Cloud Speech-to-Text v2");
     }
 
     #[test]
-    fn test_create_azure_unsupported() {
+    fn test_create_azure() {
         let factory = DefaultSttProviderFactory::new();
         let config = SttConfig::new(SttProviderType::Azure);
-        let result = factory.create(&config);
-        assert!(result.is_err());
+        let provider = factory.create(&config).expect("azure provider");
+        assert_eq!(provider.name(), "Azure Speech Services");
+    }
+
+    #[test]
+    fn test_create_openai_compatible() {
+        let factory = DefaultSttProviderFactory::new();
+        let config = SttConfig::new(SttProviderType::OpenAICompatible);
+        let provider = factory.create(&config).expect("openai-compatible provider");
+        assert_eq!(provider.name(), "OpenAI-compatible Transcription");
+    }
+
+    #[test]
+    fn test_create_openai_realtime() {
+        let factory = DefaultSttProviderFactory::new();
+        let config = SttConfig::new(SttProviderType::OpenAIRealtime);
+        let provider = factory.create(&config).expect("openai realtime provider");
+        assert_eq!(provider.name(), "OpenAI Realtime Transcription");
+    }
+
+    #[test]
+    fn test_create_elevenlabs() {
+        let factory = DefaultSttProviderFactory::new();
+        let config = SttConfig::new(SttProviderType::ElevenLabs);
+        let provider = factory.create(&config).expect("elevenlabs provider");
+        assert_eq!(provider.name(), "ElevenLabs Realtime");
+    }
+
+    #[test]
+    fn test_create_wraps_provider_into_failover_chain() {
+        let factory = DefaultSttProviderFactory::new();
+        let mut config = SttConfig::new(SttProviderType::Backend);
+        config.failover_providers = vec![SttProviderType::WhisperLocal];
+
+        let provider = factory.create(&config).expect("failover provider");
+
+        assert!(provider.name().starts_with("STT Failover Chain"));
+        let plain = factory
+            .create(&config.failover_chain()[0])
+            .expect("backend provider");
+        assert!(!plain.name().starts_with("STT Failover Chain"));
+    }
+
+    #[test]
+    fn test_create_keeps_provider_identity_behind_text_filter() {
+        let factory = DefaultSttProviderFactory::new();
+        let mut config = SttConfig::new(SttProviderType::ElevenLabs);
+        config.filter_profanity = true;
+
+        let provider = factory.create(&config).expect("filtered provider");
+
+        assert_eq!(provider.name(), "ElevenLabs Realtime");
+        assert!(provider.supports_profanity_filter());
     }
 }
@@ -0,0 +1,400 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::domain::{HistoryEntry, HistoryPage, HistoryQuery, HistoryRange};
+use crate::infrastructure::ConfigStore;
+
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+/// Персистентная история диктовок (JSONL в app data директории).
+///
+/// Одна строка = одна запись, старые записи первыми. Новая запись дописывается в конец файла,
+/// а delete/clear/обрезка по `max_history_items` перезаписывают файл атомарно (tmp + rename).
+/// Содержимое держим в памяти: история ограничена `max_history_items`, поиск по ней дешёвый.
+pub struct HistoryStore {
+    /// None → store только в памяти (не удалось определить data dir)
+    path: Option<PathBuf>,
+    /// None → файл ещё не прочитан (ленивая загрузка на первом обращении)
+    entries: Mutex<Option<Vec<HistoryEntry>>>,
+}
+
+impl HistoryStore {
+    fn app_dir_name() -> &'static str {
+        if cfg!(debug_assertions) {
+            "voice-to-text-dev"
+        } else {
+            "voice-to-text"
+        }
+    }
+
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path: Some(path),
+            entries: Mutex::new(None),
+        }
+    }
+
+    /// История без файла: живёт до перезапуска приложения
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: Mutex::new(Some(Vec::new())),
+        }
+    }
+
+    /// Store в стандартной data директории приложения; при ошибке — in-memory фоллбек
+    pub fn open_default() -> Self {
+        match Self::default_path() {
+            Ok(path) => Self::new(path),
+            Err(e) => {
+                log::warn!(
+                    "Failed to resolve history path, history will not persist: {}",
+                    e
+                );
+                Self::in_memory()
+            }
+        }
+    }
+
+    /// Путь к файлу истории
+    pub fn default_path() -> Result<PathBuf> {
+        // Как и VOICE_TO_TEXT_CONFIG_DIR: переопределение для тестов и отладки.
+        if let Ok(custom) = std::env::var("VOICE_TO_TEXT_DATA_DIR") {
+            let custom = custom.trim();
+            if !custom.is_empty() {
+                let dir = PathBuf::from(custom);
+                std::fs::create_dir_all(&dir)?;
+                return Ok(dir.join(HISTORY_FILE_NAME));
+            }
+        }
+
+        let data_dir =
+            dirs::data_dir().ok_or_else(|| anyhow::anyhow!("Failed to get data directory"))?;
+        let app_data_dir = data_dir.join(Self::app_dir_name());
+        std::fs::create_dir_all(&app_data_dir)?;
+
+        Ok(app_data_dir.join(HISTORY_FILE_NAME))
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    async fn read_entries(path: &Path) -> Result<Vec<HistoryEntry>> {
+        let contents = match tokio::fs::read_to_string(path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            // Битая строка (например, оборванная запись при падении) не должна ронять всю историю.
+            match serde_json::from_str::<HistoryEntry>(line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => log::warn!(
+                    "Skipping corrupted history line {} in {:?}: {}",
+                    index + 1,
+                    path,
+                    e
+                ),
+            }
+        }
+        Ok(entries)
+    }
+
+    fn serialize_entries(entries: &[HistoryEntry]) -> Result<String> {
+        let mut contents = String::new();
+        for entry in entries {
+            contents.push_str(&serde_json::to_string(entry)?);
+            contents.push('\n');
+        }
+        Ok(contents)
+    }
+
+    async fn rewrite(&self, entries: &[HistoryEntry]) -> Result<()> {
+        let Some(path) = self.path.as_deref() else {
+            return Ok(());
+        };
+        ConfigStore::write_file_atomic(path, &Self::serialize_entries(entries)?).await
+    }
+
+    async fn append_line(&self, entry: &HistoryEntry) -> Result<()> {
+        let Some(path) = self.path.as_deref() else {
+            return Ok(());
+        };
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Загружает файл при первом обращении и отдаёт записи под уже взятым локом
+    async fn loaded<'a>(
+        &self,
+        guard: &'a mut MutexGuard<'_, Option<Vec<HistoryEntry>>>,
+    ) -> Result<&'a mut Vec<HistoryEntry>> {
+        if guard.is_none() {
+            let loaded = match self.path.as_deref() {
+                Some(path) => Self::read_entries(path).await?,
+                None => Vec::new(),
+            };
+            **guard = Some(loaded);
+        }
+        Ok(guard.get_or_insert_with(Vec::new))
+    }
+
+    fn trim_to(entries: &mut Vec<HistoryEntry>, max_items: usize) -> usize {
+        let excess = entries.len().saturating_sub(max_items);
+        if excess > 0 {
+            entries.drain(0..excess);
+        }
+        excess
+    }
+
+    /// Добавляет запись и обрезает историю до `max_items` самых свежих
+    pub async fn append(&self, entry: HistoryEntry, max_items: usize) -> Result<()> {
+        let mut guard = self.entries.lock().await;
+        let entries = self.loaded(&mut guard).await?;
+
+        entries.push(entry.clone());
+        if Self::trim_to(entries, max_items) > 0 {
+            self.rewrite(entries).await
+        } else {
+            self.append_line(&entry).await
+        }
+    }
+
+    /// Страница истории: новые записи первыми, все термы поиска должны совпасть
+    pub async fn query(&self, query: &HistoryQuery) -> Result<HistoryPage> {
+        let terms = query.terms();
+        let limit = query.effective_limit();
+        let mut guard = self.entries.lock().await;
+        let entries = self.loaded(&mut guard).await?;
+
+        let matched: Vec<&HistoryEntry> = entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.matches_terms(&terms))
+            .collect();
+        Ok(HistoryPage {
+            total: matched.len(),
+            items: matched
+                .into_iter()
+                .skip(query.offset)
+                .take(limit)
+                .cloned()
+                .collect(),
+            offset: query.offset,
+            limit,
+        })
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<HistoryEntry>> {
+        let mut guard = self.entries.lock().await;
+        let entries = self.loaded(&mut guard).await?;
+        Ok(entries.iter().find(|entry| entry.id == id).cloned())
+    }
+
+    /// Записи из диапазона в хронологическом порядке (для экспорта)
+    pub async fn range(&self, range: &HistoryRange) -> Result<Vec<HistoryEntry>> {
+        let mut guard = self.entries.lock().await;
+        let entries = self.loaded(&mut guard).await?;
+        Ok(entries
+            .iter()
+            .filter(|entry| range.contains(entry))
+            .cloned()
+            .collect())
+    }
+
+    /// Удаляет запись; false, если такой записи нет
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let mut guard = self.entries.lock().await;
+        let entries = self.loaded(&mut guard).await?;
+
+        let before = entries.len();
+        entries.retain(|entry| entry.id != id);
+        if entries.len() == before {
+            return Ok(false);
+        }
+        self.rewrite(entries).await?;
+        Ok(true)
+    }
+
+    pub async fn clear(&self) -> Result<()> {
+        let mut guard = self.entries.lock().await;
+        *guard = Some(Vec::new());
+        if let Some(path) = self.path.as_deref() {
+            match tokio::fs::remove_file(path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Применяет новый `max_history_items` к уже сохранённой истории; возвращает число удалённых записей
+    pub async fn enforce_limit(&self, max_items: usize) -> Result<usize> {
+        let mut guard = self.entries.lock().await;
+        let entries = self.loaded(&mut guard).await?;
+
+        let removed = Self::trim_to(entries, max_items);
+        if removed > 0 {
+            self.rewrite(entries).await?;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Transcription;
+    use uuid::Uuid;
+
+    struct TestHistoryFile {
+        dir: PathBuf,
+    }
+
+    impl TestHistoryFile {
+        fn new() -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("voice-to-text-history-{}", Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self { dir }
+        }
+
+        fn path(&self) -> PathBuf {
+            self.dir.join(HISTORY_FILE_NAME)
+        }
+    }
+
+    impl Drop for TestHistoryFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn entry(text: &str) -> HistoryEntry {
+        HistoryEntry::from_transcription(&Transcription::final_result(text.to_string()), "deepgram")
+    }
+
+    #[tokio::test]
+    async fn test_history_persists_across_store_instances() {
+        let file = TestHistoryFile::new();
+        let store = HistoryStore::new(file.path());
+        store.append(entry("first"), 20).await.unwrap();
+        store.append(entry("second"), 20).await.unwrap();
+
+        let reopened = HistoryStore::new(file.path());
+        let page = reopened.query(&HistoryQuery::default()).await.unwrap();
+
+        assert_eq!(page.total, 2);
+        let texts: Vec<&str> = page.items.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["second", "first"]);
+    }
+
+    #[tokio::test]
+    async fn test_append_trims_to_max_items() {
+        let file = TestHistoryFile::new();
+        let store = HistoryStore::new(file.path());
+        for text in ["one", "two", "three"] {
+            store.append(entry(text), 2).await.unwrap();
+        }
+
+        let reopened = HistoryStore::new(file.path());
+        let page = reopened.query(&HistoryQuery::default()).await.unwrap();
+        let texts: Vec<&str> = page.items.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["three", "two"]);
+
+        assert_eq!(reopened.enforce_limit(1).await.unwrap(), 1);
+        assert_eq!(
+            reopened
+                .query(&HistoryQuery::default())
+                .await
+                .unwrap()
+                .total,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_searches_and_paginates() {
+        let store = HistoryStore::in_memory();
+        for i in 0..5 {
+            store
+                .append(entry(&format!("meeting note {}", i)), 20)
+                .await
+                .unwrap();
+        }
+        store.append(entry("grocery list"), 20).await.unwrap();
+
+        let page = store
+            .query(&HistoryQuery {
+                search: Some("MEETING".to_string()),
+                offset: 1,
+                limit: Some(2),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(page.total, 5);
+        let texts: Vec<&str> = page.items.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["meeting note 3", "meeting note 2"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_and_clear() {
+        let file = TestHistoryFile::new();
+        let store = HistoryStore::new(file.path());
+        let keep = entry("keep");
+        let drop = entry("drop");
+        store.append(keep.clone(), 20).await.unwrap();
+        store.append(drop.clone(), 20).await.unwrap();
+
+        assert!(store.delete(&drop.id).await.unwrap());
+        assert!(!store.delete(&drop.id).await.unwrap());
+
+        let reopened = HistoryStore::new(file.path());
+        assert_eq!(reopened.get(&keep.id).await.unwrap(), Some(keep));
+        assert_eq!(reopened.get(&drop.id).await.unwrap(), None);
+
+        reopened.clear().await.unwrap();
+        assert!(!file.path().exists());
+        assert_eq!(
+            reopened
+                .query(&HistoryQuery::default())
+                .await
+                .unwrap()
+                .total,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_lines_are_skipped() {
+        let file = TestHistoryFile::new();
+        let valid = serde_json::to_string(&entry("valid")).unwrap();
+        std::fs::write(file.path(), format!("{}\n{{\"id\": \"trunc", valid)).unwrap();
+
+        let store = HistoryStore::new(file.path());
+        let page = store.query(&HistoryQuery::default()).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].text, "valid");
+    }
+}
@@ -7,6 +7,7 @@ pub mod embedded_keys {
     include!(concat!(env!("OUT_DIR"), "/embedded_keys.rs"));
 }
 pub mod factory;
+pub mod history_store; // Персистентная история диктовок (JSONL)
 pub mod hotkey; // Нормализация/миграция хоткеев
 pub mod microphone_permission; // Проверка разрешения на микрофон (macOS)
 pub mod models;
@@ -14,9 +15,13 @@ pub mod openai; // OpenAI Realtime translation client
 /// Infrastructure layer - contains concrete implementations of domain interfaces
 /// This layer depends on domain layer but is independent of application layer
 pub mod stt;
+pub mod translation; // Текстовый перевод: OpenAI, DeepL, LibreTranslate, OpenAI-compatible
+pub mod translation_memory_store; // Персистентная память переводов субтитров (LRU)
 pub mod updater; // Auth session + device_id (Rust SoT)
 
 pub use auth_store::{AuthSession, AuthStore, AuthStoreData, AuthUser};
 pub use clipboard::copy_to_clipboard;
 pub use config_store::ConfigStore;
 pub use factory::*;
+pub use history_store::HistoryStore;
+pub use translation_memory_store::TranslationMemoryStore;
@@ -3,4 +3,4 @@ pub mod realtime_translation;
 pub mod text_translation;
 
 pub use realtime_translation::{OpenAIRealtimeTranslationClient, OpenAIRealtimeTranslationFactory};
-pub use text_translation::{OpenAITextTranslationClient, OpenAITextTranslationError};
+pub use text_translation::OpenAITextTranslationClient;
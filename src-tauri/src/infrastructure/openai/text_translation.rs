@@ -1,169 +1,238 @@
+use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::json;
-use std::error::Error as StdError;
 use std::time::Duration;
 
-use futures_util::StreamExt;
-use reqwest::StatusCode;
+use crate::domain::{
+    ContextualTranslation, LlmTextClient, TextTranslationError, TextTranslator,
+    TranslationContextSegment, TranslationGlossaryConfig,
+};
+use crate::infrastructure::translation::http::{
+    format_reqwest_error, map_http_error, read_response_body, resolve_api_url,
+    TEXT_TRANSLATION_TIMEOUT,
+};
 
+const OPENAI_API_BASE_URL: &str = "https://api.openai.com/v1";
 const OPENAI_RESPONSES_URL: &str = "https://api.openai.com/v1/responses";
 const DEFAULT_TEXT_TRANSLATION_MODEL: &str = "gpt-5-mini";
-const MAX_TEXT_TRANSLATION_RESPONSE_BYTES: usize = 1024 * 1024;
-
-#[derive(Debug, Clone, thiserror::Error)]
-pub enum OpenAITextTranslationError {
-    #[error("Authentication: {0}")]
-    Authentication(String),
-    #[error("Rate limited: {0}")]
-    RateLimited(String),
-    #[error("Connection: {0}")]
-    Connection(String),
-    #[error("Protocol: {0}")]
-    Protocol(String),
-}
 
-impl OpenAITextTranslationError {
-    pub fn error_type(&self) -> &'static str {
-        match self {
-            Self::Authentication(_) => "authentication",
-            Self::RateLimited(_) => "rate_limited",
-            Self::Connection(_) => "connection",
-            Self::Protocol(_) => "processing",
-        }
-    }
-}
+/// Префикс ответа, которым модель просит заменить предыдущий субтитр
+const CAPTION_MERGE_MARKER: &str = "[MERGE]";
 
 #[derive(Clone)]
 pub struct OpenAITextTranslationClient {
     api_key: String,
+    endpoint: String,
     model: String,
+    glossary: TranslationGlossaryConfig,
     client: reqwest::Client,
 }
 
 impl OpenAITextTranslationClient {
-    pub fn new(api_key: String) -> Result<Self, OpenAITextTranslationError> {
+    pub fn new(api_key: String) -> Result<Self, TextTranslationError> {
+        Self::with_endpoint(api_key, None, None, TEXT_TRANSLATION_TIMEOUT)
+    }
+
+    /// Клиент Responses API с другим сервером и/или моделью.
+    ///
+    /// `endpoint` — base URL (`http://localhost:11434/v1`) или полный URL `.../responses`;
+    /// для не-OpenAI сервера ключ необязателен. `model: None` — модель перевода по умолчанию.
+    pub fn with_endpoint(
+        api_key: String,
+        endpoint: Option<&str>,
+        model: Option<&str>,
+        timeout: Duration,
+    ) -> Result<Self, TextTranslationError> {
         let model = resolve_text_translation_model(
-            std::env::var("VOICETEXT_INCOMING_TRANSLATION_MODEL").ok(),
+            model
+                .map(ToString::to_string)
+                .or_else(|| std::env::var("VOICETEXT_INCOMING_TRANSLATION_MODEL").ok()),
         );
         let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(12))
+            .timeout(timeout)
             .build()
-            .map_err(|e| OpenAITextTranslationError::Connection(format_reqwest_error(&e)))?;
+            .map_err(|e| TextTranslationError::Connection(format_reqwest_error(&e)))?;
 
         Ok(Self {
             api_key: api_key.trim().to_string(),
+            endpoint: resolve_responses_endpoint(endpoint),
             model,
+            glossary: TranslationGlossaryConfig::default(),
             client,
         })
     }
 
+    /// Термины глоссария и do-not-translate добавляются в инструкцию перевода
+    pub fn with_glossary(mut self, glossary: TranslationGlossaryConfig) -> Self {
+        self.glossary = glossary;
+        self
+    }
+
     pub async fn translate_text(
         &self,
         text: &str,
         target_language: &str,
-    ) -> Result<String, OpenAITextTranslationError> {
+    ) -> Result<String, TextTranslationError> {
+        self.respond(
+            &translation_instructions(target_language, &self.glossary),
+            text,
+        )
+        .await
+    }
+
+    /// Один запрос к Responses API: `instructions` + текст, в ответ — только текст модели.
+    pub async fn respond(
+        &self,
+        instructions: &str,
+        text: &str,
+    ) -> Result<String, TextTranslationError> {
         let input = text.trim();
         if input.is_empty() {
             return Ok(String::new());
         }
-        if self.api_key.trim().is_empty() {
-            return Err(OpenAITextTranslationError::Authentication(
+        if self.api_key.is_empty() && self.endpoint == OPENAI_RESPONSES_URL {
+            return Err(TextTranslationError::Authentication(
                 "OPENAI_API_KEY не задан".to_string(),
             ));
         }
 
         let body = json!({
             "model": self.model,
-            "instructions": format!(
-                "Translate speech transcript into {target_language}. Return only the translation. Preserve meaning, names, numbers, and technical terms. Do not explain."
-            ),
+            "instructions": instructions,
             "input": input,
         });
 
-        let response = self
-            .client
-            .post(OPENAI_RESPONSES_URL)
-            .bearer_auth(&self.api_key)
-            .json(&body)
+        let mut request = self.client.post(&self.endpoint).json(&body);
+        if !self.api_key.is_empty() {
+            request = request.bearer_auth(&self.api_key);
+        }
+        let response = request
             .send()
             .await
-            .map_err(|e| OpenAITextTranslationError::Connection(format_reqwest_error(&e)))?;
+            .map_err(|e| TextTranslationError::Connection(format_reqwest_error(&e)))?;
 
-        let status = response.status();
-        let text_body = read_bounded_response_body(response)
-            .await
-            .map_err(|error| classify_response_body_error(status, error))?;
+        let (status, text_body) = read_response_body(response, "OpenAI").await?;
 
         if !status.is_success() {
             let message = extract_openai_error_message(&text_body)
                 .unwrap_or_else(|| format!("OpenAI HTTP {}", status.as_u16()));
-            return Err(map_openai_http_error(status, message));
+            return Err(map_http_error(status, message));
         }
 
         let parsed: ResponsesApiResponse = serde_json::from_str(&text_body).map_err(|e| {
-            OpenAITextTranslationError::Protocol(format!("invalid OpenAI response: {}", e))
+            TextTranslationError::Protocol(format!("invalid OpenAI response: {}", e))
         })?;
 
         extract_response_text(parsed).ok_or_else(|| {
-            OpenAITextTranslationError::Protocol("OpenAI response has no output text".to_string())
+            TextTranslationError::Protocol("OpenAI response has no output text".to_string())
         })
     }
 }
 
-fn classify_response_body_error(
-    status: StatusCode,
-    error: OpenAITextTranslationError,
-) -> OpenAITextTranslationError {
-    match error {
-        OpenAITextTranslationError::Protocol(message) if !status.is_success() => {
-            map_openai_http_error(status, message)
-        }
-        error => error,
+#[async_trait]
+impl LlmTextClient for OpenAITextTranslationClient {
+    async fn complete(
+        &self,
+        instructions: &str,
+        input: &str,
+    ) -> Result<String, TextTranslationError> {
+        self.respond(instructions, input).await
     }
 }
 
-async fn read_bounded_response_body(
-    response: reqwest::Response,
-) -> Result<String, OpenAITextTranslationError> {
-    if response
-        .content_length()
-        .is_some_and(|length| length > MAX_TEXT_TRANSLATION_RESPONSE_BYTES as u64)
-    {
-        return Err(OpenAITextTranslationError::Protocol(format!(
-            "OpenAI response exceeds {} bytes",
-            MAX_TEXT_TRANSLATION_RESPONSE_BYTES
-        )));
+#[async_trait]
+impl TextTranslator for OpenAITextTranslationClient {
+    async fn translate_text(
+        &self,
+        text: &str,
+        target_language: &str,
+    ) -> Result<String, TextTranslationError> {
+        OpenAITextTranslationClient::translate_text(self, text, target_language).await
     }
 
-    let mut body = Vec::new();
-    let mut stream = response.bytes_stream();
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|error| {
-            OpenAITextTranslationError::Connection(format_reqwest_error(&error))
-        })?;
-        append_bounded_response_chunk(&mut body, &chunk)?;
+    async fn translate_with_context(
+        &self,
+        text: &str,
+        target_language: &str,
+        context: &[TranslationContextSegment],
+    ) -> Result<ContextualTranslation, TextTranslationError> {
+        if context.is_empty() || text.trim().is_empty() {
+            return OpenAITextTranslationClient::translate_text(self, text, target_language)
+                .await
+                .map(ContextualTranslation::standalone);
+        }
+        let output = self
+            .respond(
+                &contextual_translation_instructions(target_language, &self.glossary),
+                &contextual_translation_input(context, text),
+            )
+            .await?;
+        Ok(parse_contextual_translation(output))
     }
+}
 
-    String::from_utf8(body).map_err(|error| {
-        OpenAITextTranslationError::Protocol(format!(
-            "OpenAI response is not valid UTF-8: {}",
-            error
-        ))
-    })
+/// Инструкция перевода для LLM; общая для Responses API и OpenAI-compatible chat
+pub(crate) fn translation_instructions(
+    target_language: &str,
+    glossary: &TranslationGlossaryConfig,
+) -> String {
+    let instructions = format!(
+        "Translate speech transcript into {target_language}. Return only the translation. Preserve meaning, names, numbers, and technical terms. Do not explain."
+    );
+    match glossary.prompt_hint(target_language) {
+        Some(hint) => format!("{instructions} {hint}"),
+        None => instructions,
+    }
 }
 
-fn append_bounded_response_chunk(
-    body: &mut Vec<u8>,
-    chunk: &[u8],
-) -> Result<(), OpenAITextTranslationError> {
-    if body.len().saturating_add(chunk.len()) > MAX_TEXT_TRANSLATION_RESPONSE_BYTES {
-        return Err(OpenAITextTranslationError::Protocol(format!(
-            "OpenAI response exceeds {} bytes",
-            MAX_TEXT_TRANSLATION_RESPONSE_BYTES
-        )));
+/// Инструкция перевода сегмента субтитров с предыдущими сегментами в `<context>`
+pub(crate) fn contextual_translation_instructions(
+    target_language: &str,
+    glossary: &TranslationGlossaryConfig,
+) -> String {
+    format!(
+        "{} The input has earlier caption segments in <context> with the translations already shown to the viewer, and a new segment in <segment>. Use the context only to resolve pronouns, gender agreement and sentences split across segments; never translate the context again. Translate only <segment>. If <segment> finishes a sentence cut off in the last context segment and that shown translation has to change, return {CAPTION_MERGE_MARKER} followed by one translation of the last context segment and <segment> together.",
+        translation_instructions(target_language, glossary)
+    )
+}
+
+pub(crate) fn contextual_translation_input(
+    context: &[TranslationContextSegment],
+    text: &str,
+) -> String {
+    let mut input = String::from("<context>\n");
+    for segment in context {
+        input.push_str(&format!(
+            "<source>{}</source>\n<translation>{}</translation>\n",
+            segment.source.trim(),
+            segment.translation.trim()
+        ));
+    }
+    input.push_str(&format!("</context>\n<segment>{}</segment>", text.trim()));
+    input
+}
+
+/// Ответ модели: маркер склейки в начале, иногда модель повторяет тег `<segment>`
+pub(crate) fn parse_contextual_translation(output: String) -> ContextualTranslation {
+    let output = output.trim();
+    let (text, revises_previous) = match output.strip_prefix(CAPTION_MERGE_MARKER) {
+        Some(rest) => (rest.trim(), true),
+        None => (output, false),
+    };
+    let text = text
+        .strip_prefix("<segment>")
+        .and_then(|text| text.strip_suffix("</segment>"))
+        .unwrap_or(text)
+        .trim()
+        .to_string();
+    ContextualTranslation {
+        revises_previous: revises_previous && !text.is_empty(),
+        text,
     }
-    body.extend_from_slice(chunk);
-    Ok(())
+}
+
+fn resolve_responses_endpoint(value: Option<&str>) -> String {
+    resolve_api_url(value, OPENAI_API_BASE_URL, "/responses")
 }
 
 fn resolve_text_translation_model(value: Option<String>) -> String {
@@ -232,7 +301,7 @@ fn extract_response_text(response: ResponsesApiResponse) -> Option<String> {
     }
 }
 
-fn extract_openai_error_message(body: &str) -> Option<String> {
+pub(crate) fn extract_openai_error_message(body: &str) -> Option<String> {
     serde_json::from_str::<OpenAIErrorResponse>(body)
         .ok()
         .and_then(|parsed| parsed.error.map(|err| err.message))
@@ -240,53 +309,6 @@ fn extract_openai_error_message(body: &str) -> Option<String> {
         .filter(|message| !message.is_empty())
 }
 
-fn map_openai_http_error(status: StatusCode, message: String) -> OpenAITextTranslationError {
-    let lower_message = message.to_lowercase();
-    if status == StatusCode::UNAUTHORIZED
-        || status == StatusCode::FORBIDDEN
-        || lower_message.contains("invalid api key")
-        || lower_message.contains("unauthorized")
-    {
-        return OpenAITextTranslationError::Authentication(message);
-    }
-
-    if status == StatusCode::TOO_MANY_REQUESTS
-        || lower_message.contains("rate limit")
-        || lower_message.contains("quota")
-        || lower_message.contains("billing")
-        || lower_message.contains("maximum monthly spend")
-    {
-        return OpenAITextTranslationError::RateLimited(message);
-    }
-
-    OpenAITextTranslationError::Connection(message)
-}
-
-fn format_reqwest_error(err: &reqwest::Error) -> String {
-    let mut parts = vec![err.to_string()];
-
-    if err.is_timeout() {
-        parts.push("kind=timeout".to_string());
-    }
-    if err.is_connect() {
-        parts.push("kind=connect".to_string());
-    }
-    if err.is_request() {
-        parts.push("kind=request".to_string());
-    }
-    if err.is_body() {
-        parts.push("kind=body".to_string());
-    }
-
-    let mut source = err.source();
-    while let Some(cause) = source {
-        parts.push(format!("cause={}", cause));
-        source = cause.source();
-    }
-
-    parts.join("; ")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -353,51 +375,68 @@ mod tests {
     }
 
     #[test]
-    fn maps_quota_message_to_rate_limited_even_without_429_status() {
-        let err = map_openai_http_error(
-            StatusCode::BAD_REQUEST,
-            "You exceeded your current quota, please check your billing details".to_string(),
-        );
+    fn translation_instructions_include_glossary_for_target_language() {
+        let glossary = TranslationGlossaryConfig {
+            do_not_translate: vec!["VoicetextAI".to_string()],
+            ..TranslationGlossaryConfig::default()
+        };
+
+        let instructions = translation_instructions("de", &glossary);
 
-        assert!(matches!(err, OpenAITextTranslationError::RateLimited(_)));
+        assert!(instructions.starts_with("Translate speech transcript into de."));
+        assert!(instructions
+            .ends_with("Keep these terms unchanged, with the same spelling: \"VoicetextAI\"."));
+        assert!(
+            !translation_instructions("de", &TranslationGlossaryConfig::default())
+                .contains("Keep these terms")
+        );
     }
 
     #[test]
-    fn maps_auth_message_to_authentication_even_without_401_status() {
-        let err = map_openai_http_error(
-            StatusCode::BAD_REQUEST,
-            "Invalid API key provided".to_string(),
+    fn contextual_input_wraps_context_and_parses_merge_marker() {
+        let input = contextual_translation_input(
+            &[TranslationContextSegment::new("She said", "Она сказала")],
+            " that it works ",
+        );
+        assert_eq!(
+            input,
+            "<context>\n<source>She said</source>\n<translation>Она сказала</translation>\n</context>\n<segment>that it works</segment>"
         );
 
-        assert!(matches!(err, OpenAITextTranslationError::Authentication(_)));
+        assert_eq!(
+            parse_contextual_translation(" [MERGE] Она сказала, что это работает ".to_string()),
+            ContextualTranslation {
+                text: "Она сказала, что это работает".to_string(),
+                revises_previous: true,
+            }
+        );
+        assert_eq!(
+            parse_contextual_translation("<segment>что это работает</segment>".to_string()),
+            ContextualTranslation::standalone("что это работает".to_string())
+        );
+        assert!(!parse_contextual_translation("[MERGE]".to_string()).revises_previous);
     }
 
     #[test]
-    fn bounded_response_chunks_reject_streamed_overflow() {
-        let mut body = vec![0; MAX_TEXT_TRANSLATION_RESPONSE_BYTES - 1];
+    fn responses_endpoint_accepts_base_url_or_full_url() {
+        assert_eq!(resolve_responses_endpoint(None), OPENAI_RESPONSES_URL);
+        assert_eq!(resolve_responses_endpoint(Some("  ")), OPENAI_RESPONSES_URL);
+        assert_eq!(
+            resolve_responses_endpoint(Some(" http://localhost:11434/v1/ ")),
+            "http://localhost:11434/v1/responses"
+        );
+        assert_eq!(
+            resolve_responses_endpoint(Some("http://127.0.0.1:8080/v1/responses")),
+            "http://127.0.0.1:8080/v1/responses"
+        );
+    }
 
-        append_bounded_response_chunk(&mut body, &[1]).expect("exact limit is accepted");
-        let error = append_bounded_response_chunk(&mut body, &[2]).unwrap_err();
+    #[tokio::test]
+    async fn default_endpoint_requires_api_key() {
+        let client = OpenAITextTranslationClient::new(" ".to_string()).expect("valid client");
 
-        assert!(matches!(error, OpenAITextTranslationError::Protocol(_)));
-        assert_eq!(body.len(), MAX_TEXT_TRANSLATION_RESPONSE_BYTES);
-    }
+        let error = client.respond("Fix grammar.", "hello").await.unwrap_err();
 
-    #[test]
-    fn oversized_error_body_keeps_http_auth_and_rate_limit_types() {
-        assert!(matches!(
-            classify_response_body_error(
-                StatusCode::UNAUTHORIZED,
-                OpenAITextTranslationError::Protocol("oversized".to_string())
-            ),
-            OpenAITextTranslationError::Authentication(_)
-        ));
-        assert!(matches!(
-            classify_response_body_error(
-                StatusCode::TOO_MANY_REQUESTS,
-                OpenAITextTranslationError::Protocol("oversized".to_string())
-            ),
-            OpenAITextTranslationError::RateLimited(_)
-        ));
+        assert!(matches!(error, TextTranslationError::Authentication(_)));
     }
 }
@@ -16,7 +16,7 @@ use tokio_tungstenite::{
 
 use crate::domain::{
     AudioChunk, ErrorCallback, SttConfig, SttConnectionCategory, SttConnectionError, SttError,
-    SttProvider, SttResult, Transcription, TranscriptionCallback,
+    SttProvider, SttResult, Transcription, TranscriptionCallback, Word,
 };
 use crate::infrastructure::embedded_keys;
 
@@ -58,6 +58,40 @@ fn pcm_amplitude_stats(samples: &[i16]) -> (i32, i32) {
     (peak, average)
 }
 
+/// Turn.words: `{text, start, end, confidence, word_is_final}`, время в мс от начала стрима.
+/// В отформатированном turn текст слова уже с пунктуацией и регистром.
+fn assemblyai_words(json: &Value) -> Vec<Word> {
+    let Some(words) = json.get("words").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    let formatted = json["turn_is_formatted"].as_bool().unwrap_or(false);
+
+    words
+        .iter()
+        .filter_map(|word| {
+            let text = word["text"].as_str()?;
+            let mut parsed = Word::new(
+                text,
+                word["start"].as_f64().unwrap_or(0.0) / 1000.0,
+                word["end"].as_f64().unwrap_or(0.0) / 1000.0,
+            );
+            parsed.confidence = word["confidence"].as_f64().map(|v| v as f32);
+            if formatted {
+                parsed.punctuated = Some(text.to_string());
+            }
+            Some(parsed)
+        })
+        .collect()
+}
+
+/// Тайминг turn по словам: AssemblyAI не отдаёт start/duration сегмента отдельно.
+fn assemblyai_turn_timing(words: &[Word]) -> (f64, f64) {
+    match (words.first(), words.last()) {
+        (Some(first), Some(last)) => (first.start, (last.end - first.start).max(0.0)),
+        _ => (0.0, 0.0),
+    }
+}
+
 fn assemblyai_server_error(message: impl Into<String>) -> SttError {
     let message = message.into();
     let lower = message.to_lowercase();
@@ -108,11 +142,16 @@ fn assemblyai_speech_model(language: &str) -> &'static str {
     }
 }
 
-fn assemblyai_stream_url(base_url: &str, language: &str) -> String {
+fn assemblyai_stream_url(base_url: &str, language: &str, speaker_labels: bool) -> String {
     format!(
-        "{}?sample_rate=16000&encoding=pcm_s16le&speech_model={}&language_detection=true",
+        "{}?sample_rate=16000&encoding=pcm_s16le&speech_model={}&language_detection=true{}",
         base_url,
-        assemblyai_speech_model(language)
+        assemblyai_speech_model(language),
+        if speaker_labels {
+            "&speaker_labels=true"
+        } else {
+            ""
+        }
     )
 }
 
@@ -278,7 +317,11 @@ impl SttProvider for AssemblyAIProvider {
             .map(|config| config.language.clone())
             .unwrap_or_else(|| "en".to_string());
 
-        let url = assemblyai_stream_url(&self.ws_base_url, &configured_language);
+        let speaker_labels = self
+            .config
+            .as_ref()
+            .is_some_and(|config| config.enable_diarization);
+        let url = assemblyai_stream_url(&self.ws_base_url, &configured_language, speaker_labels);
 
         log::debug!("Connecting to {}", url);
 
@@ -734,6 +777,14 @@ impl AssemblyAIProvider {
 
                 if let Some(text) = text {
                     if !text.is_empty() {
+                        let words = assemblyai_words(&json);
+                        let (start, duration) = assemblyai_turn_timing(&words);
+                        // `speaker_label` ("A", "B", ...) есть только при speaker_labels=true
+                        let speaker = json["speaker_label"]
+                            .as_str()
+                            .filter(|label| !label.is_empty() && *label != "UNKNOWN")
+                            .map(str::to_string);
+
                         if is_end_of_turn {
                             log::info!("Final transcript: {}", text);
 
@@ -748,8 +799,11 @@ impl AssemblyAIProvider {
                                     .duration_since(std::time::UNIX_EPOCH)
                                     .unwrap_or_else(|_| std::time::Duration::from_secs(0))
                                     .as_millis() as i64,
-                                start: 0.0,    // AssemblyAI не предоставляет start время
-                                duration: 0.0, // AssemblyAI не предоставляет duration
+                                start,
+                                duration,
+                                provider: None,
+                                words,
+                                speaker: speaker.clone(),
                             };
 
                             call_assemblyai_callback("final transcription", || {
@@ -769,8 +823,11 @@ impl AssemblyAIProvider {
                                     .duration_since(std::time::UNIX_EPOCH)
                                     .unwrap_or_else(|_| std::time::Duration::from_secs(0))
                                     .as_millis() as i64,
-                                start: 0.0,    // AssemblyAI не предоставляет start время
-                                duration: 0.0, // AssemblyAI не предоставляет duration
+                                start,
+                                duration,
+                                provider: None,
+                                words,
+                                speaker: speaker.clone(),
                             };
 
                             call_assemblyai_callback("partial transcription", || {
@@ -893,6 +950,41 @@ mod tests {
         assert_eq!(average, 13_107);
     }
 
+    #[test]
+    fn turn_words_provide_timing_in_seconds() {
+        let finals = Arc::new(StdMutex::new(Vec::new()));
+        let finals_clone = finals.clone();
+        let on_partial: TranscriptionCallback = Arc::new(|_| {});
+        let on_final: TranscriptionCallback =
+            Arc::new(move |t| finals_clone.lock().unwrap().push(t));
+
+        AssemblyAIProvider::handle_message(
+            json!({
+                "type": "Turn",
+                "end_of_turn": true,
+                "turn_is_formatted": true,
+                "transcript": "Hi there.",
+                "speaker_label": "B",
+                "words": [
+                    { "text": "Hi", "start": 1200, "end": 1400, "confidence": 0.9, "word_is_final": true },
+                    { "text": "there.", "start": 1500, "end": 1900, "confidence": 0.6, "word_is_final": true }
+                ]
+            }),
+            &on_partial,
+            &on_final,
+            "en",
+        );
+
+        let finals = finals.lock().unwrap();
+        let transcription = &finals[0];
+        assert!((transcription.start - 1.2).abs() < 1e-9);
+        assert!((transcription.duration - 0.7).abs() < 1e-9);
+        assert_eq!(transcription.words.len(), 2);
+        assert_eq!(transcription.words[1].display_text(), "there.");
+        assert_eq!(transcription.words[1].confidence, Some(0.6));
+        assert_eq!(transcription.speaker.as_deref(), Some("B"));
+    }
+
     #[test]
     fn amplitude_stats_handle_empty_audio() {
         assert_eq!(pcm_amplitude_stats(&[]), (0, 0));
@@ -928,10 +1020,14 @@ mod tests {
         assert_eq!(assemblyai_speech_model("ru"), "whisper-rt");
         assert_eq!(assemblyai_speech_model("ja-JP"), "whisper-rt");
 
-        let russian_url = assemblyai_stream_url(ASSEMBLYAI_WS_URL, "ru");
+        let russian_url = assemblyai_stream_url(ASSEMBLYAI_WS_URL, "ru", false);
         assert!(russian_url.contains("speech_model=whisper-rt"));
         assert!(russian_url.contains("language_detection=true"));
         assert!(!russian_url.contains("language_code="));
+        assert!(!russian_url.contains("speaker_labels"));
+
+        let diarized_url = assemblyai_stream_url(ASSEMBLYAI_WS_URL, "en", true);
+        assert!(diarized_url.ends_with("&speaker_labels=true"));
     }
 
     #[test]
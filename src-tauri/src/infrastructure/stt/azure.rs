@@ -0,0 +1,1104 @@
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use http::Request;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::{
+    connect_async_with_config, tungstenite::Message, MaybeTlsStream, WebSocketStream,
+};
+
+use crate::domain::{
+    AudioChunk, ErrorCallback, SttConfig, SttConnectionCategory, SttConnectionDetails,
+    SttConnectionError, SttError, SttProvider, SttResult, Transcription, TranscriptionCallback,
+    Word,
+};
+
+/// Azure Speech Services real-time STT provider
+///
+/// Endpoint: wss://{region}.stt.speech.microsoft.com/speech/recognition/conversation/cognitiveservices/v1
+/// Protocol: Speech SDK WebSocket protocol (text/binary frames с HTTP-подобными заголовками)
+///
+/// Protocol:
+/// 1. Connect with `Ocp-Apim-Subscription-Key` header and `language` query parameter
+/// 2. Send `speech.config` and `speech.context` (phrase list) text messages
+/// 3. Stream `audio` binary messages: the first one carries a RIFF header, an empty one ends the turn
+/// 4. Receive `speech.hypothesis` (partial) and `speech.phrase` (final), drain until `turn.end`
+const AZURE_STT_HOST_SUFFIX: &str = "stt.speech.microsoft.com";
+const AZURE_STT_PATH: &str = "/speech/recognition/conversation/cognitiveservices/v1";
+const AZURE_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const AZURE_SEND_TIMEOUT: Duration = Duration::from_secs(5);
+const AZURE_TURN_END_TIMEOUT: Duration = Duration::from_secs(5);
+const AZURE_SAMPLE_RATE: u32 = 16_000;
+// Azure считает Offset/Duration в тиках по 100 нс.
+const AZURE_TICKS_PER_SECOND: f64 = 10_000_000.0;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+fn azure_ws_base_url(region: &str) -> String {
+    format!(
+        "wss://{}.{}{}",
+        region, AZURE_STT_HOST_SUFFIX, AZURE_STT_PATH
+    )
+}
+
+fn azure_stream_url(base_url: &str, config: &SttConfig) -> String {
+    format!(
+        "{}?language={}&format=detailed&wordLevelTimestamps=true&profanity={}",
+        base_url,
+        urlencoding::encode(&super::regional_language_tag(&config.language)),
+        if config.filter_profanity {
+            "masked"
+        } else {
+            "raw"
+        }
+    )
+}
+
+fn azure_request_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string().to_uppercase()
+}
+
+fn azure_timestamp() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+fn azure_text_message(path: &str, request_id: &str, body: &Value) -> String {
+    format!(
+        "Path: {}\r\nX-RequestId: {}\r\nX-Timestamp: {}\r\nContent-Type: application/json\r\n\r\n{}",
+        path,
+        request_id,
+        azure_timestamp(),
+        body
+    )
+}
+
+/// Бинарное сообщение: 2 байта длины заголовков (big endian), заголовки, затем PCM.
+fn azure_audio_message(request_id: &str, payload: &[u8]) -> Vec<u8> {
+    let headers = format!(
+        "Path: audio\r\nX-RequestId: {}\r\nX-Timestamp: {}\r\nContent-Type: audio/x-wav\r\n",
+        request_id,
+        azure_timestamp()
+    );
+    let mut message = Vec::with_capacity(2 + headers.len() + payload.len());
+    message.extend_from_slice(&(headers.len() as u16).to_be_bytes());
+    message.extend_from_slice(headers.as_bytes());
+    message.extend_from_slice(payload);
+    message
+}
+
+fn azure_speech_config_body() -> Value {
+    json!({
+        "context": {
+            "system": {
+                "name": "VoicetextAI",
+                "version": env!("CARGO_PKG_VERSION"),
+                "build": "Rust",
+                "lang": "Rust"
+            },
+            "os": {
+                "platform": std::env::consts::OS,
+                "name": std::env::consts::OS,
+                "version": ""
+            },
+            "audio": {
+                "source": {
+                    "type": "Stream",
+                    "samplerate": AZURE_SAMPLE_RATE,
+                    "bitspersample": 16,
+                    "channelcount": 1
+                }
+            }
+        }
+    })
+}
+
+/// Phrase list передаётся через `speech.context` как динамическая грамматика (dgi).
+fn azure_speech_context_body(keyterms: Option<&str>) -> Option<Value> {
+    let items: Vec<Value> = keyterms?
+        .split(',')
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .map(|term| json!({ "Text": term }))
+        .collect();
+    if items.is_empty() {
+        return None;
+    }
+
+    Some(json!({
+        "dgi": {
+            "Groups": [{ "Type": "Generic", "Items": items }]
+        }
+    }))
+}
+
+/// Разбираем текстовое сообщение Azure на `Path` и JSON body.
+fn parse_azure_text_message(text: &str) -> Option<(String, Value)> {
+    let (headers, body) = text.split_once("\r\n\r\n")?;
+    let path = headers.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("path")
+            .then(|| value.trim().to_ascii_lowercase())
+    })?;
+    let body = if body.trim().is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(body).ok()?
+    };
+    Some((path, body))
+}
+
+fn azure_ticks_to_secs(value: &Value) -> f64 {
+    value.as_f64().unwrap_or(0.0) / AZURE_TICKS_PER_SECOND
+}
+
+/// `NBest[0].Words` (при `wordLevelTimestamps=true`): Offset/Duration в тиках по 100 ns.
+/// Слова приходят в лексической форме, без пунктуации.
+fn azure_words(best: Option<&Value>) -> Vec<Word> {
+    let Some(words) = best.and_then(|best| best["Words"].as_array()) else {
+        return Vec::new();
+    };
+
+    words
+        .iter()
+        .filter_map(|word| {
+            let text = word["Word"].as_str()?;
+            let start = azure_ticks_to_secs(&word["Offset"]);
+            let mut parsed = Word::new(text, start, start + azure_ticks_to_secs(&word["Duration"]));
+            parsed.confidence = word["Confidence"].as_f64().map(|v| v as f32);
+            Some(parsed)
+        })
+        .collect()
+}
+
+/// Событие распознавания, полученное от Azure.
+#[derive(Debug)]
+enum AzureRecognitionEvent {
+    Partial(Transcription),
+    Final(Transcription),
+    TurnEnd,
+    Error(SttError),
+    Ignored,
+}
+
+fn azure_recognition_event(path: &str, body: &Value, language: &str) -> AzureRecognitionEvent {
+    match path {
+        "speech.hypothesis" | "speech.fragment" => {
+            let text = body["Text"].as_str().unwrap_or_default().trim();
+            if text.is_empty() {
+                return AzureRecognitionEvent::Ignored;
+            }
+            AzureRecognitionEvent::Partial(
+                Transcription::partial(text.to_string())
+                    .with_language(language.to_string())
+                    .with_timing(
+                        azure_ticks_to_secs(&body["Offset"]),
+                        azure_ticks_to_secs(&body["Duration"]),
+                    ),
+            )
+        }
+        "speech.phrase" => match body["RecognitionStatus"].as_str() {
+            Some("Success") => {
+                let best = body["NBest"].get(0);
+                let text = best
+                    .and_then(|best| best["Display"].as_str())
+                    .or_else(|| body["DisplayText"].as_str())
+                    .unwrap_or_default()
+                    .trim();
+                if text.is_empty() {
+                    return AzureRecognitionEvent::Ignored;
+                }
+                let mut transcription = Transcription::final_result(text.to_string())
+                    .with_language(language.to_string())
+                    .with_timing(
+                        azure_ticks_to_secs(&body["Offset"]),
+                        azure_ticks_to_secs(&body["Duration"]),
+                    );
+                transcription.confidence = best
+                    .and_then(|best| best["Confidence"].as_f64())
+                    .map(|confidence| confidence as f32);
+                transcription.words = azure_words(best);
+                AzureRecognitionEvent::Final(transcription)
+            }
+            // Тишина/неразборчивая речь — не ошибка, просто пустая фраза.
+            Some("NoMatch")
+            | Some("InitialSilenceTimeout")
+            | Some("BabbleTimeout")
+            | Some("EndOfDictation") => AzureRecognitionEvent::Ignored,
+            Some(status) => AzureRecognitionEvent::Error(SttError::Processing(format!(
+                "Azure Speech recognition failed: {}",
+                status
+            ))),
+            None => AzureRecognitionEvent::Ignored,
+        },
+        "turn.end" => AzureRecognitionEvent::TurnEnd,
+        _ => AzureRecognitionEvent::Ignored,
+    }
+}
+
+/// Azure закрывает сокет с кодом 1007 (невалидные данные/конфиг), 1008 (политика:
+/// ключ, квота, лимиты) или 1011 (внутренняя ошибка сервиса).
+fn azure_close_error(frame: Option<&CloseFrame<'_>>, context: &str) -> SttError {
+    let (message, code, reason) = match frame {
+        Some(frame) => {
+            let code = u16::from(frame.code);
+            let reason = frame.reason.to_string();
+            let message = if reason.trim().is_empty() {
+                format!("{} (close code {})", context, code)
+            } else {
+                format!("{} (close code {}): {}", context, code, reason)
+            };
+            (message, Some(code), reason)
+        }
+        None => (context.to_string(), None, String::new()),
+    };
+    let lower = reason.to_lowercase();
+    if lower.contains("auth") || lower.contains("subscription key") || lower.contains("401") {
+        return SttError::Authentication(message);
+    }
+    if code == Some(1007) {
+        return SttError::Configuration(message);
+    }
+
+    let category = if lower.contains("quota") {
+        SttConnectionCategory::ProviderQuotaExceeded
+    } else if lower.contains("too many") || lower.contains("throttl") {
+        SttConnectionCategory::RateLimited
+    } else if lower.contains("timeout") {
+        SttConnectionCategory::Timeout
+    } else if matches!(code, Some(1011..=1014)) {
+        SttConnectionCategory::ServerUnavailable
+    } else {
+        SttConnectionCategory::Closed
+    };
+    SttError::Connection(SttConnectionError {
+        message,
+        details: SttConnectionDetails {
+            category: Some(category),
+            ws_close_code: code,
+            ..Default::default()
+        },
+    })
+}
+
+fn azure_closed_error(message: impl Into<String>) -> SttError {
+    SttError::Connection(SttConnectionError::with_category(
+        message,
+        SttConnectionCategory::Closed,
+    ))
+}
+
+fn azure_timeout_error(message: impl Into<String>) -> SttError {
+    SttError::Connection(SttConnectionError::with_category(
+        message,
+        SttConnectionCategory::Timeout,
+    ))
+}
+
+async fn await_azure_send<F>(future: F, operation: &str) -> SttResult<()>
+where
+    F: Future<Output = Result<(), tokio_tungstenite::tungstenite::Error>>,
+{
+    match tokio::time::timeout(AZURE_SEND_TIMEOUT, future).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(error)) => Err(azure_closed_error(format!(
+            "Azure Speech {} failed: {}",
+            operation, error
+        ))),
+        Err(_) => Err(azure_timeout_error(format!(
+            "Azure Speech {} timed out after {} ms",
+            operation,
+            AZURE_SEND_TIMEOUT.as_millis()
+        ))),
+    }
+}
+
+fn call_azure_callback(label: &str, callback: impl FnOnce()) {
+    if catch_unwind(AssertUnwindSafe(callback)).is_err() {
+        log::error!("Azure Speech {} callback panicked", label);
+    }
+}
+
+pub struct AzureProvider {
+    config: Option<SttConfig>,
+    is_streaming: bool,
+    subscription_key: Option<String>,
+    ws_write: Option<futures_util::stream::SplitSink<WsStream, Message>>,
+    receiver_task: Option<JoinHandle<()>>,
+    stop_requested: Arc<AtomicBool>,
+    request_id: String,
+    wav_header_sent: bool,
+    ws_base_url: Option<String>,
+}
+
+impl AzureProvider {
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            is_streaming: false,
+            subscription_key: None,
+            ws_write: None,
+            receiver_task: None,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+            request_id: String::new(),
+            wav_header_sent: false,
+            ws_base_url: None,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_ws_base_url(ws_base_url: String) -> Self {
+        let mut provider = Self::new();
+        provider.ws_base_url = Some(ws_base_url);
+        provider
+    }
+
+    async fn send_text(&mut self, path: &str, body: &Value) -> SttResult<()> {
+        let message = azure_text_message(path, &self.request_id, body);
+        let write = self.ws_write.as_mut().ok_or_else(|| {
+            SttError::Processing("WebSocket write handle not available".to_string())
+        })?;
+        await_azure_send(write.send(Message::Text(message)), path).await
+    }
+
+    fn reset_session_state(&mut self) {
+        self.ws_write = None;
+        self.is_streaming = false;
+        self.wav_header_sent = false;
+    }
+}
+
+impl Default for AzureProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for AzureProvider {
+    fn drop(&mut self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        super::abort_background_task(&mut self.receiver_task);
+    }
+}
+
+#[async_trait]
+impl SttProvider for AzureProvider {
+    async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
+        log::info!("AzureProvider: Initializing");
+
+        let subscription_key = config
+            .azure_speech_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                SttError::Configuration("Azure Speech subscription key is required".to_string())
+            })?;
+        let region = config
+            .azure_speech_region
+            .as_deref()
+            .map(str::trim)
+            .filter(|region| !region.is_empty())
+            .ok_or_else(|| {
+                SttError::Configuration("Azure Speech region is required".to_string())
+            })?;
+        if !region
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(SttError::Configuration(format!(
+                "Invalid Azure Speech region: {}",
+                region
+            )));
+        }
+
+        let mut config = config.clone();
+        config.azure_speech_region = Some(region.to_ascii_lowercase());
+        self.subscription_key = Some(subscription_key);
+        self.config = Some(config);
+        Ok(())
+    }
+
+    async fn start_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        _on_connection_quality: crate::domain::ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        log::info!("AzureProvider: Starting stream");
+
+        if self.is_streaming {
+            return Err(SttError::Processing("Stream already active".to_string()));
+        }
+
+        let (Some(subscription_key), Some(config)) =
+            (self.subscription_key.clone(), self.config.clone())
+        else {
+            return Err(SttError::Configuration(
+                "Azure Speech provider is not initialized".to_string(),
+            ));
+        };
+
+        let base_url = self.ws_base_url.clone().unwrap_or_else(|| {
+            azure_ws_base_url(config.azure_speech_region.as_deref().unwrap_or_default())
+        });
+        let url = azure_stream_url(&base_url, &config);
+        let host = url
+            .split("://")
+            .nth(1)
+            .and_then(|rest| rest.split('/').next())
+            .unwrap_or_default()
+            .to_string();
+
+        log::debug!("Connecting to {}", url);
+
+        let request = Request::builder()
+            .method("GET")
+            .uri(&url)
+            .header("Host", host)
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .header("Sec-WebSocket-Version", "13")
+            .header(
+                "Sec-WebSocket-Key",
+                tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+            )
+            .header("Ocp-Apim-Subscription-Key", &subscription_key)
+            .header("X-ConnectionId", azure_request_id())
+            .body(())
+            .map_err(|e| {
+                SttError::Connection(SttConnectionError::simple(format!(
+                    "Failed to build WS request: {}",
+                    e
+                )))
+            })?;
+
+        let (ws_stream, _response) = tokio::time::timeout(
+            AZURE_CONNECT_TIMEOUT,
+            connect_async_with_config(request, Some(super::streaming_websocket_config()), false),
+        )
+        .await
+        .map_err(|_| {
+            azure_timeout_error(format!(
+                "Azure Speech WebSocket connection timed out after {} ms",
+                AZURE_CONNECT_TIMEOUT.as_millis()
+            ))
+        })?
+        .map_err(|error| super::websocket_handshake_error("Azure Speech", error))?;
+
+        log::info!("Azure Speech WebSocket connected");
+
+        let (write, mut read) = ws_stream.split();
+        self.ws_write = Some(write);
+        self.request_id = azure_request_id();
+        self.wav_header_sent = false;
+        self.stop_requested.store(false, Ordering::SeqCst);
+
+        // Конфиг и phrase list должны уйти до первого аудио-сообщения turn'а.
+        let mut setup_result = self
+            .send_text("speech.config", &azure_speech_config_body())
+            .await;
+        if setup_result.is_ok() {
+            if let Some(context) = azure_speech_context_body(config.streaming_keyterms.as_deref()) {
+                setup_result = self.send_text("speech.context", &context).await;
+            }
+        }
+        if let Err(error) = setup_result {
+            self.reset_session_state();
+            return Err(error);
+        }
+
+        let stop_requested = self.stop_requested.clone();
+        let language = super::regional_language_tag(&config.language);
+        let receiver_task = tokio::spawn(async move {
+            log::debug!("Azure Speech receiver task started");
+
+            while let Some(msg_result) = read.next().await {
+                match msg_result {
+                    Ok(Message::Text(text)) => {
+                        let Some((path, body)) = parse_azure_text_message(&text) else {
+                            log::warn!("Failed to parse Azure Speech message: {}", text);
+                            continue;
+                        };
+                        log::debug!("Azure Speech message: {}", path);
+
+                        match azure_recognition_event(&path, &body, &language) {
+                            AzureRecognitionEvent::Partial(transcription) => {
+                                log::debug!("Partial transcript: {}", transcription.text);
+                                call_azure_callback("partial transcription", || {
+                                    on_partial(transcription)
+                                });
+                            }
+                            AzureRecognitionEvent::Final(transcription) => {
+                                log::info!("Final transcript: {}", transcription.text);
+                                call_azure_callback("final transcription", || {
+                                    on_final(transcription)
+                                });
+                            }
+                            AzureRecognitionEvent::Error(error) => {
+                                log::error!("Azure Speech recognition error: {}", error);
+                                call_azure_callback("error", || on_error(error));
+                            }
+                            AzureRecognitionEvent::TurnEnd => {
+                                if !stop_requested.load(Ordering::SeqCst) {
+                                    // Сервис сам завершил turn (лимит длительности, долгая тишина):
+                                    // дальше аудио уже не распознаётся, отдаём решение о
+                                    // переподключении верхнему уровню.
+                                    call_azure_callback("error", || {
+                                        on_error(azure_closed_error(
+                                            "Azure Speech ended the recognition turn",
+                                        ))
+                                    });
+                                }
+                                break;
+                            }
+                            AzureRecognitionEvent::Ignored => {}
+                        }
+                    }
+                    Ok(Message::Close(frame)) => {
+                        log::info!("Azure Speech WebSocket closed: {:?}", frame);
+                        if !stop_requested.load(Ordering::SeqCst) {
+                            let error =
+                                azure_close_error(frame.as_ref(), "Azure Speech WebSocket closed");
+                            call_azure_callback("error", || on_error(error));
+                        }
+                        break;
+                    }
+                    Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {
+                        log::trace!("Azure Speech received Ping/Pong");
+                    }
+                    Err(e) => {
+                        log::error!("Azure Speech WebSocket error: {}", e);
+                        if !stop_requested.load(Ordering::SeqCst) {
+                            call_azure_callback("error", || {
+                                on_error(azure_closed_error(format!(
+                                    "Azure Speech WebSocket error: {}",
+                                    e
+                                )))
+                            });
+                        }
+                        break;
+                    }
+                    Ok(msg) => {
+                        log::debug!("Azure Speech received unexpected message: {:?}", msg);
+                    }
+                }
+            }
+
+            log::debug!("Azure Speech receiver task ended");
+        });
+
+        self.receiver_task = Some(receiver_task);
+        self.is_streaming = true;
+
+        log::info!("Azure Speech stream started successfully");
+        Ok(())
+    }
+
+    async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
+        if !self.is_streaming {
+            return Err(SttError::Processing("Not streaming".to_string()));
+        }
+
+        let mut payload = if self.wav_header_sent {
+            Vec::with_capacity(chunk.data.len() * 2)
+        } else {
+            super::pcm16_wav_header(AZURE_SAMPLE_RATE, 1, 0)
+        };
+        payload.extend(chunk.data.iter().flat_map(|&sample| sample.to_le_bytes()));
+
+        let message = azure_audio_message(&self.request_id, &payload);
+        let write = self.ws_write.as_mut().ok_or_else(|| {
+            SttError::Processing("WebSocket write handle not available".to_string())
+        })?;
+        await_azure_send(write.send(Message::Binary(message)), "audio send").await?;
+        self.wav_header_sent = true;
+        Ok(())
+    }
+
+    async fn stop_stream(&mut self) -> SttResult<()> {
+        log::info!("AzureProvider: Stopping stream");
+        self.stop_requested.store(true, Ordering::SeqCst);
+
+        if !self.is_streaming {
+            log::warn!("Stream not active");
+            return Ok(());
+        }
+
+        let mut stop_result = Ok(());
+
+        // Пустое аудио-сообщение = конец аудио: Azure дораспознаёт хвост и пришлёт turn.end.
+        if self.wav_header_sent {
+            let end_of_audio = azure_audio_message(&self.request_id, &[]);
+            stop_result = match self.ws_write.as_mut() {
+                Some(write) => {
+                    await_azure_send(write.send(Message::Binary(end_of_audio)), "end of audio")
+                        .await
+                }
+                None => Err(azure_closed_error(
+                    "Azure Speech end of audio could not be sent: WebSocket writer is missing",
+                )),
+            };
+
+            if stop_result.is_ok() {
+                if let Some(mut task) = self.receiver_task.take() {
+                    match tokio::time::timeout(AZURE_TURN_END_TIMEOUT, &mut task).await {
+                        Ok(Ok(())) => {}
+                        Ok(Err(join_error)) => {
+                            stop_result = Err(SttError::Internal(format!(
+                                "Azure Speech receiver task failed during drain: {}",
+                                join_error
+                            )));
+                        }
+                        Err(_) => {
+                            task.abort();
+                            let _ = task.await;
+                            stop_result = Err(azure_timeout_error(format!(
+                                "Azure Speech turn.end timed out after {} ms",
+                                AZURE_TURN_END_TIMEOUT.as_millis()
+                            )));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(write) = self.ws_write.as_mut() {
+            let _ = tokio::time::timeout(AZURE_SEND_TIMEOUT, write.close()).await;
+        }
+        if let Some(task) = self.receiver_task.take() {
+            task.abort();
+            let _ = task.await;
+        }
+
+        self.reset_session_state();
+
+        match &stop_result {
+            Ok(()) => log::info!("Azure Speech stream stopped after turn.end"),
+            Err(error) => log::warn!("Azure Speech stream stop failed: {}", error),
+        }
+        stop_result
+    }
+
+    async fn abort(&mut self) -> SttResult<()> {
+        log::info!("AzureProvider: Aborting stream");
+        self.stop_requested.store(true, Ordering::SeqCst);
+
+        if let Some(task) = self.receiver_task.take() {
+            task.abort();
+            let _ = task.await;
+        }
+        self.reset_session_state();
+
+        log::info!("Azure Speech stream aborted");
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "Azure Speech Services"
+    }
+
+    // `profanity=masked` в URL; пунктуацию Azure выключить не даёт
+    fn supports_profanity_filter(&self) -> bool {
+        true
+    }
+
+    fn is_online(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SttProviderType;
+    use std::sync::Mutex as StdMutex;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_hdr_async;
+    use tokio_tungstenite::tungstenite::handshake::server::{
+        Callback, ErrorResponse, Request as HandshakeRequest, Response as HandshakeResponse,
+    };
+
+    #[derive(Debug, Default)]
+    struct AzureServerCapture {
+        uri: String,
+        subscription_key: Option<String>,
+        text_paths: Vec<String>,
+        context: Option<Value>,
+        audio_payloads: Vec<Vec<u8>>,
+    }
+
+    fn azure_server_text(path: &str, body: Value) -> Message {
+        Message::Text(format!(
+            "X-RequestId: TEST\r\nPath: {}\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{}",
+            path, body
+        ))
+    }
+
+    fn split_audio_message(message: &[u8]) -> (String, Vec<u8>) {
+        let header_len = u16::from_be_bytes([message[0], message[1]]) as usize;
+        let headers = String::from_utf8(message[2..2 + header_len].to_vec()).expect("headers");
+        (headers, message[2 + header_len..].to_vec())
+    }
+
+    /// Запоминает URI и subscription key из handshake
+    struct CaptureHandshake(Arc<StdMutex<AzureServerCapture>>);
+
+    impl Callback for CaptureHandshake {
+        fn on_request(
+            self,
+            request: &HandshakeRequest,
+            response: HandshakeResponse,
+        ) -> Result<HandshakeResponse, ErrorResponse> {
+            let mut capture = self.0.lock().unwrap();
+            capture.uri = request.uri().to_string();
+            capture.subscription_key = request
+                .headers()
+                .get("Ocp-Apim-Subscription-Key")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            Ok(response)
+        }
+    }
+
+    async fn spawn_azure_server() -> (String, JoinHandle<AzureServerCapture>) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock Azure server");
+        let address = listener.local_addr().expect("mock server address");
+        let task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("accept websocket client");
+            let capture = Arc::new(StdMutex::new(AzureServerCapture::default()));
+            let mut websocket = accept_hdr_async(stream, CaptureHandshake(capture.clone()))
+                .await
+                .expect("accept websocket");
+
+            loop {
+                match websocket.next().await.expect("client websocket message") {
+                    Ok(Message::Text(text)) => {
+                        let (path, body) = parse_azure_text_message(&text).expect("azure message");
+                        let mut capture = capture.lock().unwrap();
+                        if path == "speech.context" {
+                            capture.context = Some(body);
+                        }
+                        capture.text_paths.push(path);
+                    }
+                    Ok(Message::Binary(bytes)) => {
+                        let (headers, payload) = split_audio_message(&bytes);
+                        assert!(headers.starts_with("Path: audio\r\n"));
+                        let end_of_audio = payload.is_empty();
+                        capture.lock().unwrap().audio_payloads.push(payload);
+                        if end_of_audio {
+                            break;
+                        }
+                    }
+                    Ok(Message::Close(_)) => panic!("client closed before end of audio"),
+                    Ok(_) => {}
+                    Err(error) => panic!("mock websocket failed: {error}"),
+                }
+            }
+
+            websocket
+                .send(azure_server_text(
+                    "speech.hypothesis",
+                    json!({ "Text": "привет", "Offset": 5_000_000, "Duration": 4_000_000 }),
+                ))
+                .await
+                .expect("send hypothesis");
+            websocket
+                .send(azure_server_text(
+                    "speech.phrase",
+                    json!({
+                        "RecognitionStatus": "Success",
+                        "Offset": 5_000_000,
+                        "Duration": 9_000_000,
+                        "NBest": [{ "Confidence": 0.93, "Display": "Привет, мир." }]
+                    }),
+                ))
+                .await
+                .expect("send phrase");
+            websocket
+                .send(azure_server_text("turn.end", json!({})))
+                .await
+                .expect("send turn.end");
+            let _ = websocket.next().await;
+
+            Arc::try_unwrap(capture)
+                .expect("capture still shared")
+                .into_inner()
+                .unwrap()
+        });
+
+        (format!("ws://{address}{AZURE_STT_PATH}"), task)
+    }
+
+    fn azure_test_config() -> SttConfig {
+        let mut config = SttConfig::new(SttProviderType::Azure).with_language("ru");
+        config.azure_speech_key = Some(" test-key ".to_string());
+        config.azure_speech_region = Some("WestEurope".to_string());
+        config.streaming_keyterms = Some("Kubernetes, VoicetextAI".to_string());
+        config
+    }
+
+    #[test]
+    fn stream_url_uses_region_locale_and_profanity_mode() {
+        let mut config = azure_test_config();
+        let url = azure_stream_url(&azure_ws_base_url("westeurope"), &config);
+        assert_eq!(
+            url,
+            "wss://westeurope.stt.speech.microsoft.com/speech/recognition/conversation/cognitiveservices/v1?language=ru-RU&format=detailed&wordLevelTimestamps=true&profanity=raw"
+        );
+
+        config.filter_profanity = true;
+        assert!(azure_stream_url("wss://x", &config).ends_with("profanity=masked"));
+    }
+
+    #[test]
+    fn audio_message_prefixes_big_endian_header_length() {
+        let message = azure_audio_message("REQ", &[1, 2, 3]);
+        let (headers, payload) = split_audio_message(&message);
+
+        assert!(headers.contains("X-RequestId: REQ\r\n"));
+        assert!(headers.contains("Content-Type: audio/x-wav\r\n"));
+        assert_eq!(payload, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn wav_header_describes_pcm16_mono() {
+        let header = super::super::pcm16_wav_header(AZURE_SAMPLE_RATE, 1, 0);
+
+        assert_eq!(header.len(), 44);
+        assert_eq!(&header[0..4], b"RIFF");
+        assert_eq!(u16::from_le_bytes([header[22], header[23]]), 1);
+        assert_eq!(
+            u32::from_le_bytes([header[24], header[25], header[26], header[27]]),
+            16_000
+        );
+    }
+
+    #[test]
+    fn keyterms_become_phrase_list_context() {
+        assert!(azure_speech_context_body(None).is_none());
+        assert!(azure_speech_context_body(Some(" ,, ")).is_none());
+        assert_eq!(
+            azure_speech_context_body(Some("Kubernetes, VoicetextAI")),
+            Some(json!({
+                "dgi": {
+                    "Groups": [{
+                        "Type": "Generic",
+                        "Items": [{ "Text": "Kubernetes" }, { "Text": "VoicetextAI" }]
+                    }]
+                }
+            }))
+        );
+    }
+
+    #[test]
+    fn text_message_parser_reads_path_case_insensitively() {
+        let (path, body) =
+            parse_azure_text_message("X-RequestId: A\r\npath: Turn.End\r\n\r\n").expect("parse");
+        assert_eq!(path, "turn.end");
+        assert_eq!(body, Value::Null);
+        assert!(parse_azure_text_message("no headers").is_none());
+    }
+
+    #[test]
+    fn recognition_events_map_phrase_statuses() {
+        let AzureRecognitionEvent::Final(final_result) = azure_recognition_event(
+            "speech.phrase",
+            &json!({
+                "RecognitionStatus": "Success",
+                "Offset": 10_000_000,
+                "Duration": 15_000_000,
+                "DisplayText": "fallback",
+                "NBest": [{
+                    "Confidence": 0.5,
+                    "Display": "Hello.",
+                    "Words": [{ "Word": "hello", "Offset": 10_000_000, "Duration": 5_000_000 }]
+                }]
+            }),
+            "en-US",
+        ) else {
+            panic!("expected final");
+        };
+        assert_eq!(final_result.text, "Hello.");
+        assert_eq!(final_result.words, vec![Word::new("hello", 1.0, 1.5)]);
+        assert_eq!(final_result.confidence, Some(0.5));
+        assert!((final_result.start - 1.0).abs() < 1e-9);
+        assert!((final_result.duration - 1.5).abs() < 1e-9);
+
+        assert!(matches!(
+            azure_recognition_event(
+                "speech.phrase",
+                &json!({ "RecognitionStatus": "InitialSilenceTimeout" }),
+                "en-US"
+            ),
+            AzureRecognitionEvent::Ignored
+        ));
+        assert!(matches!(
+            azure_recognition_event(
+                "speech.phrase",
+                &json!({ "RecognitionStatus": "Error" }),
+                "en-US"
+            ),
+            AzureRecognitionEvent::Error(SttError::Processing(_))
+        ));
+    }
+
+    #[test]
+    fn close_errors_are_classified() {
+        let frame = |code: u16, reason: &'static str| CloseFrame {
+            code: code.into(),
+            reason: reason.into(),
+        };
+
+        assert!(matches!(
+            azure_close_error(Some(&frame(1008, "Authentication failed")), "closed"),
+            SttError::Authentication(_)
+        ));
+        assert!(matches!(
+            azure_close_error(Some(&frame(1007, "Invalid language")), "closed"),
+            SttError::Configuration(_)
+        ));
+        assert!(matches!(
+            azure_close_error(Some(&frame(1008, "Quota exceeded")), "closed"),
+            SttError::Connection(connection)
+                if connection.details.category
+                    == Some(SttConnectionCategory::ProviderQuotaExceeded)
+                    && connection.details.ws_close_code == Some(1008)
+        ));
+        assert!(matches!(
+            azure_close_error(Some(&frame(1011, "")), "closed"),
+            SttError::Connection(connection)
+                if connection.details.category == Some(SttConnectionCategory::ServerUnavailable)
+        ));
+    }
+
+    #[tokio::test]
+    async fn initialize_requires_key_and_valid_region() {
+        let mut provider = AzureProvider::new();
+
+        let mut config = azure_test_config();
+        config.azure_speech_region = None;
+        assert!(matches!(
+            provider.initialize(&config).await,
+            Err(SttError::Configuration(_))
+        ));
+
+        config.azure_speech_region = Some("west europe/../".to_string());
+        assert!(matches!(
+            provider.initialize(&config).await,
+            Err(SttError::Configuration(_))
+        ));
+
+        provider
+            .initialize(&azure_test_config())
+            .await
+            .expect("initialize provider");
+        assert_eq!(provider.subscription_key.as_deref(), Some("test-key"));
+        assert_eq!(
+            provider
+                .config
+                .as_ref()
+                .and_then(|config| config.azure_speech_region.as_deref()),
+            Some("westeurope")
+        );
+    }
+
+    #[tokio::test]
+    async fn handshake_unauthorized_is_authentication_error() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock Azure server");
+        let address = listener.local_addr().expect("mock server address");
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept client");
+            let mut buffer = [0u8; 2048];
+            let _ = tokio::io::AsyncReadExt::read(&mut stream, &mut buffer).await;
+            let _ = stream
+                .write_all(b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\n\r\n")
+                .await;
+        });
+
+        let mut provider = AzureProvider::with_ws_base_url(format!("ws://{address}/v1"));
+        provider
+            .initialize(&azure_test_config())
+            .await
+            .expect("initialize provider");
+        let result = provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_, _| {}),
+            )
+            .await;
+
+        assert!(matches!(result, Err(SttError::Authentication(_))));
+        assert!(!provider.is_streaming);
+    }
+
+    #[tokio::test]
+    async fn graceful_stop_sends_end_of_audio_and_drains_final_phrase() {
+        let (ws_base_url, server) = spawn_azure_server().await;
+        let mut provider = AzureProvider::with_ws_base_url(ws_base_url);
+        provider
+            .initialize(&azure_test_config())
+            .await
+            .expect("initialize provider");
+
+        let partials = Arc::new(StdMutex::new(Vec::<Transcription>::new()));
+        let finals = Arc::new(StdMutex::new(Vec::<Transcription>::new()));
+        let partials_for_callback = partials.clone();
+        let finals_for_callback = finals.clone();
+        provider
+            .start_stream(
+                Arc::new(move |t| partials_for_callback.lock().unwrap().push(t)),
+                Arc::new(move |t| finals_for_callback.lock().unwrap().push(t)),
+                Arc::new(|error| panic!("unexpected Azure error: {error}")),
+                Arc::new(|_, _| {}),
+            )
+            .await
+            .expect("start mock stream");
+
+        provider
+            .send_audio(&AudioChunk::new(vec![5i16; 160], 16_000, 1))
+            .await
+            .expect("send first audio");
+        provider
+            .send_audio(&AudioChunk::new(vec![6i16; 160], 16_000, 1))
+            .await
+            .expect("send second audio");
+        provider.stop_stream().await.expect("graceful stop");
+
+        let capture = server.await.expect("mock server task");
+        assert!(capture.uri.contains("language=ru-RU"));
+        assert_eq!(capture.subscription_key.as_deref(), Some("test-key"));
+        assert_eq!(capture.text_paths, vec!["speech.config", "speech.context"]);
+        assert_eq!(
+            capture.context.as_ref().expect("phrase list")["dgi"]["Groups"][0]["Items"][1]["Text"],
+            "VoicetextAI"
+        );
+        assert_eq!(capture.audio_payloads.len(), 3);
+        assert_eq!(capture.audio_payloads[0].len(), 44 + 320);
+        assert_eq!(&capture.audio_payloads[0][0..4], b"RIFF");
+        assert_eq!(capture.audio_payloads[1].len(), 320);
+        assert!(capture.audio_payloads[2].is_empty());
+
+        assert_eq!(partials.lock().unwrap()[0].text, "привет");
+        let finals = finals.lock().unwrap();
+        assert_eq!(finals.len(), 1);
+        assert_eq!(finals[0].text, "Привет, мир.");
+        assert_eq!(finals[0].language.as_deref(), Some("ru-RU"));
+    }
+}
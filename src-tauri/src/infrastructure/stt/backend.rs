@@ -20,10 +20,12 @@ use tokio_tungstenite::{
 use crate::domain::{
     AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttConnectionCategory,
     SttConnectionDetails, SttConnectionError, SttError, SttProvider, SttProviderType, SttResult,
-    Transcription, TranscriptionCallback,
+    Transcription, TranscriptionCallback, Word,
 };
 
 use super::backend_messages::{ClientMessage, ServerMessage};
+use super::backend_resume::{SessionResume, RESUME_REPLAY_MAX_BYTES};
+use crate::infrastructure::audio::OpusFrameEncoder;
 
 /// URL бэкенда для production
 const PROD_BACKEND_URL: &str = "wss://api.voicetext.site";
@@ -42,6 +44,20 @@ const WS_SEND_TIMEOUT_SECS: u64 = 3;
 const FINALIZE_DRAIN_ACK_TIMEOUT: Duration = Duration::from_secs(12);
 const FINALIZE_POST_ACK_TEXT_GRACE_MS: u64 = 350;
 const CAPABILITY_FINALIZE_ACK: &str = "finalize_ack";
+const CAPABILITY_WORD_TIMINGS: &str = "word_timings";
+const CAPABILITY_DIARIZATION: &str = "diarization";
+const CAPABILITY_RESUME: &str = "resume";
+const CAPABILITY_OPUS: &str = "opus";
+const ENCODING_PCM: &str = "pcm_s16le";
+const ENCODING_OPUS: &str = "opus";
+// Backend подтверждает кодировку в `Ready`; не дождались — переподключаемся на PCM.
+const OPUS_NEGOTIATION_TIMEOUT: Duration = Duration::from_secs(3);
+// Восстановление сессии после обрыва: все попытки должны уложиться в окно,
+// иначе диктовка падает с исходной ошибкой, как и раньше.
+const RESUME_WINDOW: Duration = Duration::from_secs(10);
+const RESUME_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const RESUME_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(3);
+const RESUME_RETRY_DELAYS_MS: [u64; 3] = [0, 500, 1500];
 
 /// Проверяем, что URL указывает на локальный бэкенд (localhost/loopback).
 ///
@@ -82,6 +98,8 @@ fn get_default_backend_url() -> String {
 }
 
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type WsSink = futures_util::stream::SplitSink<WsStream, Message>;
+type WsSource = futures_util::stream::SplitStream<WsStream>;
 
 fn backend_streaming_provider_name(config: &SttConfig) -> &'static str {
     match config.provider {
@@ -103,7 +121,7 @@ pub struct BackendProvider {
     auth_token: Option<String>,
     backend_url: String,
     session_id: Option<String>,
-    ws_write: Option<Arc<Mutex<futures_util::stream::SplitSink<WsStream, Message>>>>,
+    ws_write: Option<Arc<Mutex<WsSink>>>,
     receiver_task: Option<JoinHandle<()>>,
     keepalive_task: Option<JoinHandle<()>>,
 
@@ -116,6 +134,9 @@ pub struct BackendProvider {
     /// отличать limit_exceeded от обычного обрыва.
     last_remaining_secs: Arc<AtomicU32>,
 
+    /// Replay buffer и session_id текущего соединения для восстановления после обрыва
+    resume: Arc<SessionResume>,
+
     // Callbacks: active/pending (для keep-alive режима).
     //
     // Важно: receiver task живёт дольше одной "записи" (мы держим WS живым между старт/стопами).
@@ -131,6 +152,10 @@ pub struct BackendProvider {
 
     audio_batch: Vec<u8>,
 
+    /// Opus-кодер, если backend принял `encoding=opus` для текущего соединения.
+    /// `None` — шлём PCM батчами из `audio_batch`.
+    audio_encoder: Option<OpusFrameEncoder>,
+
     next_send_at: Option<std::time::Instant>,
     batch_started_at: Option<std::time::Instant>,
 
@@ -211,6 +236,14 @@ fn server_error_closes_stream(code: &str) -> bool {
     )
 }
 
+/// Метка говорящего сегмента: явная от backend, иначе доминирующая по словам
+fn with_backend_speaker(transcription: Transcription, speaker: Option<String>) -> Transcription {
+    match speaker.or_else(|| Transcription::dominant_speaker(&transcription.words)) {
+        Some(speaker) => transcription.with_speaker(speaker),
+        None => transcription,
+    }
+}
+
 fn call_backend_callback(label: &str, callback: impl FnOnce()) {
     if catch_unwind(AssertUnwindSafe(callback)).is_err() {
         log::error!("Backend {} callback panicked", label);
@@ -248,6 +281,610 @@ async fn report_backend_unexpected_eof(
     }
 }
 
+async fn emit_backend_quality(
+    callbacks_state: &Arc<Mutex<CallbackState>>,
+    quality: &str,
+    reason: Option<String>,
+) {
+    let cb = {
+        let state = callbacks_state.lock().await;
+        state
+            .active
+            .as_ref()
+            .map(|c| c.on_connection_quality.clone())
+    };
+    if let Some(cb) = cb {
+        call_backend_callback("connection quality", || cb(quality.to_string(), reason));
+    }
+}
+
+/// Обрывы транспорта, после которых сессия на backend ещё жива и её можно продолжить
+fn backend_error_is_transient(error: &tokio_tungstenite::tungstenite::Error) -> bool {
+    use tokio_tungstenite::tungstenite::error::ProtocolError;
+    use tokio_tungstenite::tungstenite::Error;
+
+    match error {
+        Error::Io(io_error) => matches!(
+            io_error.kind(),
+            std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+                | std::io::ErrorKind::BrokenPipe
+                | std::io::ErrorKind::NotConnected
+                | std::io::ErrorKind::TimedOut
+                | std::io::ErrorKind::UnexpectedEof
+        ),
+        Error::Protocol(ProtocolError::ResetWithoutClosingHandshake) => true,
+        _ => false,
+    }
+}
+
+/// 1001 (going away) и 1012 (service restart): уходит инстанс backend'а, а не сессия.
+/// Остальные close frame — осознанное закрытие, восстанавливать нечего.
+fn backend_close_is_transient(
+    frame: Option<&tokio_tungstenite::tungstenite::protocol::CloseFrame<'_>>,
+) -> bool {
+    frame.is_some_and(|frame| matches!(u16::from(frame.code), 1001 | 1012))
+}
+
+/// Отправка аудио-фрейма не удалась, и сессию восстановить нельзя
+#[derive(Debug)]
+enum AudioFrameSendError {
+    Sink(tokio_tungstenite::tungstenite::Error),
+    Timeout,
+}
+
+fn audio_send_error(error: AudioFrameSendError) -> SttError {
+    match error {
+        AudioFrameSendError::Sink(e) => SttError::Connection(SttConnectionError::simple(format!(
+            "Failed to send audio: {}",
+            e
+        ))),
+        AudioFrameSendError::Timeout => SttError::Connection(SttConnectionError::with_category(
+            "WS send timeout".to_string(),
+            SttConnectionCategory::Timeout,
+        )),
+    }
+}
+
+/// Всё, что нужно receiver task, чтобы переподключиться к той же backend-сессии
+struct ResumeContext {
+    backend_url: String,
+    auth_token: String,
+    resume: Arc<SessionResume>,
+    ws_write: Arc<Mutex<WsSink>>,
+    is_closed: Arc<AtomicBool>,
+    finalize_waiter: Arc<Mutex<Option<tokio::sync::oneshot::Sender<FinalizeDrainComplete>>>>,
+    callbacks: Arc<Mutex<CallbackState>>,
+}
+
+impl ResumeContext {
+    /// Переподключается с прежним `session_id` и досылает неподтверждённое аудио.
+    ///
+    /// `None` — восстановить нельзя или не удалось; вызывающий сообщает об исходной ошибке.
+    async fn resume_after_disconnect(&self, reason: &str) -> Option<WsSource> {
+        if self.is_closed.load(Ordering::SeqCst) {
+            return None;
+        }
+        // Finalize в полёте: неизвестно, успел ли backend сделать drain — честнее упасть,
+        // чем молча потерять хвост.
+        if self.finalize_waiter.lock().await.is_some() {
+            self.resume.abandon();
+            return None;
+        }
+        let Some((session_id, last_seq)) = self.resume.begin() else {
+            self.resume.abandon();
+            return None;
+        };
+
+        log::warn!(
+            "[ReconnectDiag] Backend transport lost ({}), resuming session {} (last_seq={})",
+            reason,
+            session_id,
+            last_seq
+        );
+        emit_backend_quality(
+            &self.callbacks,
+            "Recovering",
+            Some("Reconnecting to backend...".to_string()),
+        )
+        .await;
+
+        let attempts = async {
+            let mut last_error = None;
+            for delay_ms in RESUME_RETRY_DELAYS_MS {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                if self.is_closed.load(Ordering::SeqCst) {
+                    break;
+                }
+                match self.try_resume(&session_id).await {
+                    Ok(read) => return Ok(read),
+                    Err(error) => {
+                        log::warn!("Backend resume attempt failed: {}", error);
+                        let retryable = matches!(error, SttError::Connection(_));
+                        last_error = Some(error);
+                        if !retryable {
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(last_error.unwrap_or_else(|| {
+                SttError::Processing("Backend connection closed during resume".to_string())
+            }))
+        };
+
+        match tokio::time::timeout(RESUME_WINDOW, attempts).await {
+            Ok(Ok(read)) => {
+                emit_backend_quality(&self.callbacks, "Good", Some("Session resumed".to_string()))
+                    .await;
+                Some(read)
+            }
+            Ok(Err(error)) => {
+                log::warn!("Backend session resume failed: {}", error);
+                self.resume.abandon();
+                None
+            }
+            Err(_) => {
+                log::warn!(
+                    "Backend session resume timed out after {} ms",
+                    RESUME_WINDOW.as_millis()
+                );
+                self.resume.abandon();
+                None
+            }
+        }
+    }
+
+    async fn try_resume(&self, session_id: &str) -> SttResult<WsSource> {
+        let ws_stream =
+            connect_backend_socket(&self.backend_url, &self.auth_token, RESUME_CONNECT_TIMEOUT)
+                .await?;
+        let (mut write, mut read) = ws_stream.split();
+
+        // Повторный begin: неудачная попытка могла успеть снять флаг, а sender — добавить фреймы
+        let last_seq = self.resume.begin().map(|(_, seq)| seq).unwrap_or(0);
+        let json = serde_json::to_string(&ClientMessage::Resume {
+            session_id: session_id.to_string(),
+            last_seq,
+        })
+        .map_err(|e| SttError::Processing(format!("JSON serialize error: {}", e)))?;
+        match tokio::time::timeout(
+            Duration::from_secs(WS_SEND_TIMEOUT_SECS),
+            write.send(Message::Text(json)),
+        )
+        .await
+        {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                return Err(SttError::Connection(SttConnectionError::simple(format!(
+                    "Failed to send resume request: {}",
+                    e
+                ))))
+            }
+            Err(_) => {
+                return Err(SttError::Connection(SttConnectionError::with_category(
+                    "Resume request send timeout".to_string(),
+                    SttConnectionCategory::Timeout,
+                )))
+            }
+        }
+
+        let last_seq_acked =
+            tokio::time::timeout(RESUME_HANDSHAKE_TIMEOUT, wait_for_resumed(&mut read))
+                .await
+                .map_err(|_| {
+                    SttError::Connection(SttConnectionError::with_category(
+                        "Backend did not confirm session resume".to_string(),
+                        SttConnectionCategory::Timeout,
+                    ))
+                })??;
+
+        // Writer lock держим до конца replay: send_audio, увидевший снятый флаг
+        // восстановления, отправит свой фрейм только после уже буферизованных.
+        let mut sink = self.ws_write.lock().await;
+        *sink = write;
+        let frames = self.resume.complete(last_seq_acked);
+        log::info!(
+            "[ReconnectDiag] Backend session {} resumed at seq {}, replaying {} frames",
+            session_id,
+            last_seq_acked,
+            frames.len()
+        );
+        for (seq, bytes) in frames {
+            match tokio::time::timeout(
+                Duration::from_secs(WS_SEND_TIMEOUT_SECS),
+                sink.send(Message::Binary(bytes)),
+            )
+            .await
+            {
+                Ok(Ok(())) => {}
+                _ => {
+                    return Err(SttError::Connection(SttConnectionError::with_category(
+                        format!("Failed to replay audio frame {} after resume", seq),
+                        SttConnectionCategory::Closed,
+                    )))
+                }
+            }
+        }
+
+        Ok(read)
+    }
+}
+
+/// `Config` для новой сессии. С `opus = true` просим Opus и заявляем capability `opus`:
+/// backend, который его умеет, подтверждает кодировку в `Ready`.
+fn backend_config_message(config: &SttConfig, opus: bool) -> ClientMessage {
+    // Парсим keyterms из конфига (строка через запятую → Vec<String>)
+    let keyterms = config.streaming_keyterms.as_ref().and_then(|raw| {
+        let terms: Vec<String> = raw
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if terms.is_empty() {
+            None
+        } else {
+            Some(terms)
+        }
+    });
+
+    let mut capabilities = vec![
+        CAPABILITY_FINALIZE_ACK.to_string(),
+        CAPABILITY_WORD_TIMINGS.to_string(),
+        CAPABILITY_RESUME.to_string(),
+    ];
+    if config.enable_diarization {
+        capabilities.push(CAPABILITY_DIARIZATION.to_string());
+    }
+    if opus {
+        capabilities.push(CAPABILITY_OPUS.to_string());
+    }
+
+    ClientMessage::Config {
+        protocol_v: 2,
+        provider: backend_streaming_provider_name(config).to_string(),
+        language: config.language.clone(),
+        sample_rate: 16000,
+        channels: 1,
+        encoding: if opus { ENCODING_OPUS } else { ENCODING_PCM }.to_string(),
+        keyterms,
+        punctuate: config.enable_punctuation,
+        profanity_filter: config.filter_profanity,
+        capabilities,
+    }
+}
+
+/// Сессия `Ready` становится возобновляемой, только если backend подтвердил capability
+/// `resume`: старый backend не знает `Resume`, после обрыва к нему подключаемся заново.
+fn register_ready_session(resume: &SessionResume, session_id: String, capabilities: &[String]) {
+    log::info!("Session ready: {}", session_id);
+    if capabilities
+        .iter()
+        .any(|capability| capability == CAPABILITY_RESUME)
+    {
+        resume.set_session_id(session_id);
+    } else {
+        log::info!("Backend did not confirm session resume, reconnects will start a new session");
+    }
+}
+
+/// `Ready` для согласования кодировки: `(session_id, подтверждённая кодировка, capabilities)`
+type ReadyMessage = (String, Option<String>, Vec<String>);
+
+/// Ждёт `Ready` после `Config` с запросом Opus.
+/// Старый backend кодировку не возвращает — значит, он декодирует PCM.
+async fn wait_for_ready(read: &mut WsSource) -> SttResult<ReadyMessage> {
+    while let Some(message) = read.next().await {
+        match message {
+            Ok(Message::Text(text)) => match serde_json::from_str::<ServerMessage>(&text) {
+                Ok(ServerMessage::Ready {
+                    session_id,
+                    encoding,
+                    capabilities,
+                }) => return Ok((session_id, encoding, capabilities)),
+                Ok(ServerMessage::Error { code, message }) => {
+                    return Err(SttError::Processing(format!(
+                        "Backend rejected session config: {} - {}",
+                        code, message
+                    )))
+                }
+                Ok(other) => log::debug!("Ignoring {:?} before Ready", other),
+                Err(e) => log::warn!("Failed to parse server message: {} - {}", e, text),
+            },
+            Ok(Message::Close(frame)) => {
+                return Err(SttError::Connection(SttConnectionError::with_category(
+                    format!("Backend closed the socket before Ready: {:?}", frame),
+                    SttConnectionCategory::Closed,
+                )))
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return Err(SttError::Connection(SttConnectionError::simple(format!(
+                    "WebSocket error before Ready: {}",
+                    e
+                ))))
+            }
+        }
+    }
+    Err(SttError::Connection(SttConnectionError::with_category(
+        "Backend stream ended before Ready".to_string(),
+        SttConnectionCategory::Closed,
+    )))
+}
+
+/// Ждёт `Resumed` на новом сокете. `Error` от сервера (например, сессия уже истекла)
+/// не ретраим — это не сетевая проблема.
+async fn wait_for_resumed(read: &mut WsSource) -> SttResult<u64> {
+    while let Some(message) = read.next().await {
+        match message {
+            Ok(Message::Text(text)) => match serde_json::from_str::<ServerMessage>(&text) {
+                Ok(ServerMessage::Resumed { last_seq_acked, .. }) => return Ok(last_seq_acked),
+                Ok(ServerMessage::Error { code, message }) => {
+                    return Err(SttError::Processing(format!(
+                        "Backend refused session resume: {} - {}",
+                        code, message
+                    )))
+                }
+                Ok(other) => log::debug!("Ignoring {:?} before Resumed", other),
+                Err(e) => log::warn!("Failed to parse server message: {} - {}", e, text),
+            },
+            Ok(Message::Close(frame)) => {
+                return Err(SttError::Connection(SttConnectionError::with_category(
+                    format!("Backend closed the socket during resume: {:?}", frame),
+                    SttConnectionCategory::Closed,
+                )))
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return Err(SttError::Connection(SttConnectionError::simple(format!(
+                    "WebSocket error during resume: {}",
+                    e
+                ))))
+            }
+        }
+    }
+
+    Err(SttError::Connection(SttConnectionError::with_category(
+        "Backend closed the socket before confirming resume".to_string(),
+        SttConnectionCategory::Closed,
+    )))
+}
+
+/// Открывает WebSocket к `/api/v1/transcribe/stream` с Bearer-токеном.
+///
+/// Используется и при старте стрима, и при восстановлении сессии после обрыва.
+async fn connect_backend_socket(
+    backend_url: &str,
+    auth_token: &str,
+    connect_timeout: Duration,
+) -> SttResult<WsStream> {
+    // WebSocket URL
+    let ws_url = format!("{}/api/v1/transcribe/stream", backend_url);
+
+    log::debug!("Connecting to backend: {}", ws_url);
+
+    // Формируем WebSocket запрос с Authorization header
+    let request = Request::builder()
+        .method("GET")
+        .uri(&ws_url)
+        .header(
+            "Host",
+            backend_url.replace("wss://", "").replace("ws://", ""),
+        )
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header(
+            "Sec-WebSocket-Key",
+            tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+        )
+        .header("Authorization", format!("Bearer {}", auth_token))
+        .body(())
+        .map_err(|e| {
+            SttError::Connection(SttConnectionError::simple(format!(
+                "Failed to build WS request: {}",
+                e
+            )))
+        })?;
+
+    let (ws_stream, _response) = tokio::time::timeout(
+        connect_timeout,
+        connect_async_with_config(
+            request,
+            Some(super::streaming_websocket_config()),
+            false,
+        ),
+    )
+    .await
+    .map_err(|_| {
+        SttError::Connection(SttConnectionError {
+            message: "WS connection timeout".to_string(),
+            details: SttConnectionDetails {
+                category: Some(SttConnectionCategory::Timeout),
+                ..Default::default()
+            },
+        })
+    })?
+    .map_err(|e| match e {
+        tokio_tungstenite::tungstenite::Error::Http(resp) => {
+            let status = resp.status();
+
+            if status == http::StatusCode::UNAUTHORIZED {
+                // В dev режиме это почти всегда означает, что local backend не принял dev токен
+                // (например, не выставлен SECURITY_ALLOW_DEV_TOKEN=true).
+                if cfg!(debug_assertions) && is_local_backend_url(backend_url) {
+                    return SttError::Authentication(
+                        "401 Unauthorized от локального бэкенда. Проверь, что backend запущен с SECURITY_ALLOW_DEV_TOKEN=true (и APP_ENV=local). Если хочешь использовать свой сохранённый токен — укажи VOICE_TO_TEXT_BACKEND_URL=wss://api.voicetext.site"
+                            .to_string(),
+                    );
+                }
+
+                return SttError::Authentication(
+                    "401 Unauthorized. Токен недействителен/истёк — попробуй перелогиниться."
+                        .to_string(),
+                );
+            }
+
+            if status == http::StatusCode::TOO_MANY_REQUESTS {
+                // Парсим body от сервера для точной причины (rate_limit vs too_many_sessions).
+                //
+                // Важно: backend API ошибки имеют форму:
+                // { success:false, error:{ code, message, details? } }
+                // Но некоторые WS/proxy могут вернуть { code, message } без envelope.
+                let mut server_message: Option<String> = None;
+                let mut server_code: Option<String> = None;
+                let mut retry_after_secs: Option<u64> = None;
+
+                if let Some(body) = resp.body().as_ref() {
+                    if let Ok(text) = std::str::from_utf8(body) {
+                        if let Ok(json) = serde_json::from_str::<serde_json::Value>(text) {
+                            // API envelope: { error: { code, message, details } }
+                            if let Some(err) = json.get("error") {
+                                server_message = err
+                                    .get("message")
+                                    .and_then(|v| v.as_str())
+                                    .map(|s| s.to_string());
+                                server_code = err
+                                    .get("code")
+                                    .and_then(|v| v.as_str())
+                                    .map(|s| s.to_string());
+                                retry_after_secs = err
+                                    .get("details")
+                                    .and_then(|d| d.get("retry_after_seconds"))
+                                    .and_then(|v| v.as_u64());
+                            } else {
+                                // Fallback: { code, message }
+                                server_message = json
+                                    .get("message")
+                                    .and_then(|v| v.as_str())
+                                    .map(|s| s.to_string());
+                                server_code = json
+                                    .get("code")
+                                    .and_then(|v| v.as_str())
+                                    .map(|s| s.to_string());
+                            }
+                        }
+                    }
+                }
+
+                // Для WS-handshake ошибок tungstenite часто не отдаёт body, поэтому
+                // backend дублирует код в заголовке.
+                if server_code.is_none() {
+                    server_code = resp
+                        .headers()
+                        .get("x-voicetext-error-code")
+                        .and_then(|v| v.to_str().ok())
+                        .map(|s| s.to_string());
+                }
+
+                // Иногда retry-after приходит только хедером (например, глобальный rate limit middleware).
+                if retry_after_secs.is_none() {
+                    retry_after_secs = resp
+                        .headers()
+                        .get("Retry-After")
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|s| s.parse::<u64>().ok());
+                }
+
+                let display_message = match (&server_message, &server_code, retry_after_secs) {
+                    (Some(msg), Some(code), Some(secs)) => {
+                        format!("WS connection failed: 429 ({}): {} (retry after {}s)", code, msg, secs)
+                    }
+                    (Some(msg), Some(code), None) => {
+                        format!("WS connection failed: 429 ({}): {}", code, msg)
+                    }
+                    (Some(msg), None, Some(secs)) => {
+                        format!("WS connection failed: 429 — {} (retry after {}s)", msg, secs)
+                    }
+                    (Some(msg), None, None) => format!("WS connection failed: 429 — {}", msg),
+                    (None, Some(code), Some(secs)) => {
+                        format!("WS connection failed: 429 ({}) (retry after {}s)", code, secs)
+                    }
+                    (None, Some(code), None) => format!("WS connection failed: 429 ({})", code),
+                    (None, None, Some(secs)) => {
+                        format!("WS connection failed: HTTP error: {} (retry after {}s)", status, secs)
+                    }
+                    (None, None, None) => format!("WS connection failed: HTTP error: {}", status),
+                };
+
+                let category = match server_code.as_deref() {
+                    // Важно: backend использует HTTP 429 и для limit_exceeded и для rate limiting,
+                    // поэтому определяем категорию по коду.
+                    Some("LIMIT_EXCEEDED") => SttConnectionCategory::LimitExceeded,
+                    Some("TOO_MANY_SESSIONS") | Some("RATE_LIMIT_EXCEEDED") => {
+                        SttConnectionCategory::RateLimited
+                    }
+                    _ => SttConnectionCategory::RateLimited,
+                };
+
+                return SttError::Connection(SttConnectionError {
+                    message: display_message,
+                    details: SttConnectionDetails {
+                        category: Some(category),
+                        http_status: Some(429),
+                        server_code,
+                        ..Default::default()
+                    },
+                });
+            }
+
+            {
+                let status_u16 = status.as_u16();
+                let category = if matches!(status_u16, 502 | 503 | 504) {
+                    SttConnectionCategory::ServerUnavailable
+                } else {
+                    SttConnectionCategory::Http
+                };
+                SttError::Connection(SttConnectionError {
+                    message: format!("WS connection failed: HTTP error: {}", status),
+                    details: SttConnectionDetails {
+                        category: Some(category),
+                        http_status: Some(status_u16),
+                        ..Default::default()
+                    },
+                })
+            }
+        }
+        tokio_tungstenite::tungstenite::Error::Tls(other) => SttError::Connection(SttConnectionError {
+            message: format!("WS connection failed: {}", other),
+            details: SttConnectionDetails {
+                category: Some(SttConnectionCategory::Tls),
+                ..Default::default()
+            },
+        }),
+        tokio_tungstenite::tungstenite::Error::Io(ioe) => {
+            let kind = ioe.kind();
+            let kind_str = format!("{:?}", kind);
+            let os_error = ioe.raw_os_error();
+            let category = match kind {
+                std::io::ErrorKind::ConnectionRefused => SttConnectionCategory::Refused,
+                std::io::ErrorKind::ConnectionReset => SttConnectionCategory::Reset,
+                std::io::ErrorKind::NotConnected
+                | std::io::ErrorKind::NetworkUnreachable
+                | std::io::ErrorKind::HostUnreachable
+                | std::io::ErrorKind::AddrNotAvailable => SttConnectionCategory::Offline,
+                std::io::ErrorKind::TimedOut => SttConnectionCategory::Timeout,
+                _ => SttConnectionCategory::Unknown,
+            };
+            SttError::Connection(SttConnectionError {
+                message: format!("WS connection failed: {}", ioe),
+                details: SttConnectionDetails {
+                    category: Some(category),
+                    io_error_kind: Some(kind_str),
+                    os_error,
+                    ..Default::default()
+                },
+            })
+        }
+        other => SttError::Connection(SttConnectionError {
+            message: format!("WS connection failed: {}", other),
+            details: SttConnectionDetails::default(),
+        }),
+    })?;
+
+    Ok(ws_stream)
+}
+
 impl BackendProvider {
     pub fn new() -> Self {
         Self {
@@ -262,12 +899,14 @@ impl BackendProvider {
             keepalive_task: None,
             is_closed: Arc::new(AtomicBool::new(true)), // Изначально закрыто
             last_remaining_secs: Arc::new(AtomicU32::new(f32::MAX.to_bits())),
+            resume: Arc::new(SessionResume::new(RESUME_REPLAY_MAX_BYTES)),
             callbacks: Arc::new(Mutex::new(CallbackState::default())),
             finalize_waiter: Arc::new(Mutex::new(None)),
             on_usage_update_callback: None,
             sent_chunks_count: 0,
             sent_bytes_total: 0,
             audio_batch: Vec::new(),
+            audio_encoder: None,
             next_send_at: None,
             batch_started_at: None,
             finalize_drain_ack_timeout: FINALIZE_DRAIN_ACK_TIMEOUT,
@@ -333,7 +972,62 @@ impl BackendProvider {
         }
     }
 
+    /// Отправляет бинарный фрейм, предварительно записав его в replay buffer.
+    ///
+    /// Пока идёт восстановление сессии, фрейм только буферизуется. Если отправка упала,
+    /// но сессию можно продолжить, ошибку не поднимаем: receiver переподключится и дошлёт
+    /// фрейм после `Resumed`.
+    async fn deliver_audio_frame(
+        &self,
+        ws_write: &Arc<Mutex<WsSink>>,
+        bytes: Vec<u8>,
+    ) -> Result<(), AudioFrameSendError> {
+        let mut registered = false;
+        let send_fut = async {
+            // Решение "слать или отложить" принимаем под writer lock: receiver держит его
+            // на время replay, поэтому фрейм не уйдёт ни раньше буферизованных, ни дважды.
+            let mut guard = ws_write.lock().await;
+            registered = true;
+            if self.resume.push_frame(bytes.clone()) {
+                return Ok(());
+            }
+            guard.send(Message::Binary(bytes.clone())).await
+        };
+
+        let failure =
+            match tokio::time::timeout(Duration::from_secs(WS_SEND_TIMEOUT_SECS), send_fut).await {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(e)) => AudioFrameSendError::Sink(e),
+                Err(_) => AudioFrameSendError::Timeout,
+            };
+        if !registered {
+            self.resume.push_frame(bytes);
+        }
+
+        if self.resume.request_recovery(&self.is_closed) {
+            log::warn!(
+                "[ReconnectDiag] BackendProvider audio send failed ({:?}), keeping frame for session resume",
+                failure
+            );
+            return Ok(());
+        }
+        self.is_closed.store(true, Ordering::SeqCst);
+        Err(failure)
+    }
+
     async fn flush_pending_audio_batch(&mut self, context: &'static str) -> SttResult<()> {
+        // В Opus-режиме `audio_batch` хранит ещё не принятый пакет: добиваем хвост кодера
+        // тишиной до кадра, чтобы последние миллисекунды речи дошли до Finalize.
+        if self.audio_batch.is_empty() {
+            if let Some(encoder) = self.audio_encoder.as_mut() {
+                if let Some(packet) = encoder
+                    .flush()
+                    .map_err(|e| SttError::Processing(e.to_string()))?
+                {
+                    self.audio_batch = packet;
+                }
+            }
+        }
         if self.audio_batch.is_empty() {
             return Ok(());
         }
@@ -348,16 +1042,12 @@ impl BackendProvider {
             )));
         }
 
-        if let Some(ref ws_write) = self.ws_write {
+        if let Some(ws_write) = self.ws_write.clone() {
             let bytes = self.audio_batch.clone();
             let bytes_len = bytes.len();
-            let flush_fut = async {
-                let mut guard = ws_write.lock().await;
-                guard.send(Message::Binary(bytes)).await
-            };
 
-            match tokio::time::timeout(Duration::from_secs(WS_SEND_TIMEOUT_SECS), flush_fut).await {
-                Ok(Ok(())) => {
+            match self.deliver_audio_frame(&ws_write, bytes).await {
+                Ok(()) => {
                     self.audio_batch.clear();
                     self.next_send_at = None;
                     self.batch_started_at = None;
@@ -369,8 +1059,7 @@ impl BackendProvider {
                     );
                     Ok(())
                 }
-                Ok(Err(e)) => {
-                    self.is_closed.store(true, Ordering::SeqCst);
+                Err(AudioFrameSendError::Sink(e)) => {
                     Err(SttError::Connection(SttConnectionError::with_category(
                         format!(
                             "Backend {} failed to flush pending audio batch: {}",
@@ -379,8 +1068,7 @@ impl BackendProvider {
                         SttConnectionCategory::Closed,
                     )))
                 }
-                Err(_) => {
-                    self.is_closed.store(true, Ordering::SeqCst);
+                Err(AudioFrameSendError::Timeout) => {
                     Err(SttError::Connection(SttConnectionError::with_category(
                         format!("Backend {} timed out flushing pending audio batch", context),
                         SttConnectionCategory::Timeout,
@@ -399,7 +1087,32 @@ impl BackendProvider {
         }
     }
 
+    /// Дожидается окончания восстановления сессии, если оно сейчас идёт.
+    ///
+    /// Finalize нельзя отправлять в сокет, который receiver вот-вот заменит.
+    async fn wait_for_session_resume(&self, context: &'static str) -> SttResult<()> {
+        let wait = async {
+            loop {
+                let settled = self.resume.settled.notified();
+                if !self.resume.is_resuming() || self.is_closed.load(Ordering::SeqCst) {
+                    return;
+                }
+                settled.await;
+            }
+        };
+
+        tokio::time::timeout(RESUME_WINDOW + Duration::from_secs(1), wait)
+            .await
+            .map_err(|_| {
+                SttError::Connection(SttConnectionError::with_category(
+                    format!("Backend session resume did not settle before {}", context),
+                    SttConnectionCategory::Timeout,
+                ))
+            })
+    }
+
     async fn finalize_and_wait_for_drain(&self, context: &'static str) -> SttResult<()> {
+        self.wait_for_session_resume(context).await?;
         if self.is_closed.load(Ordering::SeqCst) || self.ws_write.is_none() {
             return Err(SttError::Connection(SttConnectionError::with_category(
                 format!(
@@ -585,238 +1298,74 @@ impl SttProvider for BackendProvider {
             .ok_or_else(|| SttError::Configuration("Config not set".to_string()))?
             .clone();
 
-        // WebSocket URL
-        let ws_url = format!("{}/api/v1/transcribe/stream", self.backend_url);
-
-        log::debug!("Connecting to backend: {}", ws_url);
-
-        // Формируем WebSocket запрос с Authorization header
-        let request = Request::builder()
-            .method("GET")
-            .uri(&ws_url)
-            .header(
-                "Host",
-                self.backend_url.replace("wss://", "").replace("ws://", ""),
-            )
-            .header("Connection", "Upgrade")
-            .header("Upgrade", "websocket")
-            .header("Sec-WebSocket-Version", "13")
-            .header(
-                "Sec-WebSocket-Key",
-                tokio_tungstenite::tungstenite::handshake::client::generate_key(),
-            )
-            .header("Authorization", format!("Bearer {}", auth_token))
-            .body(())
-            .map_err(|e| {
-                SttError::Connection(SttConnectionError::simple(format!(
-                    "Failed to build WS request: {}",
-                    e
-                )))
-            })?;
-
-        let (ws_stream, _response) = tokio::time::timeout(
-            Duration::from_secs(WS_CONNECT_TIMEOUT_SECS),
-            connect_async_with_config(
-                request,
-                Some(super::streaming_websocket_config()),
-                false,
-            ),
-        )
-        .await
-        .map_err(|_| {
-            SttError::Connection(SttConnectionError {
-                message: "WS connection timeout".to_string(),
-                details: SttConnectionDetails {
-                    category: Some(SttConnectionCategory::Timeout),
-                    ..Default::default()
-                },
-            })
-        })?
-        .map_err(|e| match e {
-            tokio_tungstenite::tungstenite::Error::Http(resp) => {
-                let status = resp.status();
-
-                if status == http::StatusCode::UNAUTHORIZED {
-                    // В dev режиме это почти всегда означает, что local backend не принял dev токен
-                    // (например, не выставлен SECURITY_ALLOW_DEV_TOKEN=true).
-                    if cfg!(debug_assertions) && is_local_backend_url(&self.backend_url) {
-                        return SttError::Authentication(
-                            "401 Unauthorized от локального бэкенда. Проверь, что backend запущен с SECURITY_ALLOW_DEV_TOKEN=true (и APP_ENV=local). Если хочешь использовать свой сохранённый токен — укажи VOICE_TO_TEXT_BACKEND_URL=wss://api.voicetext.site"
-                                .to_string(),
-                        );
-                    }
-
-                    return SttError::Authentication(
-                        "401 Unauthorized. Токен недействителен/истёк — попробуй перелогиниться."
-                            .to_string(),
-                    );
+        // Opus только если сборка его поддерживает — иначе сразу PCM, без лишнего round-trip
+        let mut audio_encoder = if config.compress_audio {
+            match OpusFrameEncoder::new() {
+                Ok(encoder) => Some(encoder),
+                Err(e) => {
+                    log::warn!("BackendProvider: audio compression unavailable: {}", e);
+                    None
                 }
+            }
+        } else {
+            None
+        };
+        let mut ready_session = None;
 
-                if status == http::StatusCode::TOO_MANY_REQUESTS {
-                    // Парсим body от сервера для точной причины (rate_limit vs too_many_sessions).
-                    //
-                    // Важно: backend API ошибки имеют форму:
-                    // { success:false, error:{ code, message, details? } }
-                    // Но некоторые WS/proxy могут вернуть { code, message } без envelope.
-                    let mut server_message: Option<String> = None;
-                    let mut server_code: Option<String> = None;
-                    let mut retry_after_secs: Option<u64> = None;
-
-                    if let Some(body) = resp.body().as_ref() {
-                        if let Ok(text) = std::str::from_utf8(body) {
-                            if let Ok(json) = serde_json::from_str::<serde_json::Value>(text) {
-                                // API envelope: { error: { code, message, details } }
-                                if let Some(err) = json.get("error") {
-                                    server_message = err
-                                        .get("message")
-                                        .and_then(|v| v.as_str())
-                                        .map(|s| s.to_string());
-                                    server_code = err
-                                        .get("code")
-                                        .and_then(|v| v.as_str())
-                                        .map(|s| s.to_string());
-                                    retry_after_secs = err
-                                        .get("details")
-                                        .and_then(|d| d.get("retry_after_seconds"))
-                                        .and_then(|v| v.as_u64());
-                                } else {
-                                    // Fallback: { code, message }
-                                    server_message = json
-                                        .get("message")
-                                        .and_then(|v| v.as_str())
-                                        .map(|s| s.to_string());
-                                    server_code = json
-                                        .get("code")
-                                        .and_then(|v| v.as_str())
-                                        .map(|s| s.to_string());
-                                }
-                            }
-                        }
-                    }
+        let (ws_write, mut read) = loop {
+            let ws_stream = connect_backend_socket(
+                &self.backend_url,
+                &auth_token,
+                Duration::from_secs(WS_CONNECT_TIMEOUT_SECS),
+            )
+            .await?;
 
-                    // Для WS-handshake ошибок tungstenite часто не отдаёт body, поэтому
-                    // backend дублирует код в заголовке.
-                    if server_code.is_none() {
-                        server_code = resp
-                            .headers()
-                            .get("x-voicetext-error-code")
-                            .and_then(|v| v.to_str().ok())
-                            .map(|s| s.to_string());
-                    }
+            log::info!("Backend WebSocket connected");
 
-                    // Иногда retry-after приходит только хедером (например, глобальный rate limit middleware).
-                    if retry_after_secs.is_none() {
-                        retry_after_secs = resp
-                            .headers()
-                            .get("Retry-After")
-                            .and_then(|v| v.to_str().ok())
-                            .and_then(|s| s.parse::<u64>().ok());
-                    }
+            // Сбрасываем флаг закрытия — соединение установлено
+            self.is_closed.store(false, Ordering::SeqCst);
 
-                    let display_message = match (&server_message, &server_code, retry_after_secs) {
-                        (Some(msg), Some(code), Some(secs)) => {
-                            format!("WS connection failed: 429 ({}): {} (retry after {}s)", code, msg, secs)
-                        }
-                        (Some(msg), Some(code), None) => {
-                            format!("WS connection failed: 429 ({}): {}", code, msg)
-                        }
-                        (Some(msg), None, Some(secs)) => {
-                            format!("WS connection failed: 429 — {} (retry after {}s)", msg, secs)
-                        }
-                        (Some(msg), None, None) => format!("WS connection failed: 429 — {}", msg),
-                        (None, Some(code), Some(secs)) => {
-                            format!("WS connection failed: 429 ({}) (retry after {}s)", code, secs)
-                        }
-                        (None, Some(code), None) => format!("WS connection failed: 429 ({})", code),
-                        (None, None, Some(secs)) => {
-                            format!("WS connection failed: HTTP error: {} (retry after {}s)", status, secs)
-                        }
-                        (None, None, None) => format!("WS connection failed: HTTP error: {}", status),
-                    };
+            let (write, mut read) = ws_stream.split();
+            let ws_write = Arc::new(Mutex::new(write));
+            self.ws_write = Some(ws_write.clone());
 
-                    let category = match server_code.as_deref() {
-                        // Важно: backend использует HTTP 429 и для limit_exceeded и для rate limiting,
-                        // поэтому определяем категорию по коду.
-                        Some("LIMIT_EXCEEDED") => SttConnectionCategory::LimitExceeded,
-                        Some("TOO_MANY_SESSIONS") | Some("RATE_LIMIT_EXCEEDED") => {
-                            SttConnectionCategory::RateLimited
-                        }
-                        _ => SttConnectionCategory::RateLimited,
-                    };
+            self.send_json(&backend_config_message(&config, audio_encoder.is_some()))
+                .await?;
+            log::debug!("Config message sent");
 
-                    return SttError::Connection(SttConnectionError {
-                        message: display_message,
-                        details: SttConnectionDetails {
-                            category: Some(category),
-                            http_status: Some(429),
-                            server_code,
-                            ..Default::default()
-                        },
-                    });
-                }
+            let Some(encoder) = audio_encoder.take() else {
+                break (ws_write, read);
+            };
 
-                {
-                    let status_u16 = status.as_u16();
-                    let category = if matches!(status_u16, 502 | 503 | 504) {
-                        SttConnectionCategory::ServerUnavailable
+            // Кодировку подтверждает `Ready`. Ошибка или тишина — backend Opus не принимает:
+            // закрываем сокет и повторяем с PCM.
+            match tokio::time::timeout(OPUS_NEGOTIATION_TIMEOUT, wait_for_ready(&mut read)).await {
+                Ok(Ok((session_id, encoding, capabilities))) => {
+                    if encoding.as_deref() == Some(ENCODING_OPUS) {
+                        log::info!("Backend accepted Opus audio transport");
+                        audio_encoder = Some(encoder);
                     } else {
-                        SttConnectionCategory::Http
-                    };
-                    SttError::Connection(SttConnectionError {
-                        message: format!("WS connection failed: HTTP error: {}", status),
-                        details: SttConnectionDetails {
-                            category: Some(category),
-                            http_status: Some(status_u16),
-                            ..Default::default()
-                        },
-                    })
+                        log::info!(
+                            "Backend answered Ready with encoding {:?}, falling back to PCM",
+                            encoding
+                        );
+                    }
+                    ready_session = Some((session_id, capabilities));
+                    break (ws_write, read);
                 }
+                Ok(Err(e)) => {
+                    log::warn!("Backend rejected Opus config, falling back to PCM: {}", e)
+                }
+                Err(_) => log::warn!(
+                    "Backend did not confirm Opus within {} ms, falling back to PCM",
+                    OPUS_NEGOTIATION_TIMEOUT.as_millis()
+                ),
             }
-            tokio_tungstenite::tungstenite::Error::Tls(other) => SttError::Connection(SttConnectionError {
-                message: format!("WS connection failed: {}", other),
-                details: SttConnectionDetails {
-                    category: Some(SttConnectionCategory::Tls),
-                    ..Default::default()
-                },
-            }),
-            tokio_tungstenite::tungstenite::Error::Io(ioe) => {
-                let kind = ioe.kind();
-                let kind_str = format!("{:?}", kind);
-                let os_error = ioe.raw_os_error();
-                let category = match kind {
-                    std::io::ErrorKind::ConnectionRefused => SttConnectionCategory::Refused,
-                    std::io::ErrorKind::ConnectionReset => SttConnectionCategory::Reset,
-                    std::io::ErrorKind::NotConnected
-                    | std::io::ErrorKind::NetworkUnreachable
-                    | std::io::ErrorKind::HostUnreachable
-                    | std::io::ErrorKind::AddrNotAvailable => SttConnectionCategory::Offline,
-                    std::io::ErrorKind::TimedOut => SttConnectionCategory::Timeout,
-                    _ => SttConnectionCategory::Unknown,
-                };
-                SttError::Connection(SttConnectionError {
-                    message: format!("WS connection failed: {}", ioe),
-                    details: SttConnectionDetails {
-                        category: Some(category),
-                        io_error_kind: Some(kind_str),
-                        os_error,
-                        ..Default::default()
-                    },
-                })
-            }
-            other => SttError::Connection(SttConnectionError {
-                message: format!("WS connection failed: {}", other),
-                details: SttConnectionDetails::default(),
-            }),
-        })?;
-
-        log::info!("Backend WebSocket connected");
-
-        // Сбрасываем флаг закрытия — соединение установлено
-        self.is_closed.store(false, Ordering::SeqCst);
-
-        let (write, mut read) = ws_stream.split();
-        let ws_write = Arc::new(Mutex::new(write));
-        self.ws_write = Some(ws_write.clone());
+            self.ws_write = None;
+            let _ = ws_write.lock().await.close().await;
+        };
+        self.audio_encoder = audio_encoder;
+        self.resume = Arc::new(SessionResume::new(RESUME_REPLAY_MAX_BYTES));
 
         // Сохраняем callbacks как "active" (для receiver task).
         {
@@ -832,36 +1381,11 @@ impl SttProvider for BackendProvider {
             state.swap_after_seq = 0;
         }
 
-        // Отправляем Config message
-        let provider_name = backend_streaming_provider_name(&config);
-
-        // Парсим keyterms из конфига (строка через запятую → Vec<String>)
-        let keyterms = config.streaming_keyterms.as_ref().and_then(|raw| {
-            let terms: Vec<String> = raw
-                .split(',')
-                .map(|t| t.trim().to_string())
-                .filter(|t| !t.is_empty())
-                .collect();
-            if terms.is_empty() {
-                None
-            } else {
-                Some(terms)
-            }
-        });
-
-        let config_msg = ClientMessage::Config {
-            protocol_v: 2,
-            provider: provider_name.to_string(),
-            language: config.language.clone(),
-            sample_rate: 16000,
-            channels: 1,
-            encoding: "pcm_s16le".to_string(),
-            keyterms,
-            capabilities: vec![CAPABILITY_FINALIZE_ACK.to_string()],
-        };
-
-        self.send_json(&config_msg).await?;
-        log::debug!("Config message sent");
+        // `Ready` уже прочитан при согласовании кодировки — receiver его не увидит
+        if let Some((session_id, capabilities)) = ready_session {
+            register_ready_session(&self.resume, session_id, &capabilities);
+            emit_backend_quality(&self.callbacks, "Good", None).await;
+        }
 
         // Запускаем receiver task для обработки сообщений от сервера.
         // Берём callbacks из self.callbacks, чтобы они могли обновляться при resume_stream.
@@ -870,6 +1394,15 @@ impl SttProvider for BackendProvider {
         let on_usage_cb = self.on_usage_update_callback.clone();
         let is_closed_flag = self.is_closed.clone();
         let shared_remaining = self.last_remaining_secs.clone();
+        let resume_ctx = ResumeContext {
+            backend_url: self.backend_url.clone(),
+            auth_token,
+            resume: self.resume.clone(),
+            ws_write: ws_write.clone(),
+            is_closed: self.is_closed.clone(),
+            finalize_waiter: self.finalize_waiter.clone(),
+            callbacks: self.callbacks.clone(),
+        };
 
         // Сбрасываем remaining на старте нового соединения
         shared_remaining.store(f32::MAX.to_bits(), Ordering::SeqCst);
@@ -881,14 +1414,51 @@ impl SttProvider for BackendProvider {
             let mut server_error_reported = false;
             let mut finalize_text_results_seen = 0usize;
 
-            while let Some(msg_result) = read.next().await {
+            loop {
+                let msg_result = tokio::select! {
+                    next = read.next() => next,
+                    _ = resume_ctx.resume.transport_failed.notified() => {
+                        // Сигнал мог остаться от уже завершённого восстановления
+                        if !resume_ctx.resume.is_resuming() {
+                            continue;
+                        }
+                        if let Some(next_read) =
+                            resume_ctx.resume_after_disconnect("audio send failed").await
+                        {
+                            read = next_read;
+                            continue;
+                        }
+                        break;
+                    }
+                };
+                let Some(msg_result) = msg_result else {
+                    // EOF без close frame — типичный обрыв Wi-Fi/NAT
+                    let remaining = f32::from_bits(shared_remaining.load(Ordering::SeqCst));
+                    if !server_error_reported && remaining >= LIMIT_REMAINING_THRESHOLD {
+                        if let Some(next_read) =
+                            resume_ctx.resume_after_disconnect("stream ended").await
+                        {
+                            read = next_read;
+                            continue;
+                        }
+                    }
+                    break;
+                };
                 match msg_result {
                     Ok(Message::Text(text)) => {
                         match serde_json::from_str::<ServerMessage>(&text) {
                             Ok(server_msg) => {
                                 match server_msg {
-                                    ServerMessage::Ready { session_id } => {
-                                        log::info!("Session ready: {}", session_id);
+                                    ServerMessage::Ready {
+                                        session_id,
+                                        capabilities,
+                                        ..
+                                    } => {
+                                        register_ready_session(
+                                            &resume_ctx.resume,
+                                            session_id,
+                                            &capabilities,
+                                        );
                                         // Уведомляем о хорошем качестве связи
                                         let cb = {
                                             let state = callbacks_state.lock().await;
@@ -906,6 +1476,7 @@ impl SttProvider for BackendProvider {
 
                                     ServerMessage::Ack { seq } => {
                                         log::trace!("Ack received: seq={}", seq);
+                                        resume_ctx.resume.ack(seq);
                                         // Если есть pending callbacks (новая UI-сессия) — активируем их на первом ACK.
                                         // Это даёт чёткую границу между "старыми" и "новыми" результатами.
                                         let swapped = {
@@ -933,6 +1504,8 @@ impl SttProvider for BackendProvider {
                                         is_segment_final,
                                         start_ms,
                                         duration_ms,
+                                        words,
+                                        speaker,
                                     } => {
                                         log::debug!("Partial: {} (conf: {:?})", text, confidence);
                                         let has_text = !text.trim().is_empty();
@@ -947,7 +1520,10 @@ impl SttProvider for BackendProvider {
                                         .with_timing(
                                             start_ms.unwrap_or(0) as f64 / 1000.0,
                                             duration_ms.unwrap_or(0) as f64 / 1000.0,
-                                        );
+                                        )
+                                        .with_words(words.into_iter().map(Word::from).collect());
+                                        transcription =
+                                            with_backend_speaker(transcription, speaker);
                                         if let Some(conf) = confidence {
                                             transcription = transcription.with_confidence(conf);
                                         }
@@ -967,6 +1543,8 @@ impl SttProvider for BackendProvider {
                                         confidence,
                                         start_ms,
                                         duration_ms,
+                                        words,
+                                        speaker,
                                     } => {
                                         log::debug!(
                                             "Final: {} (conf: {:?}, dur: {}ms)",
@@ -983,7 +1561,12 @@ impl SttProvider for BackendProvider {
                                             .with_timing(
                                                 start_ms.unwrap_or(0) as f64 / 1000.0,
                                                 duration_ms as f64 / 1000.0,
+                                            )
+                                            .with_words(
+                                                words.into_iter().map(Word::from).collect(),
                                             );
+                                        transcription =
+                                            with_backend_speaker(transcription, speaker);
                                         if let Some(conf) = confidence {
                                             transcription = transcription.with_confidence(conf);
                                         }
@@ -1105,6 +1688,18 @@ impl SttProvider for BackendProvider {
                         if is_closed_flag.load(Ordering::SeqCst) || server_error_reported {
                             break;
                         }
+                        let remaining = f32::from_bits(shared_remaining.load(Ordering::SeqCst));
+                        if backend_close_is_transient(frame.as_ref())
+                            && remaining >= LIMIT_REMAINING_THRESHOLD
+                        {
+                            if let Some(next_read) = resume_ctx
+                                .resume_after_disconnect("server going away")
+                                .await
+                            {
+                                read = next_read;
+                                continue;
+                            }
+                        }
                         is_closed_flag.store(true, Ordering::SeqCst);
                         let cb = {
                             let state = callbacks_state.lock().await;
@@ -1173,6 +1768,16 @@ impl SttProvider for BackendProvider {
                         if is_closed_flag.load(Ordering::SeqCst) || server_error_reported {
                             break;
                         }
+                        let remaining = f32::from_bits(shared_remaining.load(Ordering::SeqCst));
+                        if backend_error_is_transient(&e) && remaining >= LIMIT_REMAINING_THRESHOLD
+                        {
+                            if let Some(next_read) =
+                                resume_ctx.resume_after_disconnect("websocket error").await
+                            {
+                                read = next_read;
+                                continue;
+                            }
+                        }
                         is_closed_flag.store(true, Ordering::SeqCst);
                         let cb = {
                             let state = callbacks_state.lock().await;
@@ -1285,6 +1890,7 @@ impl SttProvider for BackendProvider {
         // Поэтому держим TTL коротким и всегда закрываем соединение по таймеру в TranscriptionService.
         let ws_write_for_keepalive = ws_write.clone();
         let is_closed_for_keepalive = self.is_closed.clone();
+        let resume_for_keepalive = self.resume.clone();
         let keepalive_task = tokio::spawn(async move {
             log::debug!("Backend keepalive task started");
             loop {
@@ -1303,6 +1909,13 @@ impl SttProvider for BackendProvider {
                     .and_then(|r| r.ok())
                     .is_none()
                 {
+                    // Сессию можно продолжить — receiver переподключится сам.
+                    if resume_for_keepalive.request_recovery(&is_closed_for_keepalive) {
+                        log::warn!(
+                            "[ReconnectDiag] Backend keepalive ping failed, requesting session resume"
+                        );
+                        continue;
+                    }
                     // Пинг не смогли отправить → считаем соединение закрытым/битым.
                     log::warn!(
                         "[ReconnectDiag] Backend keepalive ping failed, marking connection closed"
@@ -1347,6 +1960,22 @@ impl SttProvider for BackendProvider {
         }
 
         if let Some(ref ws_write) = self.ws_write {
+            // Opus: каждый 40 мс пакет — отдельное сообщение (~25 msg/s), батчинг не нужен
+            if let Some(encoder) = self.audio_encoder.as_mut() {
+                let packets = encoder
+                    .encode(&chunk.data)
+                    .map_err(|e| SttError::Processing(e.to_string()))?;
+                for packet in packets {
+                    let packet_len = packet.len();
+                    self.deliver_audio_frame(ws_write, packet)
+                        .await
+                        .map_err(audio_send_error)?;
+                    self.sent_chunks_count += 1;
+                    self.sent_bytes_total += packet_len;
+                }
+                return Ok(());
+            }
+
             const SAMPLE_RATE_HZ: usize = 16_000;
             const FRAME_MS: usize = 30;
             const SAMPLES_PER_FRAME: usize = SAMPLE_RATE_HZ * FRAME_MS / 1000; // 480
@@ -1397,13 +2026,8 @@ impl SttProvider for BackendProvider {
                 std::time::Instant::now() + std::time::Duration::from_millis(MIN_SEND_INTERVAL_MS),
             );
 
-            let send_fut = async {
-                let mut guard = ws_write.lock().await;
-                guard.send(Message::Binary(bytes)).await
-            };
-
-            match tokio::time::timeout(Duration::from_secs(WS_SEND_TIMEOUT_SECS), send_fut).await {
-                Ok(Ok(())) => {
+            match self.deliver_audio_frame(ws_write, bytes).await {
+                Ok(()) => {
                     self.audio_batch.drain(..bytes_to_send);
                     self.sent_chunks_count += 1;
                     self.sent_bytes_total += bytes_to_send;
@@ -1415,20 +2039,7 @@ impl SttProvider for BackendProvider {
                         );
                     }
                 }
-                Ok(Err(e)) => {
-                    self.is_closed.store(true, Ordering::SeqCst);
-                    return Err(SttError::Connection(SttConnectionError::simple(format!(
-                        "Failed to send audio: {}",
-                        e
-                    ))));
-                }
-                Err(_) => {
-                    self.is_closed.store(true, Ordering::SeqCst);
-                    return Err(SttError::Connection(SttConnectionError::with_category(
-                        "WS send timeout".to_string(),
-                        SttConnectionCategory::Timeout,
-                    )));
-                }
+                Err(e) => return Err(audio_send_error(e)),
             }
 
             if self.audio_batch.is_empty() {
@@ -1509,6 +2120,7 @@ impl SttProvider for BackendProvider {
         self.is_paused = false;
         self.session_id = None;
         self.audio_batch.clear();
+        self.audio_encoder = None;
         self.next_send_at = None;
         self.batch_started_at = None;
         {
@@ -1560,6 +2172,7 @@ impl SttProvider for BackendProvider {
         self.is_paused = false;
         self.session_id = None;
         self.audio_batch.clear();
+        self.audio_encoder = None;
         self.next_send_at = None;
         self.batch_started_at = None;
         {
@@ -1665,7 +2278,16 @@ impl SttProvider for BackendProvider {
         true
     }
 
-    fn supports_keep_alive(&self) -> bool {
+    fn supports_keep_alive(&self) -> bool {
+        true
+    }
+
+    // Флаги уходят в ClientMessage::Config, фильтрует провайдер за backend'ом
+    fn supports_punctuation_control(&self) -> bool {
+        true
+    }
+
+    fn supports_profanity_filter(&self) -> bool {
         true
     }
 
@@ -1786,6 +2408,27 @@ mod tests {
         assert_eq!(backend_streaming_provider_name(&config), "assemblyai");
     }
 
+    #[test]
+    fn test_backend_speaker_falls_back_to_word_labels() {
+        let explicit = with_backend_speaker(
+            Transcription::final_result("hi".to_string()),
+            Some("2".into()),
+        );
+        assert_eq!(explicit.speaker.as_deref(), Some("2"));
+
+        let from_words = with_backend_speaker(
+            Transcription::final_result("hi there".to_string()).with_words(vec![
+                Word::new("hi", 0.0, 0.2).with_speaker("0"),
+                Word::new("there", 0.2, 0.4).with_speaker("0"),
+            ]),
+            None,
+        );
+        assert_eq!(from_words.speaker.as_deref(), Some("0"));
+
+        let unlabeled = with_backend_speaker(Transcription::final_result("hi".to_string()), None);
+        assert_eq!(unlabeled.speaker, None);
+    }
+
     async fn spawn_config_capture_server() -> (String, JoinHandle<serde_json::Value>) {
         let listener = TcpListener::bind("127.0.0.1:0")
             .await
@@ -2203,6 +2846,167 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_opus_config_requests_encoding_and_capability() {
+        let config = SttConfig::new(SttProviderType::Backend);
+
+        let opus = serde_json::to_value(backend_config_message(&config, true)).unwrap();
+        assert_eq!(opus["encoding"], ENCODING_OPUS);
+        assert!(opus["capabilities"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!(CAPABILITY_OPUS)));
+
+        let pcm = serde_json::to_value(backend_config_message(&config, false)).unwrap();
+        assert_eq!(pcm["encoding"], ENCODING_PCM);
+        assert!(!pcm["capabilities"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!(CAPABILITY_OPUS)));
+    }
+
+    #[cfg(not(feature = "opus"))]
+    #[tokio::test]
+    async fn backend_provider_streams_pcm_when_opus_is_not_built_in() {
+        let (backend_url, config_task) = spawn_config_capture_server().await;
+        let mut config = SttConfig::new(SttProviderType::Backend);
+        config.backend_url = Some(backend_url);
+        config.backend_auth_token = Some("test-token".to_string());
+        config.compress_audio = true;
+
+        let mut provider = BackendProvider::new();
+        provider.initialize(&config).await.unwrap();
+        provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_, _| {}),
+            )
+            .await
+            .unwrap();
+
+        let config_msg = tokio::time::timeout(Duration::from_secs(3), config_task)
+            .await
+            .expect("config capture timeout")
+            .expect("config capture task");
+        assert!(provider.audio_encoder.is_none());
+        let _ = provider.abort().await;
+
+        assert_eq!(config_msg["encoding"], ENCODING_PCM);
+    }
+
+    /// Первое соединение отвергает Opus ошибкой, второе (PCM) работает как обычно
+    #[cfg(feature = "opus")]
+    async fn spawn_opus_rejecting_mock_backend() -> (String, JoinHandle<Vec<serde_json::Value>>) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind local websocket listener");
+        let addr = listener.local_addr().expect("listener addr");
+
+        let task = tokio::spawn(async move {
+            let mut configs = Vec::new();
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().await.expect("accept websocket tcp");
+                let mut ws = accept_async(stream).await.expect("accept websocket");
+                while let Some(Ok(msg)) = ws.next().await {
+                    let Message::Text(text) = msg else { continue };
+                    let value: serde_json::Value =
+                        serde_json::from_str(&text).expect("client json message");
+                    if value["type"] != "config" {
+                        continue;
+                    }
+                    let reply = if value["encoding"] == ENCODING_OPUS {
+                        r#"{"type":"error","code":"UNSUPPORTED_ENCODING","message":"opus"}"#
+                    } else {
+                        r#"{"type":"ready","session_id":"pcm-session"}"#
+                    };
+                    configs.push(value);
+                    ws.send(Message::Text(reply.to_string()))
+                        .await
+                        .expect("send reply");
+                    break;
+                }
+            }
+            configs
+        });
+
+        (format!("ws://{addr}"), task)
+    }
+
+    #[cfg(feature = "opus")]
+    #[tokio::test]
+    async fn backend_provider_falls_back_to_pcm_when_opus_is_rejected() {
+        let (backend_url, server_task) = spawn_opus_rejecting_mock_backend().await;
+        let mut config = SttConfig::new(SttProviderType::Backend);
+        config.backend_url = Some(backend_url);
+        config.backend_auth_token = Some("test-token".to_string());
+        config.compress_audio = true;
+
+        let mut provider = BackendProvider::new();
+        provider.initialize(&config).await.unwrap();
+        provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_, _| {}),
+            )
+            .await
+            .unwrap();
+
+        let configs = tokio::time::timeout(Duration::from_secs(3), server_task)
+            .await
+            .expect("mock backend timeout")
+            .expect("mock backend task");
+        assert!(provider.audio_encoder.is_none());
+        let _ = provider.abort().await;
+
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0]["encoding"], ENCODING_OPUS);
+        assert_eq!(configs[1]["encoding"], ENCODING_PCM);
+    }
+
+    #[tokio::test]
+    async fn test_backend_provider_sends_diarization_and_profanity_settings() {
+        for enabled in [false, true] {
+            let (backend_url, config_task) = spawn_config_capture_server().await;
+            let mut config = SttConfig::new(SttProviderType::Backend);
+            config.backend_url = Some(backend_url);
+            config.backend_auth_token = Some("test-token".to_string());
+            config.enable_diarization = enabled;
+            config.filter_profanity = enabled;
+
+            let mut provider = BackendProvider::new();
+            provider.initialize(&config).await.unwrap();
+            provider
+                .start_stream(
+                    Arc::new(|_| {}),
+                    Arc::new(|_| {}),
+                    Arc::new(|_| {}),
+                    Arc::new(|_, _| {}),
+                )
+                .await
+                .unwrap();
+
+            let config_msg = tokio::time::timeout(Duration::from_secs(3), config_task)
+                .await
+                .expect("config capture timeout")
+                .expect("config capture task");
+
+            let _ = provider.abort().await;
+
+            let requested = config_msg["capabilities"]
+                .as_array()
+                .expect("capabilities array")
+                .iter()
+                .any(|capability| capability == CAPABILITY_DIARIZATION);
+            assert_eq!(requested, enabled);
+            assert_eq!(config_msg["profanity_filter"], enabled);
+            assert_eq!(config_msg["punctuate"], true);
+        }
+    }
+
     #[tokio::test]
     async fn test_backend_provider_elevenlabs_full_mock_stream_lifecycle() {
         let (backend_url, server_task) = spawn_lifecycle_mock_backend().await;
@@ -2292,6 +3096,8 @@ mod tests {
         assert_eq!(capture.config["keyterms"][0], "VoicetextAI");
         assert_eq!(capture.config["keyterms"][1], "ElevenLabs");
         assert_eq!(capture.config["capabilities"][0], CAPABILITY_FINALIZE_ACK);
+        assert_eq!(capture.config["capabilities"][1], CAPABILITY_WORD_TIMINGS);
+        assert_eq!(capture.config["capabilities"][2], CAPABILITY_RESUME);
         assert_eq!(capture.binary_lengths, vec![1920, 128]);
         assert_eq!(capture.binary_lengths.iter().sum::<usize>(), 1024 * 2);
         assert!(capture.saw_finalize);
@@ -2371,6 +3177,237 @@ mod tests {
         assert!(capture.saw_finalize);
     }
 
+    /// Первое соединение подтверждает только фрейм #1 и обрывается без close frame.
+    /// Второе ждёт `Resume`, отвечает `Resumed { last_seq_acked: 1 }` и принимает досылку.
+    async fn spawn_resume_mock_backend() -> (String, JoinHandle<(serde_json::Value, Vec<usize>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind local websocket listener");
+        let addr = listener.local_addr().expect("listener addr");
+
+        let task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("accept first websocket tcp");
+            let mut ws = accept_async(stream).await.expect("accept first websocket");
+            let mut binary_count = 0usize;
+            while let Some(next) = ws.next().await {
+                match next.expect("websocket message") {
+                    Message::Text(text) if text.contains(r#""type":"config""#) => {
+                        ws.send(Message::Text(
+                            r#"{"type":"ready","session_id":"resume-session","capabilities":["resume"]}"#
+                                .to_string(),
+                        ))
+                        .await
+                        .expect("send ready");
+                    }
+                    Message::Binary(_) => {
+                        binary_count += 1;
+                        if binary_count == 1 {
+                            ws.send(Message::Text(r#"{"type":"ack","seq":1}"#.to_string()))
+                                .await
+                                .expect("send ack");
+                        } else {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // Обрыв транспорта без close frame.
+            drop(ws);
+
+            let (stream, _) = listener
+                .accept()
+                .await
+                .expect("accept resumed websocket tcp");
+            let mut ws = accept_async(stream)
+                .await
+                .expect("accept resumed websocket");
+            let mut resume_message = None;
+            let mut replayed = Vec::new();
+            while let Some(next) = ws.next().await {
+                match next.expect("websocket message") {
+                    Message::Text(text) => {
+                        let value: serde_json::Value =
+                            serde_json::from_str(&text).expect("client json message");
+                        if value["type"] == "resume" {
+                            resume_message = Some(value);
+                            ws.send(Message::Text(
+                                r#"{"type":"resumed","session_id":"resume-session","last_seq_acked":1}"#
+                                    .to_string(),
+                            ))
+                            .await
+                            .expect("send resumed");
+                        }
+                    }
+                    Message::Binary(bytes) => {
+                        replayed.push(bytes.len());
+                        let seq = 1 + replayed.len();
+                        ws.send(Message::Text(format!(r#"{{"type":"ack","seq":{seq}}}"#)))
+                            .await
+                            .expect("send ack");
+                        ws.send(Message::Text(
+                            r#"{"type":"final","text":"after resume","confidence":0.9,"start_ms":0,"duration_ms":60}"#
+                                .to_string(),
+                        ))
+                        .await
+                        .expect("send final");
+                    }
+                    Message::Close(_) => break,
+                    _ => {}
+                }
+            }
+
+            (resume_message.expect("client resume message"), replayed)
+        });
+
+        (format!("ws://{addr}"), task)
+    }
+
+    #[tokio::test]
+    async fn backend_provider_resumes_session_and_replays_unacked_audio_after_disconnect() {
+        let (backend_url, server_task) = spawn_resume_mock_backend().await;
+        let mut config = SttConfig::new(SttProviderType::Backend);
+        config.backend_url = Some(backend_url);
+        config.backend_auth_token = Some("test-token".to_string());
+
+        let (quality_tx, mut quality_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let (final_tx, mut final_rx) = tokio::sync::mpsc::unbounded_channel::<Transcription>();
+
+        let mut provider = BackendProvider::new();
+        provider.initialize(&config).await.unwrap();
+        provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(move |t| {
+                    let _ = final_tx.send(t);
+                }),
+                Arc::new(|err| panic!("transient disconnect must not surface: {err}")),
+                Arc::new(move |quality, _reason| {
+                    let _ = quality_tx.send(quality);
+                }),
+            )
+            .await
+            .unwrap();
+
+        let quality = tokio::time::timeout(Duration::from_secs(3), quality_rx.recv())
+            .await
+            .expect("quality callback timeout")
+            .expect("quality callback");
+        assert_eq!(quality, "Good");
+
+        for _ in 0..2 {
+            provider
+                .send_audio(&AudioChunk::new(vec![1000; 960], 16_000, 1))
+                .await
+                .unwrap();
+        }
+
+        let final_result = tokio::time::timeout(Duration::from_secs(5), final_rx.recv())
+            .await
+            .expect("final callback timeout")
+            .expect("final callback");
+        assert_eq!(final_result.text, "after resume");
+
+        let mut qualities = Vec::new();
+        while let Ok(quality) = quality_rx.try_recv() {
+            qualities.push(quality);
+        }
+        assert_eq!(
+            qualities,
+            vec!["Recovering".to_string(), "Good".to_string()]
+        );
+
+        provider.abort().await.unwrap();
+
+        let (resume_message, replayed) = tokio::time::timeout(Duration::from_secs(3), server_task)
+            .await
+            .expect("mock backend timeout")
+            .expect("mock backend task");
+        assert_eq!(resume_message["session_id"], "resume-session");
+        assert_eq!(resume_message["last_seq"], 2);
+        assert_eq!(replayed, vec![1920]);
+    }
+
+    /// Старый backend: `Ready` без capability `resume`, после первого чанка рвёт транспорт.
+    /// Возвращает, пришло ли повторное подключение.
+    async fn spawn_resume_unsupported_mock_backend() -> (String, JoinHandle<bool>) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind local websocket listener");
+        let addr = listener.local_addr().expect("listener addr");
+
+        let task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("accept websocket tcp");
+            let mut ws = accept_async(stream).await.expect("accept websocket");
+            while let Some(next) = ws.next().await {
+                match next.expect("websocket message") {
+                    Message::Text(text) if text.contains(r#""type":"config""#) => {
+                        ws.send(Message::Text(
+                            r#"{"type":"ready","session_id":"legacy-session"}"#.to_string(),
+                        ))
+                        .await
+                        .expect("send ready");
+                    }
+                    Message::Binary(_) => break,
+                    _ => {}
+                }
+            }
+            drop(ws);
+
+            tokio::time::timeout(Duration::from_secs(1), listener.accept())
+                .await
+                .is_ok()
+        });
+
+        (format!("ws://{addr}"), task)
+    }
+
+    #[tokio::test]
+    async fn backend_provider_does_not_resume_without_backend_capability() {
+        let (backend_url, server_task) = spawn_resume_unsupported_mock_backend().await;
+        let mut config = SttConfig::new(SttProviderType::Backend);
+        config.backend_url = Some(backend_url);
+        config.backend_auth_token = Some("test-token".to_string());
+
+        let (error_tx, mut error_rx) = tokio::sync::mpsc::unbounded_channel::<SttError>();
+
+        let mut provider = BackendProvider::new();
+        provider.initialize(&config).await.unwrap();
+        provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(move |err| {
+                    let _ = error_tx.send(err);
+                }),
+                Arc::new(|_, _| {}),
+            )
+            .await
+            .unwrap();
+
+        provider
+            .send_audio(&AudioChunk::new(vec![1000; 960], 16_000, 1))
+            .await
+            .unwrap();
+
+        let error = tokio::time::timeout(Duration::from_secs(3), error_rx.recv())
+            .await
+            .expect("error callback timeout")
+            .expect("error callback");
+        assert!(matches!(error, SttError::Connection(_)), "{error}");
+
+        let reconnected = tokio::time::timeout(Duration::from_secs(3), server_task)
+            .await
+            .expect("mock backend timeout")
+            .expect("mock backend task");
+        assert!(
+            !reconnected,
+            "client must not try to resume a legacy session"
+        );
+
+        let _ = provider.abort().await;
+    }
+
     #[tokio::test]
     async fn backend_provider_accepts_provider_bounded_delayed_finalize_ack() {
         let (backend_url, server_task) =
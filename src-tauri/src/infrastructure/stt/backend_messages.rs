@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::domain::Word;
+
 /// Сообщения от клиента к бэкенду
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -20,16 +22,28 @@ pub enum ClientMessage {
         sample_rate: u32,
         /// Количество каналов (1 = моно)
         channels: u8,
-        /// Кодировка: pcm_s16le
+        /// Кодировка: pcm_s16le или opus (вместе с capability `opus`)
         encoding: String,
         /// Ключевые термины для улучшения распознавания
         #[serde(skip_serializing_if = "Option::is_none")]
         keyterms: Option<Vec<String>>,
+        /// Автоматическая пунктуация (`SttConfig::enable_punctuation`)
+        punctuate: bool,
+        /// Маскирование мата на стороне провайдера (`SttConfig::filter_profanity`)
+        profanity_filter: bool,
         /// Optional protocol capabilities. Unknown values are ignored by older backends.
         #[serde(skip_serializing_if = "Vec::is_empty")]
         capabilities: Vec<String>,
     },
 
+    /// Переподключение к существующей сессии после обрыва транспорта (capability `resume`).
+    /// Сервер отвечает `Resumed` с последним подтверждённым seq.
+    Resume {
+        session_id: String,
+        /// Последний seq, который клиент успел отправить
+        last_seq: u64,
+    },
+
     /// Клиент закрывает сессию
     Close,
 
@@ -47,7 +61,15 @@ pub enum ClientMessage {
 #[allow(dead_code)]
 pub enum ServerMessage {
     /// Сессия готова к приёму аудио
-    Ready { session_id: String },
+    Ready {
+        session_id: String,
+        /// Кодировка, которую принял backend. Нет поля — старый backend, только PCM.
+        #[serde(default)]
+        encoding: Option<String>,
+        /// Capabilities из `Config`, которые backend поддержал. Нет поля — старый backend.
+        #[serde(default)]
+        capabilities: Vec<String>,
+    },
 
     /// Подтверждение приёма аудио чанка
     Ack { seq: u64 },
@@ -63,6 +85,12 @@ pub enum ServerMessage {
         start_ms: Option<u64>,
         #[serde(default)]
         duration_ms: Option<u64>,
+        /// Пословные тайминги (только если клиент заявил capability `word_timings`)
+        #[serde(default)]
+        words: Vec<ServerWord>,
+        /// Метка говорящего (только при capability `diarization`)
+        #[serde(default)]
+        speaker: Option<String>,
     },
 
     /// Финальный результат (не изменится)
@@ -75,6 +103,10 @@ pub enum ServerMessage {
         /// Длительность обработанного аудио в мс
         #[serde(default)]
         duration_ms: u64,
+        #[serde(default)]
+        words: Vec<ServerWord>,
+        #[serde(default)]
+        speaker: Option<String>,
     },
 
     /// Обновление usage (для отображения на клиенте)
@@ -100,6 +132,33 @@ pub enum ServerMessage {
     FinalizeComplete { status: String, saw_result: bool },
 }
 
+/// Слово в Partial/Final: время в мс от начала стрима, как и `start_ms`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerWord {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+    #[serde(default)]
+    pub punctuated_word: Option<String>,
+    #[serde(default)]
+    pub speaker: Option<String>,
+}
+
+impl From<ServerWord> for Word {
+    fn from(word: ServerWord) -> Self {
+        Word {
+            text: word.word,
+            start: word.start_ms as f64 / 1000.0,
+            end: word.end_ms as f64 / 1000.0,
+            confidence: word.confidence,
+            punctuated: word.punctuated_word,
+            speaker: word.speaker,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,11 +173,15 @@ mod tests {
             channels: 1,
             encoding: "pcm_s16le".to_string(),
             keyterms: None,
+            punctuate: true,
+            profanity_filter: false,
             capabilities: vec!["finalize_ack".to_string()],
         };
 
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains(r#""type":"config""#));
+        assert!(json.contains(r#""punctuate":true"#));
+        assert!(json.contains(r#""profanity_filter":false"#));
         assert!(json.contains(r#""provider":"deepgram""#));
         assert!(json.contains(r#""capabilities":["finalize_ack"]"#));
     }
@@ -129,13 +192,55 @@ mod tests {
         let msg: ServerMessage = serde_json::from_str(json).unwrap();
 
         match msg {
-            ServerMessage::Ready { session_id } => {
+            ServerMessage::Ready {
+                session_id,
+                encoding,
+                capabilities,
+            } => {
                 assert_eq!(session_id, "abc-123");
+                assert_eq!(encoding, None);
+                assert!(capabilities.is_empty());
             }
             _ => panic!("Expected Ready message"),
         }
     }
 
+    #[test]
+    fn test_deserialize_ready_with_confirmed_capabilities() {
+        let json = r#"{"type":"ready","session_id":"abc-123","capabilities":["resume"]}"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(
+            msg,
+            ServerMessage::Ready { ref capabilities, .. } if capabilities == &["resume"]
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_ready_with_negotiated_encoding() {
+        let json = r#"{"type":"ready","session_id":"abc-123","encoding":"opus"}"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(
+            msg,
+            ServerMessage::Ready { encoding: Some(ref encoding), .. } if encoding == "opus"
+        ));
+    }
+
+    #[test]
+    fn test_serialize_resume_message() {
+        let msg = ClientMessage::Resume {
+            session_id: "session-1".to_string(),
+            last_seq: 42,
+        };
+
+        let json = serde_json::to_string(&msg).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"resume","session_id":"session-1","last_seq":42}"#
+        );
+    }
+
     #[test]
     fn test_deserialize_partial_message() {
         let json = r#"{"type":"partial","text":"привет","confidence":0.85}"#;
@@ -148,8 +253,12 @@ mod tests {
                 is_segment_final,
                 start_ms,
                 duration_ms,
+                words,
+                speaker,
             } => {
                 assert_eq!(text, "привет");
+                assert!(words.is_empty());
+                assert_eq!(speaker, None);
                 assert_eq!(confidence, Some(0.85));
                 assert_eq!(is_segment_final, None);
                 assert_eq!(start_ms, None);
@@ -171,8 +280,12 @@ mod tests {
                 is_segment_final,
                 start_ms,
                 duration_ms,
+                words,
+                speaker,
             } => {
                 assert_eq!(text, "первый кусок");
+                assert!(words.is_empty());
+                assert_eq!(speaker, None);
                 assert_eq!(confidence, Some(0.9));
                 assert_eq!(is_segment_final, Some(true));
                 assert_eq!(start_ms, Some(120));
@@ -193,8 +306,12 @@ mod tests {
                 confidence,
                 start_ms,
                 duration_ms,
+                words,
+                speaker,
             } => {
                 assert_eq!(text, "готово");
+                assert!(words.is_empty());
+                assert_eq!(speaker, None);
                 assert_eq!(confidence, Some(0.9));
                 assert_eq!(start_ms, Some(120));
                 assert_eq!(duration_ms, 0);
@@ -203,6 +320,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_deserialize_final_with_word_timings() {
+        let json = r#"{"type":"final","text":"Привет, мир","start_ms":500,"duration_ms":900,"words":[{"word":"привет","start_ms":500,"end_ms":900,"confidence":0.95,"punctuated_word":"Привет,"},{"word":"мир","start_ms":1000,"end_ms":1400}]}"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+
+        let ServerMessage::Final { words, .. } = msg else {
+            panic!("Expected Final message");
+        };
+        let words: Vec<Word> = words.into_iter().map(Word::from).collect();
+        assert_eq!(
+            words,
+            vec![
+                Word::new("привет", 0.5, 0.9)
+                    .with_confidence(0.95)
+                    .with_punctuated("Привет,"),
+                Word::new("мир", 1.0, 1.4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_final_with_speaker_labels() {
+        let json = r#"{"type":"final","text":"да","duration_ms":300,"speaker":"1","words":[{"word":"да","start_ms":0,"end_ms":300,"speaker":"1"}]}"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+
+        let ServerMessage::Final { words, speaker, .. } = msg else {
+            panic!("Expected Final message");
+        };
+        assert_eq!(speaker.as_deref(), Some("1"));
+        let word = Word::from(words.into_iter().next().unwrap());
+        assert_eq!(word.speaker.as_deref(), Some("1"));
+    }
+
     #[test]
     fn test_deserialize_usage_update() {
         let json = r#"{"type":"usage_update","seconds_used":10.5,"seconds_remaining_plan":989.5}"#;
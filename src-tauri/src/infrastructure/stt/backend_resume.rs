@@ -0,0 +1,251 @@
+//! Возобновление backend-сессии после обрыва транспорта.
+//!
+//! Каждый бинарный фрейм получает seq (1, 2, ...) — так же считает backend в `Ack { seq }`.
+//! Неподтверждённые фреймы держим в ограниченном replay buffer: после переподключения
+//! с прежним `session_id` сервер отвечает `Resumed { last_seq_acked }`, и мы досылаем
+//! всё, что после него. Пока идёт восстановление, новые фреймы только буферизуются.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex as StdMutex;
+
+use tokio::sync::Notify;
+
+/// ~20 секунд PCM16 16 кГц моно: с запасом покрывает окно переподключения
+pub(super) const RESUME_REPLAY_MAX_BYTES: usize = 16_000 * 2 * 20;
+
+/// Ограниченная очередь отправленных, но ещё не подтверждённых фреймов
+#[derive(Debug)]
+pub(super) struct ReplayBuffer {
+    frames: VecDeque<(u64, Vec<u8>)>,
+    bytes: usize,
+    max_bytes: usize,
+    last_seq: u64,
+}
+
+impl ReplayBuffer {
+    pub(super) fn new(max_bytes: usize) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            bytes: 0,
+            max_bytes,
+            last_seq: 0,
+        }
+    }
+
+    /// Кладёт фрейм и возвращает его seq. Самые старые фреймы вытесняются при переполнении.
+    pub(super) fn push(&mut self, bytes: Vec<u8>) -> u64 {
+        self.last_seq += 1;
+        self.bytes += bytes.len();
+        self.frames.push_back((self.last_seq, bytes));
+        while self.bytes > self.max_bytes && self.frames.len() > 1 {
+            if let Some((_, dropped)) = self.frames.pop_front() {
+                self.bytes -= dropped.len();
+            }
+        }
+        self.last_seq
+    }
+
+    /// Сервер подтвердил всё до `seq` включительно
+    pub(super) fn ack(&mut self, seq: u64) {
+        while self
+            .frames
+            .front()
+            .is_some_and(|(frame_seq, _)| *frame_seq <= seq)
+        {
+            if let Some((_, acked)) = self.frames.pop_front() {
+                self.bytes -= acked.len();
+            }
+        }
+    }
+
+    /// Фреймы, которые нужно дослать после `Resumed { last_seq_acked }`
+    pub(super) fn after(&self, seq: u64) -> Vec<(u64, Vec<u8>)> {
+        self.frames
+            .iter()
+            .filter(|(frame_seq, _)| *frame_seq > seq)
+            .cloned()
+            .collect()
+    }
+
+    /// Первый seq, который ещё можно дослать (`None` — буфер пуст)
+    pub(super) fn first_seq(&self) -> Option<u64> {
+        self.frames.front().map(|(seq, _)| *seq)
+    }
+
+    pub(super) fn last_seq(&self) -> u64 {
+        self.last_seq
+    }
+}
+
+#[derive(Debug)]
+struct ResumeState {
+    session_id: Option<String>,
+    replay: ReplayBuffer,
+    resuming: bool,
+}
+
+/// Общее состояние восстановления между `BackendProvider` и receiver task одного соединения
+pub(super) struct SessionResume {
+    state: StdMutex<ResumeState>,
+    /// Отправка упала — receiver должен начать восстановление, даже если чтение ещё "живое"
+    pub(super) transport_failed: Notify,
+    /// Восстановление завершилось (успешно или нет)
+    pub(super) settled: Notify,
+}
+
+impl SessionResume {
+    pub(super) fn new(max_replay_bytes: usize) -> Self {
+        Self {
+            state: StdMutex::new(ResumeState {
+                session_id: None,
+                replay: ReplayBuffer::new(max_replay_bytes),
+                resuming: false,
+            }),
+            transport_failed: Notify::new(),
+            settled: Notify::new(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ResumeState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(super) fn set_session_id(&self, session_id: String) {
+        self.lock().session_id = Some(session_id);
+    }
+
+    pub(super) fn ack(&self, seq: u64) {
+        self.lock().replay.ack(seq);
+    }
+
+    pub(super) fn is_resuming(&self) -> bool {
+        self.lock().resuming
+    }
+
+    /// Регистрирует фрейм. `true` — сейчас идёт восстановление, слать в сокет не нужно:
+    /// фрейм уйдёт вместе с остальными после `Resumed`.
+    pub(super) fn push_frame(&self, bytes: Vec<u8>) -> bool {
+        let mut state = self.lock();
+        state.replay.push(bytes);
+        state.resuming
+    }
+
+    /// Сторона отправки увидела сломанный транспорт. `false` — восстановить нельзя
+    /// (соединение закрыто нами или сессия ещё не получила `Ready`).
+    pub(super) fn request_recovery(&self, is_closed: &AtomicBool) -> bool {
+        if is_closed.load(Ordering::SeqCst) {
+            return false;
+        }
+        let mut state = self.lock();
+        if state.session_id.is_none() {
+            return false;
+        }
+        if !state.resuming {
+            state.resuming = true;
+            self.transport_failed.notify_one();
+        }
+        true
+    }
+
+    /// Начало восстановления в receiver task: `(session_id, last_seq)` для `Resume`
+    pub(super) fn begin(&self) -> Option<(String, u64)> {
+        let mut state = self.lock();
+        let session_id = state.session_id.clone()?;
+        state.resuming = true;
+        Some((session_id, state.replay.last_seq()))
+    }
+
+    /// Сервер принял сессию: отбрасываем подтверждённое и снимаем флаг восстановления.
+    /// Возвращённые фреймы нужно отправить до того, как отпустить writer lock.
+    pub(super) fn complete(&self, last_seq_acked: u64) -> Vec<(u64, Vec<u8>)> {
+        let frames = {
+            let mut state = self.lock();
+            state.replay.ack(last_seq_acked);
+            if let Some(first_seq) = state.replay.first_seq() {
+                if first_seq > last_seq_acked + 1 {
+                    log::warn!(
+                        "Backend resume: frames {}..{} were evicted from the replay buffer",
+                        last_seq_acked + 1,
+                        first_seq - 1
+                    );
+                }
+            }
+            state.resuming = false;
+            state.replay.after(last_seq_acked)
+        };
+        self.settled.notify_waiters();
+        frames
+    }
+
+    /// Восстановить не удалось
+    pub(super) fn abandon(&self) {
+        self.lock().resuming = false;
+        self.settled.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_buffer_numbers_frames_and_drops_acked() {
+        let mut replay = ReplayBuffer::new(1024);
+        assert_eq!(replay.push(vec![1; 10]), 1);
+        assert_eq!(replay.push(vec![2; 10]), 2);
+        assert_eq!(replay.push(vec![3; 10]), 3);
+
+        replay.ack(1);
+        let pending: Vec<u64> = replay.after(0).into_iter().map(|(seq, _)| seq).collect();
+        assert_eq!(pending, vec![2, 3]);
+        assert_eq!(replay.after(2).len(), 1);
+        assert_eq!(replay.last_seq(), 3);
+    }
+
+    #[test]
+    fn replay_buffer_is_bounded_by_bytes() {
+        let mut replay = ReplayBuffer::new(25);
+        for _ in 0..5 {
+            replay.push(vec![0; 10]);
+        }
+
+        assert_eq!(replay.first_seq(), Some(4));
+        assert_eq!(replay.after(0).len(), 2);
+        assert_eq!(replay.last_seq(), 5);
+    }
+
+    #[test]
+    fn frames_pushed_while_resuming_are_replayed_after_resume() {
+        let resume = SessionResume::new(1024);
+        let is_closed = AtomicBool::new(false);
+
+        assert!(!resume.push_frame(vec![1]));
+        assert!(
+            !resume.request_recovery(&is_closed),
+            "no session id before Ready"
+        );
+
+        resume.set_session_id("session".to_string());
+        assert!(!resume.push_frame(vec![2]));
+        assert!(resume.request_recovery(&is_closed));
+        assert!(resume.push_frame(vec![3]));
+        assert_eq!(resume.begin(), Some(("session".to_string(), 3)));
+
+        let replay: Vec<u64> = resume.complete(1).into_iter().map(|(seq, _)| seq).collect();
+        assert_eq!(replay, vec![2, 3]);
+        assert!(!resume.is_resuming());
+        assert!(!resume.push_frame(vec![4]));
+    }
+
+    #[test]
+    fn recovery_is_refused_after_local_close() {
+        let resume = SessionResume::new(1024);
+        resume.set_session_id("session".to_string());
+
+        assert!(!resume.request_recovery(&AtomicBool::new(true)));
+        assert!(!resume.is_resuming());
+    }
+}
@@ -18,8 +18,9 @@ use tokio_tungstenite::{
 use crate::domain::{
     AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttConnectionCategory,
     SttConnectionDetails, SttConnectionError, SttError, SttProvider, SttResult, Transcription,
-    TranscriptionCallback,
+    TranscriptionCallback, Word,
 };
+use crate::infrastructure::audio::OggOpusStream;
 use crate::infrastructure::embedded_keys;
 
 /// Deepgram cloud STT provider
@@ -31,7 +32,7 @@ use crate::infrastructure::embedded_keys;
 /// Protocol:
 /// 1. Connect with Authorization: Token API_KEY header
 /// 2. Pass encoding, sample_rate, model, language as query params
-/// 3. Stream raw PCM binary audio data
+/// 3. Stream raw PCM binary audio data (or Ogg Opus with `compress_audio`)
 /// 4. Receive JSON messages: type=Results, is_final, speech_final
 const DEEPGRAM_WS_URL: &str = "wss://api.deepgram.com/v1/listen";
 // Deepgram default endpointing is aggressive for conversational speech.
@@ -78,22 +79,87 @@ fn build_keyterms_query(keyterms: &Option<String>) -> String {
         .collect()
 }
 
+/// Флаги распознавания из `SttConfig`, которые Deepgram принимает query-параметрами
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DeepgramListenFlags {
+    punctuate: bool,
+    profanity_filter: bool,
+    diarize: bool,
+    /// `encoding=opus` (Ogg Opus) вместо `linear16`
+    opus: bool,
+}
+
+impl DeepgramListenFlags {
+    fn from_config(config: Option<&SttConfig>) -> Self {
+        config.map_or_else(Self::default, |config| Self {
+            punctuate: config.enable_punctuation,
+            profanity_filter: config.filter_profanity,
+            diarize: config.enable_diarization,
+            opus: config.compress_audio,
+        })
+    }
+}
+
+impl Default for DeepgramListenFlags {
+    fn default() -> Self {
+        Self {
+            punctuate: true,
+            profanity_filter: false,
+            diarize: false,
+            opus: false,
+        }
+    }
+}
+
 fn build_deepgram_listen_url(
     base_url: &str,
     model: &str,
     language: &str,
     keyterms: &Option<String>,
+    flags: DeepgramListenFlags,
 ) -> String {
     format!(
-        "{}?encoding=linear16&sample_rate=16000&channels=1&model={}&language={}&punctuate=true&interim_results=true&endpointing={}{}",
+        "{}?encoding={}&sample_rate=16000&channels=1&model={}&language={}&punctuate={}&interim_results=true&endpointing={}{}{}{}",
         base_url,
+        if flags.opus { "opus" } else { "linear16" },
         urlencoding::encode(model),
         urlencoding::encode(language),
+        flags.punctuate,
         DEEPGRAM_ENDPOINTING_MS,
+        if flags.profanity_filter {
+            "&profanity_filter=true"
+        } else {
+            ""
+        },
+        if flags.diarize { "&diarize=true" } else { "" },
         build_keyterms_query(keyterms)
     )
 }
 
+/// Байты для отправки: Ogg Opus, если кодер включён, иначе PCM16 little-endian.
+/// `flush` добивает хвост кодера тишиной — перед Finalize/CloseStream, чтобы не потерять
+/// последние миллисекунды. В Opus-режиме результат может быть пустым (меньше кадра).
+fn deepgram_audio_payload(
+    encoder: Option<&mut OggOpusStream>,
+    samples: &[i16],
+    flush: bool,
+) -> SttResult<Vec<u8>> {
+    let Some(encoder) = encoder else {
+        return Ok(samples
+            .iter()
+            .flat_map(|&sample| sample.to_le_bytes())
+            .collect());
+    };
+    let encode_error = |e: crate::infrastructure::audio::AudioEncodeError| {
+        SttError::Processing(format!("Deepgram audio encoding failed: {}", e))
+    };
+    let mut bytes = encoder.encode(samples).map_err(encode_error)?;
+    if flush {
+        bytes.extend(encoder.flush().map_err(encode_error)?);
+    }
+    Ok(bytes)
+}
+
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 /// Актуальные callbacks текущей сессии записи.
@@ -180,6 +246,31 @@ fn deepgram_transport_error(error: impl std::fmt::Display, context: &str) -> Stt
     ))
 }
 
+/// `alternatives[0].words`: `{word, start, end, confidence, punctuated_word, speaker}`, время в секундах
+/// от начала стрима. `punctuated_word` есть только при `smart_format`/`punctuate`.
+fn deepgram_words(alternative: &Value) -> Vec<Word> {
+    let Some(words) = alternative.get("words").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    words
+        .iter()
+        .filter_map(|word| {
+            let text = word["word"].as_str()?;
+            let mut parsed = Word::new(
+                text,
+                word["start"].as_f64().unwrap_or(0.0),
+                word["end"].as_f64().unwrap_or(0.0),
+            );
+            parsed.confidence = word["confidence"].as_f64().map(|v| v as f32);
+            parsed.punctuated = word["punctuated_word"].as_str().map(str::to_string);
+            // `speaker` (0, 1, ...) приходит только при diarize=true
+            parsed.speaker = word["speaker"].as_u64().map(|speaker| speaker.to_string());
+            Some(parsed)
+        })
+        .collect()
+}
+
 fn call_deepgram_callback(label: &str, callback: impl FnOnce()) {
     if catch_unwind(AssertUnwindSafe(callback)).is_err() {
         log::error!("Deepgram {} callback panicked", label);
@@ -213,6 +304,8 @@ pub struct DeepgramProvider {
     callbacks: Arc<Mutex<Option<ActiveCallbacks>>>, // общий держатель callbacks, обновляется при resume
     finalize_flush_rx: Option<tokio::sync::watch::Receiver<u64>>, // сигнал от receiver: пришёл from_finalize
     audio_buffer: Vec<i16>,
+    audio_encoder: Option<OggOpusStream>, // Some — соединение открыто с encoding=opus
+    opus_rejected: bool, // сервер отверг encoding=opus (HTTP 400) — дальше только linear16
     on_partial_callback: Option<TranscriptionCallback>, // сохраняем для resume
     on_final_callback: Option<TranscriptionCallback>,
     on_error_callback: Option<ErrorCallback>,
@@ -249,6 +342,8 @@ impl DeepgramProvider {
             callbacks: Arc::new(Mutex::new(None)),
             finalize_flush_rx: None,
             audio_buffer: Vec::new(),
+            audio_encoder: None,
+            opus_rejected: false,
             on_partial_callback: None,
             on_final_callback: None,
             on_error_callback: None,
@@ -267,6 +362,15 @@ impl DeepgramProvider {
         }
     }
 
+    /// Есть ли что дослать перед Finalize/CloseStream: PCM-буфер или хвост Opus-кодера
+    fn has_unsent_audio(&self) -> bool {
+        !self.audio_buffer.is_empty()
+            || self
+                .audio_encoder
+                .as_ref()
+                .is_some_and(OggOpusStream::has_pending)
+    }
+
     #[cfg(test)]
     fn with_ws_base_url(ws_base_url: String) -> Self {
         let mut provider = Self::new();
@@ -365,46 +469,93 @@ impl SttProvider for DeepgramProvider {
             language
         );
 
-        // Собираем URL с параметрами (добавляем channels=1 для mono)
-        let url = build_deepgram_listen_url(
-            &self.ws_base_url,
-            &model,
-            &language,
-            &self
-                .config
-                .as_ref()
-                .and_then(|c| c.streaming_keyterms.clone()),
-        );
+        // Opus только если сборка его поддерживает и сервер ещё не отказывал
+        self.audio_encoder = match self.config.as_ref() {
+            Some(config) if config.compress_audio && !self.opus_rejected => {
+                match OggOpusStream::new() {
+                    Ok(encoder) => Some(encoder),
+                    Err(e) => {
+                        log::warn!("DeepgramProvider: audio compression unavailable: {}", e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
 
-        log::debug!("Connecting to Deepgram: {}", url);
-
-        // Формируем WebSocket запрос с заголовком авторизации
-        let request = Request::builder()
-            .method("GET")
-            .uri(&url)
-            .header("Host", "api.deepgram.com")
-            .header("Connection", "Upgrade")
-            .header("Upgrade", "websocket")
-            .header("Sec-WebSocket-Version", "13")
-            .header(
-                "Sec-WebSocket-Key",
-                tokio_tungstenite::tungstenite::handshake::client::generate_key(),
-            )
-            .header("Authorization", format!("Token {}", api_key))
-            .body(())
-            .map_err(|e| {
-                SttError::Connection(SttConnectionError::simple(format!(
-                    "Failed to build WS request: {}",
-                    e
-                )))
-            })?;
+        let (ws_stream, _response) = loop {
+            let mut flags = DeepgramListenFlags::from_config(self.config.as_ref());
+            flags.opus = self.audio_encoder.is_some();
 
-        let (ws_stream, _response) = super::await_streaming_websocket_connect(
-            connect_async_with_config(request, Some(super::streaming_websocket_config()), false),
-            DEEPGRAM_CONNECT_TIMEOUT,
-            "Deepgram",
-        )
-        .await?;
+            // Собираем URL с параметрами (добавляем channels=1 для mono)
+            let url = build_deepgram_listen_url(
+                &self.ws_base_url,
+                &model,
+                &language,
+                &self
+                    .config
+                    .as_ref()
+                    .and_then(|c| c.streaming_keyterms.clone()),
+                flags,
+            );
+
+            log::debug!("Connecting to Deepgram: {}", url);
+
+            // Формируем WebSocket запрос с заголовком авторизации
+            let request = Request::builder()
+                .method("GET")
+                .uri(&url)
+                .header("Host", "api.deepgram.com")
+                .header("Connection", "Upgrade")
+                .header("Upgrade", "websocket")
+                .header("Sec-WebSocket-Version", "13")
+                .header(
+                    "Sec-WebSocket-Key",
+                    tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+                )
+                .header("Authorization", format!("Token {}", api_key))
+                .body(())
+                .map_err(|e| {
+                    SttError::Connection(SttConnectionError::simple(format!(
+                        "Failed to build WS request: {}",
+                        e
+                    )))
+                })?;
+
+            let mut http_status = None;
+            let connect = async {
+                let result = connect_async_with_config(
+                    request,
+                    Some(super::streaming_websocket_config()),
+                    false,
+                )
+                .await;
+                if let Err(tokio_tungstenite::tungstenite::Error::Http(response)) = &result {
+                    http_status = Some(response.status().as_u16());
+                }
+                result
+            };
+
+            let connected = super::await_streaming_websocket_connect(
+                connect,
+                DEEPGRAM_CONNECT_TIMEOUT,
+                "Deepgram",
+            )
+            .await;
+            match connected {
+                Ok(connected) => break connected,
+                // Endpoint (например, self-hosted/прокси) не принимает opus — повторяем на PCM
+                Err(e) if flags.opus && http_status == Some(400) => {
+                    log::warn!(
+                        "Deepgram rejected encoding=opus, falling back to linear16: {}",
+                        e
+                    );
+                    self.opus_rejected = true;
+                    self.audio_encoder = None;
+                }
+                Err(e) => return Err(e),
+            }
+        };
 
         log::info!("Deepgram WebSocket connected");
 
@@ -716,12 +867,9 @@ impl SttProvider for DeepgramProvider {
         const MIN_SAMPLES: usize = 800;
 
         if self.audio_buffer.len() >= MIN_SAMPLES {
-            // Конвертируем i16 семплы в байты (little-endian PCM)
-            let bytes: Vec<u8> = self
-                .audio_buffer
-                .iter()
-                .flat_map(|&sample| sample.to_le_bytes())
-                .collect();
+            // Конвертируем i16 семплы в байты (little-endian PCM или Ogg Opus)
+            let bytes =
+                deepgram_audio_payload(self.audio_encoder.as_mut(), &self.audio_buffer, false)?;
 
             // Буфер чистим только после успешной отправки: если очистить заранее,
             // при ошибке send этот кусок речи теряется безвозвратно.
@@ -899,27 +1047,30 @@ impl SttProvider for DeepgramProvider {
 
         let mut stop_result = Ok(());
 
-        if !self.audio_buffer.is_empty() {
+        if self.has_unsent_audio() {
             if let Some(write) = self.ws_write.as_ref() {
-                let bytes: Vec<u8> = self
-                    .audio_buffer
-                    .iter()
-                    .flat_map(|&sample| sample.to_le_bytes())
-                    .collect();
-
                 log::debug!(
                     "Flushing remaining {} samples from buffer",
                     self.audio_buffer.len()
                 );
 
-                let mut write_guard = write.lock().await;
-                match await_deepgram_send(
-                    write_guard.send(Message::Binary(bytes)),
-                    DEEPGRAM_STREAM_SEND_TIMEOUT,
-                    "Deepgram final audio send failed",
-                )
-                .await
-                {
+                let send_result = match deepgram_audio_payload(
+                    self.audio_encoder.as_mut(),
+                    &self.audio_buffer,
+                    true,
+                ) {
+                    Ok(bytes) => {
+                        let mut write_guard = write.lock().await;
+                        await_deepgram_send(
+                            write_guard.send(Message::Binary(bytes)),
+                            DEEPGRAM_STREAM_SEND_TIMEOUT,
+                            "Deepgram final audio send failed",
+                        )
+                        .await
+                    }
+                    Err(error) => Err(error),
+                };
+                match send_result {
                     Ok(()) => self.audio_buffer.clear(),
                     Err(error) => stop_result = Err(error),
                 }
@@ -1087,13 +1238,10 @@ impl SttProvider for DeepgramProvider {
 
         // Досылаем остаток локального буфера перед Finalize, иначе последние миллисекунды речи
         // могут остаться только в памяти клиента.
-        if !self.audio_buffer.is_empty() {
+        if self.has_unsent_audio() {
             if let Some(write) = self.ws_write.as_ref() {
-                let bytes: Vec<u8> = self
-                    .audio_buffer
-                    .iter()
-                    .flat_map(|&sample| sample.to_le_bytes())
-                    .collect();
+                let bytes =
+                    deepgram_audio_payload(self.audio_encoder.as_mut(), &self.audio_buffer, true)?;
 
                 let mut write_guard = write.lock().await;
                 await_deepgram_send(
@@ -1276,6 +1424,14 @@ impl SttProvider for DeepgramProvider {
         true
     }
 
+    fn supports_punctuation_control(&self) -> bool {
+        true
+    }
+
+    fn supports_profanity_filter(&self) -> bool {
+        true
+    }
+
     fn is_connection_alive(&self) -> bool {
         // Базовая проверка (синхронная)
         if !(self.is_streaming && self.is_paused && self.ws_write.is_some()) {
@@ -1436,6 +1592,10 @@ impl DeepgramProvider {
                 config.model.as_deref().unwrap_or("nova-3"),
                 &config.language,
                 &config.streaming_keyterms,
+                DeepgramListenFlags {
+                    opus: self.audio_encoder.is_some(),
+                    ..DeepgramListenFlags::from_config(Some(&config))
+                },
             );
 
             let request = match Request::builder()
@@ -1701,6 +1861,11 @@ impl DeepgramProvider {
                 }
             });
 
+            // Новый сокет — новый Ogg-поток с заголовками
+            if let Some(encoder) = self.audio_encoder.as_mut() {
+                encoder.reset();
+            }
+
             // Сохраняем новое соединение
             self.ws_write = Some(ws_write);
             self.receiver_task = Some(receiver_task);
@@ -1727,11 +1892,12 @@ impl DeepgramProvider {
                 for (index, chunk) in buffered_chunks.iter().enumerate() {
                     // Отправляем через send_audio но НЕ через рекурсию
                     // Просто отправляем напрямую через WebSocket
-                    let bytes: Vec<u8> = chunk
-                        .data
-                        .iter()
-                        .flat_map(|&sample| sample.to_le_bytes())
-                        .collect();
+                    let bytes =
+                        deepgram_audio_payload(self.audio_encoder.as_mut(), &chunk.data, false)?;
+                    if bytes.is_empty() {
+                        // Меньше одного Opus-кадра — хвост остался в кодере
+                        continue;
+                    }
 
                     if let Some(write) = self.ws_write.as_ref() {
                         let mut write_guard = write.lock().await;
@@ -1843,7 +2009,9 @@ impl DeepgramProvider {
                                 // - is_final=true, speech_final=false: сегмент завершен, но речь продолжается
                                 // - is_final=true, speech_final=true: вся речь завершена
 
+                                let words = deepgram_words(first_alt);
                                 let transcription = Transcription {
+                                    speaker: Transcription::dominant_speaker(&words),
                                     text: text.to_string(),
                                     confidence,
                                     is_final: is_final || closes_utterance,
@@ -1851,6 +2019,8 @@ impl DeepgramProvider {
                                     timestamp,
                                     start,    // передаем start время из Deepgram
                                     duration, // передаем duration из Deepgram
+                                    provider: None,
+                                    words,
                                 };
 
                                 // Детальное логирование для отладки
@@ -1897,6 +2067,9 @@ impl DeepgramProvider {
                                         timestamp,
                                         start,
                                         duration,
+                                        provider: None,
+                                        words: Vec::new(),
+                                        speaker: None,
                                     })
                                 });
                             } else {
@@ -2153,7 +2326,13 @@ mod tests {
     #[test]
     fn test_build_deepgram_url_keeps_streaming_params_on_reconnect() {
         let keyterms = Some("Codex, Deepgram Nova".to_string());
-        let url = build_deepgram_listen_url(DEEPGRAM_WS_URL, "nova-3", "ru", &keyterms);
+        let url = build_deepgram_listen_url(
+            DEEPGRAM_WS_URL,
+            "nova-3",
+            "ru",
+            &keyterms,
+            DeepgramListenFlags::default(),
+        );
 
         assert!(url.contains("interim_results=true"));
         assert!(url.contains("punctuate=true"));
@@ -2162,10 +2341,71 @@ mod tests {
         assert!(url.contains("keyterm=Deepgram%20Nova"));
         assert!(!url.contains("keywords="));
 
-        let encoded =
-            build_deepgram_listen_url(DEEPGRAM_WS_URL, "nova 3", "en&model=invalid", &None);
+        let encoded = build_deepgram_listen_url(
+            DEEPGRAM_WS_URL,
+            "nova 3",
+            "en&model=invalid",
+            &None,
+            DeepgramListenFlags::default(),
+        );
         assert!(encoded.contains("model=nova%203"));
         assert!(encoded.contains("language=en%26model%3Dinvalid"));
+        assert!(!encoded.contains("diarize="));
+        assert!(!encoded.contains("profanity_filter="));
+
+        let mut config = SttConfig::new(crate::domain::SttProviderType::Deepgram);
+        config.enable_diarization = true;
+        let diarized = build_deepgram_listen_url(
+            DEEPGRAM_WS_URL,
+            "nova-3",
+            "en",
+            &None,
+            DeepgramListenFlags::from_config(Some(&config)),
+        );
+        assert!(diarized.contains("&diarize=true"));
+    }
+
+    #[test]
+    fn test_listen_url_honors_punctuation_and_profanity_settings() {
+        let mut config = SttConfig::new(crate::domain::SttProviderType::Deepgram);
+        config.enable_punctuation = false;
+        config.filter_profanity = true;
+
+        let url = build_deepgram_listen_url(
+            DEEPGRAM_WS_URL,
+            "nova-3",
+            "en",
+            &None,
+            DeepgramListenFlags::from_config(Some(&config)),
+        );
+
+        assert!(url.contains("punctuate=false"));
+        assert!(url.contains("&profanity_filter=true"));
+    }
+
+    #[test]
+    fn test_listen_url_switches_encoding_for_opus_transport() {
+        let url = |opus| {
+            build_deepgram_listen_url(
+                DEEPGRAM_WS_URL,
+                "nova-3",
+                "en",
+                &None,
+                DeepgramListenFlags {
+                    opus,
+                    ..DeepgramListenFlags::default()
+                },
+            )
+        };
+
+        assert!(url(false).contains("?encoding=linear16&sample_rate=16000"));
+        assert!(url(true).contains("?encoding=opus&sample_rate=16000"));
+    }
+
+    #[test]
+    fn test_audio_payload_without_encoder_is_pcm16_le() {
+        let bytes = deepgram_audio_payload(None, &[1, -2], true).unwrap();
+        assert_eq!(bytes, vec![1, 0, 0xfe, 0xff]);
     }
 
     #[test]
@@ -2435,6 +2675,84 @@ mod tests {
         assert!(final_transcription.timestamp > 1_000_000_000_000);
     }
 
+    #[test]
+    fn test_handle_message_passes_word_timings() {
+        let final_transcription = Arc::new(std::sync::Mutex::new(None::<Transcription>));
+        let on_partial: TranscriptionCallback = Arc::new(|_: Transcription| {});
+        let f_transcription = final_transcription.clone();
+        let on_final: TranscriptionCallback = Arc::new(move |t: Transcription| {
+            *f_transcription.lock().unwrap() = Some(t);
+        });
+
+        let json = json!({
+            "type": "Results",
+            "is_final": true,
+            "speech_final": true,
+            "channel": {
+                "alternatives": [
+                    {
+                        "transcript": "hello world",
+                        "confidence": 0.97,
+                        "words": [
+                            { "word": "hello", "start": 0.5, "end": 0.9, "confidence": 0.99, "punctuated_word": "Hello" },
+                            { "word": "world", "start": 1.0, "end": 1.4, "confidence": 0.42 }
+                        ]
+                    }
+                ]
+            }
+        });
+
+        DeepgramProvider::handle_message(json, &on_partial, &on_final);
+
+        let final_transcription = final_transcription.lock().unwrap();
+        let words = &final_transcription.as_ref().unwrap().words;
+        assert_eq!(
+            words,
+            &vec![
+                Word::new("hello", 0.5, 0.9)
+                    .with_confidence(0.99)
+                    .with_punctuated("Hello"),
+                Word::new("world", 1.0, 1.4).with_confidence(0.42),
+            ]
+        );
+        assert_eq!(final_transcription.as_ref().unwrap().speaker, None);
+    }
+
+    #[test]
+    fn test_handle_message_labels_diarized_speaker() {
+        let final_transcription = Arc::new(std::sync::Mutex::new(None::<Transcription>));
+        let on_partial: TranscriptionCallback = Arc::new(|_: Transcription| {});
+        let f_transcription = final_transcription.clone();
+        let on_final: TranscriptionCallback = Arc::new(move |t: Transcription| {
+            *f_transcription.lock().unwrap() = Some(t);
+        });
+
+        let json = json!({
+            "type": "Results",
+            "is_final": true,
+            "speech_final": true,
+            "channel": {
+                "alternatives": [
+                    {
+                        "transcript": "yes I agree",
+                        "words": [
+                            { "word": "yes", "start": 0.1, "end": 0.3, "speaker": 0 },
+                            { "word": "i", "start": 0.5, "end": 0.6, "speaker": 1 },
+                            { "word": "agree", "start": 0.6, "end": 0.9, "speaker": 1 }
+                        ]
+                    }
+                ]
+            }
+        });
+
+        DeepgramProvider::handle_message(json, &on_partial, &on_final);
+
+        let final_transcription = final_transcription.lock().unwrap();
+        let transcription = final_transcription.as_ref().unwrap();
+        assert_eq!(transcription.speaker.as_deref(), Some("1"));
+        assert_eq!(transcription.words[0].speaker.as_deref(), Some("0"));
+    }
+
     #[test]
     fn test_handle_message_from_finalize_is_final() {
         let partial_called = Arc::new(std::sync::Mutex::new(false));
@@ -0,0 +1,988 @@
+use async_trait::async_trait;
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use http::HeaderValue;
+use serde::Deserialize;
+use serde_json::json;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::{
+    connect_async_with_config, tungstenite::Message, MaybeTlsStream, WebSocketStream,
+};
+
+use crate::domain::{
+    AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttConnectionCategory,
+    SttConnectionDetails, SttConnectionError, SttError, SttProvider, SttResult, Transcription,
+    TranscriptionCallback, Word,
+};
+
+/// ElevenLabs realtime speech-to-text provider (Scribe realtime)
+///
+/// Endpoint: wss://api.elevenlabs.io/v1/speech-to-text/realtime
+/// Auth: `xi-api-key` header with the user's own key
+///
+/// Protocol:
+/// 1. Connect with model/audio format/language in query params, wait for `session_started`
+/// 2. Stream `input_audio_chunk` messages (base64 PCM16 16 kHz mono); server VAD commits phrases
+/// 3. Receive `partial_transcript` (partial) and `committed_transcript_with_timestamps` (final)
+/// 4. On stop send a chunk with `commit = true` and wait for the last committed transcript
+///
+/// Тот же провайдер доступен через наш Backend (`BackendStreamingProvider::ElevenLabs`);
+/// здесь подключаемся напрямую с ключом пользователя.
+const ELEVENLABS_REALTIME_URL: &str = "wss://api.elevenlabs.io/v1/speech-to-text/realtime";
+const ELEVENLABS_DEFAULT_MODEL: &str = "scribe_v2_realtime";
+const ELEVENLABS_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const ELEVENLABS_SESSION_READY_TIMEOUT: Duration = Duration::from_secs(10);
+const ELEVENLABS_SEND_TIMEOUT: Duration = Duration::from_secs(5);
+// Ручной commit обычно подтверждается быстро, но при занятом VAD сервис может
+// держать хвост до ~5 s (см. FINALIZE_DRAIN_ACK_TIMEOUT в backend.rs).
+const ELEVENLABS_COMMIT_TIMEOUT: Duration = Duration::from_secs(8);
+const ELEVENLABS_SAMPLE_RATE: u32 = 16_000;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// В `model` может остаться имя модели другого провайдера, поэтому берём только `scribe*`.
+fn elevenlabs_model(model: Option<&str>) -> String {
+    model
+        .map(str::trim)
+        .filter(|model| model.starts_with("scribe"))
+        .unwrap_or(ELEVENLABS_DEFAULT_MODEL)
+        .to_string()
+}
+
+fn elevenlabs_stream_url(base_url: &str, config: &SttConfig) -> String {
+    let mut url = format!(
+        "{}?model_id={}&audio_format=pcm_{}&commit_strategy=vad&include_timestamps=true",
+        base_url,
+        urlencoding::encode(&elevenlabs_model(config.model.as_deref())),
+        ELEVENLABS_SAMPLE_RATE
+    );
+    if !config.auto_detect_language {
+        if let Some(language) = super::short_language_code(&config.language) {
+            url.push_str("&language_code=");
+            url.push_str(&urlencoding::encode(&language));
+        }
+    }
+    url
+}
+
+fn elevenlabs_audio_message(samples: &[i16], commit: bool) -> String {
+    let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    json!({
+        "message_type": "input_audio_chunk",
+        "audio_base_64": base64::engine::general_purpose::STANDARD.encode(bytes),
+        "commit": commit,
+        "sample_rate": ELEVENLABS_SAMPLE_RATE,
+    })
+    .to_string()
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ElevenLabsWord {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    start: Option<f64>,
+    #[serde(default)]
+    end: Option<f64>,
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    logprob: Option<f64>,
+}
+
+impl ElevenLabsWord {
+    fn is_word(&self) -> bool {
+        self.kind.as_deref().map_or(true, |kind| kind == "word")
+    }
+
+    /// Scribe отдаёт слова уже с пунктуацией, поэтому `punctuated` совпадает с текстом.
+    fn to_word(&self) -> Option<Word> {
+        let text = self.text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let start = self.start?;
+        let mut word = Word::new(text, start, self.end.unwrap_or(start)).with_punctuated(text);
+        word.confidence = self
+            .logprob
+            .map(|logprob| logprob.exp().clamp(0.0, 1.0) as f32);
+        Some(word)
+    }
+}
+
+/// Все сообщения сервера имеют `message_type`; поля, которых нет в конкретном типе,
+/// остаются дефолтными.
+#[derive(Debug, Deserialize)]
+struct ElevenLabsMessage {
+    message_type: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    language_code: Option<String>,
+    #[serde(default)]
+    words: Vec<ElevenLabsWord>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug)]
+enum ElevenLabsUpdate {
+    Ready,
+    Partial(Transcription),
+    Final(Transcription),
+    /// Сервис закоммитил фразу без текста (тишина) — для drain это тоже подтверждение.
+    EmptyCommit,
+    Error(SttError),
+    Ignored,
+}
+
+fn elevenlabs_error(message_type: &str, error: Option<&str>) -> SttError {
+    let message = format!(
+        "ElevenLabs realtime {}: {}",
+        message_type,
+        error.unwrap_or("no details")
+    );
+    let category = match message_type {
+        "auth_error" => return SttError::Authentication(message),
+        "input_error" | "chunk_size_exceeded" | "unaccepted_terms" => {
+            return SttError::Configuration(message)
+        }
+        "transcriber_error" => return SttError::Processing(message),
+        "quota_exceeded" => SttConnectionCategory::ProviderQuotaExceeded,
+        "rate_limited" | "queue_overflow" | "commit_throttled" => {
+            SttConnectionCategory::RateLimited
+        }
+        "resource_exhausted" => SttConnectionCategory::LimitExceeded,
+        "session_time_limit_exceeded" | "insufficient_audio_activity" => {
+            SttConnectionCategory::Closed
+        }
+        _ => SttConnectionCategory::ServerUnavailable,
+    };
+    SttError::Connection(SttConnectionError {
+        message,
+        details: SttConnectionDetails {
+            category: Some(category),
+            server_code: Some(message_type.to_string()),
+            ..Default::default()
+        },
+    })
+}
+
+fn elevenlabs_final(message: &ElevenLabsMessage, fallback_language: Option<&str>) -> Transcription {
+    let mut transcription = Transcription::final_result(message.text.trim().to_string());
+    if let Some(language) = message.language_code.as_deref().or(fallback_language) {
+        transcription = transcription.with_language(language.to_string());
+    }
+
+    let words: Vec<&ElevenLabsWord> = message.words.iter().filter(|w| w.is_word()).collect();
+    let start = words.iter().find_map(|word| word.start);
+    let end = words.iter().rev().find_map(|word| word.end);
+    if let (Some(start), Some(end)) = (start, end) {
+        transcription = transcription.with_timing(start, (end - start).max(0.0));
+    }
+    let logprobs: Vec<f64> = words.iter().filter_map(|word| word.logprob).collect();
+    if !logprobs.is_empty() {
+        let mean = logprobs.iter().sum::<f64>() / logprobs.len() as f64;
+        transcription.confidence = Some(mean.exp().clamp(0.0, 1.0) as f32);
+    }
+    transcription.words = words.iter().filter_map(|word| word.to_word()).collect();
+    transcription
+}
+
+fn elevenlabs_update(
+    message: ElevenLabsMessage,
+    fallback_language: Option<&str>,
+) -> ElevenLabsUpdate {
+    match message.message_type.as_str() {
+        "session_started" => ElevenLabsUpdate::Ready,
+        "partial_transcript" => {
+            let text = message.text.trim();
+            if text.is_empty() {
+                return ElevenLabsUpdate::Ignored;
+            }
+            let mut transcription = Transcription::partial(text.to_string());
+            if let Some(language) = fallback_language {
+                transcription = transcription.with_language(language.to_string());
+            }
+            ElevenLabsUpdate::Partial(transcription)
+        }
+        // Мы просим `include_timestamps=true`: сервис шлёт `committed_transcript`, а следом
+        // `committed_transcript_with_timestamps` с тем же текстом. Final берём из второго.
+        "committed_transcript" => ElevenLabsUpdate::Ignored,
+        "committed_transcript_with_timestamps" => {
+            if message.text.trim().is_empty() {
+                return ElevenLabsUpdate::EmptyCommit;
+            }
+            ElevenLabsUpdate::Final(elevenlabs_final(&message, fallback_language))
+        }
+        message_type if message.error.is_some() || message_type.ends_with("error") => {
+            ElevenLabsUpdate::Error(elevenlabs_error(message_type, message.error.as_deref()))
+        }
+        _ => ElevenLabsUpdate::Ignored,
+    }
+}
+
+fn elevenlabs_close_error(frame: Option<&CloseFrame<'_>>) -> SttError {
+    let (message, code, reason) = match frame {
+        Some(frame) => {
+            let code = u16::from(frame.code);
+            (
+                format!(
+                    "ElevenLabs realtime WebSocket closed (close code {}): {}",
+                    code, frame.reason
+                ),
+                Some(code),
+                frame.reason.to_lowercase(),
+            )
+        }
+        None => (
+            "ElevenLabs realtime WebSocket closed".to_string(),
+            None,
+            String::new(),
+        ),
+    };
+    if reason.contains("api key") || reason.contains("unauthorized") {
+        return SttError::Authentication(message);
+    }
+
+    let category = if reason.contains("quota") {
+        SttConnectionCategory::ProviderQuotaExceeded
+    } else if matches!(code, Some(1011..=1014)) {
+        SttConnectionCategory::ServerUnavailable
+    } else {
+        SttConnectionCategory::Closed
+    };
+    SttError::Connection(SttConnectionError {
+        message,
+        details: SttConnectionDetails {
+            category: Some(category),
+            ws_close_code: code,
+            ..Default::default()
+        },
+    })
+}
+
+fn elevenlabs_closed_error(message: impl Into<String>) -> SttError {
+    SttError::Connection(SttConnectionError::with_category(
+        message,
+        SttConnectionCategory::Closed,
+    ))
+}
+
+fn elevenlabs_timeout_error(message: impl Into<String>) -> SttError {
+    SttError::Connection(SttConnectionError::with_category(
+        message,
+        SttConnectionCategory::Timeout,
+    ))
+}
+
+async fn await_elevenlabs_send<F>(future: F, operation: &str) -> SttResult<()>
+where
+    F: Future<Output = Result<(), tokio_tungstenite::tungstenite::Error>>,
+{
+    match tokio::time::timeout(ELEVENLABS_SEND_TIMEOUT, future).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(error)) => Err(elevenlabs_closed_error(format!(
+            "ElevenLabs realtime {} failed: {}",
+            operation, error
+        ))),
+        Err(_) => Err(elevenlabs_timeout_error(format!(
+            "ElevenLabs realtime {} timed out after {} ms",
+            operation,
+            ELEVENLABS_SEND_TIMEOUT.as_millis()
+        ))),
+    }
+}
+
+fn call_elevenlabs_callback(label: &str, callback: impl FnOnce()) {
+    if catch_unwind(AssertUnwindSafe(callback)).is_err() {
+        log::error!("ElevenLabs realtime {} callback panicked", label);
+    }
+}
+
+struct ReaderContext {
+    language: Option<String>,
+    ready_tx: Option<oneshot::Sender<SttResult<()>>>,
+    stop_requested: Arc<AtomicBool>,
+    final_commit_sent: Arc<AtomicBool>,
+    on_partial: TranscriptionCallback,
+    on_final: TranscriptionCallback,
+    on_error: ErrorCallback,
+}
+
+impl ReaderContext {
+    /// До `session_started` ошибки уходят в `start_stream`, после — в `on_error`.
+    fn report_error(&mut self, error: SttError) {
+        if let Some(ready_tx) = self.ready_tx.take() {
+            let _ = ready_tx.send(Err(error));
+            return;
+        }
+        if !self.stop_requested.load(Ordering::SeqCst) {
+            let on_error = self.on_error.clone();
+            call_elevenlabs_callback("error", || on_error(error));
+        }
+    }
+}
+
+async fn run_elevenlabs_reader(
+    mut read: futures_util::stream::SplitStream<WsStream>,
+    mut context: ReaderContext,
+) {
+    log::debug!("ElevenLabs realtime receiver task started");
+
+    while let Some(msg_result) = read.next().await {
+        match msg_result {
+            Ok(Message::Text(text)) => {
+                let message = match serde_json::from_str::<ElevenLabsMessage>(&text) {
+                    Ok(message) => message,
+                    Err(error) => {
+                        log::warn!("Failed to parse ElevenLabs realtime message: {}", error);
+                        continue;
+                    }
+                };
+
+                let committed = match elevenlabs_update(message, context.language.as_deref()) {
+                    ElevenLabsUpdate::Ready => {
+                        if let Some(ready_tx) = context.ready_tx.take() {
+                            let _ = ready_tx.send(Ok(()));
+                        }
+                        false
+                    }
+                    ElevenLabsUpdate::Partial(transcription) => {
+                        log::debug!("Partial transcript: {}", transcription.text);
+                        let on_partial = context.on_partial.clone();
+                        call_elevenlabs_callback("partial transcription", || {
+                            on_partial(transcription)
+                        });
+                        false
+                    }
+                    ElevenLabsUpdate::Final(transcription) => {
+                        log::info!("Final transcript: {}", transcription.text);
+                        let on_final = context.on_final.clone();
+                        call_elevenlabs_callback("final transcription", || on_final(transcription));
+                        true
+                    }
+                    ElevenLabsUpdate::EmptyCommit => true,
+                    ElevenLabsUpdate::Error(error) => {
+                        log::error!("ElevenLabs realtime error: {}", error);
+                        let stopping = context.final_commit_sent.load(Ordering::SeqCst);
+                        context.report_error(error);
+                        // После финального commit сервис может ответить ошибкой на пустой хвост.
+                        stopping
+                    }
+                    ElevenLabsUpdate::Ignored => false,
+                };
+
+                if committed && context.final_commit_sent.load(Ordering::SeqCst) {
+                    break;
+                }
+            }
+            Ok(Message::Close(frame)) => {
+                log::info!("ElevenLabs realtime WebSocket closed: {:?}", frame);
+                context.report_error(elevenlabs_close_error(frame.as_ref()));
+                break;
+            }
+            Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {
+                log::trace!("ElevenLabs realtime received Ping/Pong");
+            }
+            Err(e) => {
+                log::error!("ElevenLabs realtime WebSocket error: {}", e);
+                context.report_error(elevenlabs_closed_error(format!(
+                    "ElevenLabs realtime WebSocket error: {}",
+                    e
+                )));
+                break;
+            }
+            Ok(msg) => {
+                log::debug!("ElevenLabs realtime received unexpected message: {:?}", msg);
+            }
+        }
+    }
+
+    if let Some(ready_tx) = context.ready_tx.take() {
+        let _ = ready_tx.send(Err(elevenlabs_closed_error(
+            "ElevenLabs realtime WebSocket ended before session_started",
+        )));
+    }
+    log::debug!("ElevenLabs realtime receiver task ended");
+}
+
+pub struct ElevenLabsProvider {
+    config: Option<SttConfig>,
+    is_streaming: bool,
+    api_key: Option<String>,
+    ws_write: Option<futures_util::stream::SplitSink<WsStream, Message>>,
+    receiver_task: Option<JoinHandle<()>>,
+    stop_requested: Arc<AtomicBool>,
+    final_commit_sent: Arc<AtomicBool>,
+    audio_sent: bool,
+    ws_base_url: String,
+}
+
+impl ElevenLabsProvider {
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            is_streaming: false,
+            api_key: None,
+            ws_write: None,
+            receiver_task: None,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+            final_commit_sent: Arc::new(AtomicBool::new(false)),
+            audio_sent: false,
+            ws_base_url: ELEVENLABS_REALTIME_URL.to_string(),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_ws_base_url(ws_base_url: String) -> Self {
+        let mut provider = Self::new();
+        provider.ws_base_url = ws_base_url;
+        provider
+    }
+
+    async fn send_text(&mut self, message: String, operation: &str) -> SttResult<()> {
+        let write = self.ws_write.as_mut().ok_or_else(|| {
+            SttError::Processing("WebSocket write handle not available".to_string())
+        })?;
+        await_elevenlabs_send(write.send(Message::Text(message)), operation).await
+    }
+
+    async fn abort_receiver(&mut self) {
+        if let Some(task) = self.receiver_task.take() {
+            task.abort();
+            let _ = task.await;
+        }
+    }
+
+    fn reset_session_state(&mut self) {
+        self.ws_write = None;
+        self.is_streaming = false;
+        self.audio_sent = false;
+    }
+}
+
+impl Default for ElevenLabsProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ElevenLabsProvider {
+    fn drop(&mut self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        super::abort_background_task(&mut self.receiver_task);
+    }
+}
+
+#[async_trait]
+impl SttProvider for ElevenLabsProvider {
+    async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
+        log::info!("ElevenLabsProvider: Initializing");
+
+        let api_key = config
+            .elevenlabs_api_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| SttError::Configuration("ElevenLabs API key is required".to_string()))?;
+
+        self.api_key = Some(api_key);
+        self.config = Some(config.clone());
+        Ok(())
+    }
+
+    async fn start_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        _on_connection_quality: ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        log::info!("ElevenLabsProvider: Starting stream");
+
+        if self.is_streaming {
+            return Err(SttError::Processing("Stream already active".to_string()));
+        }
+
+        let (Some(api_key), Some(config)) = (self.api_key.clone(), self.config.clone()) else {
+            return Err(SttError::Configuration(
+                "ElevenLabs provider is not initialized".to_string(),
+            ));
+        };
+
+        let url = elevenlabs_stream_url(&self.ws_base_url, &config);
+        let mut request = url.as_str().into_client_request().map_err(|e| {
+            SttError::Configuration(format!("Invalid ElevenLabs realtime URL: {}", e))
+        })?;
+        let api_key_header = HeaderValue::from_str(&api_key)
+            .map_err(|_| SttError::Configuration("Invalid ElevenLabs API key".to_string()))?;
+        request.headers_mut().insert("xi-api-key", api_key_header);
+
+        log::debug!("Connecting to {}", url);
+
+        let (ws_stream, _response) = tokio::time::timeout(
+            ELEVENLABS_CONNECT_TIMEOUT,
+            connect_async_with_config(request, Some(super::streaming_websocket_config()), false),
+        )
+        .await
+        .map_err(|_| {
+            elevenlabs_timeout_error(format!(
+                "ElevenLabs realtime WebSocket connection timed out after {} ms",
+                ELEVENLABS_CONNECT_TIMEOUT.as_millis()
+            ))
+        })?
+        .map_err(|error| super::websocket_handshake_error("ElevenLabs realtime", error))?;
+
+        log::info!("ElevenLabs realtime WebSocket connected");
+
+        let (write, read) = ws_stream.split();
+        self.ws_write = Some(write);
+        self.stop_requested.store(false, Ordering::SeqCst);
+        self.final_commit_sent.store(false, Ordering::SeqCst);
+
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let context = ReaderContext {
+            language: if config.auto_detect_language {
+                None
+            } else {
+                super::short_language_code(&config.language)
+            },
+            ready_tx: Some(ready_tx),
+            stop_requested: self.stop_requested.clone(),
+            final_commit_sent: self.final_commit_sent.clone(),
+            on_partial,
+            on_final,
+            on_error,
+        };
+        self.receiver_task = Some(tokio::spawn(run_elevenlabs_reader(read, context)));
+
+        let ready_result =
+            match tokio::time::timeout(ELEVENLABS_SESSION_READY_TIMEOUT, ready_rx).await {
+                Ok(Ok(result)) => result,
+                Ok(Err(_)) => Err(elevenlabs_closed_error(
+                    "ElevenLabs realtime reader stopped before session_started",
+                )),
+                Err(_) => Err(elevenlabs_timeout_error(format!(
+                    "ElevenLabs realtime session_started was not received within {} ms",
+                    ELEVENLABS_SESSION_READY_TIMEOUT.as_millis()
+                ))),
+            };
+        if let Err(error) = ready_result {
+            self.stop_requested.store(true, Ordering::SeqCst);
+            self.abort_receiver().await;
+            self.reset_session_state();
+            return Err(error);
+        }
+
+        self.is_streaming = true;
+
+        log::info!("ElevenLabs realtime stream started successfully");
+        Ok(())
+    }
+
+    async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
+        if !self.is_streaming {
+            return Err(SttError::Processing("Not streaming".to_string()));
+        }
+        if chunk.sample_rate != ELEVENLABS_SAMPLE_RATE {
+            return Err(SttError::Processing(format!(
+                "ElevenLabs realtime expects {} Hz audio, got {} Hz",
+                ELEVENLABS_SAMPLE_RATE, chunk.sample_rate
+            )));
+        }
+        if chunk.data.is_empty() {
+            return Ok(());
+        }
+
+        self.send_text(elevenlabs_audio_message(&chunk.data, false), "audio send")
+            .await?;
+        self.audio_sent = true;
+        Ok(())
+    }
+
+    async fn stop_stream(&mut self) -> SttResult<()> {
+        log::info!("ElevenLabsProvider: Stopping stream");
+        self.stop_requested.store(true, Ordering::SeqCst);
+
+        if !self.is_streaming {
+            log::warn!("Stream not active");
+            return Ok(());
+        }
+
+        let mut stop_result = Ok(());
+
+        // Ручной commit закрывает фразу, которую VAD ещё не успел закоммитить.
+        if self.audio_sent {
+            self.final_commit_sent.store(true, Ordering::SeqCst);
+            stop_result = self
+                .send_text(elevenlabs_audio_message(&[], true), "commit send")
+                .await;
+
+            if stop_result.is_ok() {
+                if let Some(mut task) = self.receiver_task.take() {
+                    match tokio::time::timeout(ELEVENLABS_COMMIT_TIMEOUT, &mut task).await {
+                        Ok(Ok(())) => {}
+                        Ok(Err(join_error)) => {
+                            stop_result = Err(SttError::Internal(format!(
+                                "ElevenLabs realtime receiver task failed during drain: {}",
+                                join_error
+                            )));
+                        }
+                        Err(_) => {
+                            task.abort();
+                            let _ = task.await;
+                            stop_result = Err(elevenlabs_timeout_error(format!(
+                                "ElevenLabs realtime commit timed out after {} ms",
+                                ELEVENLABS_COMMIT_TIMEOUT.as_millis()
+                            )));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(write) = self.ws_write.as_mut() {
+            let _ = tokio::time::timeout(ELEVENLABS_SEND_TIMEOUT, write.close()).await;
+        }
+        self.abort_receiver().await;
+        self.reset_session_state();
+
+        match &stop_result {
+            Ok(()) => log::info!("ElevenLabs realtime stream stopped after commit"),
+            Err(error) => log::warn!("ElevenLabs realtime stream stop failed: {}", error),
+        }
+        stop_result
+    }
+
+    async fn abort(&mut self) -> SttResult<()> {
+        log::info!("ElevenLabsProvider: Aborting stream");
+        self.stop_requested.store(true, Ordering::SeqCst);
+
+        self.abort_receiver().await;
+        self.reset_session_state();
+
+        log::info!("ElevenLabs realtime stream aborted");
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "ElevenLabs Realtime"
+    }
+
+    fn is_online(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SttProviderType;
+    use serde_json::Value;
+    use std::sync::Mutex as StdMutex;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_hdr_async;
+    use tokio_tungstenite::tungstenite::handshake::server::{
+        Callback, ErrorResponse, Request as HandshakeRequest, Response as HandshakeResponse,
+    };
+
+    #[derive(Debug, Default)]
+    struct ElevenLabsServerCapture {
+        uri: String,
+        api_key: Option<String>,
+        chunks: Vec<Value>,
+    }
+
+    fn server_message(message: Value) -> Message {
+        Message::Text(message.to_string())
+    }
+
+    /// Запоминает URI и ключ из handshake
+    struct CaptureHandshake(Arc<StdMutex<ElevenLabsServerCapture>>);
+
+    impl Callback for CaptureHandshake {
+        fn on_request(
+            self,
+            request: &HandshakeRequest,
+            response: HandshakeResponse,
+        ) -> Result<HandshakeResponse, ErrorResponse> {
+            let mut capture = self.0.lock().unwrap();
+            capture.uri = request.uri().to_string();
+            capture.api_key = request
+                .headers()
+                .get("xi-api-key")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            Ok(response)
+        }
+    }
+
+    async fn spawn_elevenlabs_server(
+        first_message: Value,
+    ) -> (String, JoinHandle<ElevenLabsServerCapture>) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock ElevenLabs server");
+        let address = listener.local_addr().expect("mock server address");
+        let task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("accept websocket client");
+            let capture = Arc::new(StdMutex::new(ElevenLabsServerCapture::default()));
+            let mut websocket = accept_hdr_async(stream, CaptureHandshake(capture.clone()))
+                .await
+                .expect("accept websocket");
+
+            let is_error = first_message["message_type"] != "session_started";
+            websocket
+                .send(server_message(first_message))
+                .await
+                .expect("send first message");
+            if is_error {
+                let _ = websocket.close(None).await;
+                return Arc::try_unwrap(capture).unwrap().into_inner().unwrap();
+            }
+
+            loop {
+                let Some(Ok(Message::Text(text))) = websocket.next().await else {
+                    panic!("client closed before commit");
+                };
+                let chunk: Value = serde_json::from_str(&text).expect("client chunk json");
+                let commit = chunk["commit"] == true;
+                capture.lock().unwrap().chunks.push(chunk);
+                if commit {
+                    break;
+                }
+            }
+
+            for message in [
+                json!({ "message_type": "partial_transcript", "text": "привет" }),
+                json!({ "message_type": "committed_transcript", "text": "Привет, мир." }),
+                json!({
+                    "message_type": "committed_transcript_with_timestamps",
+                    "text": "Привет, мир.",
+                    "language_code": "ru",
+                    "words": [
+                        { "text": "Привет,", "start": 0.4, "end": 0.9, "type": "word", "logprob": -0.1 },
+                        { "text": " ", "start": 0.9, "end": 1.0, "type": "spacing" },
+                        { "text": "мир.", "start": 1.0, "end": 1.4, "type": "word", "logprob": -0.3 }
+                    ]
+                }),
+            ] {
+                websocket
+                    .send(server_message(message))
+                    .await
+                    .expect("send transcript");
+            }
+            let _ = websocket.next().await;
+
+            Arc::try_unwrap(capture)
+                .expect("capture still shared")
+                .into_inner()
+                .unwrap()
+        });
+
+        (format!("ws://{address}/v1/speech-to-text/realtime"), task)
+    }
+
+    fn elevenlabs_test_config() -> SttConfig {
+        let mut config = SttConfig::new(SttProviderType::ElevenLabs).with_language("ru-RU");
+        config.elevenlabs_api_key = Some(" xi-test ".to_string());
+        config
+    }
+
+    #[test]
+    fn stream_url_carries_model_format_and_language() {
+        let mut config = elevenlabs_test_config();
+        assert_eq!(
+            elevenlabs_stream_url(ELEVENLABS_REALTIME_URL, &config),
+            "wss://api.elevenlabs.io/v1/speech-to-text/realtime?model_id=scribe_v2_realtime&audio_format=pcm_16000&commit_strategy=vad&include_timestamps=true&language_code=ru"
+        );
+
+        config.auto_detect_language = true;
+        config.model = Some("whisper-large-v3".to_string());
+        let url = elevenlabs_stream_url("wss://x", &config);
+        assert!(url.contains("model_id=scribe_v2_realtime"));
+        assert!(!url.contains("language_code"));
+    }
+
+    #[test]
+    fn committed_transcript_with_timestamps_becomes_timed_final() {
+        let message: ElevenLabsMessage = serde_json::from_value(json!({
+            "message_type": "committed_transcript_with_timestamps",
+            "text": " Hello world ",
+            "words": [
+                { "text": "Hello", "start": 1.0, "end": 1.4, "type": "word", "logprob": 0.0 },
+                { "text": " ", "start": 1.4, "end": 1.5, "type": "spacing" },
+                { "text": "world", "start": 1.5, "end": 2.0, "type": "word", "logprob": 0.0 }
+            ]
+        }))
+        .unwrap();
+
+        let ElevenLabsUpdate::Final(transcription) = elevenlabs_update(message, Some("en")) else {
+            panic!("expected final");
+        };
+        assert_eq!(transcription.text, "Hello world");
+        assert_eq!(transcription.language.as_deref(), Some("en"));
+        assert!((transcription.start - 1.0).abs() < 1e-9);
+        assert!((transcription.duration - 1.0).abs() < 1e-9);
+        assert_eq!(transcription.confidence, Some(1.0));
+        let words: Vec<(&str, f64, f64)> = transcription
+            .words
+            .iter()
+            .map(|word| (word.text.as_str(), word.start, word.end))
+            .collect();
+        assert_eq!(words, vec![("Hello", 1.0, 1.4), ("world", 1.5, 2.0)]);
+    }
+
+    #[test]
+    fn plain_committed_transcript_is_not_duplicated() {
+        let message: ElevenLabsMessage = serde_json::from_value(
+            json!({ "message_type": "committed_transcript", "text": "Hello" }),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            elevenlabs_update(message, None),
+            ElevenLabsUpdate::Ignored
+        ));
+    }
+
+    #[test]
+    fn error_messages_are_classified() {
+        assert!(matches!(
+            elevenlabs_error("auth_error", Some("invalid key")),
+            SttError::Authentication(_)
+        ));
+        assert!(matches!(
+            elevenlabs_error("input_error", None),
+            SttError::Configuration(_)
+        ));
+        assert!(matches!(
+            elevenlabs_error("quota_exceeded", None),
+            SttError::Connection(connection)
+                if connection.details.category
+                    == Some(SttConnectionCategory::ProviderQuotaExceeded)
+                    && connection.details.server_code.as_deref() == Some("quota_exceeded")
+        ));
+        assert!(matches!(
+            elevenlabs_error("rate_limited", None),
+            SttError::Connection(connection)
+                if connection.details.category == Some(SttConnectionCategory::RateLimited)
+        ));
+
+        let message: ElevenLabsMessage = serde_json::from_value(
+            json!({ "message_type": "auth_error", "error": "Invalid API key" }),
+        )
+        .unwrap();
+        assert!(matches!(
+            elevenlabs_update(message, None),
+            ElevenLabsUpdate::Error(SttError::Authentication(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn initialize_requires_api_key() {
+        let mut provider = ElevenLabsProvider::new();
+
+        assert!(matches!(
+            provider
+                .initialize(&SttConfig::new(SttProviderType::ElevenLabs))
+                .await,
+            Err(SttError::Configuration(_))
+        ));
+        provider
+            .initialize(&elevenlabs_test_config())
+            .await
+            .expect("initialize provider");
+        assert_eq!(provider.api_key.as_deref(), Some("xi-test"));
+    }
+
+    #[tokio::test]
+    async fn auth_error_before_session_start_fails_start_stream() {
+        let (ws_base_url, server) = spawn_elevenlabs_server(
+            json!({ "message_type": "auth_error", "error": "Invalid API key" }),
+        )
+        .await;
+        let mut provider = ElevenLabsProvider::with_ws_base_url(ws_base_url);
+        provider
+            .initialize(&elevenlabs_test_config())
+            .await
+            .expect("initialize provider");
+
+        let result = provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_, _| {}),
+            )
+            .await;
+
+        assert!(matches!(result, Err(SttError::Authentication(_))));
+        assert!(!provider.is_streaming);
+        server.await.expect("mock server task");
+    }
+
+    #[tokio::test]
+    async fn graceful_stop_commits_and_drains_final_transcript() {
+        let (ws_base_url, server) = spawn_elevenlabs_server(
+            json!({ "message_type": "session_started", "session_id": "s1" }),
+        )
+        .await;
+        let mut provider = ElevenLabsProvider::with_ws_base_url(ws_base_url);
+        provider
+            .initialize(&elevenlabs_test_config())
+            .await
+            .expect("initialize provider");
+
+        let partials = Arc::new(StdMutex::new(Vec::<Transcription>::new()));
+        let finals = Arc::new(StdMutex::new(Vec::<Transcription>::new()));
+        let partials_for_callback = partials.clone();
+        let finals_for_callback = finals.clone();
+        provider
+            .start_stream(
+                Arc::new(move |t| partials_for_callback.lock().unwrap().push(t)),
+                Arc::new(move |t| finals_for_callback.lock().unwrap().push(t)),
+                Arc::new(|error| panic!("unexpected ElevenLabs error: {error}")),
+                Arc::new(|_, _| {}),
+            )
+            .await
+            .expect("start mock stream");
+
+        provider
+            .send_audio(&AudioChunk::new(vec![7i16; 320], 16_000, 1))
+            .await
+            .expect("send audio");
+        provider.stop_stream().await.expect("graceful stop");
+
+        let capture = server.await.expect("mock server task");
+        assert!(capture.uri.contains("language_code=ru"));
+        assert_eq!(capture.api_key.as_deref(), Some("xi-test"));
+        assert_eq!(capture.chunks.len(), 2);
+        assert_eq!(capture.chunks[0]["commit"], false);
+        assert_eq!(capture.chunks[0]["sample_rate"], 16_000);
+        let audio = base64::engine::general_purpose::STANDARD
+            .decode(capture.chunks[0]["audio_base_64"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(audio.len(), 640);
+        assert_eq!(capture.chunks[1]["commit"], true);
+
+        assert_eq!(partials.lock().unwrap()[0].text, "привет");
+        let finals = finals.lock().unwrap();
+        assert_eq!(finals.len(), 1);
+        assert_eq!(finals[0].text, "Привет, мир.");
+        assert_eq!(finals[0].language.as_deref(), Some("ru"));
+        assert!((finals[0].start - 0.4).abs() < 1e-9);
+        assert!((finals[0].duration - 1.0).abs() < 1e-9);
+    }
+}
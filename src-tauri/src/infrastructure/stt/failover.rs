@@ -0,0 +1,999 @@
+//! Цепочка failover для диктовки
+//!
+//! Оборачивает упорядоченный список провайдеров (`SttConfig::failover_chain`) в один
+//! `SttProvider`. Если активный провайдер недоступен — нет сети, DNS, 5xx или кончилась
+//! квота — переключаемся на следующий, как при старте, так и посреди записи.
+//! Аудио с последнего final переигрывается новому провайдеру, чтобы текущая фраза
+//! не потерялась, а каждый сегмент помечается именем провайдера, который его распознал.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, MutexGuard, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::runtime::Handle;
+use tokio::sync::Mutex;
+
+use crate::domain::{
+    AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttConnectionCategory,
+    SttError, SttProvider, SttProviderFactory, SttResult, Transcription, TranscriptionCallback,
+};
+
+/// Сколько аудио держим для переигрывания: 60 s при 16 kHz mono.
+const FAILOVER_REPLAY_MAX_SAMPLES: usize = 16_000 * 60;
+const FAILOVER_ABORT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Ошибки, при которых имеет смысл идти к следующему провайдеру.
+/// Auth/конфигурация/лимиты аккаунта на резервном провайдере не исправятся.
+fn is_failover_error(error: &SttError) -> bool {
+    match error {
+        SttError::Connection(error) => matches!(
+            error.details.category,
+            Some(
+                SttConnectionCategory::Offline
+                    | SttConnectionCategory::Dns
+                    | SttConnectionCategory::ServerUnavailable
+                    | SttConnectionCategory::ProviderQuotaExceeded
+            )
+        ),
+        _ => false,
+    }
+}
+
+/// Аудио с последнего final. Чанки нумеруются, чтобы знать, что уже отдано
+/// активному провайдеру, а что ещё нет (например, пришло во время переключения).
+#[derive(Default)]
+struct ReplayBuffer {
+    chunks: VecDeque<(u64, AudioChunk)>,
+    samples: usize,
+    next_seq: u64,
+    /// Все чанки с `seq < delivered_seq` уже отданы активному провайдеру
+    delivered_seq: u64,
+}
+
+impl ReplayBuffer {
+    fn push(&mut self, chunk: AudioChunk) {
+        self.samples += chunk.data.len();
+        self.chunks.push_back((self.next_seq, chunk));
+        self.next_seq += 1;
+
+        while self.samples > FAILOVER_REPLAY_MAX_SAMPLES && self.chunks.len() > 1 {
+            self.pop_front();
+        }
+    }
+
+    fn undelivered(&self) -> Vec<(u64, AudioChunk)> {
+        let start = self
+            .chunks
+            .partition_point(|(seq, _)| *seq < self.delivered_seq);
+        self.chunks.range(start..).cloned().collect()
+    }
+
+    fn mark_delivered(&mut self, seq: u64) {
+        self.delivered_seq = self.delivered_seq.max(seq + 1);
+    }
+
+    /// Final закрыл фразу: отданное провайдеру аудио переигрывать больше не нужно.
+    fn commit(&mut self) {
+        while self
+            .chunks
+            .front()
+            .is_some_and(|(seq, _)| *seq < self.delivered_seq)
+        {
+            self.pop_front();
+        }
+    }
+
+    /// После переключения всё, что осталось в буфере, нужно отдать новому провайдеру.
+    fn rewind(&mut self) {
+        self.delivered_seq = self.chunks.front().map_or(self.next_seq, |(seq, _)| *seq);
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn pop_front(&mut self) {
+        if let Some((_, chunk)) = self.chunks.pop_front() {
+            self.samples -= chunk.data.len();
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ChainCallbacks {
+    on_partial: TranscriptionCallback,
+    on_final: TranscriptionCallback,
+    on_error: ErrorCallback,
+    on_connection_quality: ConnectionQualityCallback,
+}
+
+struct ActiveProvider {
+    index: usize,
+    label: String,
+    provider: Box<dyn SttProvider>,
+}
+
+#[derive(Default)]
+struct ChainState {
+    chain: Vec<SttConfig>,
+    active: Option<ActiveProvider>,
+    callbacks: Option<ChainCallbacks>,
+}
+
+impl ChainState {
+    /// Ошибка активного провайдера лечится переключением, только если есть куда переключаться.
+    fn should_fail_over(&self, error: &SttError) -> bool {
+        is_failover_error(error)
+            && self
+                .active
+                .as_ref()
+                .is_some_and(|active| active.index + 1 < self.chain.len())
+    }
+}
+
+struct FailoverShared {
+    factory: Arc<dyn SttProviderFactory>,
+    state: Mutex<ChainState>,
+    replay: StdMutex<ReplayBuffer>,
+    /// Растёт при каждом переключении/остановке: события от прежних провайдеров игнорируем
+    generation: AtomicU64,
+    stopping: AtomicBool,
+    online: AtomicBool,
+}
+
+impl FailoverShared {
+    fn replay(&self) -> MutexGuard<'_, ReplayBuffer> {
+        self.replay
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+
+    /// Новое поколение для следующего кандидата. Если поколение уже сменили снаружи
+    /// (abort/stop во время failover), остаёмся на устаревшем: события кандидата отсекаются.
+    fn next_attempt_generation(&self, generation: u64) -> u64 {
+        match self.generation.compare_exchange(
+            generation,
+            generation + 1,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        ) {
+            Ok(_) => generation + 1,
+            Err(_) => generation,
+        }
+    }
+
+    /// Запускаем провайдеров цепочки начиная с `from`, пока один не стартует.
+    ///
+    /// `failed` — ошибка, из-за которой уже идёт failover. Пока её нет (старт основного
+    /// провайдера), неподходящая для failover ошибка сразу уходит наверх.
+    ///
+    /// У каждой попытки своё поколение: кандидат, который упал на старте и успел сообщить
+    /// ошибку через `on_error`, не должен переключить цепочку дальше уже запущенного.
+    async fn start_from(
+        self: &Arc<Self>,
+        state: &mut ChainState,
+        from: usize,
+        mut generation: u64,
+        mut failed: Option<SttError>,
+    ) -> SttResult<()> {
+        let callbacks = state.callbacks.clone().ok_or_else(|| {
+            SttError::Internal("STT failover stream started without callbacks".to_string())
+        })?;
+
+        for index in from..state.chain.len() {
+            let config = state.chain[index].clone();
+            let has_next = index + 1 < state.chain.len();
+            match self
+                .start_candidate(index, &config, &callbacks, generation, has_next)
+                .await
+            {
+                Ok(active) => {
+                    log::info!(
+                        "STT failover chain: streaming via {} ({}/{})",
+                        active.label,
+                        index + 1,
+                        state.chain.len()
+                    );
+                    self.online
+                        .store(active.provider.is_online(), Ordering::SeqCst);
+                    state.active = Some(active);
+                    return Ok(());
+                }
+                Err(error) => {
+                    if failed.is_none() && !is_failover_error(&error) {
+                        return Err(error);
+                    }
+                    log::warn!(
+                        "STT failover chain: {:?} failed to start: {}",
+                        config.provider,
+                        error
+                    );
+                    failed.get_or_insert(error);
+                    generation = self.next_attempt_generation(generation);
+                }
+            }
+        }
+
+        Err(failed
+            .unwrap_or_else(|| SttError::Configuration("STT failover chain is empty".to_string())))
+    }
+
+    async fn start_candidate(
+        self: &Arc<Self>,
+        index: usize,
+        config: &SttConfig,
+        callbacks: &ChainCallbacks,
+        generation: u64,
+        has_next: bool,
+    ) -> SttResult<ActiveProvider> {
+        let mut provider = self.factory.create(config)?;
+        provider.initialize(config).await?;
+
+        let label = provider.name().to_string();
+        let wrapped = chain_callbacks(self, callbacks, generation, &label, has_next);
+        provider
+            .start_stream(
+                wrapped.on_partial,
+                wrapped.on_final,
+                wrapped.on_error,
+                wrapped.on_connection_quality,
+            )
+            .await?;
+
+        Ok(ActiveProvider {
+            index,
+            label,
+            provider,
+        })
+    }
+
+    /// Отдаём активному провайдеру всё, что он ещё не получил.
+    async fn flush(&self, state: &mut ChainState) -> SttResult<()> {
+        let active = state
+            .active
+            .as_mut()
+            .ok_or_else(|| SttError::Processing("No active STT provider".to_string()))?;
+
+        let pending = self.replay().undelivered();
+        for (seq, chunk) in pending {
+            active.provider.send_audio(&chunk).await?;
+            self.replay().mark_delivered(seq);
+        }
+        Ok(())
+    }
+
+    /// Гасим упавший провайдер и поднимаем следующий по цепочке.
+    async fn switch_to_next(
+        self: &Arc<Self>,
+        state: &mut ChainState,
+        error: SttError,
+    ) -> SttResult<()> {
+        let Some(mut failed) = state.active.take() else {
+            return Err(error);
+        };
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        log::warn!(
+            "STT failover chain: {} is unavailable ({}), switching to the next provider",
+            failed.label,
+            error
+        );
+
+        if tokio::time::timeout(FAILOVER_ABORT_TIMEOUT, failed.provider.abort())
+            .await
+            .is_err()
+        {
+            log::warn!(
+                "STT failover chain: abort of {} timed out after {:?}",
+                failed.label,
+                FAILOVER_ABORT_TIMEOUT
+            );
+        }
+
+        self.replay().rewind();
+        self.start_from(state, failed.index + 1, generation, Some(error))
+            .await?;
+
+        if let (Some(active), Some(callbacks)) = (&state.active, &state.callbacks) {
+            (callbacks.on_connection_quality)(
+                "Good".to_string(),
+                Some(format!("Switched to {}", active.label)),
+            );
+        }
+        Ok(())
+    }
+
+    /// Переключаемся дальше по цепочке, пока новый провайдер не примет переигранное аудио.
+    async fn recover(self: &Arc<Self>, state: &mut ChainState, error: SttError) -> SttResult<()> {
+        let mut error = error;
+        loop {
+            self.switch_to_next(state, error).await?;
+            match self.flush(state).await {
+                Ok(()) => return Ok(()),
+                Err(next) if state.should_fail_over(&next) => error = next,
+                Err(next) => return Err(next),
+            }
+        }
+    }
+
+    async fn fail_over(self: Arc<Self>, failed_generation: u64, error: SttError) {
+        let mut state = self.state.lock().await;
+        if self.stopping.load(Ordering::SeqCst) || !self.is_current(failed_generation) {
+            return;
+        }
+
+        if let Err(error) = self.recover(&mut state, error).await {
+            log::error!("STT failover chain exhausted: {}", error);
+            if let Some(callbacks) = &state.callbacks {
+                (callbacks.on_error)(error);
+            }
+        }
+
+        // abort() пришёл, пока поднимали резервный провайдер, и не смог его дождаться
+        if self.stopping.load(Ordering::SeqCst) {
+            if let Some(mut active) = state.active.take() {
+                let _ = active.provider.abort().await;
+            }
+        }
+    }
+}
+
+fn spawn_failover(
+    shared: &Arc<FailoverShared>,
+    runtime: &Handle,
+    generation: u64,
+    error: SttError,
+) {
+    let shared = shared.clone();
+    runtime.spawn(shared.fail_over(generation, error));
+}
+
+/// Callbacks для провайдера цепочки: отсекаем события прошлых поколений,
+/// подписываем сегменты именем провайдера и перехватываем ошибки для failover.
+fn chain_callbacks(
+    shared: &Arc<FailoverShared>,
+    callbacks: &ChainCallbacks,
+    generation: u64,
+    label: &str,
+    has_next: bool,
+) -> ChainCallbacks {
+    let weak: Weak<FailoverShared> = Arc::downgrade(shared);
+    let runtime = Handle::current();
+
+    let on_partial: TranscriptionCallback = {
+        let weak = weak.clone();
+        let inner = callbacks.on_partial.clone();
+        let label = label.to_string();
+        Arc::new(move |transcription: Transcription| {
+            if weak
+                .upgrade()
+                .is_some_and(|shared| shared.is_current(generation))
+            {
+                inner(transcription.with_provider(label.clone()));
+            }
+        })
+    };
+
+    let on_final: TranscriptionCallback = {
+        let weak = weak.clone();
+        let inner = callbacks.on_final.clone();
+        let label = label.to_string();
+        Arc::new(move |transcription: Transcription| {
+            let Some(shared) = weak.upgrade() else {
+                return;
+            };
+            if !shared.is_current(generation) {
+                return;
+            }
+            shared.replay().commit();
+            inner(transcription.with_provider(label.clone()));
+        })
+    };
+
+    let on_error: ErrorCallback = {
+        let weak = weak.clone();
+        let inner = callbacks.on_error.clone();
+        Arc::new(move |error: SttError| {
+            let Some(shared) = weak.upgrade() else {
+                return;
+            };
+            if !shared.is_current(generation) {
+                return;
+            }
+            if has_next && !shared.stopping.load(Ordering::SeqCst) && is_failover_error(&error) {
+                spawn_failover(&shared, &runtime, generation, error);
+                return;
+            }
+            inner(error);
+        })
+    };
+
+    let on_connection_quality: ConnectionQualityCallback = {
+        let inner = callbacks.on_connection_quality.clone();
+        Arc::new(move |quality: String, reason: Option<String>| {
+            if weak
+                .upgrade()
+                .is_some_and(|shared| shared.is_current(generation))
+            {
+                inner(quality, reason);
+            }
+        })
+    };
+
+    ChainCallbacks {
+        on_partial,
+        on_final,
+        on_error,
+        on_connection_quality,
+    }
+}
+
+/// Композитный провайдер: основной + резервные из `SttConfig::failover_providers`
+pub struct FailoverProvider {
+    shared: Arc<FailoverShared>,
+    name: String,
+}
+
+impl FailoverProvider {
+    pub fn new(factory: Arc<dyn SttProviderFactory>) -> Self {
+        Self {
+            shared: Arc::new(FailoverShared {
+                factory,
+                state: Mutex::new(ChainState::default()),
+                replay: StdMutex::new(ReplayBuffer::default()),
+                generation: AtomicU64::new(0),
+                stopping: AtomicBool::new(false),
+                online: AtomicBool::new(true),
+            }),
+            name: "STT Failover Chain".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl SttProvider for FailoverProvider {
+    async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
+        let chain = config.failover_chain();
+        self.name = format!(
+            "STT Failover Chain ({})",
+            chain
+                .iter()
+                .map(|config| format!("{:?}", config.provider))
+                .collect::<Vec<_>>()
+                .join(" → ")
+        );
+        log::info!("Initialized {}", self.name);
+
+        let mut state = self.shared.state.lock().await;
+        state.chain = chain;
+        Ok(())
+    }
+
+    async fn start_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        on_connection_quality: ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        let mut state = self.shared.state.lock().await;
+        if let Some(mut previous) = state.active.take() {
+            let _ = previous.provider.abort().await;
+        }
+
+        self.shared.stopping.store(false, Ordering::SeqCst);
+        let generation = self.shared.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.shared.replay().clear();
+        state.callbacks = Some(ChainCallbacks {
+            on_partial,
+            on_final,
+            on_error,
+            on_connection_quality,
+        });
+
+        self.shared
+            .start_from(&mut state, 0, generation, None)
+            .await
+    }
+
+    async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
+        self.shared.replay().push(chunk.clone());
+
+        let Ok(mut state) = self.shared.state.try_lock() else {
+            // Идёт переключение провайдера: чанк уже в буфере и уйдёт новому провайдеру.
+            return Ok(());
+        };
+
+        let generation = self.shared.generation.load(Ordering::SeqCst);
+        match self.shared.flush(&mut state).await {
+            Ok(()) => Ok(()),
+            Err(error) if state.should_fail_over(&error) => {
+                drop(state);
+                spawn_failover(&self.shared, &Handle::current(), generation, error);
+                Ok(())
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    async fn stop_stream(&mut self) -> SttResult<()> {
+        self.shared.stopping.store(true, Ordering::SeqCst);
+        let mut state = self.shared.state.lock().await;
+
+        let result = loop {
+            if state.active.is_none() {
+                break Ok(());
+            }
+
+            let stopped = match self.shared.flush(&mut state).await {
+                Ok(()) => match state.active.as_mut() {
+                    Some(active) => active.provider.stop_stream().await,
+                    None => Ok(()),
+                },
+                Err(error) => Err(error),
+            };
+
+            match stopped {
+                Err(error) if state.should_fail_over(&error) => {
+                    // Хвост фразы не теряем: дочитываем его резервным провайдером.
+                    if let Err(error) = self.shared.recover(&mut state, error).await {
+                        break Err(error);
+                    }
+                }
+                other => break other,
+            }
+        };
+
+        state.active = None;
+        self.shared.generation.fetch_add(1, Ordering::SeqCst);
+        self.shared.replay().clear();
+        result
+    }
+
+    async fn abort(&mut self) -> SttResult<()> {
+        self.shared.stopping.store(true, Ordering::SeqCst);
+        self.shared.generation.fetch_add(1, Ordering::SeqCst);
+        self.shared.replay().clear();
+
+        let Ok(mut state) = self.shared.state.try_lock() else {
+            // Переключение ещё идёт; задача failover сама погасит поднятый провайдер.
+            return Ok(());
+        };
+        match state.active.take() {
+            Some(mut active) => active.provider.abort().await,
+            None => Ok(()),
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_online(&self) -> bool {
+        self.shared.online.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for FailoverProvider {
+    fn drop(&mut self) {
+        self.shared.stopping.store(true, Ordering::SeqCst);
+        self.shared.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{SttConnectionError, SttProviderType};
+
+    #[derive(Default)]
+    struct MockRecord {
+        started: usize,
+        stopped: usize,
+        aborted: usize,
+        sent: Vec<i16>,
+        callbacks: Option<ChainCallbacks>,
+    }
+
+    struct MockProvider {
+        name: &'static str,
+        start_error: Option<SttError>,
+        /// Как Google: ошибка старта дублируется в `on_error` до возврата Err
+        report_start_error: bool,
+        record: Arc<StdMutex<MockRecord>>,
+    }
+
+    #[async_trait]
+    impl SttProvider for MockProvider {
+        async fn initialize(&mut self, _config: &SttConfig) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn start_stream(
+            &mut self,
+            on_partial: TranscriptionCallback,
+            on_final: TranscriptionCallback,
+            on_error: ErrorCallback,
+            on_connection_quality: ConnectionQualityCallback,
+        ) -> SttResult<()> {
+            if let Some(error) = self.start_error.clone() {
+                if self.report_start_error {
+                    on_error(error.clone());
+                }
+                return Err(error);
+            }
+            let mut record = self.record.lock().unwrap();
+            record.started += 1;
+            record.callbacks = Some(ChainCallbacks {
+                on_partial,
+                on_final,
+                on_error,
+                on_connection_quality,
+            });
+            Ok(())
+        }
+
+        async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
+            self.record.lock().unwrap().sent.extend(&chunk.data);
+            Ok(())
+        }
+
+        async fn stop_stream(&mut self) -> SttResult<()> {
+            self.record.lock().unwrap().stopped += 1;
+            Ok(())
+        }
+
+        async fn abort(&mut self) -> SttResult<()> {
+            self.record.lock().unwrap().aborted += 1;
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn is_online(&self) -> bool {
+            true
+        }
+    }
+
+    /// Backend и Deepgram — "облачные" моки, WhisperLocal — резервный локальный.
+    struct MockFactory {
+        start_errors: StdMutex<Vec<(SttProviderType, SttError)>>,
+        report_start_errors: AtomicBool,
+        records: Vec<(SttProviderType, Arc<StdMutex<MockRecord>>)>,
+    }
+
+    impl MockFactory {
+        fn new(start_errors: Vec<(SttProviderType, SttError)>) -> Arc<Self> {
+            let records = [
+                SttProviderType::Backend,
+                SttProviderType::Deepgram,
+                SttProviderType::WhisperLocal,
+            ]
+            .into_iter()
+            .map(|provider| (provider, Arc::new(StdMutex::new(MockRecord::default()))))
+            .collect();
+            Arc::new(Self {
+                start_errors: StdMutex::new(start_errors),
+                report_start_errors: AtomicBool::new(false),
+                records,
+            })
+        }
+
+        fn record(&self, provider: SttProviderType) -> Arc<StdMutex<MockRecord>> {
+            self.records
+                .iter()
+                .find(|(kind, _)| *kind == provider)
+                .map(|(_, record)| record.clone())
+                .unwrap()
+        }
+    }
+
+    impl SttProviderFactory for MockFactory {
+        fn create(&self, config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
+            let start_error = self
+                .start_errors
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(kind, _)| *kind == config.provider)
+                .map(|(_, error)| error.clone());
+            let name = match config.provider {
+                SttProviderType::Backend => "Backend",
+                SttProviderType::Deepgram => "Deepgram",
+                _ => "Whisper",
+            };
+            Ok(Box::new(MockProvider {
+                name,
+                start_error,
+                report_start_error: self.report_start_errors.load(Ordering::SeqCst),
+                record: self.record(config.provider),
+            }))
+        }
+    }
+
+    fn connection_error(category: SttConnectionCategory) -> SttError {
+        SttError::Connection(SttConnectionError::with_category("mock failure", category))
+    }
+
+    fn chain_config() -> SttConfig {
+        let mut config = SttConfig::new(SttProviderType::Backend);
+        config.failover_providers = vec![SttProviderType::Deepgram, SttProviderType::WhisperLocal];
+        config
+    }
+
+    struct Collected {
+        finals: Arc<StdMutex<Vec<Transcription>>>,
+        errors: Arc<StdMutex<Vec<SttError>>>,
+    }
+
+    async fn start(provider: &mut FailoverProvider) -> (SttResult<()>, Collected) {
+        let finals = Arc::new(StdMutex::new(Vec::new()));
+        let errors = Arc::new(StdMutex::new(Vec::new()));
+        let finals_clone = finals.clone();
+        let errors_clone = errors.clone();
+        provider.initialize(&chain_config()).await.unwrap();
+        let result = provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(move |t| finals_clone.lock().unwrap().push(t)),
+                Arc::new(move |e| errors_clone.lock().unwrap().push(e)),
+                Arc::new(|_, _| {}),
+            )
+            .await;
+        (result, Collected { finals, errors })
+    }
+
+    fn chunk(value: i16) -> AudioChunk {
+        AudioChunk::new(vec![value; 160], 16_000, 1)
+    }
+
+    fn callbacks_of(record: &Arc<StdMutex<MockRecord>>) -> ChainCallbacks {
+        record
+            .lock()
+            .unwrap()
+            .callbacks
+            .clone()
+            .expect("provider started")
+    }
+
+    async fn wait_until_started(record: &Arc<StdMutex<MockRecord>>) {
+        for _ in 0..200 {
+            if record.lock().unwrap().started > 0 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("fallback provider was not started");
+    }
+
+    #[test]
+    fn only_network_and_quota_errors_trigger_failover() {
+        assert!(is_failover_error(&connection_error(
+            SttConnectionCategory::Offline
+        )));
+        assert!(is_failover_error(&connection_error(
+            SttConnectionCategory::Dns
+        )));
+        assert!(is_failover_error(&connection_error(
+            SttConnectionCategory::ServerUnavailable
+        )));
+        assert!(is_failover_error(&connection_error(
+            SttConnectionCategory::ProviderQuotaExceeded
+        )));
+        assert!(!is_failover_error(&connection_error(
+            SttConnectionCategory::LimitExceeded
+        )));
+        assert!(!is_failover_error(&SttError::Authentication(
+            "bad key".to_string()
+        )));
+    }
+
+    #[test]
+    fn replay_buffer_keeps_audio_since_last_final() {
+        let mut buffer = ReplayBuffer::default();
+        buffer.push(chunk(1));
+        buffer.push(chunk(2));
+        buffer.mark_delivered(1);
+        buffer.commit();
+        buffer.push(chunk(3));
+        buffer.mark_delivered(2);
+
+        assert!(buffer.undelivered().is_empty());
+        buffer.rewind();
+
+        let replay: Vec<i16> = buffer
+            .undelivered()
+            .into_iter()
+            .map(|(_, chunk)| chunk.data[0])
+            .collect();
+        assert_eq!(replay, vec![3]);
+    }
+
+    #[test]
+    fn replay_buffer_is_bounded() {
+        let mut buffer = ReplayBuffer::default();
+        for _ in 0..(FAILOVER_REPLAY_MAX_SAMPLES / 160 + 50) {
+            buffer.push(chunk(1));
+        }
+        assert!(buffer.samples <= FAILOVER_REPLAY_MAX_SAMPLES);
+    }
+
+    #[tokio::test]
+    async fn startup_failure_falls_through_to_next_provider() {
+        let factory = MockFactory::new(vec![(
+            SttProviderType::Backend,
+            connection_error(SttConnectionCategory::Dns),
+        )]);
+        let mut provider = FailoverProvider::new(factory.clone());
+
+        let (result, _) = start(&mut provider).await;
+        result.expect("fallback should start");
+        provider.send_audio(&chunk(7)).await.unwrap();
+
+        let deepgram = factory.record(SttProviderType::Deepgram);
+        assert_eq!(deepgram.lock().unwrap().started, 1);
+        assert_eq!(deepgram.lock().unwrap().sent.len(), 160);
+        assert_eq!(
+            factory
+                .record(SttProviderType::WhisperLocal)
+                .lock()
+                .unwrap()
+                .started,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn startup_error_reported_via_callback_does_not_skip_started_fallback() {
+        let factory = MockFactory::new(vec![(
+            SttProviderType::Backend,
+            connection_error(SttConnectionCategory::Dns),
+        )]);
+        factory.report_start_errors.store(true, Ordering::SeqCst);
+        let mut provider = FailoverProvider::new(factory.clone());
+
+        let (result, collected) = start(&mut provider).await;
+        result.expect("fallback should start");
+        // Даём отработать failover-задаче, которую запросил on_error упавшего кандидата
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        provider.send_audio(&chunk(7)).await.unwrap();
+
+        let deepgram = factory.record(SttProviderType::Deepgram);
+        assert_eq!(deepgram.lock().unwrap().aborted, 0);
+        assert_eq!(deepgram.lock().unwrap().sent.len(), 160);
+        assert_eq!(
+            factory
+                .record(SttProviderType::WhisperLocal)
+                .lock()
+                .unwrap()
+                .started,
+            0
+        );
+        assert!(collected.errors.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn non_failover_startup_error_is_returned() {
+        let factory = MockFactory::new(vec![(
+            SttProviderType::Backend,
+            SttError::Authentication("expired license".to_string()),
+        )]);
+        let mut provider = FailoverProvider::new(factory.clone());
+
+        let (result, _) = start(&mut provider).await;
+
+        assert!(matches!(result, Err(SttError::Authentication(_))));
+        assert_eq!(
+            factory
+                .record(SttProviderType::Deepgram)
+                .lock()
+                .unwrap()
+                .started,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn exhausted_chain_reports_original_error() {
+        let factory = MockFactory::new(vec![
+            (
+                SttProviderType::Backend,
+                connection_error(SttConnectionCategory::Offline),
+            ),
+            (
+                SttProviderType::Deepgram,
+                connection_error(SttConnectionCategory::Offline),
+            ),
+            (
+                SttProviderType::WhisperLocal,
+                SttError::Configuration("model missing".to_string()),
+            ),
+        ]);
+        let mut provider = FailoverProvider::new(factory);
+
+        let (result, _) = start(&mut provider).await;
+
+        let Err(SttError::Connection(error)) = result else {
+            panic!("expected original connection error");
+        };
+        assert_eq!(error.details.category, Some(SttConnectionCategory::Offline));
+    }
+
+    #[tokio::test]
+    async fn mid_stream_failure_replays_audio_since_last_final() {
+        let factory = MockFactory::new(Vec::new());
+        let mut provider = FailoverProvider::new(factory.clone());
+        let (result, collected) = start(&mut provider).await;
+        result.unwrap();
+
+        let backend = factory.record(SttProviderType::Backend);
+        let backend_callbacks = callbacks_of(&backend);
+        provider.send_audio(&chunk(1)).await.unwrap();
+        (backend_callbacks.on_final)(Transcription::final_result("first".to_string()));
+        provider.send_audio(&chunk(2)).await.unwrap();
+        provider.send_audio(&chunk(3)).await.unwrap();
+
+        (backend_callbacks.on_error)(connection_error(SttConnectionCategory::ServerUnavailable));
+        let deepgram = factory.record(SttProviderType::Deepgram);
+        wait_until_started(&deepgram).await;
+        provider.send_audio(&chunk(4)).await.unwrap();
+
+        // Поздний final от упавшего провайдера не должен попасть в текст.
+        (backend_callbacks.on_final)(Transcription::final_result("stale".to_string()));
+        (callbacks_of(&deepgram).on_final)(Transcription::final_result("second".to_string()));
+        provider.stop_stream().await.unwrap();
+
+        let sent: Vec<i16> = deepgram
+            .lock()
+            .unwrap()
+            .sent
+            .chunks(160)
+            .map(|chunk| chunk[0])
+            .collect();
+        assert_eq!(sent, vec![2, 3, 4]);
+        assert_eq!(backend.lock().unwrap().aborted, 1);
+        assert_eq!(deepgram.lock().unwrap().stopped, 1);
+        assert!(collected.errors.lock().unwrap().is_empty());
+
+        let finals = collected.finals.lock().unwrap();
+        let labelled: Vec<(&str, Option<&str>)> = finals
+            .iter()
+            .map(|t| (t.text.as_str(), t.provider.as_deref()))
+            .collect();
+        assert_eq!(
+            labelled,
+            vec![("first", Some("Backend")), ("second", Some("Deepgram"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn error_from_last_provider_is_forwarded() {
+        let factory = MockFactory::new(vec![
+            (
+                SttProviderType::Backend,
+                connection_error(SttConnectionCategory::Offline),
+            ),
+            (
+                SttProviderType::Deepgram,
+                connection_error(SttConnectionCategory::Offline),
+            ),
+        ]);
+        let mut provider = FailoverProvider::new(factory.clone());
+        let (result, collected) = start(&mut provider).await;
+        result.unwrap();
+
+        let whisper = factory.record(SttProviderType::WhisperLocal);
+        (callbacks_of(&whisper).on_error)(connection_error(
+            SttConnectionCategory::ServerUnavailable,
+        ));
+
+        assert_eq!(collected.errors.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,1311 @@
+use async_trait::async_trait;
+use http::uri::PathAndQuery;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+use tonic::codec::ProstCodec;
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+
+use super::google_cloud_messages::{
+    AdaptationPhraseSet, AdaptationPhraseSetValue, DecodingConfig, ExplicitDecodingConfig, Phrase,
+    PhraseSet, RecognitionConfig, RecognitionFeatures, SpeechAdaptation,
+    StreamingRecognitionConfig, StreamingRecognitionFeatures, StreamingRecognizeRequest,
+    StreamingRecognizeResponse, StreamingRequest, WordInfo, AUDIO_ENCODING_LINEAR16,
+};
+use crate::domain::{
+    AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttConnectionCategory,
+    SttConnectionDetails, SttConnectionError, SttError, SttProvider, SttResult, Transcription,
+    TranscriptionCallback, Word,
+};
+
+/// Google Cloud Speech-to-Text v2 streaming provider
+///
+/// Endpoint: https://speech.googleapis.com (или `{location}-speech.googleapis.com`)
+/// Transport: gRPC `google.cloud.speech.v2.Speech/StreamingRecognize`
+///
+/// Protocol:
+/// 1. Open a bidirectional gRPC stream with `x-goog-api-key` metadata
+/// 2. First request carries recognizer + StreamingRecognitionConfig (LINEAR16, 16 kHz)
+/// 3. Following requests carry raw PCM16 audio (≤ 15 KB each)
+/// 4. Responses contain interim and final results; half-close drains the last finals
+const GOOGLE_SPEECH_GLOBAL_ENDPOINT: &str = "https://speech.googleapis.com";
+const GOOGLE_STREAMING_RECOGNIZE_PATH: &str = "/google.cloud.speech.v2.Speech/StreamingRecognize";
+const GOOGLE_DEFAULT_LOCATION: &str = "global";
+const GOOGLE_DEFAULT_MODEL: &str = "long";
+const GOOGLE_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+// Ошибки авторизации/квоты Google отдаёт сразу в ответ на заголовки стрима.
+// Если заголовки задерживаются, не блокируем старт записи дольше этого окна.
+const GOOGLE_SESSION_READY_TIMEOUT: Duration = Duration::from_millis(1500);
+const GOOGLE_SEND_TIMEOUT: Duration = Duration::from_secs(5);
+const GOOGLE_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+const GOOGLE_MAX_AUDIO_BYTES_PER_REQUEST: usize = 15_360;
+const GOOGLE_REQUEST_QUEUE_CAPACITY: usize = 64;
+// Очередь запросов наполняется, когда HTTP/2 flow control не пускает аудио дальше.
+const GOOGLE_POOR_QUALITY_QUEUE_DEPTH: usize = GOOGLE_REQUEST_QUEUE_CAPACITY / 2;
+const GOOGLE_RECOVERED_AFTER: Duration = Duration::from_secs(2);
+const GOOGLE_KEYTERM_BOOST: f32 = 10.0;
+
+fn google_speech_endpoint(location: &str) -> String {
+    if location == GOOGLE_DEFAULT_LOCATION {
+        GOOGLE_SPEECH_GLOBAL_ENDPOINT.to_string()
+    } else {
+        format!("https://{}-speech.googleapis.com", location)
+    }
+}
+
+fn google_recognizer_name(project_id: &str, location: &str) -> String {
+    // "_" — неявный recognizer: вся конфигурация приходит в StreamingRecognitionConfig.
+    format!(
+        "projects/{}/locations/{}/recognizers/_",
+        project_id, location
+    )
+}
+
+/// Google v2 ожидает BCP-47 коды; китайский у Google называется `cmn-Hans-CN`.
+fn google_language_code(language: &str) -> String {
+    if language.trim().eq_ignore_ascii_case("zh") {
+        return "cmn-Hans-CN".to_string();
+    }
+    super::regional_language_tag(language)
+}
+
+fn google_phrase_adaptation(keyterms: Option<&str>) -> Option<SpeechAdaptation> {
+    let phrases: Vec<Phrase> = keyterms?
+        .split(',')
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .map(|term| Phrase {
+            value: term.to_string(),
+            boost: GOOGLE_KEYTERM_BOOST,
+        })
+        .collect();
+    if phrases.is_empty() {
+        return None;
+    }
+
+    Some(SpeechAdaptation {
+        phrase_sets: vec![AdaptationPhraseSet {
+            value: Some(AdaptationPhraseSetValue::InlinePhraseSet(PhraseSet {
+                phrases,
+            })),
+        }],
+    })
+}
+
+fn google_streaming_config_request(
+    recognizer: &str,
+    config: &SttConfig,
+) -> StreamingRecognizeRequest {
+    let model = config
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|model| !model.is_empty())
+        .unwrap_or(GOOGLE_DEFAULT_MODEL)
+        .to_string();
+
+    StreamingRecognizeRequest {
+        recognizer: recognizer.to_string(),
+        streaming_request: Some(StreamingRequest::StreamingConfig(
+            StreamingRecognitionConfig {
+                config: Some(RecognitionConfig {
+                    decoding_config: Some(DecodingConfig::ExplicitDecodingConfig(
+                        ExplicitDecodingConfig {
+                            encoding: AUDIO_ENCODING_LINEAR16,
+                            sample_rate_hertz: 16_000,
+                            audio_channel_count: 1,
+                        },
+                    )),
+                    model,
+                    language_codes: vec![google_language_code(&config.language)],
+                    features: Some(RecognitionFeatures {
+                        profanity_filter: config.filter_profanity,
+                        enable_word_time_offsets: true,
+                        enable_word_confidence: true,
+                        enable_automatic_punctuation: config.enable_punctuation,
+                    }),
+                    adaptation: google_phrase_adaptation(config.streaming_keyterms.as_deref()),
+                }),
+                streaming_features: Some(StreamingRecognitionFeatures {
+                    enable_voice_activity_events: false,
+                    interim_results: true,
+                }),
+            },
+        )),
+    }
+}
+
+fn google_status_error(status: &tonic::Status) -> SttError {
+    let code = status.code();
+    let message = format!(
+        "Google Cloud Speech error ({:?}): {}",
+        code,
+        status.message()
+    );
+    let category = match code {
+        tonic::Code::Unauthenticated | tonic::Code::PermissionDenied => {
+            return SttError::Authentication(message)
+        }
+        tonic::Code::InvalidArgument | tonic::Code::NotFound | tonic::Code::FailedPrecondition => {
+            return SttError::Configuration(message)
+        }
+        tonic::Code::ResourceExhausted => {
+            if status.message().to_lowercase().contains("quota") {
+                SttConnectionCategory::ProviderQuotaExceeded
+            } else {
+                SttConnectionCategory::RateLimited
+            }
+        }
+        tonic::Code::Unavailable => SttConnectionCategory::ServerUnavailable,
+        tonic::Code::DeadlineExceeded => SttConnectionCategory::Timeout,
+        tonic::Code::Cancelled | tonic::Code::Aborted => SttConnectionCategory::Closed,
+        _ => SttConnectionCategory::Unknown,
+    };
+
+    SttError::Connection(SttConnectionError {
+        message,
+        details: SttConnectionDetails {
+            category: Some(category),
+            server_code: Some(format!("{:?}", code)),
+            ..Default::default()
+        },
+    })
+}
+
+fn google_transport_error(error: &(dyn std::error::Error + 'static), context: &str) -> SttError {
+    let mut details = SttConnectionDetails::default();
+    let mut source = Some(error);
+    while let Some(current) = source {
+        if let Some(io_error) = current.downcast_ref::<std::io::Error>() {
+            details.io_error_kind = Some(format!("{:?}", io_error.kind()));
+            details.os_error = io_error.raw_os_error();
+            details.category = Some(match io_error.kind() {
+                std::io::ErrorKind::ConnectionRefused => SttConnectionCategory::Refused,
+                std::io::ErrorKind::ConnectionReset | std::io::ErrorKind::ConnectionAborted => {
+                    SttConnectionCategory::Reset
+                }
+                std::io::ErrorKind::TimedOut => SttConnectionCategory::Timeout,
+                _ => SttConnectionCategory::Unknown,
+            });
+            break;
+        }
+        source = current.source();
+    }
+
+    let message = format!("{}: {}", context, error);
+    if details.category.is_none() {
+        let lower = message.to_lowercase();
+        if lower.contains("dns") || lower.contains("lookup address") {
+            details.category = Some(SttConnectionCategory::Dns);
+        } else if lower.contains("tls") || lower.contains("certificate") {
+            details.category = Some(SttConnectionCategory::Tls);
+        }
+    }
+
+    SttError::Connection(SttConnectionError { message, details })
+}
+
+fn google_closed_error(message: impl Into<String>) -> SttError {
+    SttError::Connection(SttConnectionError::with_category(
+        message,
+        SttConnectionCategory::Closed,
+    ))
+}
+
+fn google_timeout_error(message: impl Into<String>) -> SttError {
+    SttError::Connection(SttConnectionError::with_category(
+        message,
+        SttConnectionCategory::Timeout,
+    ))
+}
+
+fn call_google_callback(label: &str, callback: impl FnOnce()) {
+    if catch_unwind(AssertUnwindSafe(callback)).is_err() {
+        log::error!("Google Cloud Speech {} callback panicked", label);
+    }
+}
+
+/// Результаты одного `StreamingRecognizeResponse`, разложенные на finals и partial.
+#[derive(Debug, Default)]
+struct GoogleResponseUpdate {
+    finals: Vec<Transcription>,
+    partial: Option<Transcription>,
+}
+
+/// Google отдаёт смещения слов от начала стрима; слово в `word` уже с пунктуацией,
+/// если включена автоматическая расстановка.
+fn google_words(words: &[WordInfo]) -> Vec<Word> {
+    words
+        .iter()
+        .filter(|word| !word.word.trim().is_empty())
+        .map(|word| {
+            let start = word
+                .start_offset
+                .map(|offset| offset.as_secs_f64())
+                .unwrap_or(0.0);
+            let end = word
+                .end_offset
+                .map(|offset| offset.as_secs_f64())
+                .unwrap_or(start);
+            let mut parsed = Word::new(word.word.trim(), start, end);
+            parsed.confidence = (word.confidence > 0.0).then_some(word.confidence);
+            parsed
+        })
+        .collect()
+}
+
+/// Раскладываем ответ Google на final/partial транскрипции.
+///
+/// `committed_end` — конец последнего финального сегмента в секундах от начала стрима;
+/// Google отдаёт только `result_end_offset`, поэтому start считаем от предыдущего final.
+fn google_response_update(
+    response: &StreamingRecognizeResponse,
+    committed_end: &mut f64,
+    configured_language: &str,
+) -> GoogleResponseUpdate {
+    let mut update = GoogleResponseUpdate::default();
+    let mut partial_text = String::new();
+    let mut partial_confidence: Option<f32> = None;
+    let mut partial_language: Option<String> = None;
+    let mut partial_end = *committed_end;
+
+    for result in &response.results {
+        let Some(alternative) = result.alternatives.first() else {
+            continue;
+        };
+        let text = alternative.transcript.trim();
+        let language = if result.language_code.is_empty() {
+            configured_language.to_string()
+        } else {
+            result.language_code.clone()
+        };
+        let end = result
+            .result_end_offset
+            .map(|offset| offset.as_secs_f64())
+            .unwrap_or(*committed_end)
+            .max(*committed_end);
+        let confidence = (alternative.confidence > 0.0).then_some(alternative.confidence);
+
+        if result.is_final {
+            if !text.is_empty() {
+                let mut transcription = Transcription::final_result(text.to_string())
+                    .with_language(language)
+                    .with_timing(*committed_end, end - *committed_end);
+                transcription.confidence = confidence;
+                transcription.words = google_words(&alternative.words);
+                update.finals.push(transcription);
+            }
+            *committed_end = end;
+            partial_end = end;
+        } else if !text.is_empty() {
+            // Google дробит interim гипотезу на стабильную и нестабильную части —
+            // показываем их одной строкой, как и рекомендует документация.
+            if !partial_text.is_empty() {
+                partial_text.push(' ');
+            }
+            partial_text.push_str(text);
+            partial_confidence = partial_confidence.or(confidence);
+            partial_language.get_or_insert(language);
+            partial_end = partial_end.max(end);
+        }
+    }
+
+    if !partial_text.is_empty() {
+        let mut transcription = Transcription::partial(partial_text)
+            .with_timing(*committed_end, partial_end - *committed_end);
+        transcription.confidence = partial_confidence;
+        transcription.language = partial_language.or_else(|| Some(configured_language.to_string()));
+        update.partial = Some(transcription);
+    }
+
+    update
+}
+
+async fn report_google_receiver_error(
+    startup_error: &Arc<Mutex<Option<SttError>>>,
+    session_ready: &Arc<Notify>,
+    session_started: bool,
+    on_error: &ErrorCallback,
+    error: SttError,
+) {
+    if !session_started {
+        *startup_error.lock().await = Some(error.clone());
+        session_ready.notify_one();
+    }
+    call_google_callback("error", || on_error(error));
+}
+
+pub struct GoogleCloudProvider {
+    config: Option<SttConfig>,
+    is_streaming: bool,
+    api_key: Option<String>,
+    recognizer: Option<String>,
+    endpoint_url: Option<String>,
+    request_tx: Option<async_channel::Sender<StreamingRecognizeRequest>>,
+    receiver_task: Option<JoinHandle<()>>,
+    session_ready: Arc<Notify>,
+    startup_error: Arc<Mutex<Option<SttError>>>,
+    stop_requested: Arc<AtomicBool>,
+    on_connection_quality: Option<ConnectionQualityCallback>,
+    current_quality: &'static str,
+    queue_drained_since: Option<Instant>,
+}
+
+impl GoogleCloudProvider {
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            is_streaming: false,
+            api_key: None,
+            recognizer: None,
+            endpoint_url: None,
+            request_tx: None,
+            receiver_task: None,
+            session_ready: Arc::new(Notify::new()),
+            startup_error: Arc::new(Mutex::new(None)),
+            stop_requested: Arc::new(AtomicBool::new(false)),
+            on_connection_quality: None,
+            current_quality: "Good",
+            queue_drained_since: None,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_endpoint_url(endpoint_url: String) -> Self {
+        let mut provider = Self::new();
+        provider.endpoint_url = Some(endpoint_url);
+        provider
+    }
+
+    fn resolved_endpoint_url(&self) -> String {
+        if let Some(url) = &self.endpoint_url {
+            return url.clone();
+        }
+        let location = self
+            .config
+            .as_ref()
+            .and_then(|config| config.google_cloud_location.as_deref())
+            .map(str::trim)
+            .filter(|location| !location.is_empty())
+            .unwrap_or(GOOGLE_DEFAULT_LOCATION);
+        google_speech_endpoint(location)
+    }
+
+    async fn connect_channel(&self) -> SttResult<Channel> {
+        let url = self.resolved_endpoint_url();
+        let mut endpoint = Endpoint::from_shared(url.clone())
+            .map_err(|e| {
+                SttError::Configuration(format!("Invalid Google Cloud Speech endpoint: {}", e))
+            })?
+            .connect_timeout(GOOGLE_CONNECT_TIMEOUT)
+            .tcp_nodelay(true);
+        if url.starts_with("https://") {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new().with_native_roots())
+                .map_err(|e| {
+                    SttError::Configuration(format!(
+                        "Failed to configure TLS for Google Cloud Speech: {}",
+                        e
+                    ))
+                })?;
+        }
+
+        log::debug!("Connecting to Google Cloud Speech: {}", url);
+        match tokio::time::timeout(GOOGLE_CONNECT_TIMEOUT, endpoint.connect()).await {
+            Ok(Ok(channel)) => Ok(channel),
+            Ok(Err(error)) => Err(google_transport_error(
+                &error,
+                "Google Cloud Speech connection failed",
+            )),
+            Err(_) => Err(google_timeout_error(format!(
+                "Google Cloud Speech connection timed out after {} ms",
+                GOOGLE_CONNECT_TIMEOUT.as_millis()
+            ))),
+        }
+    }
+
+    fn report_quality(&mut self, quality: &'static str, reason: Option<String>) {
+        if self.current_quality == quality {
+            return;
+        }
+        log::info!(
+            "Google Cloud Speech connection quality: {} -> {} ({:?})",
+            self.current_quality,
+            quality,
+            reason
+        );
+        self.current_quality = quality;
+        if let Some(callback) = self.on_connection_quality.clone() {
+            call_google_callback("connection quality", || {
+                callback(quality.to_string(), reason)
+            });
+        }
+    }
+
+    /// Качество связи по глубине очереди запросов: если gRPC не успевает
+    /// отдавать аудио серверу, очередь растёт раньше, чем случится timeout.
+    fn update_connection_quality(&mut self, queued_requests: usize) {
+        if queued_requests >= GOOGLE_POOR_QUALITY_QUEUE_DEPTH {
+            self.queue_drained_since = None;
+            self.report_quality(
+                "Poor",
+                Some(format!(
+                    "{} audio requests are waiting to be sent",
+                    queued_requests
+                )),
+            );
+            return;
+        }
+
+        if self.current_quality == "Good" || queued_requests > 0 {
+            return;
+        }
+
+        let drained_since = *self.queue_drained_since.get_or_insert_with(Instant::now);
+        if self.current_quality == "Poor" {
+            self.report_quality("Recovering", None);
+        } else if drained_since.elapsed() >= GOOGLE_RECOVERED_AFTER {
+            self.queue_drained_since = None;
+            self.report_quality("Good", None);
+        }
+    }
+
+    fn reset_session_state(&mut self) {
+        self.request_tx = None;
+        self.is_streaming = false;
+        self.on_connection_quality = None;
+        self.current_quality = "Good";
+        self.queue_drained_since = None;
+    }
+}
+
+impl Default for GoogleCloudProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for GoogleCloudProvider {
+    fn drop(&mut self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        if let Some(request_tx) = self.request_tx.take() {
+            request_tx.close();
+        }
+        super::abort_background_task(&mut self.receiver_task);
+    }
+}
+
+#[async_trait]
+impl SttProvider for GoogleCloudProvider {
+    async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
+        log::info!("GoogleCloudProvider: Initializing (Speech-to-Text v2)");
+
+        let api_key = config
+            .google_cloud_api_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                SttError::Configuration("Google Cloud Speech API key is required".to_string())
+            })?;
+        let project_id = config
+            .google_cloud_project_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|project| !project.is_empty())
+            .ok_or_else(|| {
+                SttError::Configuration("Google Cloud project ID is required".to_string())
+            })?;
+        let location = config
+            .google_cloud_location
+            .as_deref()
+            .map(str::trim)
+            .filter(|location| !location.is_empty())
+            .unwrap_or(GOOGLE_DEFAULT_LOCATION);
+
+        self.recognizer = Some(google_recognizer_name(project_id, location));
+        self.api_key = Some(api_key);
+        self.config = Some(config.clone());
+        Ok(())
+    }
+
+    async fn start_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        on_connection_quality: ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        log::info!("GoogleCloudProvider: Starting stream");
+
+        if self.is_streaming {
+            return Err(SttError::Processing("Stream already active".to_string()));
+        }
+
+        let (Some(api_key), Some(recognizer), Some(config)) = (
+            self.api_key.clone(),
+            self.recognizer.clone(),
+            self.config.clone(),
+        ) else {
+            return Err(SttError::Configuration(
+                "Google Cloud Speech provider is not initialized".to_string(),
+            ));
+        };
+
+        let channel = self.connect_channel().await?;
+        log::info!("Google Cloud Speech channel connected");
+
+        let (request_tx, request_rx) = async_channel::bounded(GOOGLE_REQUEST_QUEUE_CAPACITY);
+        request_tx
+            .try_send(google_streaming_config_request(&recognizer, &config))
+            .map_err(|e| {
+                SttError::Internal(format!("Failed to queue Google streaming config: {}", e))
+            })?;
+
+        let mut request = tonic::Request::new(request_rx);
+        let api_key_value = MetadataValue::try_from(api_key.as_str()).map_err(|_| {
+            SttError::Configuration(
+                "Google Cloud Speech API key contains invalid characters".to_string(),
+            )
+        })?;
+        request
+            .metadata_mut()
+            .insert("x-goog-api-key", api_key_value);
+        let routing_value =
+            MetadataValue::try_from(format!("recognizer={}", urlencoding::encode(&recognizer)))
+                .map_err(|_| {
+                    SttError::Configuration("Invalid Google Cloud recognizer name".to_string())
+                })?;
+        request
+            .metadata_mut()
+            .insert("x-goog-request-params", routing_value);
+
+        self.session_ready = Arc::new(Notify::new());
+        *self.startup_error.lock().await = None;
+        self.stop_requested.store(false, Ordering::SeqCst);
+
+        let session_ready = self.session_ready.clone();
+        let startup_error = self.startup_error.clone();
+        let stop_requested = self.stop_requested.clone();
+        let configured_language = google_language_code(&config.language);
+        let receiver_task = tokio::spawn(async move {
+            log::debug!("Google Cloud Speech receiver task started");
+
+            let mut grpc = tonic::client::Grpc::new(channel);
+            if let Err(error) = grpc.ready().await {
+                report_google_receiver_error(
+                    &startup_error,
+                    &session_ready,
+                    false,
+                    &on_error,
+                    SttError::Connection(SttConnectionError::simple(format!(
+                        "Google Cloud Speech channel is not ready: {}",
+                        error
+                    ))),
+                )
+                .await;
+                return;
+            }
+
+            let codec: ProstCodec<StreamingRecognizeRequest, StreamingRecognizeResponse> =
+                ProstCodec::default();
+            let mut responses = match grpc
+                .streaming(
+                    request,
+                    PathAndQuery::from_static(GOOGLE_STREAMING_RECOGNIZE_PATH),
+                    codec,
+                )
+                .await
+            {
+                Ok(response) => response.into_inner(),
+                Err(status) => {
+                    log::error!("Google Cloud Speech stream rejected: {}", status);
+                    report_google_receiver_error(
+                        &startup_error,
+                        &session_ready,
+                        false,
+                        &on_error,
+                        google_status_error(&status),
+                    )
+                    .await;
+                    return;
+                }
+            };
+
+            log::info!("Google Cloud Speech stream accepted");
+            session_ready.notify_one();
+
+            let mut committed_end = 0.0f64;
+            loop {
+                match responses.message().await {
+                    Ok(Some(response)) => {
+                        let update = google_response_update(
+                            &response,
+                            &mut committed_end,
+                            &configured_language,
+                        );
+                        if let Some(partial) = update.partial {
+                            log::debug!("Google partial transcript: {}", partial.text);
+                            call_google_callback("partial transcription", || on_partial(partial));
+                        }
+                        for transcription in update.finals {
+                            log::info!("Google final transcript: {}", transcription.text);
+                            call_google_callback("final transcription", || on_final(transcription));
+                        }
+                    }
+                    Ok(None) => {
+                        if !stop_requested.load(Ordering::SeqCst) {
+                            report_google_receiver_error(
+                                &startup_error,
+                                &session_ready,
+                                true,
+                                &on_error,
+                                google_closed_error(
+                                    "Google Cloud Speech stream ended unexpectedly",
+                                ),
+                            )
+                            .await;
+                        }
+                        break;
+                    }
+                    Err(status) => {
+                        log::error!("Google Cloud Speech stream error: {}", status);
+                        if !stop_requested.load(Ordering::SeqCst) {
+                            report_google_receiver_error(
+                                &startup_error,
+                                &session_ready,
+                                true,
+                                &on_error,
+                                google_status_error(&status),
+                            )
+                            .await;
+                        }
+                        break;
+                    }
+                }
+            }
+
+            log::debug!("Google Cloud Speech receiver task ended");
+        });
+
+        self.request_tx = Some(request_tx);
+        self.receiver_task = Some(receiver_task);
+        self.on_connection_quality = Some(on_connection_quality);
+        self.current_quality = "Good";
+        self.queue_drained_since = None;
+        self.is_streaming = true;
+
+        if tokio::time::timeout(GOOGLE_SESSION_READY_TIMEOUT, self.session_ready.notified())
+            .await
+            .is_err()
+        {
+            log::debug!(
+                "Google Cloud Speech response headers not received within {} ms; continuing",
+                GOOGLE_SESSION_READY_TIMEOUT.as_millis()
+            );
+        }
+
+        let startup_error = self.startup_error.lock().await.take();
+        if let Some(error) = startup_error {
+            if let Err(cleanup_error) = self.abort().await {
+                log::warn!(
+                    "Google Cloud Speech startup cleanup failed after {}: {}",
+                    error,
+                    cleanup_error
+                );
+            }
+            return Err(error);
+        }
+
+        log::info!("Google Cloud Speech stream started successfully");
+        Ok(())
+    }
+
+    async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
+        if !self.is_streaming {
+            return Err(SttError::Processing("Not streaming".to_string()));
+        }
+
+        let request_tx = self.request_tx.clone().ok_or_else(|| {
+            SttError::Processing("Google request stream not available".to_string())
+        })?;
+        let recognizer = self.recognizer.clone().unwrap_or_default();
+
+        let bytes: Vec<u8> = chunk
+            .data
+            .iter()
+            .flat_map(|&sample| sample.to_le_bytes())
+            .collect();
+
+        for audio in bytes.chunks(GOOGLE_MAX_AUDIO_BYTES_PER_REQUEST) {
+            let request = StreamingRecognizeRequest {
+                // Recognizer обязателен только в первом сообщении стрима.
+                recognizer: String::new(),
+                streaming_request: Some(StreamingRequest::Audio(audio.to_vec())),
+            };
+            match tokio::time::timeout(GOOGLE_SEND_TIMEOUT, request_tx.send(request)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(_)) => {
+                    return Err(google_closed_error(format!(
+                        "Google Cloud Speech stream for {} is closed",
+                        recognizer
+                    )))
+                }
+                Err(_) => {
+                    self.report_quality(
+                        "Poor",
+                        Some("Audio could not be sent to Google Cloud Speech".to_string()),
+                    );
+                    return Err(google_timeout_error(format!(
+                        "Google Cloud Speech audio send timed out after {} ms",
+                        GOOGLE_SEND_TIMEOUT.as_millis()
+                    )));
+                }
+            }
+        }
+
+        self.update_connection_quality(request_tx.len());
+        Ok(())
+    }
+
+    async fn stop_stream(&mut self) -> SttResult<()> {
+        log::info!("GoogleCloudProvider: Stopping stream");
+        self.stop_requested.store(true, Ordering::SeqCst);
+
+        if !self.is_streaming {
+            log::warn!("Stream not active");
+            return Ok(());
+        }
+
+        // Half-close: Google дорасшифровывает отправленное аудио, присылает finals
+        // и закрывает серверный стрим.
+        if let Some(request_tx) = self.request_tx.take() {
+            request_tx.close();
+        }
+
+        let mut stop_result = Ok(());
+        if let Some(mut task) = self.receiver_task.take() {
+            match tokio::time::timeout(GOOGLE_DRAIN_TIMEOUT, &mut task).await {
+                Ok(Ok(())) => {}
+                Ok(Err(join_error)) => {
+                    stop_result = Err(SttError::Internal(format!(
+                        "Google Cloud Speech receiver task failed during drain: {}",
+                        join_error
+                    )));
+                }
+                Err(_) => {
+                    task.abort();
+                    let _ = task.await;
+                    stop_result = Err(google_timeout_error(format!(
+                        "Google Cloud Speech drain timed out after {} ms",
+                        GOOGLE_DRAIN_TIMEOUT.as_millis()
+                    )));
+                }
+            }
+        }
+
+        self.reset_session_state();
+        *self.startup_error.lock().await = None;
+
+        match &stop_result {
+            Ok(()) => log::info!("Google Cloud Speech stream stopped after drain"),
+            Err(error) => log::warn!("Google Cloud Speech stream stop failed: {}", error),
+        }
+        stop_result
+    }
+
+    async fn abort(&mut self) -> SttResult<()> {
+        log::info!("GoogleCloudProvider: Aborting stream");
+        self.stop_requested.store(true, Ordering::SeqCst);
+
+        if let Some(request_tx) = self.request_tx.take() {
+            request_tx.close();
+        }
+        if let Some(task) = self.receiver_task.take() {
+            task.abort();
+            let _ = task.await;
+        }
+
+        self.reset_session_state();
+        *self.startup_error.lock().await = None;
+
+        log::info!("Google Cloud Speech stream aborted");
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "Google Cloud Speech-to-Text v2"
+    }
+
+    fn supports_punctuation_control(&self) -> bool {
+        true
+    }
+
+    fn supports_profanity_filter(&self) -> bool {
+        true
+    }
+
+    fn is_online(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::google_cloud_messages::{
+        ProtoDuration, SpeechRecognitionAlternative, StreamingRecognitionResult,
+    };
+    use super::*;
+    use crate::domain::SttProviderType;
+    use futures_util::stream::BoxStream;
+    use futures_util::StreamExt;
+    use std::convert::Infallible;
+    use std::sync::Mutex as StdMutex;
+    use std::task::{Context, Poll};
+    use tokio::net::TcpListener;
+
+    /// Локальная замена `google.cloud.speech.v2.Speech` для проверки протокола без сети.
+    #[derive(Clone)]
+    struct StandInSpeech {
+        responses: Arc<Vec<StreamingRecognizeResponse>>,
+        rejection: Option<(tonic::Code, &'static str)>,
+        captured: Arc<StdMutex<Vec<StreamingRecognizeRequest>>>,
+        api_keys: Arc<StdMutex<Vec<String>>>,
+    }
+
+    impl tonic::server::NamedService for StandInSpeech {
+        const NAME: &'static str = "google.cloud.speech.v2.Speech";
+    }
+
+    impl<B> tonic::codegen::Service<http::Request<B>> for StandInSpeech
+    where
+        B: tonic::codegen::Body + Send + 'static,
+        B::Error: Into<tonic::codegen::StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Infallible;
+        type Future = tonic::codegen::BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: http::Request<B>) -> Self::Future {
+            let service = self.clone();
+            Box::pin(async move {
+                assert_eq!(request.uri().path(), GOOGLE_STREAMING_RECOGNIZE_PATH);
+                let codec: ProstCodec<StreamingRecognizeResponse, StreamingRecognizeRequest> =
+                    ProstCodec::default();
+                let mut grpc = tonic::server::Grpc::new(codec);
+                Ok(grpc.streaming(StandInRecognize(service), request).await)
+            })
+        }
+    }
+
+    struct StandInRecognize(StandInSpeech);
+
+    impl tonic::server::StreamingService<StreamingRecognizeRequest> for StandInRecognize {
+        type Response = StreamingRecognizeResponse;
+        type ResponseStream = BoxStream<'static, Result<StreamingRecognizeResponse, tonic::Status>>;
+        type Future =
+            tonic::codegen::BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+
+        fn call(
+            &mut self,
+            request: tonic::Request<tonic::Streaming<StreamingRecognizeRequest>>,
+        ) -> Self::Future {
+            let service = self.0.clone();
+            Box::pin(async move {
+                if let Some(key) = request
+                    .metadata()
+                    .get("x-goog-api-key")
+                    .and_then(|value| value.to_str().ok())
+                {
+                    service.api_keys.lock().unwrap().push(key.to_string());
+                }
+                if let Some((code, message)) = service.rejection {
+                    return Err(tonic::Status::new(code, message));
+                }
+
+                let mut inbound = request.into_inner();
+                let (response_tx, response_rx) = async_channel::unbounded();
+                tokio::spawn(async move {
+                    while let Ok(Some(message)) = inbound.message().await {
+                        service.captured.lock().unwrap().push(message);
+                    }
+                    for response in service.responses.iter().cloned() {
+                        let _ = response_tx.send(Ok(response)).await;
+                    }
+                });
+                Ok(tonic::Response::new(response_rx.boxed()))
+            })
+        }
+    }
+
+    async fn spawn_stand_in_speech(
+        responses: Vec<StreamingRecognizeResponse>,
+        rejection: Option<(tonic::Code, &'static str)>,
+    ) -> (String, StandInSpeech) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind stand-in Google server");
+        let address = listener.local_addr().expect("stand-in server address");
+        let service = StandInSpeech {
+            responses: Arc::new(responses),
+            rejection,
+            captured: Arc::new(StdMutex::new(Vec::new())),
+            api_keys: Arc::new(StdMutex::new(Vec::new())),
+        };
+        let incoming = futures_util::stream::unfold(listener, |listener| async move {
+            let accepted = listener.accept().await.map(|(stream, _)| stream);
+            Some((accepted, listener))
+        })
+        .boxed();
+        let server_service = service.clone();
+        tokio::spawn(async move {
+            let _ = tonic::transport::Server::builder()
+                .add_service(server_service)
+                .serve_with_incoming(incoming)
+                .await;
+        });
+        (format!("http://{}", address), service)
+    }
+
+    fn google_test_config() -> SttConfig {
+        let mut config = SttConfig::new(SttProviderType::GoogleCloud).with_language("ru");
+        config.google_cloud_api_key = Some(" test-key ".to_string());
+        config.google_cloud_project_id = Some("demo-project".to_string());
+        config
+    }
+
+    #[test]
+    fn streaming_config_honors_punctuation_and_profanity_settings() {
+        let mut config = google_test_config();
+        config.enable_punctuation = false;
+        config.filter_profanity = true;
+
+        let request = google_streaming_config_request("recognizer", &config);
+        let Some(StreamingRequest::StreamingConfig(streaming)) = request.streaming_request else {
+            panic!("expected streaming config");
+        };
+        let features = streaming
+            .config
+            .and_then(|config| config.features)
+            .expect("recognition features");
+
+        assert!(features.profanity_filter);
+        assert!(!features.enable_automatic_punctuation);
+    }
+
+    fn result(text: &str, is_final: bool, end_secs: f64) -> StreamingRecognitionResult {
+        StreamingRecognitionResult {
+            alternatives: vec![SpeechRecognitionAlternative {
+                transcript: text.to_string(),
+                confidence: if is_final { 0.9 } else { 0.0 },
+                words: Vec::new(),
+            }],
+            is_final,
+            stability: 0.0,
+            result_end_offset: Some(ProtoDuration {
+                seconds: end_secs.trunc() as i64,
+                nanos: (end_secs.fract() * 1_000_000_000.0).round() as i32,
+            }),
+            language_code: String::new(),
+        }
+    }
+
+    #[test]
+    fn endpoint_and_recognizer_follow_location() {
+        assert_eq!(
+            google_speech_endpoint("global"),
+            "https://speech.googleapis.com"
+        );
+        assert_eq!(
+            google_speech_endpoint("europe-west4"),
+            "https://europe-west4-speech.googleapis.com"
+        );
+        assert_eq!(
+            google_recognizer_name("demo", "us-central1"),
+            "projects/demo/locations/us-central1/recognizers/_"
+        );
+    }
+
+    #[test]
+    fn language_codes_are_expanded_to_bcp47() {
+        assert_eq!(google_language_code("ru"), "ru-RU");
+        assert_eq!(google_language_code("en-GB"), "en-GB");
+        assert_eq!(google_language_code("zh"), "cmn-Hans-CN");
+    }
+
+    #[test]
+    fn keyterms_become_inline_phrase_set() {
+        assert!(google_phrase_adaptation(None).is_none());
+        assert!(google_phrase_adaptation(Some(" , ")).is_none());
+
+        let adaptation =
+            google_phrase_adaptation(Some("Kubernetes, VoicetextAI")).expect("phrase adaptation");
+        let Some(AdaptationPhraseSetValue::InlinePhraseSet(phrase_set)) =
+            &adaptation.phrase_sets[0].value
+        else {
+            panic!("expected inline phrase set");
+        };
+        let phrases: Vec<&str> = phrase_set
+            .phrases
+            .iter()
+            .map(|phrase| phrase.value.as_str())
+            .collect();
+        assert_eq!(phrases, vec!["Kubernetes", "VoicetextAI"]);
+    }
+
+    #[test]
+    fn status_errors_map_to_connection_categories() {
+        assert!(matches!(
+            google_status_error(&tonic::Status::unauthenticated("bad key")),
+            SttError::Authentication(_)
+        ));
+        assert!(matches!(
+            google_status_error(&tonic::Status::invalid_argument("bad recognizer")),
+            SttError::Configuration(_)
+        ));
+        assert!(matches!(
+            google_status_error(&tonic::Status::resource_exhausted("Quota exceeded")),
+            SttError::Connection(connection)
+                if connection.details.category
+                    == Some(SttConnectionCategory::ProviderQuotaExceeded)
+                    && connection.details.server_code.as_deref() == Some("ResourceExhausted")
+        ));
+        assert!(matches!(
+            google_status_error(&tonic::Status::unavailable("maintenance")),
+            SttError::Connection(connection)
+                if connection.details.category == Some(SttConnectionCategory::ServerUnavailable)
+        ));
+    }
+
+    #[test]
+    fn transport_error_keeps_io_error_kind() {
+        let error = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+
+        let SttError::Connection(connection) = google_transport_error(&error, "connect") else {
+            panic!("expected connection error");
+        };
+        assert_eq!(
+            connection.details.category,
+            Some(SttConnectionCategory::Refused)
+        );
+        assert_eq!(
+            connection.details.io_error_kind.as_deref(),
+            Some("ConnectionRefused")
+        );
+    }
+
+    #[test]
+    fn response_update_joins_interim_results_and_times_finals() {
+        let mut committed_end = 0.0;
+        let interim = StreamingRecognizeResponse {
+            results: vec![result("привет", false, 0.8), result("мир", false, 1.2)],
+            speech_event_type: 0,
+        };
+
+        let update = google_response_update(&interim, &mut committed_end, "ru-RU");
+        assert!(update.finals.is_empty());
+        let partial = update.partial.expect("partial");
+        assert_eq!(partial.text, "привет мир");
+        assert!(!partial.is_final);
+        assert_eq!(committed_end, 0.0);
+
+        let final_response = StreamingRecognizeResponse {
+            results: vec![result("привет мир", true, 1.5)],
+            speech_event_type: 0,
+        };
+        let update = google_response_update(&final_response, &mut committed_end, "ru-RU");
+        assert!(update.partial.is_none());
+        assert_eq!(update.finals.len(), 1);
+        assert_eq!(update.finals[0].text, "привет мир");
+        assert_eq!(update.finals[0].confidence, Some(0.9));
+        assert_eq!(update.finals[0].language.as_deref(), Some("ru-RU"));
+        assert!((update.finals[0].duration - 1.5).abs() < 1e-6);
+
+        let next_final = StreamingRecognizeResponse {
+            results: vec![result("дальше", true, 2.5)],
+            speech_event_type: 0,
+        };
+        let update = google_response_update(&next_final, &mut committed_end, "ru-RU");
+        assert!((update.finals[0].start - 1.5).abs() < 1e-6);
+        assert!((update.finals[0].duration - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn final_carries_word_offsets() {
+        let offset = |secs: i64, nanos: i32| {
+            Some(ProtoDuration {
+                seconds: secs,
+                nanos,
+            })
+        };
+        let mut final_result = result("Привет, мир.", true, 1.5);
+        final_result.alternatives[0].words = vec![
+            WordInfo {
+                start_offset: offset(0, 200_000_000),
+                end_offset: offset(0, 700_000_000),
+                word: "Привет,".to_string(),
+                confidence: 0.8,
+            },
+            WordInfo {
+                start_offset: offset(0, 800_000_000),
+                end_offset: offset(1, 400_000_000),
+                word: "мир.".to_string(),
+                confidence: 0.0,
+            },
+        ];
+        let mut committed_end = 0.0;
+
+        let update = google_response_update(
+            &StreamingRecognizeResponse {
+                results: vec![final_result],
+                speech_event_type: 0,
+            },
+            &mut committed_end,
+            "ru-RU",
+        );
+
+        let words = &update.finals[0].words;
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Привет,");
+        assert_eq!(words[0].confidence, Some(0.8));
+        assert!((words[1].start - 0.8).abs() < 1e-6);
+        assert!((words[1].end - 1.4).abs() < 1e-6);
+        assert_eq!(words[1].confidence, None);
+    }
+
+    #[tokio::test]
+    async fn initialize_requires_key_and_project() {
+        let mut provider = GoogleCloudProvider::new();
+        let mut config = google_test_config();
+        config.google_cloud_project_id = None;
+        assert!(matches!(
+            provider.initialize(&config).await,
+            Err(SttError::Configuration(_))
+        ));
+
+        let mut config = google_test_config();
+        config.google_cloud_api_key = Some("   ".to_string());
+        assert!(matches!(
+            provider.initialize(&config).await,
+            Err(SttError::Configuration(_))
+        ));
+
+        provider
+            .initialize(&google_test_config())
+            .await
+            .expect("initialize provider");
+        assert_eq!(provider.api_key.as_deref(), Some("test-key"));
+        assert_eq!(
+            provider.recognizer.as_deref(),
+            Some("projects/demo-project/locations/global/recognizers/_")
+        );
+    }
+
+    #[tokio::test]
+    async fn stand_in_stream_delivers_partials_and_finals_after_half_close() {
+        let (endpoint, server) = spawn_stand_in_speech(
+            vec![
+                StreamingRecognizeResponse {
+                    results: vec![result("привет", false, 0.4)],
+                    speech_event_type: 0,
+                },
+                StreamingRecognizeResponse {
+                    results: vec![result("привет мир", true, 0.9)],
+                    speech_event_type: 0,
+                },
+            ],
+            None,
+        )
+        .await;
+        let mut provider = GoogleCloudProvider::with_endpoint_url(endpoint);
+        provider
+            .initialize(&google_test_config())
+            .await
+            .expect("initialize provider");
+
+        let partials = Arc::new(StdMutex::new(Vec::<Transcription>::new()));
+        let finals = Arc::new(StdMutex::new(Vec::<Transcription>::new()));
+        let partials_for_callback = partials.clone();
+        let finals_for_callback = finals.clone();
+        provider
+            .start_stream(
+                Arc::new(move |t| partials_for_callback.lock().unwrap().push(t)),
+                Arc::new(move |t| finals_for_callback.lock().unwrap().push(t)),
+                Arc::new(|error| panic!("unexpected Google error: {error}")),
+                Arc::new(|_, _| {}),
+            )
+            .await
+            .expect("start stand-in stream");
+
+        provider
+            .send_audio(&AudioChunk::new(vec![7i16; 1600], 16_000, 1))
+            .await
+            .expect("send audio");
+        provider.stop_stream().await.expect("graceful stop");
+
+        let captured = server.captured.lock().unwrap().clone();
+        assert_eq!(captured.len(), 2);
+        assert_eq!(
+            captured[0].recognizer,
+            "projects/demo-project/locations/global/recognizers/_"
+        );
+        let Some(StreamingRequest::StreamingConfig(streaming_config)) =
+            &captured[0].streaming_request
+        else {
+            panic!("first request must carry streaming config");
+        };
+        let recognition = streaming_config
+            .config
+            .as_ref()
+            .expect("recognition config");
+        assert_eq!(recognition.language_codes, vec!["ru-RU".to_string()]);
+        assert!(matches!(
+            captured[1].streaming_request,
+            Some(StreamingRequest::Audio(ref audio)) if audio.len() == 3200
+        ));
+        assert_eq!(
+            server.api_keys.lock().unwrap().as_slice(),
+            &["test-key".to_string()]
+        );
+
+        assert_eq!(partials.lock().unwrap()[0].text, "привет");
+        let finals = finals.lock().unwrap();
+        assert_eq!(finals.len(), 1);
+        assert_eq!(finals[0].text, "привет мир");
+    }
+
+    #[tokio::test]
+    async fn stand_in_rejection_fails_startup_with_typed_error() {
+        let (endpoint, _server) = spawn_stand_in_speech(
+            Vec::new(),
+            Some((tonic::Code::Unauthenticated, "API key not valid")),
+        )
+        .await;
+        let mut provider = GoogleCloudProvider::with_endpoint_url(endpoint);
+        provider
+            .initialize(&google_test_config())
+            .await
+            .expect("initialize provider");
+
+        let result = provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_, _| {}),
+            )
+            .await;
+
+        assert!(matches!(result, Err(SttError::Authentication(_))));
+        assert!(!provider.is_streaming);
+    }
+}
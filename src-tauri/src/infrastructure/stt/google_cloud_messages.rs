@@ -0,0 +1,208 @@
+//! Подмножество protobuf-сообщений Google Cloud Speech-to-Text v2
+//!
+//! Формат совпадает с google/cloud/speech/v2/cloud_speech.proto. Описываем вручную
+//! только поля, которые реально использует стриминговый клиент, чтобы не тянуть
+//! protoc и весь googleapis в сборку. Номера тегов менять нельзя.
+
+/// Сообщение клиентского стрима `StreamingRecognize`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StreamingRecognizeRequest {
+    /// `projects/{project}/locations/{location}/recognizers/{recognizer}`
+    #[prost(string, tag = "3")]
+    pub recognizer: String,
+    #[prost(oneof = "StreamingRequest", tags = "6, 5")]
+    pub streaming_request: Option<StreamingRequest>,
+}
+
+/// Первое сообщение несёт конфиг, все последующие — аудио
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum StreamingRequest {
+    #[prost(message, tag = "6")]
+    StreamingConfig(StreamingRecognitionConfig),
+    #[prost(bytes, tag = "5")]
+    Audio(Vec<u8>),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StreamingRecognitionConfig {
+    #[prost(message, optional, tag = "1")]
+    pub config: Option<RecognitionConfig>,
+    #[prost(message, optional, tag = "4")]
+    pub streaming_features: Option<StreamingRecognitionFeatures>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RecognitionConfig {
+    #[prost(oneof = "DecodingConfig", tags = "8")]
+    pub decoding_config: Option<DecodingConfig>,
+    #[prost(string, tag = "9")]
+    pub model: String,
+    #[prost(string, repeated, tag = "10")]
+    pub language_codes: Vec<String>,
+    #[prost(message, optional, tag = "2")]
+    pub features: Option<RecognitionFeatures>,
+    #[prost(message, optional, tag = "6")]
+    pub adaptation: Option<SpeechAdaptation>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum DecodingConfig {
+    #[prost(message, tag = "8")]
+    ExplicitDecodingConfig(ExplicitDecodingConfig),
+}
+
+/// `ExplicitDecodingConfig.AudioEncoding.LINEAR16`
+pub const AUDIO_ENCODING_LINEAR16: i32 = 1;
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExplicitDecodingConfig {
+    #[prost(int32, tag = "1")]
+    pub encoding: i32,
+    #[prost(int32, tag = "2")]
+    pub sample_rate_hertz: i32,
+    #[prost(int32, tag = "3")]
+    pub audio_channel_count: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RecognitionFeatures {
+    #[prost(bool, tag = "1")]
+    pub profanity_filter: bool,
+    #[prost(bool, tag = "2")]
+    pub enable_word_time_offsets: bool,
+    #[prost(bool, tag = "3")]
+    pub enable_word_confidence: bool,
+    #[prost(bool, tag = "4")]
+    pub enable_automatic_punctuation: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StreamingRecognitionFeatures {
+    #[prost(bool, tag = "1")]
+    pub enable_voice_activity_events: bool,
+    #[prost(bool, tag = "2")]
+    pub interim_results: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SpeechAdaptation {
+    #[prost(message, repeated, tag = "1")]
+    pub phrase_sets: Vec<AdaptationPhraseSet>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AdaptationPhraseSet {
+    #[prost(oneof = "AdaptationPhraseSetValue", tags = "2")]
+    pub value: Option<AdaptationPhraseSetValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum AdaptationPhraseSetValue {
+    #[prost(message, tag = "2")]
+    InlinePhraseSet(PhraseSet),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PhraseSet {
+    #[prost(message, repeated, tag = "3")]
+    pub phrases: Vec<Phrase>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Phrase {
+    #[prost(string, tag = "1")]
+    pub value: String,
+    #[prost(float, tag = "2")]
+    pub boost: f32,
+}
+
+/// Сообщение серверного стрима `StreamingRecognize`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StreamingRecognizeResponse {
+    #[prost(message, repeated, tag = "6")]
+    pub results: Vec<StreamingRecognitionResult>,
+    #[prost(int32, tag = "3")]
+    pub speech_event_type: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StreamingRecognitionResult {
+    #[prost(message, repeated, tag = "1")]
+    pub alternatives: Vec<SpeechRecognitionAlternative>,
+    #[prost(bool, tag = "2")]
+    pub is_final: bool,
+    #[prost(float, tag = "3")]
+    pub stability: f32,
+    /// Смещение конца результата относительно начала стрима
+    #[prost(message, optional, tag = "4")]
+    pub result_end_offset: Option<ProtoDuration>,
+    #[prost(string, tag = "6")]
+    pub language_code: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SpeechRecognitionAlternative {
+    #[prost(string, tag = "1")]
+    pub transcript: String,
+    #[prost(float, tag = "2")]
+    pub confidence: f32,
+    /// Только в final и только при `enable_word_time_offsets`
+    #[prost(message, repeated, tag = "3")]
+    pub words: Vec<WordInfo>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WordInfo {
+    #[prost(message, optional, tag = "1")]
+    pub start_offset: Option<ProtoDuration>,
+    #[prost(message, optional, tag = "2")]
+    pub end_offset: Option<ProtoDuration>,
+    #[prost(string, tag = "3")]
+    pub word: String,
+    #[prost(float, tag = "4")]
+    pub confidence: f32,
+}
+
+/// `google.protobuf.Duration`
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct ProtoDuration {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+impl ProtoDuration {
+    pub fn as_secs_f64(&self) -> f64 {
+        self.seconds as f64 + self.nanos as f64 / 1_000_000_000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn audio_request_roundtrips_through_oneof() {
+        let request = StreamingRecognizeRequest {
+            recognizer: "projects/p/locations/global/recognizers/_".to_string(),
+            streaming_request: Some(StreamingRequest::Audio(vec![1, 2, 3, 4])),
+        };
+
+        let decoded = StreamingRecognizeRequest::decode(request.encode_to_vec().as_slice())
+            .expect("decode request");
+
+        assert_eq!(decoded, request);
+    }
+
+    #[test]
+    fn proto_duration_converts_nanos_to_seconds() {
+        let duration = ProtoDuration {
+            seconds: 2,
+            nanos: 500_000_000,
+        };
+
+        assert!((duration.as_secs_f64() - 2.5).abs() < f64::EPSILON);
+    }
+}
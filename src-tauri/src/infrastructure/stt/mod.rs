@@ -1,8 +1,18 @@
 mod assemblyai;
+mod azure;
 mod backend;
 mod backend_messages;
+mod backend_resume;
 /// STT provider implementations
 mod deepgram;
+mod elevenlabs;
+mod failover;
+mod google_cloud;
+mod google_cloud_messages;
+mod openai_compatible;
+mod openai_realtime;
+mod text_filter;
+mod utterance_segmenter;
 mod whisper_local;
 
 use std::future::Future;
@@ -10,7 +20,9 @@ use std::time::Duration;
 
 use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
 
-use crate::domain::{SttConnectionCategory, SttConnectionError, SttError, SttResult};
+use crate::domain::{
+    SttConnectionCategory, SttConnectionDetails, SttConnectionError, SttError, SttResult,
+};
 
 const STREAMING_WS_MAX_MESSAGE_BYTES: usize = 4 * 1024 * 1024;
 const STREAMING_WS_MAX_WRITE_BUFFER_BYTES: usize = 4 * 1024 * 1024;
@@ -21,6 +33,78 @@ pub(super) fn abort_background_task(task: &mut Option<tokio::task::JoinHandle<()
     }
 }
 
+/// Облачные API (Google, Azure) ожидают BCP-47 локаль, а в конфиге чаще лежит
+/// короткий код языка. Подставляем основной регион для распространённых языков.
+pub(super) fn regional_language_tag(language: &str) -> String {
+    let language = language.trim();
+    if language.contains('-') {
+        return language.to_string();
+    }
+
+    match language.to_ascii_lowercase().as_str() {
+        "en" => "en-US",
+        "ru" => "ru-RU",
+        "uk" => "uk-UA",
+        "de" => "de-DE",
+        "fr" => "fr-FR",
+        "es" => "es-ES",
+        "it" => "it-IT",
+        "pt" => "pt-BR",
+        "pl" => "pl-PL",
+        "tr" => "tr-TR",
+        "ja" => "ja-JP",
+        "ko" => "ko-KR",
+        "zh" => "zh-CN",
+        _ => return language.to_string(),
+    }
+    .to_string()
+}
+
+/// Whisper-подобные API (OpenAI, ElevenLabs) принимают ISO-639-1 код без региона.
+/// `auto`/`multi` означают автоопределение — язык не передаём.
+pub(super) fn short_language_code(language: &str) -> Option<String> {
+    let base = language
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    (!base.is_empty() && base != "auto" && base != "multi").then_some(base)
+}
+
+/// RIFF/WAVE заголовок для PCM16. `data_len = 0` подходит для потоковой передачи,
+/// когда итоговый размер заранее неизвестен.
+pub(super) fn pcm16_wav_header(sample_rate: u32, channels: u16, data_len: u32) -> Vec<u8> {
+    let bits_per_sample: u16 = 16;
+    let block_align = channels * bits_per_sample / 8;
+    let byte_rate = sample_rate * block_align as u32;
+    let riff_len = if data_len == 0 { 0 } else { 36 + data_len };
+
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&riff_len.to_le_bytes());
+    header.extend_from_slice(b"WAVEfmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&byte_rate.to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&bits_per_sample.to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&data_len.to_le_bytes());
+    header
+}
+
+/// Полноценный WAV файл (PCM16) для загрузки в batch API.
+pub(super) fn pcm16_wav_file(samples: &[i16], sample_rate: u32, channels: u16) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = pcm16_wav_header(sample_rate, channels, data_len);
+    wav.reserve(samples.len() * 2);
+    wav.extend(samples.iter().flat_map(|sample| sample.to_le_bytes()));
+    wav
+}
+
 pub(super) fn streaming_websocket_config() -> WebSocketConfig {
     WebSocketConfig {
         max_write_buffer_size: STREAMING_WS_MAX_WRITE_BUFFER_BYTES,
@@ -56,9 +140,73 @@ where
     }
 }
 
+/// Ошибка WebSocket handshake с сохранением HTTP статуса: `await_streaming_websocket_connect`
+/// его теряет, а для ключевых провайдеров 401/403 должны стать ошибкой авторизации.
+pub(super) fn websocket_handshake_error(
+    provider: &str,
+    error: tokio_tungstenite::tungstenite::Error,
+) -> SttError {
+    match error {
+        tokio_tungstenite::tungstenite::Error::Http(response) => {
+            let status = response.status();
+            let message = format!("{} WebSocket handshake failed: HTTP {}", provider, status);
+            if matches!(status.as_u16(), 401 | 403) {
+                return SttError::Authentication(message);
+            }
+            let category = match status.as_u16() {
+                429 => SttConnectionCategory::RateLimited,
+                500..=599 => SttConnectionCategory::ServerUnavailable,
+                _ => SttConnectionCategory::Http,
+            };
+            SttError::Connection(SttConnectionError {
+                message,
+                details: SttConnectionDetails {
+                    category: Some(category),
+                    http_status: Some(status.as_u16()),
+                    ..Default::default()
+                },
+            })
+        }
+        tokio_tungstenite::tungstenite::Error::Tls(error) => {
+            SttError::Connection(SttConnectionError::with_category(
+                format!("{} WebSocket connection failed: {}", provider, error),
+                SttConnectionCategory::Tls,
+            ))
+        }
+        tokio_tungstenite::tungstenite::Error::Io(io_error) => {
+            let category = match io_error.kind() {
+                std::io::ErrorKind::ConnectionRefused => SttConnectionCategory::Refused,
+                std::io::ErrorKind::ConnectionReset => SttConnectionCategory::Reset,
+                std::io::ErrorKind::TimedOut => SttConnectionCategory::Timeout,
+                _ => SttConnectionCategory::Unknown,
+            };
+            SttError::Connection(SttConnectionError {
+                message: format!("{} WebSocket connection failed: {}", provider, io_error),
+                details: SttConnectionDetails {
+                    category: Some(category),
+                    io_error_kind: Some(format!("{:?}", io_error.kind())),
+                    os_error: io_error.raw_os_error(),
+                    ..Default::default()
+                },
+            })
+        }
+        other => SttError::Connection(SttConnectionError::simple(format!(
+            "{} WebSocket connection failed: {}",
+            provider, other
+        ))),
+    }
+}
+
 pub use assemblyai::AssemblyAIProvider;
+pub use azure::AzureProvider;
 pub use backend::BackendProvider;
 pub use deepgram::DeepgramProvider;
+pub use elevenlabs::ElevenLabsProvider;
+pub use failover::FailoverProvider;
+pub use google_cloud::GoogleCloudProvider;
+pub use openai_compatible::OpenAICompatibleProvider;
+pub use openai_realtime::OpenAIRealtimeProvider;
+pub use text_filter::{strip_punctuation, ProfanityFilter, TextFilterProvider};
 pub use whisper_local::WhisperLocalProvider;
 
 #[cfg(test)]
@@ -78,6 +226,33 @@ mod websocket_tests {
         }
     }
 
+    #[test]
+    fn regional_language_tag_expands_short_codes() {
+        assert_eq!(regional_language_tag("ru"), "ru-RU");
+        assert_eq!(regional_language_tag(" EN "), "en-US");
+        assert_eq!(regional_language_tag("en-GB"), "en-GB");
+        assert_eq!(regional_language_tag("xx"), "xx");
+    }
+
+    #[test]
+    fn short_language_code_drops_region_and_auto_modes() {
+        assert_eq!(short_language_code("ru-RU").as_deref(), Some("ru"));
+        assert_eq!(short_language_code(" EN_us ").as_deref(), Some("en"));
+        assert_eq!(short_language_code("auto"), None);
+        assert_eq!(short_language_code("multi"), None);
+    }
+
+    #[test]
+    fn pcm16_wav_file_has_consistent_sizes() {
+        let wav = pcm16_wav_file(&[1, -1, 2], 16_000, 1);
+
+        assert_eq!(wav.len(), 44 + 6);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes([wav[4], wav[5], wav[6], wav[7]]), 36 + 6);
+        assert_eq!(u32::from_le_bytes([wav[40], wav[41], wav[42], wav[43]]), 6);
+        assert_eq!(i16::from_le_bytes([wav[46], wav[47]]), -1);
+    }
+
     #[test]
     fn streaming_websocket_config_bounds_reads_and_failed_writes() {
         let config = streaming_websocket_config();
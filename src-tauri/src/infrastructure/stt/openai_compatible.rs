@@ -0,0 +1,822 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::error::Error as StdError;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use super::utterance_segmenter::{SegmentEvent, UtteranceAudio, UtteranceSegmenter, SAMPLE_RATE};
+use crate::domain::{
+    AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttConnectionCategory,
+    SttConnectionDetails, SttConnectionError, SttError, SttProvider, SttResult, Transcription,
+    TranscriptionCallback, Word,
+};
+use crate::infrastructure::audio::{VadProcessor, VadResult};
+
+/// OpenAI-compatible batch transcription provider
+///
+/// Endpoint: `{base_url}/audio/transcriptions` (OpenAI, faster-whisper-server, whisper.cpp server, vLLM)
+/// Transport: HTTP multipart upload, one WAV file per utterance
+///
+/// Flow:
+/// 1. VAD режет поток на фразы (см. `UtteranceSegmenter`)
+/// 2. Каждая законченная фраза кодируется в WAV и загружается отдельным запросом
+/// 3. Ответ (`verbose_json` или `json`) превращается в final с таймингом фразы
+///
+/// Partials нет: batch API возвращает текст только для целого файла.
+const OPENAI_COMPATIBLE_DEFAULT_MODEL: &str = "whisper-1";
+const OPENAI_COMPATIBLE_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const OPENAI_COMPATIBLE_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+// Самые короткие фразы (щелчок, вдох) не стоит отправлять на сервер.
+const OPENAI_COMPATIBLE_MIN_UTTERANCE_SAMPLES: usize = SAMPLE_RATE / 4;
+
+fn openai_compatible_transcriptions_url(base_url: &str) -> String {
+    format!(
+        "{}/audio/transcriptions",
+        base_url.trim().trim_end_matches('/')
+    )
+}
+
+/// Ключевые термины уходят в `prompt`: Whisper использует его как подсказку словаря.
+pub(super) fn openai_compatible_prompt(keyterms: Option<&str>) -> Option<String> {
+    let terms: Vec<&str> = keyterms?
+        .split(',')
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(", "))
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    segments: Vec<TranscriptionSegment>,
+    /// Есть только при `timestamp_granularities[]=word`
+    #[serde(default)]
+    words: Vec<TranscriptionWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionWord {
+    word: String,
+    #[serde(default)]
+    start: f64,
+    #[serde(default)]
+    end: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionSegment {
+    #[serde(default)]
+    start: f64,
+    #[serde(default)]
+    end: f64,
+    #[serde(default)]
+    avg_logprob: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: Option<ErrorBody>,
+    detail: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    message: String,
+}
+
+/// Переводим ответ сервера в final. Тайминг берём из сегментов (если есть), со сдвигом
+/// на начало фразы в потоке.
+fn transcription_from_response(
+    response: TranscriptionResponse,
+    utterance: &UtteranceAudio,
+    language: &str,
+) -> Option<Transcription> {
+    let text = response.text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let (offset, duration) = match (response.segments.first(), response.segments.last()) {
+        (Some(first), Some(last)) if last.end > first.start => {
+            (first.start, last.end - first.start)
+        }
+        _ => (
+            0.0,
+            response
+                .duration
+                .filter(|duration| *duration > 0.0)
+                .unwrap_or_else(|| utterance.duration()),
+        ),
+    };
+
+    let mut transcription = Transcription::final_result(text.to_string())
+        .with_language(language.to_string())
+        .with_timing(utterance.start + offset, duration);
+
+    // verbose_json отдаёт средний log-prob по токенам — переводим в вероятность.
+    let logprobs: Vec<f64> = response
+        .segments
+        .iter()
+        .filter_map(|segment| segment.avg_logprob)
+        .collect();
+    if !logprobs.is_empty() {
+        let mean = logprobs.iter().sum::<f64>() / logprobs.len() as f64;
+        transcription.confidence = Some(mean.exp().clamp(0.0, 1.0) as f32);
+    }
+
+    transcription.words = response
+        .words
+        .iter()
+        .filter(|word| !word.word.trim().is_empty())
+        .map(|word| {
+            Word::new(
+                word.word.trim(),
+                utterance.start + word.start,
+                utterance.start + word.end,
+            )
+        })
+        .collect();
+
+    Some(transcription)
+}
+
+fn extract_error_message(body: &str) -> Option<String> {
+    let parsed = serde_json::from_str::<ErrorResponse>(body).ok()?;
+    parsed
+        .error
+        .map(|error| error.message)
+        .or_else(|| {
+            parsed.detail.map(|detail| match detail {
+                serde_json::Value::String(message) => message,
+                other => other.to_string(),
+            })
+        })
+        .map(|message| message.trim().to_string())
+        .filter(|message| !message.is_empty())
+}
+
+fn openai_compatible_http_error(status: reqwest::StatusCode, body: &str) -> SttError {
+    let message = format!(
+        "OpenAI-compatible transcription failed (HTTP {}): {}",
+        status.as_u16(),
+        extract_error_message(body).unwrap_or_else(|| status.to_string())
+    );
+    match status.as_u16() {
+        401 | 403 => SttError::Authentication(message),
+        // Неверная модель/формат — ошибка конфигурации, ретраи не помогут.
+        400 | 404 | 415 | 422 => SttError::Configuration(message),
+        status_code => {
+            let category = match status_code {
+                429 if message.to_lowercase().contains("quota") => {
+                    SttConnectionCategory::ProviderQuotaExceeded
+                }
+                429 => SttConnectionCategory::RateLimited,
+                500..=599 => SttConnectionCategory::ServerUnavailable,
+                _ => SttConnectionCategory::Http,
+            };
+            SttError::Connection(SttConnectionError {
+                message,
+                details: SttConnectionDetails {
+                    category: Some(category),
+                    http_status: Some(status_code),
+                    ..Default::default()
+                },
+            })
+        }
+    }
+}
+
+fn openai_compatible_request_error(error: &reqwest::Error) -> SttError {
+    let mut details = SttConnectionDetails::default();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        if let Some(io_error) = cause.downcast_ref::<std::io::Error>() {
+            details.io_error_kind = Some(format!("{:?}", io_error.kind()));
+            details.os_error = io_error.raw_os_error();
+            break;
+        }
+        source = cause.source();
+    }
+
+    details.category = Some(if error.is_timeout() {
+        SttConnectionCategory::Timeout
+    } else if error.is_connect() {
+        match details.io_error_kind.as_deref() {
+            Some("ConnectionRefused") => SttConnectionCategory::Refused,
+            _ => SttConnectionCategory::Unknown,
+        }
+    } else {
+        SttConnectionCategory::Unknown
+    });
+
+    SttError::Connection(SttConnectionError {
+        message: format!("OpenAI-compatible transcription request failed: {}", error),
+        details,
+    })
+}
+
+fn call_openai_compatible_callback(label: &str, callback: impl FnOnce()) {
+    if catch_unwind(AssertUnwindSafe(callback)).is_err() {
+        log::error!("OpenAI-compatible {} callback panicked", label);
+    }
+}
+
+/// Параметры загрузки, общие для всех фраз сессии
+#[derive(Clone)]
+struct UploadSettings {
+    url: String,
+    api_key: Option<String>,
+    model: String,
+    language: Option<String>,
+    prompt: Option<String>,
+    result_language: String,
+}
+
+async fn transcribe_utterance(
+    client: &reqwest::Client,
+    settings: &UploadSettings,
+    utterance: &UtteranceAudio,
+) -> SttResult<Option<Transcription>> {
+    let wav = super::pcm16_wav_file(&utterance.samples, SAMPLE_RATE as u32, 1);
+    let file = reqwest::multipart::Part::bytes(wav)
+        .file_name("utterance.wav")
+        .mime_str("audio/wav")
+        .map_err(|e| SttError::Internal(format!("Failed to build multipart body: {}", e)))?;
+
+    let mut form = reqwest::multipart::Form::new()
+        .part("file", file)
+        .text("model", settings.model.clone())
+        .text("response_format", "verbose_json")
+        // Обе гранулярности: сегменты нужны для confidence, слова — для пословных таймингов
+        .text("timestamp_granularities[]", "segment")
+        .text("timestamp_granularities[]", "word")
+        .text("temperature", "0");
+    if let Some(language) = &settings.language {
+        form = form.text("language", language.clone());
+    }
+    if let Some(prompt) = &settings.prompt {
+        form = form.text("prompt", prompt.clone());
+    }
+
+    let mut request = client.post(&settings.url).multipart(form);
+    if let Some(api_key) = &settings.api_key {
+        request = request.bearer_auth(api_key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| openai_compatible_request_error(&e))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| openai_compatible_request_error(&e))?;
+
+    if !status.is_success() {
+        return Err(openai_compatible_http_error(status, &body));
+    }
+
+    let parsed: TranscriptionResponse = serde_json::from_str(&body).map_err(|e| {
+        SttError::Processing(format!(
+            "Invalid OpenAI-compatible transcription response: {}",
+            e
+        ))
+    })?;
+    Ok(transcription_from_response(
+        parsed,
+        utterance,
+        &settings.result_language,
+    ))
+}
+
+/// Фоновая загрузка фраз по очереди: порядок finals совпадает с порядком речи.
+async fn run_upload_worker(
+    client: reqwest::Client,
+    settings: UploadSettings,
+    utterances: async_channel::Receiver<UtteranceAudio>,
+    on_final: TranscriptionCallback,
+    on_error: ErrorCallback,
+) {
+    while let Ok(utterance) = utterances.recv().await {
+        let started_at = std::time::Instant::now();
+        match transcribe_utterance(&client, &settings, &utterance).await {
+            Ok(Some(transcription)) => {
+                log::info!(
+                    "OpenAI-compatible final transcript in {} ms: {}",
+                    started_at.elapsed().as_millis(),
+                    transcription.text
+                );
+                call_openai_compatible_callback("final transcription", || on_final(transcription));
+            }
+            Ok(None) => {
+                log::debug!(
+                    "OpenAI-compatible server returned empty text for {:.2}s utterance",
+                    utterance.duration()
+                );
+            }
+            Err(error) => {
+                log::error!("OpenAI-compatible transcription failed: {}", error);
+                call_openai_compatible_callback("error", || on_error(error));
+            }
+        }
+    }
+
+    log::debug!("OpenAI-compatible upload worker finished");
+}
+
+pub struct OpenAICompatibleProvider {
+    config: Option<SttConfig>,
+    is_streaming: bool,
+    settings: Option<UploadSettings>,
+    client: Option<reqwest::Client>,
+    segmenter: UtteranceSegmenter,
+    vad: Option<VadProcessor>,
+    utterances_tx: Option<async_channel::Sender<UtteranceAudio>>,
+    upload_task: Option<JoinHandle<()>>,
+}
+
+impl OpenAICompatibleProvider {
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            is_streaming: false,
+            settings: None,
+            client: None,
+            segmenter: UtteranceSegmenter::new(),
+            vad: None,
+            utterances_tx: None,
+            upload_task: None,
+        }
+    }
+
+    fn queue_segment_event(&self, event: SegmentEvent) -> SttResult<()> {
+        let SegmentEvent::Final(utterance) = event else {
+            return Ok(());
+        };
+        if utterance.samples.len() < OPENAI_COMPATIBLE_MIN_UTTERANCE_SAMPLES {
+            log::debug!(
+                "OpenAI-compatible: skipping {:.2}s utterance",
+                utterance.duration()
+            );
+            return Ok(());
+        }
+
+        let utterances_tx = self.utterances_tx.as_ref().ok_or_else(|| {
+            SttError::Internal("OpenAI-compatible upload queue is not available".to_string())
+        })?;
+        utterances_tx.try_send(utterance).map_err(|_| {
+            SttError::Internal("OpenAI-compatible upload worker has stopped".to_string())
+        })
+    }
+
+    fn reset_session_state(&mut self) {
+        self.is_streaming = false;
+        self.utterances_tx = None;
+        self.vad = None;
+        self.segmenter.reset();
+    }
+}
+
+impl Default for OpenAICompatibleProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OpenAICompatibleProvider {
+    fn drop(&mut self) {
+        if let Some(utterances_tx) = self.utterances_tx.take() {
+            utterances_tx.close();
+        }
+        super::abort_background_task(&mut self.upload_task);
+    }
+}
+
+#[async_trait]
+impl SttProvider for OpenAICompatibleProvider {
+    async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
+        log::info!("OpenAICompatibleProvider: Initializing");
+
+        let base_url = config
+            .openai_compatible_base_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| {
+                SttError::Configuration("OpenAI-compatible server base URL is required".to_string())
+            })?;
+        let parsed_url = reqwest::Url::parse(base_url).map_err(|e| {
+            SttError::Configuration(format!("Invalid OpenAI-compatible base URL: {}", e))
+        })?;
+        if !matches!(parsed_url.scheme(), "http" | "https") {
+            return Err(SttError::Configuration(format!(
+                "OpenAI-compatible base URL must use http or https: {}",
+                base_url
+            )));
+        }
+
+        let api_key = config
+            .openai_compatible_api_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(str::to_string);
+        let model = config
+            .model
+            .as_deref()
+            .map(str::trim)
+            .filter(|model| !model.is_empty())
+            .unwrap_or(OPENAI_COMPATIBLE_DEFAULT_MODEL)
+            .to_string();
+
+        let client = reqwest::Client::builder()
+            .connect_timeout(OPENAI_COMPATIBLE_CONNECT_TIMEOUT)
+            .timeout(OPENAI_COMPATIBLE_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| SttError::Internal(format!("Failed to build HTTP client: {}", e)))?;
+
+        log::info!(
+            "OpenAICompatibleProvider: Using {} (model={}, auth={})",
+            base_url,
+            model,
+            if api_key.is_some() { "bearer" } else { "none" }
+        );
+
+        self.settings = Some(UploadSettings {
+            url: openai_compatible_transcriptions_url(base_url),
+            api_key,
+            model,
+            language: if config.auto_detect_language {
+                None
+            } else {
+                super::short_language_code(&config.language)
+            },
+            prompt: openai_compatible_prompt(config.streaming_keyterms.as_deref()),
+            result_language: config.language.clone(),
+        });
+        self.client = Some(client);
+        self.config = Some(config.clone());
+        Ok(())
+    }
+
+    async fn start_stream(
+        &mut self,
+        _on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        _on_connection_quality: ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        log::info!("OpenAICompatibleProvider: Starting stream (VAD-segmented uploads)");
+
+        if self.is_streaming {
+            return Err(SttError::Processing("Stream already active".to_string()));
+        }
+
+        let (Some(settings), Some(client)) = (self.settings.clone(), self.client.clone()) else {
+            return Err(SttError::Configuration(
+                "OpenAI-compatible provider is not initialized".to_string(),
+            ));
+        };
+
+        // Таймаут тишины здесь не нужен: границы фраз определяет сегментер.
+        let vad = VadProcessor::new(Some(u64::MAX), None)?;
+
+        let (utterances_tx, utterances_rx) = async_channel::unbounded();
+        self.upload_task = Some(tokio::spawn(run_upload_worker(
+            client,
+            settings,
+            utterances_rx,
+            on_final,
+            on_error,
+        )));
+        self.utterances_tx = Some(utterances_tx);
+        self.vad = Some(vad);
+        self.segmenter.reset();
+        self.is_streaming = true;
+
+        Ok(())
+    }
+
+    async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
+        if !self.is_streaming {
+            return Err(SttError::Processing("Not streaming".to_string()));
+        }
+
+        let vad = self
+            .vad
+            .as_mut()
+            .ok_or_else(|| SttError::Internal("VAD is not initialized".to_string()))?;
+        let events = self.segmenter.push_samples(&chunk.data, |frame| {
+            Ok(matches!(vad.process_samples(frame)?, VadResult::Speech))
+        })?;
+
+        for event in events {
+            self.queue_segment_event(event)?;
+        }
+        Ok(())
+    }
+
+    async fn stop_stream(&mut self) -> SttResult<()> {
+        log::info!("OpenAICompatibleProvider: Stopping stream");
+
+        if !self.is_streaming {
+            log::warn!("Stream not active");
+            return Ok(());
+        }
+
+        let mut stop_result = match self.segmenter.finish() {
+            Some(event) => self.queue_segment_event(event),
+            None => Ok(()),
+        };
+
+        // Закрываем очередь и ждём загрузки оставшихся фраз (каждая ограничена таймаутом клиента).
+        if let Some(utterances_tx) = self.utterances_tx.take() {
+            utterances_tx.close();
+        }
+        if let Some(task) = self.upload_task.take() {
+            if let Err(join_error) = task.await {
+                stop_result = Err(SttError::Internal(format!(
+                    "OpenAI-compatible upload worker failed: {}",
+                    join_error
+                )));
+            }
+        }
+
+        self.reset_session_state();
+        stop_result
+    }
+
+    async fn abort(&mut self) -> SttResult<()> {
+        log::info!("OpenAICompatibleProvider: Aborting stream");
+
+        if let Some(utterances_tx) = self.utterances_tx.take() {
+            utterances_tx.close();
+        }
+        if let Some(task) = self.upload_task.take() {
+            task.abort();
+            let _ = task.await;
+        }
+        self.reset_session_state();
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "OpenAI-compatible Transcription"
+    }
+
+    fn is_online(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SttProviderType;
+    use std::sync::{Arc, Mutex as StdMutex};
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, Request as MockRequest, ResponseTemplate};
+
+    fn provider_config(base_url: &str) -> SttConfig {
+        let mut config = SttConfig::new(SttProviderType::OpenAICompatible).with_language("ru-RU");
+        config.openai_compatible_base_url = Some(base_url.to_string());
+        config.openai_compatible_api_key = Some(" local-key ".to_string());
+        config.model = Some("Systran/faster-whisper-small".to_string());
+        config.streaming_keyterms = Some("Kubernetes, VoicetextAI".to_string());
+        config
+    }
+
+    fn utterance(seconds: f64, start: f64) -> UtteranceAudio {
+        UtteranceAudio {
+            samples: vec![1000; (seconds * SAMPLE_RATE as f64) as usize],
+            start,
+        }
+    }
+
+    fn multipart_text_field(request: &MockRequest, name: &str) -> Option<String> {
+        let body = String::from_utf8_lossy(&request.body);
+        let marker = format!("name=\"{}\"\r\n\r\n", name);
+        let start = body.find(&marker)? + marker.len();
+        let end = body[start..].find("\r\n")?;
+        Some(body[start..start + end].to_string())
+    }
+
+    #[test]
+    fn url_and_prompt_are_normalized() {
+        assert_eq!(
+            openai_compatible_transcriptions_url("http://localhost:8000/v1/ "),
+            "http://localhost:8000/v1/audio/transcriptions"
+        );
+        assert_eq!(
+            openai_compatible_prompt(Some(" Kubernetes , ,VoicetextAI")).as_deref(),
+            Some("Kubernetes, VoicetextAI")
+        );
+        assert_eq!(openai_compatible_prompt(Some(" , ")), None);
+    }
+
+    #[test]
+    fn verbose_response_timing_is_offset_by_utterance_start() {
+        let response: TranscriptionResponse = serde_json::from_str(
+            r#"{
+                "text": " Привет, мир. ",
+                "duration": 2.0,
+                "segments": [
+                    {"start": 0.2, "end": 0.9, "avg_logprob": -0.1},
+                    {"start": 0.9, "end": 1.7, "avg_logprob": -0.3}
+                ],
+                "words": [
+                    {"word": "Привет,", "start": 0.2, "end": 0.8},
+                    {"word": " мир.", "start": 0.9, "end": 1.7}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let transcription =
+            transcription_from_response(response, &utterance(2.0, 10.0), "ru").expect("final");
+
+        assert!(transcription.is_final);
+        assert_eq!(transcription.text, "Привет, мир.");
+        assert!((transcription.start - 10.2).abs() < 1e-9);
+        assert!((transcription.duration - 1.5).abs() < 1e-9);
+        let confidence = transcription.confidence.expect("confidence");
+        assert!((confidence - (-0.2f64).exp() as f32).abs() < 1e-6);
+        assert_eq!(transcription.words.len(), 2);
+        assert_eq!(transcription.words[1].text, "мир.");
+        assert!((transcription.words[1].start - 10.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn plain_json_response_uses_utterance_duration() {
+        let response: TranscriptionResponse = serde_json::from_str(r#"{"text":"hello"}"#).unwrap();
+
+        let transcription =
+            transcription_from_response(response, &utterance(1.5, 3.0), "en").expect("final");
+
+        assert!((transcription.start - 3.0).abs() < 1e-9);
+        assert!((transcription.duration - 1.5).abs() < 1e-9);
+        assert_eq!(transcription.confidence, None);
+
+        let empty: TranscriptionResponse = serde_json::from_str(r#"{"text":"  "}"#).unwrap();
+        assert!(transcription_from_response(empty, &utterance(1.0, 0.0), "en").is_none());
+    }
+
+    #[test]
+    fn http_errors_are_classified() {
+        assert!(matches!(
+            openai_compatible_http_error(
+                reqwest::StatusCode::UNAUTHORIZED,
+                r#"{"error":{"message":"bad key"}}"#
+            ),
+            SttError::Authentication(message) if message.contains("bad key")
+        ));
+        assert!(matches!(
+            openai_compatible_http_error(
+                reqwest::StatusCode::UNPROCESSABLE_ENTITY,
+                r#"{"detail":"model not found"}"#
+            ),
+            SttError::Configuration(message) if message.contains("model not found")
+        ));
+        assert!(matches!(
+            openai_compatible_http_error(reqwest::StatusCode::TOO_MANY_REQUESTS, ""),
+            SttError::Connection(connection)
+                if connection.details.category == Some(SttConnectionCategory::RateLimited)
+                    && connection.details.http_status == Some(429)
+        ));
+        assert!(matches!(
+            openai_compatible_http_error(reqwest::StatusCode::BAD_GATEWAY, "upstream"),
+            SttError::Connection(connection)
+                if connection.details.category == Some(SttConnectionCategory::ServerUnavailable)
+        ));
+    }
+
+    #[tokio::test]
+    async fn initialize_requires_valid_base_url() {
+        let mut provider = OpenAICompatibleProvider::new();
+        let mut config = provider_config("http://localhost:8000/v1");
+
+        config.openai_compatible_base_url = None;
+        assert!(matches!(
+            provider.initialize(&config).await,
+            Err(SttError::Configuration(_))
+        ));
+
+        config.openai_compatible_base_url = Some("ftp://example.com/v1".to_string());
+        assert!(matches!(
+            provider.initialize(&config).await,
+            Err(SttError::Configuration(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn uploads_wav_with_model_language_prompt_and_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .and(header("authorization", "Bearer local-key"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"text":"Привет","segments":[{"start":0.0,"end":0.8}]}"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = OpenAICompatibleProvider::new();
+        provider
+            .initialize(&provider_config(&format!("{}/v1", server.uri())))
+            .await
+            .expect("initialize");
+        let settings = provider.settings.clone().expect("settings");
+        let client = provider.client.clone().expect("client");
+
+        let transcription = transcribe_utterance(&client, &settings, &utterance(1.0, 4.0))
+            .await
+            .expect("transcribe")
+            .expect("final");
+        assert_eq!(transcription.text, "Привет");
+        assert!((transcription.start - 4.0).abs() < 1e-9);
+        assert_eq!(transcription.language.as_deref(), Some("ru-RU"));
+
+        let requests = server.received_requests().await.expect("requests");
+        let request = &requests[0];
+        assert_eq!(
+            multipart_text_field(request, "model").as_deref(),
+            Some("Systran/faster-whisper-small")
+        );
+        assert_eq!(
+            multipart_text_field(request, "language").as_deref(),
+            Some("ru")
+        );
+        assert_eq!(
+            multipart_text_field(request, "response_format").as_deref(),
+            Some("verbose_json")
+        );
+        assert_eq!(
+            multipart_text_field(request, "prompt").as_deref(),
+            Some("Kubernetes, VoicetextAI")
+        );
+        let body = &request.body;
+        assert!(body.windows(4).any(|window| window == b"RIFF"));
+        assert!(String::from_utf8_lossy(body).contains("filename=\"utterance.wav\""));
+    }
+
+    #[tokio::test]
+    async fn stop_stream_uploads_trailing_utterance_and_reports_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .set_body_string(r#"{"error":{"message":"model is loading"}}"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = OpenAICompatibleProvider::new();
+        provider
+            .initialize(&provider_config(&format!("{}/v1", server.uri())))
+            .await
+            .expect("initialize");
+
+        let errors = Arc::new(StdMutex::new(Vec::<SttError>::new()));
+        let errors_for_callback = errors.clone();
+        provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(|t| panic!("unexpected final: {}", t.text)),
+                Arc::new(move |error| errors_for_callback.lock().unwrap().push(error)),
+                Arc::new(|_, _| {}),
+            )
+            .await
+            .expect("start stream");
+
+        // Громкий тон VAD считает речью (fallback по энергии), а без паузы фраза
+        // не заканчивается — значит, загрузка произойдёт только при stop.
+        let tone: Vec<i16> = (0..SAMPLE_RATE)
+            .map(|i| ((i as f32 * 0.05).sin() * 12_000.0) as i16)
+            .collect();
+        provider
+            .send_audio(&AudioChunk::new(tone, 16_000, 1))
+            .await
+            .expect("send audio");
+        provider.stop_stream().await.expect("stop stream");
+
+        let errors = errors.lock().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            SttError::Connection(connection)
+                if connection.details.http_status == Some(503)
+                    && connection.message.contains("model is loading")
+        ));
+    }
+}
@@ -0,0 +1,1186 @@
+use async_trait::async_trait;
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use http::header::AUTHORIZATION;
+use http::HeaderValue;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::{
+    connect_async_with_config, tungstenite::Message, MaybeTlsStream, WebSocketStream,
+};
+
+use super::openai_compatible::openai_compatible_prompt;
+use crate::domain::{
+    AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttConnectionCategory,
+    SttConnectionDetails, SttConnectionError, SttError, SttProvider, SttResult, Transcription,
+    TranscriptionCallback,
+};
+
+/// OpenAI Realtime transcription provider (gpt-4o-transcribe family)
+///
+/// Endpoint: wss://api.openai.com/v1/realtime?intent=transcription
+/// Auth: `Authorization: Bearer {openai_api_key}` — тот же ключ, что и для перевода
+///
+/// Protocol:
+/// 1. Send `session.update` with `type = "transcription"` and wait for `session.updated`
+/// 2. Stream `input_audio_buffer.append` (base64 PCM16 24 kHz mono); server VAD commits turns
+/// 3. Receive `conversation.item.input_audio_transcription.delta` (partial) and `.completed` (final)
+/// 4. On stop send `input_audio_buffer.commit` and drain the remaining items
+const OPENAI_REALTIME_TRANSCRIPTION_URL: &str =
+    "wss://api.openai.com/v1/realtime?intent=transcription";
+const OPENAI_REALTIME_DEFAULT_MODEL: &str = "gpt-4o-transcribe";
+const OPENAI_REALTIME_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const OPENAI_REALTIME_SESSION_READY_TIMEOUT: Duration = Duration::from_secs(10);
+const OPENAI_REALTIME_SEND_TIMEOUT: Duration = Duration::from_secs(5);
+const OPENAI_REALTIME_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+// Realtime API принимает PCM16 только в 24 kHz, а микрофон отдаёт 16 kHz.
+const OPENAI_REALTIME_SAMPLE_RATE: u32 = 24_000;
+// Сервер отвечает этим кодом на commit пустого буфера: весь хвост уже закоммитил VAD.
+const OPENAI_COMMIT_EMPTY_CODE: &str = "input_audio_buffer_commit_empty";
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// В `model` может остаться имя модели другого провайдера (например Whisper), поэтому
+/// берём его только если оно похоже на модель OpenAI транскрипции.
+fn openai_realtime_model(model: Option<&str>) -> String {
+    model
+        .map(str::trim)
+        .filter(|model| model.starts_with("gpt-") || *model == "whisper-1")
+        .unwrap_or(OPENAI_REALTIME_DEFAULT_MODEL)
+        .to_string()
+}
+
+fn openai_realtime_session_update(
+    model: &str,
+    language: Option<&str>,
+    prompt: Option<&str>,
+) -> Value {
+    let mut transcription = json!({ "model": model });
+    if let Some(language) = language {
+        transcription["language"] = json!(language);
+    }
+    if let Some(prompt) = prompt {
+        transcription["prompt"] = json!(prompt);
+    }
+
+    json!({
+        "type": "session.update",
+        "session": {
+            "type": "transcription",
+            "audio": {
+                "input": {
+                    "format": { "type": "audio/pcm", "rate": OPENAI_REALTIME_SAMPLE_RATE },
+                    "transcription": transcription,
+                    "turn_detection": {
+                        "type": "server_vad",
+                        "threshold": 0.5,
+                        "prefix_padding_ms": 300,
+                        "silence_duration_ms": 500
+                    },
+                    "noise_reduction": { "type": "near_field" }
+                }
+            }
+        }
+    })
+}
+
+fn openai_realtime_append_message(samples: &[i16]) -> String {
+    let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    json!({
+        "type": "input_audio_buffer.append",
+        "audio": base64::engine::general_purpose::STANDARD.encode(bytes),
+    })
+    .to_string()
+}
+
+/// Линейная передискретизация в 24 kHz. Последний сэмпл и дробная позиция переносятся
+/// между чанками, чтобы на стыках не было щелчков.
+#[derive(Debug, Default)]
+struct LinearResampler {
+    source_rate: u32,
+    position: f64,
+    last_sample: Option<i16>,
+}
+
+impl LinearResampler {
+    fn process(&mut self, samples: &[i16], source_rate: u32) -> Vec<i16> {
+        if source_rate == 0 || source_rate == OPENAI_REALTIME_SAMPLE_RATE {
+            return samples.to_vec();
+        }
+        if source_rate != self.source_rate {
+            *self = Self {
+                source_rate,
+                ..Self::default()
+            };
+        }
+
+        let mut input = Vec::with_capacity(samples.len() + 1);
+        input.extend(self.last_sample);
+        input.extend_from_slice(samples);
+        if input.len() < 2 {
+            self.last_sample = input.last().copied();
+            return Vec::new();
+        }
+
+        let step = source_rate as f64 / OPENAI_REALTIME_SAMPLE_RATE as f64;
+        let last_index = (input.len() - 1) as f64;
+        let mut output = Vec::with_capacity((samples.len() as f64 / step).ceil() as usize + 1);
+        while self.position < last_index {
+            let index = self.position.floor() as usize;
+            let fraction = self.position - index as f64;
+            let from = input[index] as f64;
+            let to = input[index + 1] as f64;
+            output.push((from + (to - from) * fraction).round() as i16);
+            self.position += step;
+        }
+
+        self.position -= last_index;
+        self.last_sample = input.last().copied();
+        output
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIApiError {
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum ServerEvent {
+    #[serde(rename = "session.updated", alias = "transcription_session.updated")]
+    SessionUpdated,
+    #[serde(rename = "input_audio_buffer.speech_started")]
+    SpeechStarted {
+        item_id: String,
+        #[serde(default)]
+        audio_start_ms: Option<u64>,
+    },
+    #[serde(rename = "input_audio_buffer.speech_stopped")]
+    SpeechStopped {
+        item_id: String,
+        #[serde(default)]
+        audio_end_ms: Option<u64>,
+    },
+    #[serde(rename = "input_audio_buffer.committed")]
+    Committed { item_id: String },
+    #[serde(rename = "conversation.item.input_audio_transcription.delta")]
+    TranscriptionDelta {
+        item_id: String,
+        #[serde(default)]
+        delta: String,
+    },
+    #[serde(rename = "conversation.item.input_audio_transcription.completed")]
+    TranscriptionCompleted {
+        item_id: String,
+        #[serde(default)]
+        transcript: String,
+    },
+    #[serde(rename = "conversation.item.input_audio_transcription.failed")]
+    TranscriptionFailed {
+        item_id: String,
+        error: OpenAIApiError,
+    },
+    #[serde(rename = "error")]
+    Error { error: OpenAIApiError },
+    #[serde(other)]
+    Unknown,
+}
+
+fn openai_api_error(error: &OpenAIApiError, context: &str) -> SttError {
+    let message = format!(
+        "{}: {}",
+        context,
+        error.message.as_deref().unwrap_or("unknown error")
+    );
+    let code = error.code.as_deref().unwrap_or_default();
+    let kind = error.kind.as_deref().unwrap_or_default();
+
+    if kind == "authentication_error" || code == "invalid_api_key" {
+        return SttError::Authentication(message);
+    }
+    let category = match code {
+        "insufficient_quota" => Some(SttConnectionCategory::ProviderQuotaExceeded),
+        "rate_limit_exceeded" => Some(SttConnectionCategory::RateLimited),
+        "session_expired" => Some(SttConnectionCategory::Closed),
+        _ if kind == "server_error" => Some(SttConnectionCategory::ServerUnavailable),
+        _ => None,
+    };
+    match category {
+        Some(category) => SttError::Connection(SttConnectionError {
+            message,
+            details: SttConnectionDetails {
+                category: Some(category),
+                server_code: (!code.is_empty()).then(|| code.to_string()),
+                ..Default::default()
+            },
+        }),
+        None if kind == "invalid_request_error" => SttError::Configuration(message),
+        None => SttError::Processing(message),
+    }
+}
+
+/// Что reader должен сделать с очередным событием сервера.
+#[derive(Debug)]
+enum RealtimeUpdate {
+    Ready,
+    Partial(Transcription),
+    Final(Transcription),
+    Error(SttError),
+    /// Ответ на наш `input_audio_buffer.commit` (или на авто-коммит VAD)
+    CommitAcknowledged,
+    Ignored,
+}
+
+#[derive(Debug, Default)]
+struct RealtimeItem {
+    text: String,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+}
+
+/// Собирает дельты по `item_id`: каждая реплика, закоммиченная VAD, транскрибируется отдельно.
+#[derive(Debug, Default)]
+struct RealtimeTranscriptState {
+    language: Option<String>,
+    items: HashMap<String, RealtimeItem>,
+}
+
+impl RealtimeTranscriptState {
+    fn new(language: Option<String>) -> Self {
+        Self {
+            language,
+            items: HashMap::new(),
+        }
+    }
+
+    fn has_pending_items(&self) -> bool {
+        !self.items.is_empty()
+    }
+
+    fn apply(&mut self, event: ServerEvent) -> RealtimeUpdate {
+        match event {
+            ServerEvent::SessionUpdated => RealtimeUpdate::Ready,
+            ServerEvent::SpeechStarted {
+                item_id,
+                audio_start_ms,
+            } => {
+                self.items.entry(item_id).or_default().start_ms = audio_start_ms;
+                RealtimeUpdate::Ignored
+            }
+            ServerEvent::SpeechStopped {
+                item_id,
+                audio_end_ms,
+            } => {
+                self.items.entry(item_id).or_default().end_ms = audio_end_ms;
+                RealtimeUpdate::Ignored
+            }
+            ServerEvent::Committed { item_id } => {
+                self.items.entry(item_id).or_default();
+                RealtimeUpdate::CommitAcknowledged
+            }
+            ServerEvent::TranscriptionDelta { item_id, delta } => {
+                let item = self.items.entry(item_id).or_default();
+                item.text.push_str(&delta);
+                let text = item.text.trim();
+                if text.is_empty() {
+                    return RealtimeUpdate::Ignored;
+                }
+                RealtimeUpdate::Partial(realtime_transcription(
+                    Transcription::partial(text.to_string()),
+                    &self.language,
+                    item,
+                ))
+            }
+            ServerEvent::TranscriptionCompleted {
+                item_id,
+                transcript,
+            } => {
+                let item = self.items.remove(&item_id).unwrap_or_default();
+                let text = transcript.trim();
+                if text.is_empty() {
+                    return RealtimeUpdate::Ignored;
+                }
+                RealtimeUpdate::Final(realtime_transcription(
+                    Transcription::final_result(text.to_string()),
+                    &self.language,
+                    &item,
+                ))
+            }
+            ServerEvent::TranscriptionFailed { item_id, error } => {
+                self.items.remove(&item_id);
+                RealtimeUpdate::Error(openai_api_error(
+                    &error,
+                    "OpenAI realtime transcription failed",
+                ))
+            }
+            ServerEvent::Error { error } => {
+                if error.code.as_deref() == Some(OPENAI_COMMIT_EMPTY_CODE) {
+                    return RealtimeUpdate::CommitAcknowledged;
+                }
+                RealtimeUpdate::Error(openai_api_error(&error, "OpenAI realtime error"))
+            }
+            ServerEvent::Unknown => RealtimeUpdate::Ignored,
+        }
+    }
+}
+
+/// `audio_start_ms`/`audio_end_ms` считаются от начала аудио сессии, как и тайминги других провайдеров.
+fn realtime_transcription(
+    mut transcription: Transcription,
+    language: &Option<String>,
+    item: &RealtimeItem,
+) -> Transcription {
+    if let Some(language) = language {
+        transcription = transcription.with_language(language.clone());
+    }
+    if let Some(start_ms) = item.start_ms {
+        let duration_ms = item
+            .end_ms
+            .map(|end_ms| end_ms.saturating_sub(start_ms))
+            .unwrap_or(0);
+        transcription =
+            transcription.with_timing(start_ms as f64 / 1000.0, duration_ms as f64 / 1000.0);
+    }
+    transcription
+}
+
+fn openai_realtime_close_error(frame: Option<&CloseFrame<'_>>) -> SttError {
+    let (message, code, reason) = match frame {
+        Some(frame) => {
+            let code = u16::from(frame.code);
+            (
+                format!(
+                    "OpenAI realtime WebSocket closed (close code {}): {}",
+                    code, frame.reason
+                ),
+                Some(code),
+                frame.reason.to_lowercase(),
+            )
+        }
+        None => (
+            "OpenAI realtime WebSocket closed".to_string(),
+            None,
+            String::new(),
+        ),
+    };
+    if reason.contains("api key") || reason.contains("auth") {
+        return SttError::Authentication(message);
+    }
+
+    let category = if matches!(code, Some(1011..=1014)) {
+        SttConnectionCategory::ServerUnavailable
+    } else {
+        SttConnectionCategory::Closed
+    };
+    SttError::Connection(SttConnectionError {
+        message,
+        details: SttConnectionDetails {
+            category: Some(category),
+            ws_close_code: code,
+            ..Default::default()
+        },
+    })
+}
+
+fn openai_realtime_closed_error(message: impl Into<String>) -> SttError {
+    SttError::Connection(SttConnectionError::with_category(
+        message,
+        SttConnectionCategory::Closed,
+    ))
+}
+
+fn openai_realtime_timeout_error(message: impl Into<String>) -> SttError {
+    SttError::Connection(SttConnectionError::with_category(
+        message,
+        SttConnectionCategory::Timeout,
+    ))
+}
+
+async fn await_openai_realtime_send<F>(future: F, operation: &str) -> SttResult<()>
+where
+    F: Future<Output = Result<(), tokio_tungstenite::tungstenite::Error>>,
+{
+    match tokio::time::timeout(OPENAI_REALTIME_SEND_TIMEOUT, future).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(error)) => Err(openai_realtime_closed_error(format!(
+            "OpenAI realtime {} failed: {}",
+            operation, error
+        ))),
+        Err(_) => Err(openai_realtime_timeout_error(format!(
+            "OpenAI realtime {} timed out after {} ms",
+            operation,
+            OPENAI_REALTIME_SEND_TIMEOUT.as_millis()
+        ))),
+    }
+}
+
+fn call_openai_realtime_callback(label: &str, callback: impl FnOnce()) {
+    if catch_unwind(AssertUnwindSafe(callback)).is_err() {
+        log::error!("OpenAI realtime {} callback panicked", label);
+    }
+}
+
+struct ReaderContext {
+    state: RealtimeTranscriptState,
+    ready_tx: Option<oneshot::Sender<SttResult<()>>>,
+    stop_requested: Arc<AtomicBool>,
+    final_commit_sent: Arc<AtomicBool>,
+    on_partial: TranscriptionCallback,
+    on_final: TranscriptionCallback,
+    on_error: ErrorCallback,
+}
+
+impl ReaderContext {
+    /// До `session.updated` ошибки уходят в `start_stream`, после — в `on_error`.
+    fn report_error(&mut self, error: SttError) {
+        if let Some(ready_tx) = self.ready_tx.take() {
+            let _ = ready_tx.send(Err(error));
+            return;
+        }
+        if !self.stop_requested.load(Ordering::SeqCst) {
+            let on_error = self.on_error.clone();
+            call_openai_realtime_callback("error", || on_error(error));
+        }
+    }
+}
+
+async fn run_openai_realtime_reader(
+    mut read: futures_util::stream::SplitStream<WsStream>,
+    mut context: ReaderContext,
+) {
+    log::debug!("OpenAI realtime receiver task started");
+    let mut final_commit_acknowledged = false;
+
+    while let Some(msg_result) = read.next().await {
+        match msg_result {
+            Ok(Message::Text(text)) => {
+                let event = match serde_json::from_str::<ServerEvent>(&text) {
+                    Ok(event) => event,
+                    Err(error) => {
+                        log::warn!("Failed to parse OpenAI realtime event: {}", error);
+                        continue;
+                    }
+                };
+
+                match context.state.apply(event) {
+                    RealtimeUpdate::Ready => {
+                        if let Some(ready_tx) = context.ready_tx.take() {
+                            let _ = ready_tx.send(Ok(()));
+                        }
+                    }
+                    RealtimeUpdate::Partial(transcription) => {
+                        log::debug!("Partial transcript: {}", transcription.text);
+                        let on_partial = context.on_partial.clone();
+                        call_openai_realtime_callback("partial transcription", || {
+                            on_partial(transcription)
+                        });
+                    }
+                    RealtimeUpdate::Final(transcription) => {
+                        log::info!("Final transcript: {}", transcription.text);
+                        let on_final = context.on_final.clone();
+                        call_openai_realtime_callback("final transcription", || {
+                            on_final(transcription)
+                        });
+                    }
+                    RealtimeUpdate::Error(error) => {
+                        log::error!("OpenAI realtime error: {}", error);
+                        context.report_error(error);
+                    }
+                    RealtimeUpdate::CommitAcknowledged => {
+                        if context.final_commit_sent.load(Ordering::SeqCst) {
+                            final_commit_acknowledged = true;
+                        }
+                    }
+                    RealtimeUpdate::Ignored => {}
+                }
+
+                // Финальный commit подтверждён и все реплики дотранскрибированы — drain закончен.
+                if final_commit_acknowledged && !context.state.has_pending_items() {
+                    break;
+                }
+            }
+            Ok(Message::Close(frame)) => {
+                log::info!("OpenAI realtime WebSocket closed: {:?}", frame);
+                context.report_error(openai_realtime_close_error(frame.as_ref()));
+                break;
+            }
+            Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {
+                log::trace!("OpenAI realtime received Ping/Pong");
+            }
+            Err(e) => {
+                log::error!("OpenAI realtime WebSocket error: {}", e);
+                context.report_error(openai_realtime_closed_error(format!(
+                    "OpenAI realtime WebSocket error: {}",
+                    e
+                )));
+                break;
+            }
+            Ok(msg) => {
+                log::debug!("OpenAI realtime received unexpected message: {:?}", msg);
+            }
+        }
+    }
+
+    if let Some(ready_tx) = context.ready_tx.take() {
+        let _ = ready_tx.send(Err(openai_realtime_closed_error(
+            "OpenAI realtime WebSocket ended before session.updated",
+        )));
+    }
+    log::debug!("OpenAI realtime receiver task ended");
+}
+
+pub struct OpenAIRealtimeProvider {
+    config: Option<SttConfig>,
+    is_streaming: bool,
+    api_key: Option<String>,
+    ws_write: Option<futures_util::stream::SplitSink<WsStream, Message>>,
+    receiver_task: Option<JoinHandle<()>>,
+    stop_requested: Arc<AtomicBool>,
+    final_commit_sent: Arc<AtomicBool>,
+    resampler: LinearResampler,
+    audio_sent: bool,
+    ws_url: String,
+}
+
+impl OpenAIRealtimeProvider {
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            is_streaming: false,
+            api_key: None,
+            ws_write: None,
+            receiver_task: None,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+            final_commit_sent: Arc::new(AtomicBool::new(false)),
+            resampler: LinearResampler::default(),
+            audio_sent: false,
+            ws_url: OPENAI_REALTIME_TRANSCRIPTION_URL.to_string(),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_ws_url(ws_url: String) -> Self {
+        let mut provider = Self::new();
+        provider.ws_url = ws_url;
+        provider
+    }
+
+    async fn send_text(&mut self, message: String, operation: &str) -> SttResult<()> {
+        let write = self.ws_write.as_mut().ok_or_else(|| {
+            SttError::Processing("WebSocket write handle not available".to_string())
+        })?;
+        await_openai_realtime_send(write.send(Message::Text(message)), operation).await
+    }
+
+    async fn abort_receiver(&mut self) {
+        if let Some(task) = self.receiver_task.take() {
+            task.abort();
+            let _ = task.await;
+        }
+    }
+
+    fn reset_session_state(&mut self) {
+        self.ws_write = None;
+        self.is_streaming = false;
+        self.audio_sent = false;
+        self.resampler = LinearResampler::default();
+    }
+}
+
+impl Default for OpenAIRealtimeProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OpenAIRealtimeProvider {
+    fn drop(&mut self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        super::abort_background_task(&mut self.receiver_task);
+    }
+}
+
+#[async_trait]
+impl SttProvider for OpenAIRealtimeProvider {
+    async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
+        log::info!("OpenAIRealtimeProvider: Initializing");
+
+        let api_key = config
+            .openai_api_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| SttError::Configuration("OpenAI API key is required".to_string()))?;
+
+        self.api_key = Some(api_key);
+        self.config = Some(config.clone());
+        Ok(())
+    }
+
+    async fn start_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        _on_connection_quality: ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        log::info!("OpenAIRealtimeProvider: Starting stream");
+
+        if self.is_streaming {
+            return Err(SttError::Processing("Stream already active".to_string()));
+        }
+
+        let (Some(api_key), Some(config)) = (self.api_key.clone(), self.config.clone()) else {
+            return Err(SttError::Configuration(
+                "OpenAI realtime provider is not initialized".to_string(),
+            ));
+        };
+
+        let mut request =
+            self.ws_url.as_str().into_client_request().map_err(|e| {
+                SttError::Configuration(format!("Invalid OpenAI realtime URL: {}", e))
+            })?;
+        let authorization = HeaderValue::from_str(&format!("Bearer {}", api_key))
+            .map_err(|_| SttError::Configuration("Invalid OpenAI API key".to_string()))?;
+        request.headers_mut().insert(AUTHORIZATION, authorization);
+
+        log::debug!("Connecting to {}", self.ws_url);
+
+        let (ws_stream, _response) = tokio::time::timeout(
+            OPENAI_REALTIME_CONNECT_TIMEOUT,
+            connect_async_with_config(request, Some(super::streaming_websocket_config()), false),
+        )
+        .await
+        .map_err(|_| {
+            openai_realtime_timeout_error(format!(
+                "OpenAI realtime WebSocket connection timed out after {} ms",
+                OPENAI_REALTIME_CONNECT_TIMEOUT.as_millis()
+            ))
+        })?
+        .map_err(|error| super::websocket_handshake_error("OpenAI realtime", error))?;
+
+        log::info!("OpenAI realtime WebSocket connected");
+
+        let (write, read) = ws_stream.split();
+        self.ws_write = Some(write);
+        self.stop_requested.store(false, Ordering::SeqCst);
+        self.final_commit_sent.store(false, Ordering::SeqCst);
+
+        let language = if config.auto_detect_language {
+            None
+        } else {
+            super::short_language_code(&config.language)
+        };
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let context = ReaderContext {
+            state: RealtimeTranscriptState::new(language.clone()),
+            ready_tx: Some(ready_tx),
+            stop_requested: self.stop_requested.clone(),
+            final_commit_sent: self.final_commit_sent.clone(),
+            on_partial,
+            on_final,
+            on_error,
+        };
+        self.receiver_task = Some(tokio::spawn(run_openai_realtime_reader(read, context)));
+
+        let session_update = openai_realtime_session_update(
+            &openai_realtime_model(config.model.as_deref()),
+            language.as_deref(),
+            openai_compatible_prompt(config.streaming_keyterms.as_deref()).as_deref(),
+        );
+        let mut setup_result = self
+            .send_text(session_update.to_string(), "session.update send")
+            .await;
+        if setup_result.is_ok() {
+            setup_result =
+                match tokio::time::timeout(OPENAI_REALTIME_SESSION_READY_TIMEOUT, ready_rx).await {
+                    Ok(Ok(result)) => result,
+                    Ok(Err(_)) => Err(openai_realtime_closed_error(
+                        "OpenAI realtime reader stopped before session.updated",
+                    )),
+                    Err(_) => Err(openai_realtime_timeout_error(format!(
+                        "OpenAI realtime session.updated was not received within {} ms",
+                        OPENAI_REALTIME_SESSION_READY_TIMEOUT.as_millis()
+                    ))),
+                };
+        }
+        if let Err(error) = setup_result {
+            self.stop_requested.store(true, Ordering::SeqCst);
+            self.abort_receiver().await;
+            self.reset_session_state();
+            return Err(error);
+        }
+
+        self.is_streaming = true;
+
+        log::info!("OpenAI realtime stream started successfully");
+        Ok(())
+    }
+
+    async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
+        if !self.is_streaming {
+            return Err(SttError::Processing("Not streaming".to_string()));
+        }
+
+        let samples = self.resampler.process(&chunk.data, chunk.sample_rate);
+        if samples.is_empty() {
+            return Ok(());
+        }
+        self.send_text(openai_realtime_append_message(&samples), "audio send")
+            .await?;
+        self.audio_sent = true;
+        Ok(())
+    }
+
+    async fn stop_stream(&mut self) -> SttResult<()> {
+        log::info!("OpenAIRealtimeProvider: Stopping stream");
+        self.stop_requested.store(true, Ordering::SeqCst);
+
+        if !self.is_streaming {
+            log::warn!("Stream not active");
+            return Ok(());
+        }
+
+        let mut stop_result = Ok(());
+
+        // Коммитим хвост буфера, который server VAD ещё не закрыл, и ждём его транскрипцию.
+        if self.audio_sent {
+            self.final_commit_sent.store(true, Ordering::SeqCst);
+            stop_result = self
+                .send_text(
+                    json!({ "type": "input_audio_buffer.commit" }).to_string(),
+                    "input_audio_buffer.commit send",
+                )
+                .await;
+
+            if stop_result.is_ok() {
+                if let Some(mut task) = self.receiver_task.take() {
+                    match tokio::time::timeout(OPENAI_REALTIME_DRAIN_TIMEOUT, &mut task).await {
+                        Ok(Ok(())) => {}
+                        Ok(Err(join_error)) => {
+                            stop_result = Err(SttError::Internal(format!(
+                                "OpenAI realtime receiver task failed during drain: {}",
+                                join_error
+                            )));
+                        }
+                        Err(_) => {
+                            task.abort();
+                            let _ = task.await;
+                            stop_result = Err(openai_realtime_timeout_error(format!(
+                                "OpenAI realtime transcription drain timed out after {} ms",
+                                OPENAI_REALTIME_DRAIN_TIMEOUT.as_millis()
+                            )));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(write) = self.ws_write.as_mut() {
+            let _ = tokio::time::timeout(OPENAI_REALTIME_SEND_TIMEOUT, write.close()).await;
+        }
+        self.abort_receiver().await;
+        self.reset_session_state();
+
+        match &stop_result {
+            Ok(()) => log::info!("OpenAI realtime stream stopped after drain"),
+            Err(error) => log::warn!("OpenAI realtime stream stop failed: {}", error),
+        }
+        stop_result
+    }
+
+    async fn abort(&mut self) -> SttResult<()> {
+        log::info!("OpenAIRealtimeProvider: Aborting stream");
+        self.stop_requested.store(true, Ordering::SeqCst);
+
+        self.abort_receiver().await;
+        self.reset_session_state();
+
+        log::info!("OpenAI realtime stream aborted");
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "OpenAI Realtime Transcription"
+    }
+
+    fn is_online(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SttProviderType;
+    use std::sync::Mutex as StdMutex;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_hdr_async;
+    use tokio_tungstenite::tungstenite::handshake::server::{
+        Callback, ErrorResponse, Request as HandshakeRequest, Response as HandshakeResponse,
+    };
+
+    #[derive(Debug, Default)]
+    struct OpenAIServerCapture {
+        authorization: Option<String>,
+        session_update: Option<Value>,
+        appended_samples: usize,
+        client_events: Vec<String>,
+    }
+
+    fn server_event(event: Value) -> Message {
+        Message::Text(event.to_string())
+    }
+
+    /// Запоминает Authorization из handshake
+    struct CaptureAuthorization(Arc<StdMutex<OpenAIServerCapture>>);
+
+    impl Callback for CaptureAuthorization {
+        fn on_request(
+            self,
+            request: &HandshakeRequest,
+            response: HandshakeResponse,
+        ) -> Result<HandshakeResponse, ErrorResponse> {
+            self.0.lock().unwrap().authorization = request
+                .headers()
+                .get("Authorization")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            Ok(response)
+        }
+    }
+
+    async fn spawn_openai_server() -> (String, JoinHandle<OpenAIServerCapture>) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock OpenAI server");
+        let address = listener.local_addr().expect("mock server address");
+        let task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("accept websocket client");
+            let capture = Arc::new(StdMutex::new(OpenAIServerCapture::default()));
+            let mut websocket = accept_hdr_async(stream, CaptureAuthorization(capture.clone()))
+                .await
+                .expect("accept websocket");
+
+            loop {
+                let Some(Ok(Message::Text(text))) = websocket.next().await else {
+                    panic!("client closed before commit");
+                };
+                let event: Value = serde_json::from_str(&text).expect("client event json");
+                let event_type = event["type"].as_str().unwrap_or_default().to_string();
+                match event_type.as_str() {
+                    "session.update" => {
+                        capture.lock().unwrap().session_update = Some(event);
+                        websocket
+                            .send(server_event(json!({ "type": "session.updated" })))
+                            .await
+                            .expect("send session.updated");
+                    }
+                    "input_audio_buffer.append" => {
+                        let bytes = base64::engine::general_purpose::STANDARD
+                            .decode(event["audio"].as_str().unwrap_or_default())
+                            .expect("base64 audio");
+                        capture.lock().unwrap().appended_samples += bytes.len() / 2;
+                    }
+                    _ => {}
+                }
+                capture
+                    .lock()
+                    .unwrap()
+                    .client_events
+                    .push(event_type.clone());
+                if event_type == "input_audio_buffer.commit" {
+                    break;
+                }
+            }
+
+            for event in [
+                json!({ "type": "input_audio_buffer.speech_started", "item_id": "item_1", "audio_start_ms": 500 }),
+                json!({ "type": "input_audio_buffer.committed", "item_id": "item_1" }),
+                json!({ "type": "conversation.item.input_audio_transcription.delta", "item_id": "item_1", "delta": "При" }),
+                json!({ "type": "conversation.item.input_audio_transcription.delta", "item_id": "item_1", "delta": "вет" }),
+                json!({ "type": "conversation.item.input_audio_transcription.completed", "item_id": "item_1", "transcript": "Привет, мир." }),
+            ] {
+                websocket
+                    .send(server_event(event))
+                    .await
+                    .expect("send event");
+            }
+            let _ = websocket.next().await;
+
+            Arc::try_unwrap(capture)
+                .expect("capture still shared")
+                .into_inner()
+                .unwrap()
+        });
+
+        (
+            format!("ws://{address}/v1/realtime?intent=transcription"),
+            task,
+        )
+    }
+
+    fn openai_test_config() -> SttConfig {
+        let mut config = SttConfig::new(SttProviderType::OpenAIRealtime).with_language("ru");
+        config.openai_api_key = Some(" sk-test ".to_string());
+        config.streaming_keyterms = Some("Kubernetes, VoicetextAI".to_string());
+        config
+    }
+
+    #[test]
+    fn model_falls_back_for_non_openai_names() {
+        assert_eq!(openai_realtime_model(None), "gpt-4o-transcribe");
+        assert_eq!(
+            openai_realtime_model(Some("ggml-base")),
+            "gpt-4o-transcribe"
+        );
+        assert_eq!(
+            openai_realtime_model(Some(" gpt-4o-mini-transcribe ")),
+            "gpt-4o-mini-transcribe"
+        );
+        assert_eq!(openai_realtime_model(Some("whisper-1")), "whisper-1");
+    }
+
+    #[test]
+    fn session_update_omits_language_when_auto_detecting() {
+        let update = openai_realtime_session_update("gpt-4o-transcribe", None, None);
+        let transcription = &update["session"]["audio"]["input"]["transcription"];
+
+        assert_eq!(update["session"]["type"], "transcription");
+        assert_eq!(
+            update["session"]["audio"]["input"]["format"]["rate"],
+            OPENAI_REALTIME_SAMPLE_RATE
+        );
+        assert_eq!(transcription["model"], "gpt-4o-transcribe");
+        assert!(transcription.get("language").is_none());
+        assert!(transcription.get("prompt").is_none());
+    }
+
+    #[test]
+    fn resampler_upsamples_16k_to_24k_across_chunks() {
+        let mut resampler = LinearResampler::default();
+
+        let first = resampler.process(&[0, 300, 600, 900], 16_000);
+        let second = resampler.process(&[1200, 1500, 1800, 2100], 16_000);
+
+        // 8 входных сэмплов → ~12 выходных, линейная рампа без разрыва на стыке.
+        let all: Vec<i16> = first.iter().chain(second.iter()).copied().collect();
+        assert!((11..=12).contains(&all.len()));
+        assert!(all
+            .windows(2)
+            .all(|pair| (pair[1] - pair[0] - 200).abs() <= 1));
+        assert_eq!(resampler.process(&[7, 8], 24_000), vec![7, 8]);
+    }
+
+    #[test]
+    fn transcript_state_accumulates_deltas_per_item() {
+        let mut state = RealtimeTranscriptState::new(Some("en".to_string()));
+        let parse = |value: Value| serde_json::from_value::<ServerEvent>(value).unwrap();
+
+        state.apply(parse(json!({
+            "type": "input_audio_buffer.speech_started", "item_id": "a", "audio_start_ms": 1000
+        })));
+        state.apply(parse(json!({
+            "type": "input_audio_buffer.speech_stopped", "item_id": "a", "audio_end_ms": 2500
+        })));
+        state.apply(parse(json!({
+            "type": "conversation.item.input_audio_transcription.delta", "item_id": "a", "delta": "Hello"
+        })));
+        let RealtimeUpdate::Partial(partial) = state.apply(parse(json!({
+            "type": "conversation.item.input_audio_transcription.delta", "item_id": "a", "delta": " world"
+        }))) else {
+            panic!("expected partial");
+        };
+        assert_eq!(partial.text, "Hello world");
+        assert!(!partial.is_final);
+        assert!(state.has_pending_items());
+
+        let RealtimeUpdate::Final(final_result) = state.apply(parse(json!({
+            "type": "conversation.item.input_audio_transcription.completed",
+            "item_id": "a",
+            "transcript": "Hello world."
+        }))) else {
+            panic!("expected final");
+        };
+        assert_eq!(final_result.text, "Hello world.");
+        assert_eq!(final_result.language.as_deref(), Some("en"));
+        assert!((final_result.start - 1.0).abs() < 1e-9);
+        assert!((final_result.duration - 1.5).abs() < 1e-9);
+        assert!(!state.has_pending_items());
+
+        assert!(matches!(
+            state.apply(parse(
+                json!({ "type": "rate_limits.updated", "rate_limits": [] })
+            )),
+            RealtimeUpdate::Ignored
+        ));
+        assert!(matches!(
+            state.apply(parse(json!({ "type": "transcription_session.updated" }))),
+            RealtimeUpdate::Ready
+        ));
+    }
+
+    #[test]
+    fn api_errors_are_classified() {
+        let error = |kind: &str, code: &str| OpenAIApiError {
+            kind: Some(kind.to_string()),
+            code: Some(code.to_string()),
+            message: Some("boom".to_string()),
+        };
+
+        assert!(matches!(
+            openai_api_error(&error("invalid_request_error", "invalid_api_key"), "ctx"),
+            SttError::Authentication(_)
+        ));
+        assert!(matches!(
+            openai_api_error(&error("insufficient_quota", "insufficient_quota"), "ctx"),
+            SttError::Connection(connection)
+                if connection.details.category
+                    == Some(SttConnectionCategory::ProviderQuotaExceeded)
+        ));
+        assert!(matches!(
+            openai_api_error(&error("invalid_request_error", "invalid_value"), "ctx"),
+            SttError::Configuration(_)
+        ));
+        assert!(matches!(
+            openai_api_error(&error("server_error", ""), "ctx"),
+            SttError::Connection(connection)
+                if connection.details.category == Some(SttConnectionCategory::ServerUnavailable)
+        ));
+
+        let mut state = RealtimeTranscriptState::default();
+        assert!(matches!(
+            state.apply(ServerEvent::Error {
+                error: error("invalid_request_error", OPENAI_COMMIT_EMPTY_CODE)
+            }),
+            RealtimeUpdate::CommitAcknowledged
+        ));
+    }
+
+    #[tokio::test]
+    async fn initialize_requires_api_key() {
+        let mut provider = OpenAIRealtimeProvider::new();
+        let mut config = openai_test_config();
+        config.openai_api_key = Some("  ".to_string());
+
+        assert!(matches!(
+            provider.initialize(&config).await,
+            Err(SttError::Configuration(_))
+        ));
+        provider
+            .initialize(&openai_test_config())
+            .await
+            .expect("initialize provider");
+        assert_eq!(provider.api_key.as_deref(), Some("sk-test"));
+    }
+
+    #[tokio::test]
+    async fn handshake_unauthorized_is_authentication_error() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock OpenAI server");
+        let address = listener.local_addr().expect("mock server address");
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept client");
+            let mut buffer = [0u8; 2048];
+            let _ = tokio::io::AsyncReadExt::read(&mut stream, &mut buffer).await;
+            let _ = stream
+                .write_all(b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\n\r\n")
+                .await;
+        });
+
+        let mut provider = OpenAIRealtimeProvider::with_ws_url(format!("ws://{address}/v1"));
+        provider
+            .initialize(&openai_test_config())
+            .await
+            .expect("initialize provider");
+        let result = provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_| {}),
+                Arc::new(|_, _| {}),
+            )
+            .await;
+
+        assert!(matches!(result, Err(SttError::Authentication(_))));
+        assert!(!provider.is_streaming);
+    }
+
+    #[tokio::test]
+    async fn graceful_stop_commits_buffer_and_drains_final_transcript() {
+        let (ws_url, server) = spawn_openai_server().await;
+        let mut provider = OpenAIRealtimeProvider::with_ws_url(ws_url);
+        provider
+            .initialize(&openai_test_config())
+            .await
+            .expect("initialize provider");
+
+        let partials = Arc::new(StdMutex::new(Vec::<Transcription>::new()));
+        let finals = Arc::new(StdMutex::new(Vec::<Transcription>::new()));
+        let partials_for_callback = partials.clone();
+        let finals_for_callback = finals.clone();
+        provider
+            .start_stream(
+                Arc::new(move |t| partials_for_callback.lock().unwrap().push(t)),
+                Arc::new(move |t| finals_for_callback.lock().unwrap().push(t)),
+                Arc::new(|error| panic!("unexpected OpenAI error: {error}")),
+                Arc::new(|_, _| {}),
+            )
+            .await
+            .expect("start mock stream");
+
+        provider
+            .send_audio(&AudioChunk::new(vec![100i16; 1_600], 16_000, 1))
+            .await
+            .expect("send audio");
+        provider.stop_stream().await.expect("graceful stop");
+
+        let capture = server.await.expect("mock server task");
+        assert_eq!(capture.authorization.as_deref(), Some("Bearer sk-test"));
+        let session = capture.session_update.expect("session.update");
+        let transcription = &session["session"]["audio"]["input"]["transcription"];
+        assert_eq!(transcription["model"], "gpt-4o-transcribe");
+        assert_eq!(transcription["language"], "ru");
+        assert_eq!(transcription["prompt"], "Kubernetes, VoicetextAI");
+        assert!((2_399..=2_400).contains(&capture.appended_samples));
+        assert_eq!(
+            capture.client_events,
+            vec![
+                "session.update",
+                "input_audio_buffer.append",
+                "input_audio_buffer.commit"
+            ]
+        );
+
+        let partials = partials.lock().unwrap();
+        assert_eq!(
+            partials.iter().map(|t| t.text.as_str()).collect::<Vec<_>>(),
+            vec!["При", "Привет"]
+        );
+        let finals = finals.lock().unwrap();
+        assert_eq!(finals.len(), 1);
+        assert_eq!(finals[0].text, "Привет, мир.");
+        assert_eq!(finals[0].language.as_deref(), Some("ru"));
+        assert!((finals[0].start - 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,455 @@
+//! Клиентская фильтрация текста транскрипции.
+//!
+//! `enable_punctuation` и `filter_profanity` должны работать одинаково при любом
+//! провайдере. Deepgram/Google/Backend применяют их на сервере, остальным
+//! (Whisper, AssemblyAI streaming, OpenAI, ElevenLabs) нечего передать — для них
+//! `TextFilterProvider` правит текст в callbacks до того, как он уйдёт дальше.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::domain::{
+    AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttProvider, SttResult,
+    Transcription, TranscriptionCallback,
+};
+
+/// Встроенный список. `*` в конце — совпадение по префиксу.
+const BUILTIN_PROFANITY: &[&str] = &[
+    // en
+    "fuck*",
+    "motherfuck*",
+    "shit*",
+    "bullshit",
+    "bitch*",
+    "asshole*",
+    "cunt*",
+    "dick",
+    "dickhead*",
+    "bastard*",
+    // ru
+    "бля*",
+    "хуй*",
+    "хуе*",
+    "хуё*",
+    "хуя*",
+    "пизд*",
+    "еба*",
+    "ебу*",
+    "ебл*",
+    "ёб*",
+    "заеб*",
+    "выеб*",
+    "проеб*",
+    "сука",
+    "суки",
+    "сучк*",
+    "мудак*",
+    "мудил*",
+    "пидор*",
+    "пидар*",
+    "гандон*",
+];
+
+/// Маскирует нецензурные слова звёздочками (как `profanity=masked` у Azure).
+#[derive(Debug, Clone)]
+pub struct ProfanityFilter {
+    exact: HashSet<String>,
+    prefixes: Vec<String>,
+}
+
+impl ProfanityFilter {
+    /// Встроенный список плюс пользовательские слова из `SttConfig::profanity_words`
+    pub fn new(extra_words: &[String]) -> Self {
+        let mut exact = HashSet::new();
+        let mut prefixes = Vec::new();
+
+        let entries = BUILTIN_PROFANITY
+            .iter()
+            .map(|word| word.to_string())
+            .chain(extra_words.iter().cloned());
+        for entry in entries {
+            let entry = entry.trim().to_lowercase();
+            match entry.strip_suffix('*') {
+                Some(prefix) if !prefix.is_empty() => prefixes.push(prefix.to_string()),
+                Some(_) => {}
+                None if !entry.is_empty() => {
+                    exact.insert(entry);
+                }
+                None => {}
+            }
+        }
+
+        Self { exact, prefixes }
+    }
+
+    pub fn is_profane(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        self.exact.contains(&word) || self.prefixes.iter().any(|p| word.starts_with(p.as_str()))
+    }
+
+    /// Заменяет каждое нецензурное слово на `*` той же длины, не трогая пунктуацию вокруг
+    pub fn mask(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut word = String::new();
+
+        for ch in text.chars() {
+            if is_word_char(ch) {
+                word.push(ch);
+                continue;
+            }
+            self.flush_word(&mut word, &mut out);
+            out.push(ch);
+        }
+        self.flush_word(&mut word, &mut out);
+        out
+    }
+
+    fn flush_word(&self, word: &mut String, out: &mut String) {
+        if word.is_empty() {
+            return;
+        }
+        if self.is_profane(word) {
+            out.push_str(&"*".repeat(word.chars().count()));
+        } else {
+            out.push_str(word);
+        }
+        word.clear();
+    }
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '\'' || ch == '’'
+}
+
+/// Убирает знаки препинания, которые расставляет модель. Дефисы и апострофы внутри
+/// слов ("кто-то", "don't") остаются.
+pub fn strip_punctuation(text: &str) -> String {
+    let stripped: String = text
+        .chars()
+        .filter(|ch| !matches!(ch, '.' | ',' | '!' | '?' | ';' | ':' | '…' | '¡' | '¿'))
+        .collect();
+    stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Что нужно сделать на клиенте для конкретного провайдера
+#[derive(Debug, Clone)]
+struct TranscriptTextFilter {
+    profanity: Option<ProfanityFilter>,
+    strip_punctuation: bool,
+}
+
+impl TranscriptTextFilter {
+    fn for_provider(provider: &dyn SttProvider, config: &SttConfig) -> Option<Self> {
+        // Пользовательские слова сервер не знает — их маскируем сами даже у Deepgram/Google
+        let profanity = (config.filter_profanity
+            && (!provider.supports_profanity_filter() || !config.profanity_words.is_empty()))
+        .then(|| ProfanityFilter::new(&config.profanity_words));
+        let strip_punctuation =
+            !config.enable_punctuation && !provider.supports_punctuation_control();
+
+        (profanity.is_some() || strip_punctuation).then_some(Self {
+            profanity,
+            strip_punctuation,
+        })
+    }
+
+    fn apply(&self, mut transcription: Transcription) -> Transcription {
+        transcription.text = self.apply_text(&transcription.text);
+        for word in &mut transcription.words {
+            word.text = self.apply_text(&word.text);
+            if self.strip_punctuation {
+                word.punctuated = None;
+            } else if let Some(punctuated) = word.punctuated.take() {
+                word.punctuated = Some(self.apply_text(&punctuated));
+            }
+        }
+        transcription
+    }
+
+    fn apply_text(&self, text: &str) -> String {
+        let text = match &self.profanity {
+            Some(filter) => filter.mask(text),
+            None => text.to_string(),
+        };
+        if self.strip_punctuation {
+            strip_punctuation(&text)
+        } else {
+            text
+        }
+    }
+
+    fn wrap(self: &Arc<Self>, callback: TranscriptionCallback) -> TranscriptionCallback {
+        let filter = self.clone();
+        Arc::new(move |transcription| callback(filter.apply(transcription)))
+    }
+}
+
+/// Обёртка над провайдером, которая применяет клиентские фильтры текста.
+///
+/// Фабрика оборачивает провайдер, только когда включён хотя бы один фильтр; что именно
+/// делать на клиенте, решается в `initialize` по возможностям внутреннего провайдера.
+pub struct TextFilterProvider {
+    inner: Box<dyn SttProvider>,
+    filter: Option<Arc<TranscriptTextFilter>>,
+}
+
+impl TextFilterProvider {
+    pub fn new(inner: Box<dyn SttProvider>) -> Self {
+        Self {
+            inner,
+            filter: None,
+        }
+    }
+
+    /// Нужна ли обёртка при таком конфиге
+    pub fn is_needed(config: &SttConfig) -> bool {
+        config.filter_profanity || !config.enable_punctuation
+    }
+
+    fn wrap_callback(&self, callback: TranscriptionCallback) -> TranscriptionCallback {
+        match &self.filter {
+            Some(filter) => filter.wrap(callback),
+            None => callback,
+        }
+    }
+}
+
+#[async_trait]
+impl SttProvider for TextFilterProvider {
+    async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
+        self.inner.initialize(config).await?;
+        self.filter = TranscriptTextFilter::for_provider(self.inner.as_ref(), config).map(Arc::new);
+        if let Some(filter) = &self.filter {
+            log::info!(
+                "Client-side transcript filter for {}: profanity={}, strip_punctuation={}",
+                self.inner.name(),
+                filter.profanity.is_some(),
+                filter.strip_punctuation
+            );
+        }
+        Ok(())
+    }
+
+    async fn start_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        on_connection_quality: ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        let on_partial = self.wrap_callback(on_partial);
+        let on_final = self.wrap_callback(on_final);
+        self.inner
+            .start_stream(on_partial, on_final, on_error, on_connection_quality)
+            .await
+    }
+
+    async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
+        self.inner.send_audio(chunk).await
+    }
+
+    async fn stop_stream(&mut self) -> SttResult<()> {
+        self.inner.stop_stream().await
+    }
+
+    async fn abort(&mut self) -> SttResult<()> {
+        self.inner.abort().await
+    }
+
+    async fn pause_stream(&mut self) -> SttResult<()> {
+        self.inner.pause_stream().await
+    }
+
+    async fn resume_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        on_connection_quality: ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        let on_partial = self.wrap_callback(on_partial);
+        let on_final = self.wrap_callback(on_final);
+        self.inner
+            .resume_stream(on_partial, on_final, on_error, on_connection_quality)
+            .await
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_keep_alive(&self) -> bool {
+        self.inner.supports_keep_alive()
+    }
+
+    fn supports_punctuation_control(&self) -> bool {
+        true
+    }
+
+    fn supports_profanity_filter(&self) -> bool {
+        true
+    }
+
+    fn is_connection_alive(&self) -> bool {
+        self.inner.is_connection_alive()
+    }
+
+    fn is_online(&self) -> bool {
+        self.inner.is_online()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{SttProviderType, Word};
+    use std::sync::Mutex as StdMutex;
+
+    struct EchoProvider {
+        server_side: bool,
+        on_final: Option<TranscriptionCallback>,
+    }
+
+    #[async_trait]
+    impl SttProvider for EchoProvider {
+        async fn initialize(&mut self, _config: &SttConfig) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn start_stream(
+            &mut self,
+            _on_partial: TranscriptionCallback,
+            on_final: TranscriptionCallback,
+            _on_error: ErrorCallback,
+            _on_connection_quality: ConnectionQualityCallback,
+        ) -> SttResult<()> {
+            self.on_final = Some(on_final);
+            Ok(())
+        }
+
+        async fn send_audio(&mut self, _chunk: &AudioChunk) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn stop_stream(&mut self) -> SttResult<()> {
+            if let Some(on_final) = &self.on_final {
+                on_final(
+                    Transcription::final_result("Ну, fucking hell!".to_string()).with_words(vec![
+                        Word::new("ну", 0.0, 0.2).with_punctuated("Ну,"),
+                        Word::new("fucking", 0.2, 0.6),
+                        Word::new("hell", 0.6, 0.9).with_punctuated("hell!"),
+                    ]),
+                );
+            }
+            Ok(())
+        }
+
+        async fn abort(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "Echo"
+        }
+
+        fn supports_punctuation_control(&self) -> bool {
+            self.server_side
+        }
+
+        fn supports_profanity_filter(&self) -> bool {
+            self.server_side
+        }
+
+        fn is_online(&self) -> bool {
+            false
+        }
+    }
+
+    async fn run_final(server_side: bool, config: &SttConfig) -> Transcription {
+        let mut provider = TextFilterProvider::new(Box::new(EchoProvider {
+            server_side,
+            on_final: None,
+        }));
+        let received = Arc::new(StdMutex::new(None));
+        let sink = received.clone();
+
+        provider.initialize(config).await.unwrap();
+        provider
+            .start_stream(
+                Arc::new(|_| {}),
+                Arc::new(move |t| *sink.lock().unwrap() = Some(t)),
+                Arc::new(|_| {}),
+                Arc::new(|_, _| {}),
+            )
+            .await
+            .unwrap();
+        provider.stop_stream().await.unwrap();
+
+        let result = received.lock().unwrap().take();
+        result.expect("final transcription")
+    }
+
+    #[test]
+    fn profanity_filter_masks_words_and_keeps_punctuation() {
+        let filter = ProfanityFilter::new(&["darn".to_string()]);
+
+        assert_eq!(filter.mask("Oh, SHIT! Darn it."), "Oh, ****! **** it.");
+        assert_eq!(filter.mask("Бляха, опять"), "*****, опять");
+        assert_eq!(filter.mask("Shell dickens"), "Shell dickens");
+    }
+
+    #[test]
+    fn strip_punctuation_keeps_intra_word_marks() {
+        assert_eq!(
+            strip_punctuation("Кто-то сказал: «don't», да?"),
+            "Кто-то сказал «don't» да"
+        );
+    }
+
+    #[test]
+    fn needs_wrapper_only_when_a_filter_is_enabled() {
+        let mut config = SttConfig::new(SttProviderType::WhisperLocal);
+        assert!(!TextFilterProvider::is_needed(&config));
+
+        config.filter_profanity = true;
+        assert!(TextFilterProvider::is_needed(&config));
+
+        config.filter_profanity = false;
+        config.enable_punctuation = false;
+        assert!(TextFilterProvider::is_needed(&config));
+    }
+
+    #[tokio::test]
+    async fn client_side_filter_applies_when_provider_cannot_filter() {
+        let mut config = SttConfig::new(SttProviderType::WhisperLocal);
+        config.filter_profanity = true;
+        config.enable_punctuation = false;
+
+        let result = run_final(false, &config).await;
+
+        assert_eq!(result.text, "Ну ******* hell");
+        assert_eq!(result.words[1].text, "*******");
+        assert!(result.words.iter().all(|word| word.punctuated.is_none()));
+    }
+
+    #[tokio::test]
+    async fn server_side_filter_is_trusted_except_for_custom_words() {
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.filter_profanity = true;
+        config.enable_punctuation = false;
+
+        let untouched = run_final(true, &config).await;
+        assert_eq!(untouched.text, "Ну, fucking hell!");
+
+        config.profanity_words = vec!["hell".to_string()];
+        let masked = run_final(true, &config).await;
+        assert_eq!(masked.text, "Ну, ******* ****!");
+        assert_eq!(masked.words[2].punctuated.as_deref(), Some("****!"));
+    }
+}
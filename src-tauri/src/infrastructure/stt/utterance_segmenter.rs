@@ -0,0 +1,324 @@
+//! Нарезка потокового аудио на фразы для локального (не потокового) распознавания
+//!
+//! Whisper декодирует только законченные куски аудио, поэтому "стриминг" строим так:
+//! - VAD размечает 30 ms фреймы как речь/тишину;
+//! - пока фраза звучит, раз в `PARTIAL_INTERVAL` отдаём окно фразы на черновое декодирование;
+//! - после `ENDPOINT_SILENCE` тишины (или при достижении лимита длины) фраза коммитится как final.
+
+use crate::domain::SttResult;
+
+pub(super) const SAMPLE_RATE: usize = 16_000;
+pub(super) const FRAME_SAMPLES: usize = 480; // 30 ms @ 16 kHz — формат WebRTC VAD
+const PREROLL_SAMPLES: usize = SAMPLE_RATE * 3 / 10; // 300 ms до начала речи
+const ENDPOINT_SILENCE_SAMPLES: usize = SAMPLE_RATE * 7 / 10; // 700 ms тишины = конец фразы
+const PARTIAL_INTERVAL_SAMPLES: usize = SAMPLE_RATE; // черновик раз в секунду речи
+
+// Окно Whisper — 30 s; режем раньше, чтобы фраза гарантированно влезла в один проход.
+const MAX_UTTERANCE_SAMPLES: usize = SAMPLE_RATE * 25;
+
+/// Кусок аудио, который нужно отдать на декодирование
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct UtteranceAudio {
+    pub samples: Vec<i16>,
+    /// Начало фрагмента в секундах от старта стрима
+    pub start: f64,
+}
+
+impl UtteranceAudio {
+    pub fn duration(&self) -> f64 {
+        self.samples.len() as f64 / SAMPLE_RATE as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum SegmentEvent {
+    /// Промежуточная гипотеза по текущей (ещё не законченной) фразе
+    Partial(UtteranceAudio),
+    /// Фраза закончилась — результат коммитится
+    Final(UtteranceAudio),
+}
+
+impl SegmentEvent {
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Final(_))
+    }
+}
+
+pub(super) struct UtteranceSegmenter {
+    pending: Vec<i16>,
+    preroll: Vec<i16>,
+    utterance: Vec<i16>,
+    utterance_start_sample: usize,
+    processed_samples: usize,
+    in_speech: bool,
+    trailing_silence_samples: usize,
+    samples_since_partial: usize,
+}
+
+impl UtteranceSegmenter {
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::with_capacity(FRAME_SAMPLES * 2),
+            preroll: Vec::with_capacity(PREROLL_SAMPLES + FRAME_SAMPLES),
+            utterance: Vec::new(),
+            utterance_start_sample: 0,
+            processed_samples: 0,
+            in_speech: false,
+            trailing_silence_samples: 0,
+            samples_since_partial: 0,
+        }
+    }
+
+    /// Добавляем аудио; `is_speech` вызывается ровно для одного 30 ms фрейма за раз.
+    pub fn push_samples<F>(
+        &mut self,
+        samples: &[i16],
+        mut is_speech: F,
+    ) -> SttResult<Vec<SegmentEvent>>
+    where
+        F: FnMut(&[i16]) -> SttResult<bool>,
+    {
+        self.pending.extend_from_slice(samples);
+
+        let mut events = Vec::new();
+        let mut offset = 0;
+        while self.pending.len() - offset >= FRAME_SAMPLES {
+            let frame = &self.pending[offset..offset + FRAME_SAMPLES];
+            let speech = is_speech(frame)?;
+            let frame = frame.to_vec();
+            offset += FRAME_SAMPLES;
+            if let Some(event) = self.push_frame(&frame, speech) {
+                events.push(event);
+            }
+        }
+        self.pending.drain(..offset);
+
+        Ok(events)
+    }
+
+    /// Конец записи: недослушанная фраза (вместе с неполным фреймом) уходит в final.
+    pub fn finish(&mut self) -> Option<SegmentEvent> {
+        let pending = std::mem::take(&mut self.pending);
+        self.preroll.clear();
+        if !self.in_speech {
+            return None;
+        }
+
+        self.utterance.extend_from_slice(&pending);
+        Some(self.commit_utterance())
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn push_frame(&mut self, frame: &[i16], is_speech: bool) -> Option<SegmentEvent> {
+        let frame_start = self.processed_samples;
+        self.processed_samples += frame.len();
+
+        if !self.in_speech {
+            if !is_speech {
+                self.preroll.extend_from_slice(frame);
+                if self.preroll.len() > PREROLL_SAMPLES {
+                    let excess = self.preroll.len() - PREROLL_SAMPLES;
+                    self.preroll.drain(..excess);
+                }
+                return None;
+            }
+
+            self.in_speech = true;
+            self.utterance = std::mem::take(&mut self.preroll);
+            self.utterance_start_sample = frame_start - self.utterance.len();
+            self.trailing_silence_samples = 0;
+            self.samples_since_partial = 0;
+        }
+
+        self.utterance.extend_from_slice(frame);
+        self.samples_since_partial += frame.len();
+        if is_speech {
+            self.trailing_silence_samples = 0;
+        } else {
+            self.trailing_silence_samples += frame.len();
+        }
+
+        if self.trailing_silence_samples >= ENDPOINT_SILENCE_SAMPLES
+            || self.utterance.len() + FRAME_SAMPLES > MAX_UTTERANCE_SAMPLES
+        {
+            return Some(self.commit_utterance());
+        }
+
+        if self.samples_since_partial >= PARTIAL_INTERVAL_SAMPLES
+            && self.trailing_silence_samples == 0
+        {
+            self.samples_since_partial = 0;
+            return Some(SegmentEvent::Partial(self.current_audio()));
+        }
+
+        None
+    }
+
+    fn commit_utterance(&mut self) -> SegmentEvent {
+        // Хвостовую тишину в final не отдаём: Whisper на ней любит галлюцинировать.
+        let keep = self.utterance.len() - self.trailing_silence_samples.min(self.utterance.len());
+        self.utterance
+            .truncate(keep.max(FRAME_SAMPLES.min(self.utterance.len())));
+        let audio = self.current_audio();
+
+        self.utterance.clear();
+        self.in_speech = false;
+        self.trailing_silence_samples = 0;
+        self.samples_since_partial = 0;
+        SegmentEvent::Final(audio)
+    }
+
+    fn current_audio(&self) -> UtteranceAudio {
+        UtteranceAudio {
+            samples: self.utterance.clone(),
+            start: self.utterance_start_sample as f64 / SAMPLE_RATE as f64,
+        }
+    }
+}
+
+impl Default for UtteranceSegmenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Декодер может не успевать за речью. Из очереди оставляем все finals по порядку
+/// и только самый свежий partial, если он новее последнего final.
+#[cfg_attr(not(feature = "whisper"), allow(dead_code))]
+pub(super) fn coalesce_segment_events(events: Vec<SegmentEvent>) -> Vec<SegmentEvent> {
+    let last_final = events.iter().rposition(SegmentEvent::is_final);
+    let last_partial = events
+        .iter()
+        .rposition(|event| !event.is_final())
+        .filter(|&index| last_final.map_or(true, |final_index| index > final_index));
+
+    events
+        .into_iter()
+        .enumerate()
+        .filter(|(index, event)| event.is_final() || Some(*index) == last_partial)
+        .map(|(_, event)| event)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(count: usize, value: i16) -> Vec<i16> {
+        vec![value; FRAME_SAMPLES * count]
+    }
+
+    /// В тестах "речью" считаем ненулевые фреймы.
+    fn push(segmenter: &mut UtteranceSegmenter, samples: &[i16]) -> Vec<SegmentEvent> {
+        segmenter
+            .push_samples(samples, |frame| Ok(frame.iter().any(|&s| s != 0)))
+            .expect("segmenter push")
+    }
+
+    #[test]
+    fn silence_alone_produces_no_events() {
+        let mut segmenter = UtteranceSegmenter::new();
+
+        assert!(push(&mut segmenter, &frames(100, 0)).is_empty());
+        assert!(segmenter.finish().is_none());
+    }
+
+    #[test]
+    fn utterance_is_committed_after_endpoint_silence_with_preroll() {
+        let mut segmenter = UtteranceSegmenter::new();
+        push(&mut segmenter, &frames(20, 0));
+        let speech_events = push(&mut segmenter, &frames(20, 100));
+        assert!(speech_events.is_empty());
+
+        let events = push(&mut segmenter, &frames(30, 0));
+
+        assert_eq!(events.len(), 1);
+        let SegmentEvent::Final(audio) = &events[0] else {
+            panic!("expected final");
+        };
+        // 10 фреймов pre-roll (300 ms) + 20 фреймов речи, хвостовая тишина отрезана.
+        assert_eq!(audio.samples.len(), FRAME_SAMPLES * 30);
+        assert!((audio.start - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn partials_are_emitted_every_second_of_speech() {
+        let mut segmenter = UtteranceSegmenter::new();
+
+        let events = push(&mut segmenter, &frames(70, 100));
+
+        let partial_lengths: Vec<usize> = events
+            .iter()
+            .map(|event| match event {
+                SegmentEvent::Partial(audio) => audio.samples.len(),
+                SegmentEvent::Final(_) => panic!("unexpected final"),
+            })
+            .collect();
+        assert_eq!(partial_lengths.len(), 2);
+        assert!(partial_lengths[0] >= SAMPLE_RATE);
+        assert!(partial_lengths[1] >= 2 * SAMPLE_RATE);
+    }
+
+    #[test]
+    fn long_speech_is_force_committed_within_whisper_window() {
+        let mut segmenter = UtteranceSegmenter::new();
+
+        let events = push(
+            &mut segmenter,
+            &vec![100; MAX_UTTERANCE_SAMPLES + SAMPLE_RATE],
+        );
+
+        let finals: Vec<&SegmentEvent> = events.iter().filter(|e| e.is_final()).collect();
+        assert_eq!(finals.len(), 1);
+        let SegmentEvent::Final(audio) = finals[0] else {
+            unreachable!();
+        };
+        assert!(audio.samples.len() <= MAX_UTTERANCE_SAMPLES);
+    }
+
+    #[test]
+    fn finish_commits_unfinished_utterance_including_partial_frame() {
+        let mut segmenter = UtteranceSegmenter::new();
+        push(&mut segmenter, &frames(5, 100));
+        push(&mut segmenter, &[100; 100]);
+
+        let Some(SegmentEvent::Final(audio)) = segmenter.finish() else {
+            panic!("expected final");
+        };
+        assert_eq!(audio.samples.len(), FRAME_SAMPLES * 5 + 100);
+        assert!(segmenter.finish().is_none());
+    }
+
+    #[test]
+    fn coalesce_keeps_finals_and_latest_trailing_partial() {
+        let audio = |start: f64| UtteranceAudio {
+            samples: Vec::new(),
+            start,
+        };
+        let events = vec![
+            SegmentEvent::Partial(audio(0.0)),
+            SegmentEvent::Partial(audio(1.0)),
+            SegmentEvent::Final(audio(2.0)),
+            SegmentEvent::Partial(audio(3.0)),
+            SegmentEvent::Partial(audio(4.0)),
+        ];
+
+        assert_eq!(
+            coalesce_segment_events(events),
+            vec![
+                SegmentEvent::Final(audio(2.0)),
+                SegmentEvent::Partial(audio(4.0))
+            ]
+        );
+        assert_eq!(
+            coalesce_segment_events(vec![
+                SegmentEvent::Partial(audio(0.0)),
+                SegmentEvent::Final(audio(1.0)),
+            ]),
+            vec![SegmentEvent::Final(audio(1.0))]
+        );
+    }
+}
@@ -8,16 +8,144 @@ use crate::domain::{
 #[cfg(feature = "whisper")]
 mod whisper_impl {
     use super::*;
+    use crate::domain::{ConnectionQualityCallback, ErrorCallback, Transcription};
+    use crate::infrastructure::audio::{VadProcessor, VadResult};
     use crate::infrastructure::models::whisper_models;
+    use crate::infrastructure::stt::utterance_segmenter::{
+        coalesce_segment_events, SegmentEvent, UtteranceSegmenter, SAMPLE_RATE,
+    };
+    use std::panic::{catch_unwind, AssertUnwindSafe};
     use std::sync::Arc;
+    use tokio::task::JoinHandle;
     use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
+    // whisper.cpp молча пропускает вход короче секунды — добиваем тишиной.
+    const WHISPER_MIN_INPUT_SAMPLES: usize = SAMPLE_RATE * 11 / 10;
+
+    fn call_whisper_callback(label: &str, callback: impl FnOnce()) {
+        if catch_unwind(AssertUnwindSafe(callback)).is_err() {
+            log::error!("WhisperLocalProvider {} callback panicked", label);
+        }
+    }
+
+    fn decode_samples(ctx: &WhisperContext, samples: &[i16], language: &str) -> SttResult<String> {
+        let mut audio_f32: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        if audio_f32.len() < WHISPER_MIN_INPUT_SAMPLES {
+            audio_f32.resize(WHISPER_MIN_INPUT_SAMPLES, 0.0);
+        }
+
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_language(Some(language));
+        params.set_translate(false);
+        params.set_print_progress(false);
+        params.set_print_special(false);
+        params.set_print_realtime(false);
+        // Каждая фраза декодируется независимо: контекст прошлых фраз уже закоммичен.
+        params.set_no_context(true);
+        params.set_suppress_blank(true);
+        params.set_n_threads(num_cpus::get() as i32);
+
+        let mut state = ctx
+            .create_state()
+            .map_err(|e| SttError::Internal(format!("Failed to create Whisper state: {}", e)))?;
+
+        state
+            .full(params, &audio_f32)
+            .map_err(|e| SttError::Processing(format!("Transcription failed: {}", e)))?;
+
+        let num_segments = state
+            .full_n_segments()
+            .map_err(|e| SttError::Processing(format!("Failed to get segments: {}", e)))?;
+
+        let mut full_text = String::new();
+        for i in 0..num_segments {
+            match state.full_get_segment_text(i) {
+                Ok(segment_text) => {
+                    full_text.push_str(segment_text.trim());
+                    full_text.push(' ');
+                }
+                Err(e) => {
+                    log::warn!("Failed to get segment {} text: {}", i, e);
+                }
+            }
+        }
+
+        Ok(full_text.trim().to_string())
+    }
+
+    /// Фоновый декодер: берёт фразы из очереди по одной, устаревшие partials пропускает.
+    async fn run_decode_worker(
+        ctx: Arc<WhisperContext>,
+        language: String,
+        events: async_channel::Receiver<SegmentEvent>,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+    ) {
+        while let Ok(first) = events.recv().await {
+            let mut batch = vec![first];
+            while let Ok(next) = events.try_recv() {
+                batch.push(next);
+            }
+
+            for event in coalesce_segment_events(batch) {
+                let (audio, is_final) = match event {
+                    SegmentEvent::Partial(audio) => (audio, false),
+                    SegmentEvent::Final(audio) => (audio, true),
+                };
+                let (start, duration) = (audio.start, audio.duration());
+
+                let started_at = std::time::Instant::now();
+                let ctx = ctx.clone();
+                let decode_language = language.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    decode_samples(&ctx, &audio.samples, &decode_language)
+                })
+                .await
+                .map_err(|e| SttError::Internal(format!("Transcription task failed: {}", e)))
+                .and_then(|result| result);
+
+                let text = match result {
+                    Ok(text) => text,
+                    Err(error) => {
+                        log::error!("WhisperLocalProvider: Decoding failed: {}", error);
+                        call_whisper_callback("error", || on_error(error));
+                        continue;
+                    }
+                };
+                log::debug!(
+                    "WhisperLocalProvider: Decoded {:.2}s {} in {:.2}s: '{}'",
+                    duration,
+                    if is_final { "final" } else { "partial" },
+                    started_at.elapsed().as_secs_f32(),
+                    text
+                );
+                if text.is_empty() {
+                    continue;
+                }
+
+                let transcription = Transcription::new(text, is_final)
+                    .with_language(language.clone())
+                    .with_timing(start, duration);
+                if is_final {
+                    call_whisper_callback("final transcription", || on_final(transcription));
+                } else {
+                    call_whisper_callback("partial transcription", || on_partial(transcription));
+                }
+            }
+        }
+
+        log::debug!("WhisperLocalProvider: Decode worker finished");
+    }
+
     pub struct WhisperLocalProvider {
         config: Option<SttConfig>,
         is_streaming: bool,
-        audio_buffer: Vec<i16>,
         whisper_ctx: Option<Arc<WhisperContext>>,
-        on_final_callback: Option<TranscriptionCallback>,
+        segmenter: UtteranceSegmenter,
+        vad: Option<VadProcessor>,
+        events_tx: Option<async_channel::Sender<SegmentEvent>>,
+        decode_task: Option<JoinHandle<()>>,
     }
 
     impl WhisperLocalProvider {
@@ -25,9 +153,11 @@ mod whisper_impl {
             Self {
                 config: None,
                 is_streaming: false,
-                audio_buffer: Vec::new(),
                 whisper_ctx: None,
-                on_final_callback: None,
+                segmenter: UtteranceSegmenter::new(),
+                vad: None,
+                events_tx: None,
+                decode_task: None,
             }
         }
 
@@ -46,8 +176,20 @@ mod whisper_impl {
             Ok(model_file)
         }
 
-        fn convert_audio_to_f32(samples: &[i16]) -> Vec<f32> {
-            samples.iter().map(|&s| s as f32 / 32768.0).collect()
+        fn queue_segment_event(&self, event: SegmentEvent) -> SttResult<()> {
+            let events_tx = self.events_tx.as_ref().ok_or_else(|| {
+                SttError::Internal("Whisper decode queue is not available".to_string())
+            })?;
+            events_tx
+                .try_send(event)
+                .map_err(|_| SttError::Internal("Whisper decode worker has stopped".to_string()))
+        }
+
+        fn reset_session_state(&mut self) {
+            self.is_streaming = false;
+            self.events_tx = None;
+            self.vad = None;
+            self.segmenter.reset();
         }
     }
 
@@ -57,6 +199,15 @@ mod whisper_impl {
         }
     }
 
+    impl Drop for WhisperLocalProvider {
+        fn drop(&mut self) {
+            if let Some(events_tx) = self.events_tx.take() {
+                events_tx.close();
+            }
+            crate::infrastructure::stt::abort_background_task(&mut self.decode_task);
+        }
+    }
+
     #[async_trait]
     impl SttProvider for WhisperLocalProvider {
         async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
@@ -92,23 +243,42 @@ mod whisper_impl {
 
         async fn start_stream(
             &mut self,
-            _on_partial: TranscriptionCallback,
+            on_partial: TranscriptionCallback,
             on_final: TranscriptionCallback,
-            _on_error: crate::domain::ErrorCallback,
+            on_error: ErrorCallback,
+            _on_connection_quality: ConnectionQualityCallback,
         ) -> SttResult<()> {
-            log::info!("WhisperLocalProvider: Starting stream (buffering mode)");
+            log::info!("WhisperLocalProvider: Starting stream (VAD-segmented decoding)");
 
-            if self.whisper_ctx.is_none() {
-                return Err(SttError::Configuration(
-                    "Whisper context not initialized. Call initialize() first.".to_string(),
-                ));
+            if self.is_streaming {
+                return Err(SttError::Processing("Stream already active".to_string()));
             }
 
+            let ctx = self.whisper_ctx.clone().ok_or_else(|| {
+                SttError::Configuration(
+                    "Whisper context not initialized. Call initialize() first.".to_string(),
+                )
+            })?;
+
+            let language = self
+                .config
+                .as_ref()
+                .map(|c| c.language.clone())
+                .unwrap_or_else(|| "ru".to_string());
+
+            // Таймаут тишины здесь не нужен: границы фраз определяет сегментер.
+            let vad = VadProcessor::new(Some(u64::MAX), None)?;
+
+            let (events_tx, events_rx) = async_channel::unbounded();
+            self.decode_task = Some(tokio::spawn(run_decode_worker(
+                ctx, language, events_rx, on_partial, on_final, on_error,
+            )));
+            self.events_tx = Some(events_tx);
+            self.vad = Some(vad);
+            self.segmenter.reset();
             self.is_streaming = true;
-            self.audio_buffer.clear();
-            self.on_final_callback = Some(on_final);
 
-            log::info!("WhisperLocalProvider: Ready to buffer audio");
+            log::info!("WhisperLocalProvider: Ready to decode audio");
             Ok(())
         }
 
@@ -117,126 +287,71 @@ mod whisper_impl {
                 return Err(SttError::Processing("Not streaming".to_string()));
             }
 
-            self.audio_buffer.extend_from_slice(&chunk.data);
+            let vad = self
+                .vad
+                .as_mut()
+                .ok_or_else(|| SttError::Internal("VAD is not initialized".to_string()))?;
+            let events = self.segmenter.push_samples(&chunk.data, |frame| {
+                Ok(matches!(vad.process_samples(frame)?, VadResult::Speech))
+            })?;
 
-            if self.audio_buffer.len() % (16000 * 2) == 0 {
-                let duration_sec = self.audio_buffer.len() / 16000;
-                log::debug!("WhisperLocalProvider: Buffered {}s of audio", duration_sec);
+            for event in events {
+                if let SegmentEvent::Final(audio) = &event {
+                    log::debug!(
+                        "WhisperLocalProvider: Utterance committed at {:.2}s ({:.2}s)",
+                        audio.start,
+                        audio.duration()
+                    );
+                }
+                self.queue_segment_event(event)?;
             }
 
             Ok(())
         }
 
         async fn stop_stream(&mut self) -> SttResult<()> {
-            log::info!("WhisperLocalProvider: Stopping stream and processing audio");
-            self.is_streaming = false;
+            log::info!("WhisperLocalProvider: Stopping stream and decoding the last utterance");
 
-            if self.audio_buffer.is_empty() {
-                log::warn!("WhisperLocalProvider: No audio to process");
-                self.audio_buffer.clear();
+            if !self.is_streaming {
+                log::warn!("Stream not active");
                 return Ok(());
             }
 
-            let duration_sec = self.audio_buffer.len() as f32 / 16000.0;
-            log::info!(
-                "WhisperLocalProvider: Processing {:.2}s of audio ({} samples)",
-                duration_sec,
-                self.audio_buffer.len()
-            );
-
-            let ctx = self
-                .whisper_ctx
-                .as_ref()
-                .ok_or_else(|| SttError::Internal("Whisper context not available".to_string()))?
-                .clone();
-
-            let callback = self
-                .on_final_callback
-                .as_ref()
-                .ok_or_else(|| SttError::Internal("Final callback not set".to_string()))?
-                .clone();
-
-            let audio_f32 = Self::convert_audio_to_f32(&self.audio_buffer);
-            self.audio_buffer.clear();
-
-            let language = self
-                .config
-                .as_ref()
-                .and_then(|c| Some(c.language.clone()))
-                .unwrap_or_else(|| "ru".to_string());
+            let mut stop_result = match self.segmenter.finish() {
+                Some(event) => self.queue_segment_event(event),
+                None => Ok(()),
+            };
 
-            let start_time = std::time::Instant::now();
-
-            let transcription_result = tokio::task::spawn_blocking(move || {
-                let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-                params.set_language(Some(&language));
-                params.set_translate(false);
-                params.set_print_progress(false);
-                params.set_print_special(false);
-                params.set_print_realtime(false);
-                params.set_n_threads(num_cpus::get() as i32);
-
-                let mut state = ctx.create_state().map_err(|e| {
-                    SttError::Internal(format!("Failed to create Whisper state: {}", e))
-                })?;
-
-                state
-                    .full(params, &audio_f32)
-                    .map_err(|e| SttError::Processing(format!("Transcription failed: {}", e)))?;
-
-                let num_segments = state
-                    .full_n_segments()
-                    .map_err(|e| SttError::Processing(format!("Failed to get segments: {}", e)))?;
-
-                let mut full_text = String::new();
-                for i in 0..num_segments {
-                    match state.full_get_segment_text(i) {
-                        Ok(segment_text) => {
-                            full_text.push_str(&segment_text);
-                            full_text.push(' ');
-                        }
-                        Err(e) => {
-                            log::warn!("Failed to get segment {} text: {}", i, e);
-                        }
-                    }
+            // Закрываем очередь и ждём, пока декодер отдаст все finals.
+            if let Some(events_tx) = self.events_tx.take() {
+                events_tx.close();
+            }
+            if let Some(task) = self.decode_task.take() {
+                if let Err(join_error) = task.await {
+                    stop_result = Err(SttError::Internal(format!(
+                        "Whisper decode worker failed: {}",
+                        join_error
+                    )));
                 }
+            }
 
-                Ok::<String, SttError>(full_text.trim().to_string())
-            })
-            .await
-            .map_err(|e| SttError::Internal(format!("Transcription task failed: {}", e)))??;
-
-            let elapsed = start_time.elapsed();
-            log::info!(
-                "WhisperLocalProvider: Transcription completed in {:.2}s: '{}'",
-                elapsed.as_secs_f32(),
-                transcription_result
-            );
-
-            let transcription = Transcription {
-                text: transcription_result,
-                is_final: true,
-                confidence: None,
-                language: Some(language),
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_else(|_| std::time::Duration::from_secs(0))
-                    .as_millis() as i64,
-                start: 0.0,    // Whisper Local не предоставляет start время
-                duration: 0.0, // Whisper Local не предоставляет duration
-            };
-
-            callback(transcription);
+            self.reset_session_state();
 
             log::info!("WhisperLocalProvider: Stream stopped");
-            Ok(())
+            stop_result
         }
 
         async fn abort(&mut self) -> SttResult<()> {
             log::info!("WhisperLocalProvider: Aborting stream");
-            self.is_streaming = false;
-            self.audio_buffer.clear();
-            self.on_final_callback = None;
+
+            if let Some(events_tx) = self.events_tx.take() {
+                events_tx.close();
+            }
+            if let Some(task) = self.decode_task.take() {
+                task.abort();
+                let _ = task.await;
+            }
+            self.reset_session_state();
 
             log::info!("WhisperLocalProvider: Stream aborted");
             Ok(())
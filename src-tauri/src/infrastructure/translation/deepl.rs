@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::http::{
+    format_reqwest_error, map_http_error, read_response_body, resolve_api_url,
+    TEXT_TRANSLATION_TIMEOUT,
+};
+use crate::domain::{TextTranslationError, TextTranslator};
+
+const DEEPL_API_URL: &str = "https://api.deepl.com/v2";
+const DEEPL_FREE_API_URL: &str = "https://api-free.deepl.com/v2";
+
+/// DeepL API v2 `/translate`. Ключ free-тарифа (`...:fx`) сам уходит на api-free.deepl.com.
+pub struct DeepLTextTranslator {
+    api_key: String,
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl DeepLTextTranslator {
+    pub fn new(api_key: String, endpoint: Option<&str>) -> Result<Self, TextTranslationError> {
+        let api_key = api_key.trim().to_string();
+        if api_key.is_empty() {
+            return Err(TextTranslationError::Authentication(
+                "DeepL API key не задан".to_string(),
+            ));
+        }
+        let default_base = if api_key.ends_with(":fx") {
+            DEEPL_FREE_API_URL
+        } else {
+            DEEPL_API_URL
+        };
+        let client = reqwest::Client::builder()
+            .timeout(TEXT_TRANSLATION_TIMEOUT)
+            .build()
+            .map_err(|e| TextTranslationError::Connection(format_reqwest_error(&e)))?;
+
+        Ok(Self {
+            endpoint: resolve_api_url(endpoint, default_base, "/translate"),
+            api_key,
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl TextTranslator for DeepLTextTranslator {
+    async fn translate_text(
+        &self,
+        text: &str,
+        target_language: &str,
+    ) -> Result<String, TextTranslationError> {
+        let input = text.trim();
+        if input.is_empty() {
+            return Ok(String::new());
+        }
+
+        let body = json!({
+            "text": [input],
+            "target_lang": deepl_target_language(target_language),
+        });
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("DeepL-Auth-Key {}", self.api_key),
+            )
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| TextTranslationError::Connection(format_reqwest_error(&e)))?;
+
+        let (status, text_body) = read_response_body(response, "DeepL").await?;
+        if !status.is_success() {
+            let message = serde_json::from_str::<DeepLErrorResponse>(&text_body)
+                .ok()
+                .map(|parsed| parsed.message.trim().to_string())
+                .filter(|message| !message.is_empty())
+                .unwrap_or_else(|| format!("DeepL HTTP {}", status.as_u16()));
+            return Err(map_http_error(status, message));
+        }
+
+        let parsed: DeepLResponse = serde_json::from_str(&text_body).map_err(|e| {
+            TextTranslationError::Protocol(format!("invalid DeepL response: {}", e))
+        })?;
+        parsed
+            .translations
+            .into_iter()
+            .map(|translation| translation.text.trim().to_string())
+            .find(|text| !text.is_empty())
+            .ok_or_else(|| {
+                TextTranslationError::Protocol("DeepL response has no translations".to_string())
+            })
+    }
+}
+
+/// DeepL ждёт коды в верхнем регистре, а для английского и португальского — с вариантом
+fn deepl_target_language(value: &str) -> String {
+    let language = value.trim().replace('_', "-").to_uppercase();
+    match language.as_str() {
+        "EN" => "EN-US".to_string(),
+        "PT" => "PT-BR".to_string(),
+        _ => language,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLResponse {
+    #[serde(default)]
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLTranslation {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLErrorResponse {
+    #[serde(default)]
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_language_is_uppercased_with_required_variants() {
+        assert_eq!(deepl_target_language(" ru "), "RU");
+        assert_eq!(deepl_target_language("en"), "EN-US");
+        assert_eq!(deepl_target_language("pt"), "PT-BR");
+        assert_eq!(deepl_target_language("en_gb"), "EN-GB");
+    }
+
+    #[test]
+    fn free_key_uses_free_api_host() {
+        let free = DeepLTextTranslator::new("abc:fx".to_string(), None).unwrap();
+        assert_eq!(free.endpoint, "https://api-free.deepl.com/v2/translate");
+
+        let pro = DeepLTextTranslator::new(" abc ".to_string(), None).unwrap();
+        assert_eq!(pro.endpoint, "https://api.deepl.com/v2/translate");
+        assert_eq!(pro.api_key, "abc");
+
+        assert!(matches!(
+            DeepLTextTranslator::new(" ".to_string(), None),
+            Err(TextTranslationError::Authentication(_))
+        ));
+    }
+}
@@ -0,0 +1,233 @@
+//! Общий HTTP-код адаптеров текстового перевода: лимит тела ответа и классификация ошибок.
+
+use std::error::Error as StdError;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use reqwest::StatusCode;
+
+use crate::domain::TextTranslationError;
+
+pub(crate) const TEXT_TRANSLATION_TIMEOUT: Duration = Duration::from_secs(12);
+pub(crate) const MAX_TEXT_TRANSLATION_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// DeepL отвечает 456, когда исчерпан лимит символов
+const STATUS_QUOTA_EXCEEDED: u16 = 456;
+
+/// Читает тело ответа целиком, но не больше `MAX_TEXT_TRANSLATION_RESPONSE_BYTES`.
+/// Ошибка чтения тела неуспешного ответа сохраняет тип HTTP-ошибки (auth/rate limit).
+pub(crate) async fn read_response_body(
+    response: reqwest::Response,
+    provider: &str,
+) -> Result<(StatusCode, String), TextTranslationError> {
+    let status = response.status();
+    let body = read_bounded_response_body(response, provider)
+        .await
+        .map_err(|error| classify_response_body_error(status, error))?;
+    Ok((status, body))
+}
+
+pub(crate) async fn read_bounded_response_body(
+    response: reqwest::Response,
+    provider: &str,
+) -> Result<String, TextTranslationError> {
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_TEXT_TRANSLATION_RESPONSE_BYTES as u64)
+    {
+        return Err(oversized_response_error(provider));
+    }
+
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk
+            .map_err(|error| TextTranslationError::Connection(format_reqwest_error(&error)))?;
+        append_bounded_response_chunk(&mut body, &chunk, provider)?;
+    }
+
+    String::from_utf8(body).map_err(|error| {
+        TextTranslationError::Protocol(format!(
+            "{} response is not valid UTF-8: {}",
+            provider, error
+        ))
+    })
+}
+
+fn append_bounded_response_chunk(
+    body: &mut Vec<u8>,
+    chunk: &[u8],
+    provider: &str,
+) -> Result<(), TextTranslationError> {
+    if body.len().saturating_add(chunk.len()) > MAX_TEXT_TRANSLATION_RESPONSE_BYTES {
+        return Err(oversized_response_error(provider));
+    }
+    body.extend_from_slice(chunk);
+    Ok(())
+}
+
+fn oversized_response_error(provider: &str) -> TextTranslationError {
+    TextTranslationError::Protocol(format!(
+        "{} response exceeds {} bytes",
+        provider, MAX_TEXT_TRANSLATION_RESPONSE_BYTES
+    ))
+}
+
+pub(crate) fn classify_response_body_error(
+    status: StatusCode,
+    error: TextTranslationError,
+) -> TextTranslationError {
+    match error {
+        TextTranslationError::Protocol(message) if !status.is_success() => {
+            map_http_error(status, message)
+        }
+        error => error,
+    }
+}
+
+pub(crate) fn map_http_error(status: StatusCode, message: String) -> TextTranslationError {
+    let lower_message = message.to_lowercase();
+    if status == StatusCode::UNAUTHORIZED
+        || status == StatusCode::FORBIDDEN
+        || lower_message.contains("invalid api key")
+        || lower_message.contains("unauthorized")
+    {
+        return TextTranslationError::Authentication(message);
+    }
+
+    if status == StatusCode::TOO_MANY_REQUESTS
+        || status.as_u16() == STATUS_QUOTA_EXCEEDED
+        || lower_message.contains("rate limit")
+        || lower_message.contains("quota")
+        || lower_message.contains("billing")
+        || lower_message.contains("maximum monthly spend")
+    {
+        return TextTranslationError::RateLimited(message);
+    }
+
+    TextTranslationError::Connection(message)
+}
+
+pub(crate) fn format_reqwest_error(err: &reqwest::Error) -> String {
+    let mut parts = vec![err.to_string()];
+
+    if err.is_timeout() {
+        parts.push("kind=timeout".to_string());
+    }
+    if err.is_connect() {
+        parts.push("kind=connect".to_string());
+    }
+    if err.is_request() {
+        parts.push("kind=request".to_string());
+    }
+    if err.is_body() {
+        parts.push("kind=body".to_string());
+    }
+
+    let mut source = err.source();
+    while let Some(cause) = source {
+        parts.push(format!("cause={}", cause));
+        source = cause.source();
+    }
+
+    parts.join("; ")
+}
+
+/// Base URL без пробелов и завершающего `/`; к нему дописывается путь API.
+/// Если пользователь вставил полный URL с этим путём — берём как есть.
+pub(crate) fn resolve_api_url(endpoint: Option<&str>, default_base: &str, path: &str) -> String {
+    let base = endpoint
+        .map(|value| value.trim().trim_end_matches('/'))
+        .filter(|value| !value.is_empty())
+        .unwrap_or(default_base);
+
+    if base.ends_with(path) {
+        base.to_string()
+    } else {
+        format!("{}{}", base, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_quota_message_to_rate_limited_even_without_429_status() {
+        let err = map_http_error(
+            StatusCode::BAD_REQUEST,
+            "You exceeded your current quota, please check your billing details".to_string(),
+        );
+
+        assert!(matches!(err, TextTranslationError::RateLimited(_)));
+    }
+
+    #[test]
+    fn maps_deepl_quota_status_to_rate_limited() {
+        let err = map_http_error(
+            StatusCode::from_u16(STATUS_QUOTA_EXCEEDED).unwrap(),
+            "Quota for this billing period has been exceeded".to_string(),
+        );
+
+        assert!(matches!(err, TextTranslationError::RateLimited(_)));
+    }
+
+    #[test]
+    fn maps_auth_message_to_authentication_even_without_401_status() {
+        let err = map_http_error(
+            StatusCode::BAD_REQUEST,
+            "Invalid API key provided".to_string(),
+        );
+
+        assert!(matches!(err, TextTranslationError::Authentication(_)));
+    }
+
+    #[test]
+    fn bounded_response_chunks_reject_streamed_overflow() {
+        let mut body = vec![0; MAX_TEXT_TRANSLATION_RESPONSE_BYTES - 1];
+
+        append_bounded_response_chunk(&mut body, &[1], "OpenAI").expect("exact limit is accepted");
+        let error = append_bounded_response_chunk(&mut body, &[2], "OpenAI").unwrap_err();
+
+        assert!(matches!(error, TextTranslationError::Protocol(_)));
+        assert_eq!(body.len(), MAX_TEXT_TRANSLATION_RESPONSE_BYTES);
+    }
+
+    #[test]
+    fn oversized_error_body_keeps_http_auth_and_rate_limit_types() {
+        assert!(matches!(
+            classify_response_body_error(
+                StatusCode::UNAUTHORIZED,
+                TextTranslationError::Protocol("oversized".to_string())
+            ),
+            TextTranslationError::Authentication(_)
+        ));
+        assert!(matches!(
+            classify_response_body_error(
+                StatusCode::TOO_MANY_REQUESTS,
+                TextTranslationError::Protocol("oversized".to_string())
+            ),
+            TextTranslationError::RateLimited(_)
+        ));
+    }
+
+    #[test]
+    fn api_url_accepts_base_url_or_full_url() {
+        assert_eq!(
+            resolve_api_url(None, "http://localhost:5000", "/translate"),
+            "http://localhost:5000/translate"
+        );
+        assert_eq!(
+            resolve_api_url(Some(" https://lt.example.com/ "), "x", "/translate"),
+            "https://lt.example.com/translate"
+        );
+        assert_eq!(
+            resolve_api_url(
+                Some("http://127.0.0.1:11434/v1/chat/completions"),
+                "x",
+                "/chat/completions"
+            ),
+            "http://127.0.0.1:11434/v1/chat/completions"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::http::{
+    format_reqwest_error, map_http_error, read_response_body, resolve_api_url,
+    TEXT_TRANSLATION_TIMEOUT,
+};
+use crate::domain::{TextTranslationError, TextTranslator};
+
+/// Порт `libretranslate` по умолчанию: без endpoint считаем, что сервер поднят локально
+const DEFAULT_LIBRETRANSLATE_URL: &str = "http://localhost:5000";
+
+/// LibreTranslate-совместимый `/translate` (сам LibreTranslate, его форки и прокси)
+pub struct LibreTranslateTextTranslator {
+    api_key: Option<String>,
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl LibreTranslateTextTranslator {
+    pub fn new(api_key: String, endpoint: Option<&str>) -> Result<Self, TextTranslationError> {
+        let api_key = api_key.trim();
+        let client = reqwest::Client::builder()
+            .timeout(TEXT_TRANSLATION_TIMEOUT)
+            .build()
+            .map_err(|e| TextTranslationError::Connection(format_reqwest_error(&e)))?;
+
+        Ok(Self {
+            api_key: (!api_key.is_empty()).then(|| api_key.to_string()),
+            endpoint: resolve_api_url(endpoint, DEFAULT_LIBRETRANSLATE_URL, "/translate"),
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl TextTranslator for LibreTranslateTextTranslator {
+    async fn translate_text(
+        &self,
+        text: &str,
+        target_language: &str,
+    ) -> Result<String, TextTranslationError> {
+        let input = text.trim();
+        if input.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut body = json!({
+            "q": input,
+            "source": "auto",
+            "target": libretranslate_target_language(target_language),
+            "format": "text",
+        });
+        if let Some(api_key) = &self.api_key {
+            body["api_key"] = json!(api_key);
+        }
+
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| TextTranslationError::Connection(format_reqwest_error(&e)))?;
+
+        let (status, text_body) = read_response_body(response, "LibreTranslate").await?;
+        let parsed: LibreTranslateResponse = match serde_json::from_str(&text_body) {
+            Ok(parsed) => parsed,
+            Err(_) if !status.is_success() => {
+                return Err(map_http_error(
+                    status,
+                    format!("LibreTranslate HTTP {}", status.as_u16()),
+                ));
+            }
+            Err(e) => {
+                return Err(TextTranslationError::Protocol(format!(
+                    "invalid LibreTranslate response: {}",
+                    e
+                )));
+            }
+        };
+
+        if let Some(error) = parsed.error.filter(|error| !error.trim().is_empty()) {
+            return Err(map_http_error(status, error.trim().to_string()));
+        }
+
+        parsed
+            .translated_text
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .ok_or_else(|| {
+                TextTranslationError::Protocol(
+                    "LibreTranslate response has no translatedText".to_string(),
+                )
+            })
+    }
+}
+
+/// LibreTranslate знает базовые коды ("pt", "zh"), без регионального варианта
+fn libretranslate_target_language(value: &str) -> String {
+    let language = value.trim().to_lowercase();
+    match language.split(['-', '_']).next() {
+        Some(base) if !base.is_empty() => base.to_string(),
+        _ => language,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LibreTranslateResponse {
+    #[serde(default, rename = "translatedText")]
+    translated_text: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_language_is_reduced_to_base_code() {
+        assert_eq!(libretranslate_target_language(" DE "), "de");
+        assert_eq!(libretranslate_target_language("pt-BR"), "pt");
+        assert_eq!(libretranslate_target_language("zh_Hans"), "zh");
+    }
+
+    #[test]
+    fn defaults_to_local_server_without_key() {
+        let translator = LibreTranslateTextTranslator::new(" ".to_string(), None).unwrap();
+
+        assert_eq!(translator.endpoint, "http://localhost:5000/translate");
+        assert_eq!(translator.api_key, None);
+    }
+
+    #[test]
+    fn parses_error_body() {
+        let parsed: LibreTranslateResponse =
+            serde_json::from_str(r#"{"error":"Invalid API key"}"#).unwrap();
+
+        assert_eq!(parsed.error.as_deref(), Some("Invalid API key"));
+        assert_eq!(parsed.translated_text, None);
+    }
+}
@@ -0,0 +1,94 @@
+//! Адаптеры текстового перевода (порт `TextTranslator`): OpenAI, DeepL,
+//! LibreTranslate и OpenAI-compatible chat completions.
+
+mod deepl;
+pub(crate) mod http;
+mod libretranslate;
+mod openai_compatible;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use deepl::DeepLTextTranslator;
+pub use libretranslate::LibreTranslateTextTranslator;
+pub use openai_compatible::OpenAICompatibleTextTranslator;
+
+use self::http::TEXT_TRANSLATION_TIMEOUT;
+use crate::domain::{
+    LlmTextClient, TextTranslationConfig, TextTranslationError, TextTranslationProviderType,
+    TextTranslator, TextTranslatorFactory, TranslationGlossaryConfig,
+};
+use crate::infrastructure::openai::OpenAITextTranslationClient;
+
+/// Создаёт переводчик выбранного в `text_translation` провайдера.
+/// Глоссарий уходит только в промпт LLM-провайдеров; для DeepL и LibreTranslate
+/// его применяет пост-проверка на стороне сервиса.
+#[derive(Default)]
+pub struct DefaultTextTranslatorFactory;
+
+impl TextTranslatorFactory for DefaultTextTranslatorFactory {
+    fn create(
+        &self,
+        config: &TextTranslationConfig,
+        api_key: String,
+        glossary: &TranslationGlossaryConfig,
+    ) -> Result<Arc<dyn TextTranslator>, TextTranslationError> {
+        let endpoint = config.custom_endpoint();
+        let translator: Arc<dyn TextTranslator> = match config.provider {
+            TextTranslationProviderType::OpenAI => Arc::new(
+                OpenAITextTranslationClient::with_endpoint(
+                    api_key,
+                    endpoint,
+                    config.custom_model(),
+                    TEXT_TRANSLATION_TIMEOUT,
+                )?
+                .with_glossary(glossary.clone()),
+            ),
+            TextTranslationProviderType::DeepL => {
+                Arc::new(DeepLTextTranslator::new(api_key, endpoint)?)
+            }
+            TextTranslationProviderType::LibreTranslate => {
+                Arc::new(LibreTranslateTextTranslator::new(api_key, endpoint)?)
+            }
+            TextTranslationProviderType::OpenAICompatible => Arc::new(
+                OpenAICompatibleTextTranslator::new(api_key, endpoint, config.custom_model())?
+                    .with_glossary(glossary.clone()),
+            ),
+        };
+        Ok(translator)
+    }
+}
+
+/// LLM-клиент OpenAI или OpenAI-compatible сервера для произвольных инструкций
+/// (переписывание диктовки). `api_key` уже разрешён вызывающим.
+pub fn create_llm_text_client(
+    config: &TextTranslationConfig,
+    api_key: String,
+    timeout: Duration,
+) -> Result<Arc<dyn LlmTextClient>, TextTranslationError> {
+    let endpoint = config.custom_endpoint();
+    match config.provider {
+        TextTranslationProviderType::OpenAI => {
+            Ok(Arc::new(OpenAITextTranslationClient::with_endpoint(
+                api_key,
+                endpoint,
+                config.custom_model(),
+                timeout,
+            )?))
+        }
+        TextTranslationProviderType::OpenAICompatible => {
+            Ok(Arc::new(OpenAICompatibleTextTranslator::with_timeout(
+                api_key,
+                endpoint,
+                config.custom_model(),
+                timeout,
+            )?))
+        }
+        TextTranslationProviderType::DeepL | TextTranslationProviderType::LibreTranslate => {
+            Err(TextTranslationError::Protocol(format!(
+                "{:?} умеет только переводить, LLM-инструкции ему не передать",
+                config.provider
+            )))
+        }
+    }
+}
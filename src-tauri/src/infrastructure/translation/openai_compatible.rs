@@ -0,0 +1,209 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+use super::http::{
+    format_reqwest_error, map_http_error, read_response_body, resolve_api_url,
+    TEXT_TRANSLATION_TIMEOUT,
+};
+use crate::domain::{
+    ContextualTranslation, LlmTextClient, TextTranslationError, TextTranslator,
+    TranslationContextSegment, TranslationGlossaryConfig,
+};
+use crate::infrastructure::openai::text_translation::{
+    contextual_translation_input, contextual_translation_instructions,
+    extract_openai_error_message, parse_contextual_translation, translation_instructions,
+};
+
+/// Ollama по умолчанию; vLLM/LM Studio задаются через endpoint
+const DEFAULT_OPENAI_COMPATIBLE_URL: &str = "http://localhost:11434/v1";
+
+/// Перевод через `/v1/chat/completions` любого OpenAI-совместимого сервера
+pub struct OpenAICompatibleTextTranslator {
+    api_key: Option<String>,
+    endpoint: String,
+    model: String,
+    glossary: TranslationGlossaryConfig,
+    client: reqwest::Client,
+}
+
+impl OpenAICompatibleTextTranslator {
+    /// `model` обязателен: у локальных серверов нет общей модели по умолчанию
+    pub fn new(
+        api_key: String,
+        endpoint: Option<&str>,
+        model: Option<&str>,
+    ) -> Result<Self, TextTranslationError> {
+        Self::with_timeout(api_key, endpoint, model, TEXT_TRANSLATION_TIMEOUT)
+    }
+
+    pub fn with_timeout(
+        api_key: String,
+        endpoint: Option<&str>,
+        model: Option<&str>,
+        timeout: Duration,
+    ) -> Result<Self, TextTranslationError> {
+        let model = model
+            .map(str::trim)
+            .filter(|model| !model.is_empty())
+            .ok_or_else(|| {
+                TextTranslationError::Protocol(
+                    "text_translation.model обязателен для openai_compatible".to_string(),
+                )
+            })?;
+        let api_key = api_key.trim();
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| TextTranslationError::Connection(format_reqwest_error(&e)))?;
+
+        Ok(Self {
+            api_key: (!api_key.is_empty()).then(|| api_key.to_string()),
+            endpoint: resolve_api_url(endpoint, DEFAULT_OPENAI_COMPATIBLE_URL, "/chat/completions"),
+            model: model.to_string(),
+            glossary: TranslationGlossaryConfig::default(),
+            client,
+        })
+    }
+
+    pub fn with_glossary(mut self, glossary: TranslationGlossaryConfig) -> Self {
+        self.glossary = glossary;
+        self
+    }
+}
+
+#[async_trait]
+impl LlmTextClient for OpenAICompatibleTextTranslator {
+    /// Один chat-запрос: `instructions` как system, `input` как user
+    async fn complete(
+        &self,
+        instructions: &str,
+        input: &str,
+    ) -> Result<String, TextTranslationError> {
+        let body = json!({
+            "model": self.model,
+            "stream": false,
+            "messages": [
+                { "role": "system", "content": instructions },
+                { "role": "user", "content": input },
+            ],
+        });
+        let mut request = self.client.post(&self.endpoint).json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| TextTranslationError::Connection(format_reqwest_error(&e)))?;
+
+        let (status, text_body) = read_response_body(response, "Chat completions").await?;
+        if !status.is_success() {
+            let message = extract_openai_error_message(&text_body)
+                .unwrap_or_else(|| format!("Chat completions HTTP {}", status.as_u16()));
+            return Err(map_http_error(status, message));
+        }
+
+        let parsed: ChatCompletionResponse = serde_json::from_str(&text_body).map_err(|e| {
+            TextTranslationError::Protocol(format!("invalid chat completions response: {}", e))
+        })?;
+        extract_chat_text(parsed).ok_or_else(|| {
+            TextTranslationError::Protocol("chat completions response has no content".to_string())
+        })
+    }
+}
+
+#[async_trait]
+impl TextTranslator for OpenAICompatibleTextTranslator {
+    async fn translate_text(
+        &self,
+        text: &str,
+        target_language: &str,
+    ) -> Result<String, TextTranslationError> {
+        let input = text.trim();
+        if input.is_empty() {
+            return Ok(String::new());
+        }
+        self.complete(
+            &translation_instructions(target_language, &self.glossary),
+            input,
+        )
+        .await
+    }
+
+    async fn translate_with_context(
+        &self,
+        text: &str,
+        target_language: &str,
+        context: &[TranslationContextSegment],
+    ) -> Result<ContextualTranslation, TextTranslationError> {
+        if context.is_empty() || text.trim().is_empty() {
+            return self
+                .translate_text(text, target_language)
+                .await
+                .map(ContextualTranslation::standalone);
+        }
+        let output = self
+            .complete(
+                &contextual_translation_instructions(target_language, &self.glossary),
+                &contextual_translation_input(context, text),
+            )
+            .await?;
+        Ok(parse_contextual_translation(output))
+    }
+}
+
+fn extract_chat_text(response: ChatCompletionResponse) -> Option<String> {
+    response
+        .choices
+        .into_iter()
+        .filter_map(|choice| choice.message.content)
+        .map(|content| content.trim().to_string())
+        .find(|content| !content.is_empty())
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_first_non_empty_choice() {
+        let parsed: ChatCompletionResponse = serde_json::from_str(
+            r#"{"choices":[{"message":{"content":"  "}},{"message":{"role":"assistant","content":" Hallo \n"}}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(extract_chat_text(parsed).as_deref(), Some("Hallo"));
+    }
+
+    #[test]
+    fn requires_model_and_defaults_to_ollama() {
+        assert!(OpenAICompatibleTextTranslator::new(String::new(), None, Some(" ")).is_err());
+
+        let translator =
+            OpenAICompatibleTextTranslator::new(String::new(), None, Some("llama3.1")).unwrap();
+        assert_eq!(
+            translator.endpoint,
+            "http://localhost:11434/v1/chat/completions"
+        );
+        assert_eq!(translator.api_key, None);
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::domain::{
+    TranslationMemory, TranslationMemoryConfig, TranslationMemoryKey, TranslationMemoryStats,
+    MAX_TRANSLATION_MEMORY_ENTRIES,
+};
+use crate::infrastructure::{ConfigStore, HistoryStore};
+
+const TRANSLATION_MEMORY_FILE_NAME: &str = "translation_memory.json";
+
+/// Сколько изменений (новых переводов и попаданий) копим до записи файла
+const FLUSH_AFTER_CHANGES: usize = 20;
+
+/// Формат файла: записи от давно использованных к свежим, счётчики попаданий
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TranslationMemoryFile {
+    #[serde(default)]
+    hits: u64,
+    #[serde(default)]
+    misses: u64,
+    #[serde(default)]
+    entries: Vec<StoredTranslation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredTranslation {
+    #[serde(flatten)]
+    key: TranslationMemoryKey,
+    translation: String,
+}
+
+/// LRU в памяти: `order` — тик последнего использования → ключ, самый старый первым
+#[derive(Debug, Default)]
+struct MemoryState {
+    entries: HashMap<TranslationMemoryKey, (String, u64)>,
+    order: BTreeMap<u64, TranslationMemoryKey>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+    unsaved_changes: usize,
+}
+
+impl MemoryState {
+    fn from_file(file: TranslationMemoryFile) -> Self {
+        let mut state = Self {
+            hits: file.hits,
+            misses: file.misses,
+            ..Self::default()
+        };
+        for stored in file.entries {
+            state.insert(stored.key, stored.translation);
+        }
+        state
+    }
+
+    fn to_file(&self) -> TranslationMemoryFile {
+        TranslationMemoryFile {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self
+                .order
+                .values()
+                .filter_map(|key| {
+                    self.entries
+                        .get(key)
+                        .map(|(translation, _)| StoredTranslation {
+                            key: key.clone(),
+                            translation: translation.clone(),
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn insert(&mut self, key: TranslationMemoryKey, translation: String) {
+        let tick = self.next_tick();
+        if let Some((_, old_tick)) = self.entries.insert(key.clone(), (translation, tick)) {
+            self.order.remove(&old_tick);
+        }
+        self.order.insert(tick, key);
+    }
+
+    fn touch(&mut self, key: &TranslationMemoryKey) -> Option<String> {
+        let tick = self.next_tick();
+        let (translation, last_used) = self.entries.get_mut(key)?;
+        let old_tick = std::mem::replace(last_used, tick);
+        let translation = translation.clone();
+        self.order.remove(&old_tick);
+        self.order.insert(tick, key.clone());
+        Some(translation)
+    }
+
+    /// Вытесняет давно не использованные записи сверх `capacity`
+    fn evict_to(&mut self, capacity: usize) -> usize {
+        let mut evicted = 0;
+        while self.entries.len() > capacity {
+            let Some((_, key)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&key);
+            evicted += 1;
+        }
+        evicted
+    }
+}
+
+/// Персистентная память переводов входящих субтитров (JSON в app data директории).
+///
+/// Файл читается лениво и переписывается атомарно пачками изменений и в конце сессии,
+/// а не на каждый субтитр.
+pub struct TranslationMemoryStore {
+    /// None → только в памяти (не удалось определить data dir)
+    path: Option<PathBuf>,
+    /// None → файл ещё не прочитан
+    state: Mutex<Option<MemoryState>>,
+    capacity: AtomicUsize,
+}
+
+impl TranslationMemoryStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path: Some(path),
+            state: Mutex::new(None),
+            capacity: TranslationMemoryConfig::default().max_entries.into(),
+        }
+    }
+
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            state: Mutex::new(Some(MemoryState::default())),
+            capacity: TranslationMemoryConfig::default().max_entries.into(),
+        }
+    }
+
+    /// Рядом с историей диктовок; при ошибке — in-memory фоллбек
+    pub fn open_default() -> Self {
+        match HistoryStore::default_path() {
+            Ok(path) => Self::new(path.with_file_name(TRANSLATION_MEMORY_FILE_NAME)),
+            Err(e) => {
+                log::warn!(
+                    "Failed to resolve translation memory path, it will not persist: {}",
+                    e
+                );
+                Self::in_memory()
+            }
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    async fn read_file(path: &Path) -> Result<TranslationMemoryFile> {
+        let contents = match tokio::fs::read_to_string(path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(TranslationMemoryFile::default())
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Загружает файл при первом обращении; битый файл не должен ломать перевод субтитров
+    async fn loaded<'a>(
+        &self,
+        guard: &'a mut MutexGuard<'_, Option<MemoryState>>,
+    ) -> &'a mut MemoryState {
+        if guard.is_none() {
+            let file = match self.path.as_deref() {
+                Some(path) => Self::read_file(path).await.unwrap_or_else(|e| {
+                    log::warn!(
+                        "Failed to read translation memory {:?}, starting empty: {}",
+                        path,
+                        e
+                    );
+                    TranslationMemoryFile::default()
+                }),
+                None => TranslationMemoryFile::default(),
+            };
+            let mut state = MemoryState::from_file(file);
+            state.evict_to(self.capacity());
+            **guard = Some(state);
+        }
+        guard.get_or_insert_with(MemoryState::default)
+    }
+
+    async fn save(&self, state: &mut MemoryState) -> Result<()> {
+        state.unsaved_changes = 0;
+        let Some(path) = self.path.as_deref() else {
+            return Ok(());
+        };
+        let contents = serde_json::to_string(&state.to_file())?;
+        ConfigStore::write_file_atomic(path, &contents).await
+    }
+
+    async fn save_if_due(&self, state: &mut MemoryState) {
+        state.unsaved_changes += 1;
+        if state.unsaved_changes >= FLUSH_AFTER_CHANGES {
+            if let Err(e) = self.save(state).await {
+                log::warn!("Failed to save translation memory: {}", e);
+            }
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+            .load(Ordering::Relaxed)
+            .min(MAX_TRANSLATION_MEMORY_ENTRIES)
+    }
+
+    /// Новый лимит записей; лишние записи вытесняются сразу
+    pub async fn set_capacity(&self, max_entries: usize) -> Result<()> {
+        self.capacity.store(max_entries, Ordering::Relaxed);
+        let mut guard = self.state.lock().await;
+        let capacity = self.capacity();
+        let state = self.loaded(&mut guard).await;
+        if state.evict_to(capacity) > 0 {
+            self.save(state).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn stats(&self) -> TranslationMemoryStats {
+        let mut guard = self.state.lock().await;
+        let capacity = self.capacity();
+        let state = self.loaded(&mut guard).await;
+        TranslationMemoryStats::new(state.entries.len(), capacity, state.hits, state.misses)
+    }
+
+    /// Удаляет все переводы и обнуляет статистику
+    pub async fn clear(&self) -> Result<()> {
+        let mut guard = self.state.lock().await;
+        let state = guard.insert(MemoryState::default());
+        self.save(state).await
+    }
+}
+
+#[async_trait]
+impl TranslationMemory for TranslationMemoryStore {
+    async fn lookup(&self, key: &TranslationMemoryKey) -> Option<String> {
+        let mut guard = self.state.lock().await;
+        let state = self.loaded(&mut guard).await;
+        match state.touch(key) {
+            Some(translation) => {
+                state.hits += 1;
+                self.save_if_due(state).await;
+                Some(translation)
+            }
+            None => {
+                state.misses += 1;
+                None
+            }
+        }
+    }
+
+    async fn remember(&self, key: TranslationMemoryKey, translation: String) {
+        let translation = translation.trim();
+        if translation.is_empty() {
+            return;
+        }
+        let mut guard = self.state.lock().await;
+        let capacity = self.capacity();
+        let state = self.loaded(&mut guard).await;
+        state.insert(key, translation.to_string());
+        state.evict_to(capacity);
+        self.save_if_due(state).await;
+    }
+
+    async fn flush(&self) {
+        let mut guard = self.state.lock().await;
+        let Some(state) = guard.as_mut() else {
+            return;
+        };
+        if state.unsaved_changes == 0 {
+            return;
+        }
+        if let Err(e) = self.save(state).await {
+            log::warn!("Failed to save translation memory: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    struct TestMemoryFile {
+        dir: PathBuf,
+    }
+
+    impl TestMemoryFile {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "voice-to-text-translation-memory-{}",
+                Uuid::new_v4()
+            ));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self { dir }
+        }
+
+        fn path(&self) -> PathBuf {
+            self.dir.join(TRANSLATION_MEMORY_FILE_NAME)
+        }
+    }
+
+    impl Drop for TestMemoryFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn key(text: &str) -> TranslationMemoryKey {
+        TranslationMemoryKey::new(text, "auto", "ru", "openai:default@default").unwrap()
+    }
+
+    #[tokio::test]
+    async fn persists_entries_and_stats_across_instances() {
+        let file = TestMemoryFile::new();
+        let store = TranslationMemoryStore::new(file.path());
+
+        assert_eq!(store.lookup(&key("Can you see my screen?")).await, None);
+        store
+            .remember(
+                key("Can you see my screen?"),
+                "Вы видите мой экран?".to_string(),
+            )
+            .await;
+        assert_eq!(
+            store
+                .lookup(&key("can you see  my screen?"))
+                .await
+                .as_deref(),
+            Some("Вы видите мой экран?")
+        );
+        store.flush().await;
+
+        let reopened = TranslationMemoryStore::new(file.path());
+        assert_eq!(
+            reopened.stats().await,
+            TranslationMemoryStats::new(1, 5_000, 1, 1)
+        );
+        assert!(reopened
+            .lookup(&key("CAN YOU SEE MY SCREEN?"))
+            .await
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used() {
+        let store = TranslationMemoryStore::in_memory();
+        store.set_capacity(2).await.unwrap();
+
+        store.remember(key("one"), "раз".to_string()).await;
+        store.remember(key("two"), "два".to_string()).await;
+        assert!(store.lookup(&key("one")).await.is_some());
+        store.remember(key("three"), "три".to_string()).await;
+
+        assert!(store.lookup(&key("two")).await.is_none());
+        assert!(store.lookup(&key("one")).await.is_some());
+        assert!(store.lookup(&key("three")).await.is_some());
+
+        store.set_capacity(1).await.unwrap();
+        assert_eq!(store.stats().await.entries, 1);
+        assert!(store.lookup(&key("three")).await.is_some());
+
+        store.clear().await.unwrap();
+        assert_eq!(store.stats().await, TranslationMemoryStats::new(0, 1, 0, 0));
+    }
+}
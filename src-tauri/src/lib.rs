@@ -205,6 +205,14 @@ pub fn run() {
             commands::get_ui_preferences_snapshot,
             commands::update_ui_preferences,
             commands::update_app_config,
+            commands::get_history,
+            commands::delete_history_item,
+            commands::clear_history,
+            commands::export_session_transcript,
+            commands::export_history,
+            commands::transcribe_audio_file,
+            commands::get_control_api_status,
+            commands::update_control_api_config,
             commands::start_microphone_test,
             commands::stop_microphone_test,
             commands::register_recording_hotkey,
@@ -222,6 +230,11 @@ pub fn run() {
             commands::check_accessibility_permission,
             commands::request_accessibility_permission,
             commands::auto_paste_text,
+            commands::delete_pasted_text,
+            commands::rewrite_dictation_text,
+            commands::import_translation_glossary_csv,
+            commands::get_translation_memory_stats,
+            commands::clear_translation_memory,
             commands::copy_to_clipboard_native,
             commands::show_auth_window,
             commands::show_recording_window,
@@ -754,6 +767,20 @@ pub fn run() {
                             log::error!("Failed to start Double-Space hotkey listener: {}", e);
                         }
                     }
+
+                    // Control API поднимаем только после загрузки app-config (он там же и включается)
+                    let control_api = state.config.read().await.control_api.clone();
+                    if control_api.enabled {
+                        if let Err(e) = presentation::control_api::apply_control_api_config(
+                            state.inner(),
+                            &app_handle,
+                            &control_api,
+                        )
+                        .await
+                        {
+                            log::error!("Failed to start control API: {}", e);
+                        }
+                    }
                 }
             });
 
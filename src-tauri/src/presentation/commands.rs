@@ -5,17 +5,39 @@ use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow, Window};
 
+use crate::application::services::{
+    import_glossary_csv, render_transcript, segments_from_history,
+    validate_caption_translation_context_config, validate_dictation_rewrite_config,
+    validate_post_processing_config, validate_spoken_commands_config,
+    validate_text_translation_config, validate_translation_glossary_config,
+    validate_translation_memory_config, DictationRewriteOutcome, DictationRewriteService,
+    DictationTranslator, GlossaryImportSummary, SessionTranscriptKind, SpokenCommandInterpreter,
+    TranscriptPostProcessingChain,
+};
+use crate::application::TranscriptionService;
 use crate::domain::{
     incoming_translation_volume_gain, AppConfig, AudioCapture, AudioCaptureTarget, AudioConfig,
-    AudioError, BackendStreamingProvider, IncomingTranslationDelivery, PlatformAudioFactory,
-    PlatformAudioSetupState, PlatformAudioSetupStatus, RecordingMode, RecordingStatus,
-    RecordingWindowPosition, SttConfig, SttConnectionCategory, SttError, SttProviderType,
-    Transcription, TranslationAudioOutputConfig,
+    AudioError, BackendStreamingProvider, CaptionTranslationContextConfig, DictateTranslateConfig,
+    DictationRewriteConfig, HistoryEntry, HistoryPage, HistoryQuery, HistoryRange,
+    IncomingTranslationDelivery, PlatformAudioFactory, PlatformAudioSetupState,
+    PlatformAudioSetupStatus, RecordingMode, RecordingStatus, RecordingWindowPosition,
+    SpokenCommandsConfig, SttConfig, SttConnectionCategory, SttError, SttProviderType,
+    SubtitleCueOptions, TextTranslationConfig, TextTranslationProviderType, TextTranslatorFactory,
+    TranscriptExportFormat, TranscriptPostProcessingConfig, TranscriptSegment, Transcription,
+    TranslationAudioOutputConfig, TranslationGlossaryConfig, TranslationMemory,
+    TranslationMemoryConfig, TranslationMemoryStats,
 };
 use crate::infrastructure::{
-    audio::DefaultPlatformAudioFactory, auto_paste::AutoPasteTarget,
-    openai::OpenAITextTranslationClient, AuthSession, AuthStore, AuthUser, ConfigStore,
+    audio::{
+        is_supported_audio_file, DefaultPlatformAudioFactory, FileAudioCapture, FileCaptureOutcome,
+        SUPPORTED_AUDIO_FILE_EXTENSIONS,
+    },
+    auto_paste::AutoPasteTarget,
+    openai::OpenAITextTranslationClient,
+    translation::DefaultTextTranslatorFactory,
+    AuthSession, AuthStore, AuthUser, ConfigStore, DefaultSttProviderFactory,
 };
+use crate::presentation::control_api::{apply_control_api_config, generate_control_api_token};
 use crate::presentation::{
     events::*, AppState, AudioLevelPayload, ConnectionQualityPayload, FinalTranscriptionPayload,
     MicrophoneTestLevelPayload, PartialTranscriptionPayload, RecordingStatusPayload,
@@ -270,7 +292,8 @@ fn should_clear_active_mode_after_dictation_failure(
     current_session_id: u64,
     active_mode: Option<RecordingMode>,
 ) -> bool {
-    failed_session_id == current_session_id && matches!(active_mode, Some(RecordingMode::Dictation))
+    failed_session_id == current_session_id
+        && active_mode.is_some_and(RecordingMode::uses_dictation_pipeline)
 }
 
 async fn clear_dictation_failure_state_if_current(state: &AppState, session_id: u64) {
@@ -294,7 +317,7 @@ async fn clear_dictation_failure_state_if_current(state: &AppState, session_id:
 
     if clear_active_transcription_session_id_if_current(state, session_id) {
         let mut active_mode = state.active_recording_mode.write().await;
-        if matches!(*active_mode, Some(RecordingMode::Dictation)) {
+        if active_mode.is_some_and(RecordingMode::uses_dictation_pipeline) {
             *active_mode = None;
         }
     }
@@ -976,6 +999,37 @@ fn resolve_openai_api_key(config: &AppConfig) -> String {
         .unwrap_or_else(|| std::env::var("OPENAI_API_KEY").unwrap_or_default())
 }
 
+/// OpenAI realtime диктовка использует общий `openai_api_key` из AppConfig.
+/// Возвращает новое значение ключа для STT конфига, если его нужно обновить.
+fn openai_dictation_key_update(stt: &SttConfig, app_config: &AppConfig) -> Option<Option<String>> {
+    // Ключ нужен и когда OpenAI Realtime стоит только резервным звеном цепочки
+    let uses_openai_realtime = stt
+        .failover_chain()
+        .iter()
+        .any(|link| link.provider == SttProviderType::OpenAIRealtime);
+    if !uses_openai_realtime {
+        return None;
+    }
+    let key = Some(resolve_openai_api_key(app_config)).filter(|key| !key.is_empty());
+    (stt.openai_api_key != key).then_some(key)
+}
+
+async fn sync_openai_dictation_key(state: &AppState) {
+    let mut stt_config = state.transcription_service.get_config().await;
+    let update = {
+        let app_config = state.config.read().await;
+        openai_dictation_key_update(&stt_config, &app_config)
+    };
+    let Some(key) = update else {
+        return;
+    };
+
+    stt_config.openai_api_key = key;
+    if let Err(e) = state.transcription_service.update_config(stt_config).await {
+        log::warn!("Failed to apply OpenAI API key to STT config: {}", e);
+    }
+}
+
 fn normalize_translation_target_language(value: &str, fallback: &str) -> String {
     let language = value.trim();
     if language.is_empty()
@@ -997,6 +1051,14 @@ fn resolve_outgoing_translation_target_language(config: &AppConfig) -> String {
     }
 }
 
+fn resolve_dictate_translate_target_language(config: &AppConfig) -> String {
+    let fallback = resolve_outgoing_translation_target_language(config);
+    match config.dictate_translate.target_language.as_deref() {
+        Some(language) => normalize_translation_target_language(language, &fallback),
+        None => fallback,
+    }
+}
+
 fn resolve_incoming_translation_target_language(config: &AppConfig) -> String {
     normalize_translation_target_language(&config.stt.language, "ru")
 }
@@ -1057,14 +1119,27 @@ async fn start_live_translation_recording(
         session_id,
     };
 
+    state.session_transcripts.begin(
+        SessionTranscriptKind::LiveTranslation,
+        session_id,
+        now_ms_u64() as i64,
+    );
+
     // Callbacks: emit события во фронт
     let app_handle_transcript = app_handle.clone();
+    let session_transcripts = state.session_transcripts.clone();
     let on_transcript_delta: std::sync::Arc<dyn Fn(String) + Send + Sync> =
         std::sync::Arc::new(move |text: String| {
+            let timestamp = now_ms_u64();
+            session_transcripts.update(
+                SessionTranscriptKind::LiveTranslation,
+                session_id,
+                |session| session.push_translation_delta(&text, timestamp as i64),
+            );
             let payload = crate::presentation::events::TranslationDeltaPayload {
                 session_id,
                 text,
-                timestamp: now_ms_u64(),
+                timestamp,
             };
             if let Err(e) = app_handle_transcript.emit(EVENT_TRANSLATION_DELTA, payload) {
                 log::error!("Failed to emit translation delta: {}", e);
@@ -1301,11 +1376,13 @@ async fn start_incoming_translation_inner(
     app_handle: &AppHandle,
 ) -> Result<String, String> {
     use crate::application::services::{
-        IncomingTranslationCallbacks, IncomingTranslationConfig, IncomingTranslationError,
+        CaptionRevision, IncomingTranslationCallbacks, IncomingTranslationConfig,
+        IncomingTranslationError,
     };
     use crate::presentation::events::{
         EVENT_INCOMING_TRANSLATION_DELTA, EVENT_INCOMING_TRANSLATION_ERROR,
-        EVENT_INCOMING_TRANSLATION_SOURCE_FINAL, EVENT_INCOMING_TRANSLATION_STATUS,
+        EVENT_INCOMING_TRANSLATION_REVISION, EVENT_INCOMING_TRANSLATION_SOURCE_FINAL,
+        EVENT_INCOMING_TRANSLATION_STATUS,
     };
 
     let app_config = state.config.read().await.clone();
@@ -1329,27 +1406,60 @@ async fn start_incoming_translation_inner(
     configure_incoming_translation_source(&mut stt_config, &app_config);
     let mut cfg = IncomingTranslationConfig::new_with_defaults(stt_config, session_id);
     cfg.openai_api_key = resolve_openai_api_key(&app_config);
+    cfg.text_translation = app_config.text_translation.clone();
+    cfg.glossary = app_config.translation_glossary.clone();
+    cfg.caption_context = app_config.caption_translation_context;
+    if app_config.translation_memory.enabled {
+        if let Err(e) = state
+            .translation_memory
+            .set_capacity(app_config.translation_memory.max_entries)
+            .await
+        {
+            log::warn!("Failed to apply translation memory capacity: {}", e);
+        }
+        cfg.translation_memory =
+            Some(state.translation_memory.clone() as Arc<dyn TranslationMemory>);
+    }
     cfg.target_language = resolve_incoming_translation_target_language(&app_config);
     cfg.playback_gain = incoming_translation_volume_gain(app_config.incoming_translation_volume);
     let delivery = app_config.incoming_translation_delivery;
 
+    state.session_transcripts.begin(
+        SessionTranscriptKind::IncomingCaptions,
+        session_id,
+        now_ms_u64() as i64,
+    );
+
     let source_handle = app_handle.clone();
+    let source_transcripts = state.session_transcripts.clone();
     let on_source_final: std::sync::Arc<dyn Fn(String) + Send + Sync> =
         std::sync::Arc::new(move |text: String| {
+            let timestamp = now_ms_u64();
+            source_transcripts.update(
+                SessionTranscriptKind::IncomingCaptions,
+                session_id,
+                |session| session.push_source_final(&text, timestamp as i64),
+            );
             let _ = source_handle.emit(
                 EVENT_INCOMING_TRANSLATION_SOURCE_FINAL,
                 IncomingTranslationTextPayload {
                     session_id,
                     text,
-                    timestamp: now_ms_u64(),
+                    timestamp,
                     delivery,
                 },
             );
         });
 
     let delta_handle = app_handle.clone();
+    let delta_transcripts = state.session_transcripts.clone();
     let on_translation_delta: std::sync::Arc<dyn Fn(String) + Send + Sync> =
         std::sync::Arc::new(move |text: String| {
+            delta_transcripts.update(
+                SessionTranscriptKind::IncomingCaptions,
+                session_id,
+                |session| session.push_caption_translation(&text),
+            );
             let _ = delta_handle.emit(
                 EVENT_INCOMING_TRANSLATION_DELTA,
                 IncomingTranslationTextPayload {
@@ -1361,6 +1471,27 @@ async fn start_incoming_translation_inner(
             );
         });
 
+    let revision_handle = app_handle.clone();
+    let revision_transcripts = state.session_transcripts.clone();
+    let on_translation_revision: std::sync::Arc<dyn Fn(CaptionRevision) + Send + Sync> =
+        std::sync::Arc::new(move |revision: CaptionRevision| {
+            revision_transcripts.update(
+                SessionTranscriptKind::IncomingCaptions,
+                session_id,
+                |session| session.revise_caption_translation(&revision.text),
+            );
+            let _ = revision_handle.emit(
+                EVENT_INCOMING_TRANSLATION_REVISION,
+                IncomingTranslationRevisionPayload {
+                    session_id,
+                    previous: revision.previous,
+                    text: revision.text,
+                    timestamp: now_ms_u64(),
+                    delivery,
+                },
+            );
+        });
+
     let error_handle = app_handle.clone();
     let on_error: std::sync::Arc<dyn Fn(IncomingTranslationError) + Send + Sync> =
         std::sync::Arc::new(move |err: IncomingTranslationError| {
@@ -1386,6 +1517,7 @@ async fn start_incoming_translation_inner(
     let callbacks = IncomingTranslationCallbacks {
         on_source_final,
         on_translation_delta,
+        on_translation_revision,
         on_error,
         on_status,
     };
@@ -2033,6 +2165,49 @@ pub async fn start_recording(
         return Ok("Recording already active".to_string());
     }
 
+    // dictate_translate без ключа переводчика вставлял бы оригинал вместо перевода — отказываем до старта
+    let (selected_mode, dictate_translate) = {
+        let config = state.config.read().await;
+        let dictate_translate =
+            (config.recording_mode == RecordingMode::DictateTranslate).then(|| {
+                (
+                    config.text_translation.clone(),
+                    config
+                        .text_translation
+                        .resolved_api_key(&resolve_openai_api_key(&config)),
+                    resolve_dictate_translate_target_language(&config),
+                    config.translation_glossary.clone(),
+                )
+            });
+        (config.recording_mode, dictate_translate)
+    };
+    if let Some((translation, api_key, _, _)) = &dictate_translate {
+        if api_key.is_empty() && translation.requires_api_key() {
+            return Err(match translation.provider {
+                TextTranslationProviderType::DeepL => {
+                    "DeepL API key is required for dictate & translate mode".to_string()
+                }
+                _ => "OpenAI API key is required for dictate & translate mode".to_string(),
+            });
+        }
+    }
+    // Без переводчика сессия молча стала бы обычной диктовкой — отказываем до старта
+    let translator = match dictate_translate {
+        Some((translation, api_key, target_language, glossary)) => {
+            let translator = DefaultTextTranslatorFactory
+                .create(&translation, api_key, &glossary)
+                .map_err(|e| {
+                    log::error!("Failed to create dictation translator: {}", e);
+                    format!("Failed to start dictate & translate: {}", e)
+                })?;
+            Some((
+                translation.provider,
+                DictationTranslator::new(translator, target_language),
+            ))
+        }
+        None => None,
+    };
+
     // Новый идентификатор сессии записи. Маркируем им все события transcription:* и recording:status,
     // чтобы frontend мог игнорировать "поздние" сообщения от предыдущей сессии.
     let session_id = state
@@ -2049,8 +2224,7 @@ pub async fn start_recording(
     log::info!("Recording session started: session_id={}", session_id);
 
     // Dispatcher: если в Settings выбран live_translation, направляем в отдельный сервис
-    // и НЕ запускаем STT pipeline. Dictation идёт по прежнему пути ниже.
-    let selected_mode = state.config.read().await.recording_mode;
+    // и НЕ запускаем STT pipeline. Dictation и dictate_translate идут по прежнему пути ниже.
     if selected_mode == crate::domain::RecordingMode::LiveTranslation {
         return start_live_translation_recording(
             state.inner(),
@@ -2061,8 +2235,8 @@ pub async fn start_recording(
         )
         .await;
     }
-    // Dictation mode: помечаем active_recording_mode, чтобы stop корректно роутил.
-    *state.active_recording_mode.write().await = Some(crate::domain::RecordingMode::Dictation);
+    // Dictation pipeline: помечаем active_recording_mode, чтобы stop корректно роутил.
+    *state.active_recording_mode.write().await = Some(selected_mode);
 
     // На macOS при отсутствии разрешения на микрофон CoreAudio может отдавать "тишину" (все нули),
     // и UI будет выглядеть как "не записывает".
@@ -2102,7 +2276,7 @@ pub async fn start_recording(
                 restore_or_clear_failed_start_state_if_current(
                     state.inner(),
                     session_id,
-                    RecordingMode::Dictation,
+                    selected_mode,
                     displaced_session_id,
                     displaced_recording_mode,
                 )
@@ -2125,39 +2299,150 @@ pub async fn start_recording(
     let state_final = state.final_transcription.clone();
     let state_history = state.history.clone();
     let state_config = state.config.clone();
+    let state_app_target = state.last_focused_app_target.clone();
+    let stt_config = state.transcription_service.get_config().await;
+    let translator = translator.map(|(provider, translator)| {
+        log::info!(
+            "Dictate & translate session: session_id={}, provider={:?}, target_language={}",
+            session_id,
+            provider,
+            translator.target_language()
+        );
+        translator
+    });
+    // Пост-обработка и голосовые команды фиксируются на старте сессии, как и провайдер
+    let (post_processing, mut spoken_commands) = {
+        let config = state.config.read().await;
+        let session_language =
+            (!stt_config.auto_detect_language).then_some(stt_config.language.as_str());
+        // Команды стирают уже доставленный текст, а при переводе доставлен не он
+        let spoken_commands = if translator.is_some() {
+            None
+        } else {
+            SpokenCommandInterpreter::from_config(&config.spoken_commands, session_language)
+        };
+        (
+            TranscriptPostProcessingChain::from_config(&config.post_processing),
+            spoken_commands,
+        )
+    };
+    let history_provider = history_provider_name(stt_config.provider);
+    let recording_started_at_ms = chrono::Utc::now().timestamp_millis();
+    let session_transcripts = state.session_transcripts.clone();
+    session_transcripts.begin(
+        SessionTranscriptKind::Dictation,
+        session_id,
+        recording_started_at_ms,
+    );
 
     tokio::spawn(async move {
+        let mut speaker_turns = SpeakerTurnTracker::default();
+        let mut last_segment_final: Option<DeliveredSegment> = None;
         while let Some(event) = transcript_rx.recv().await {
             match event {
                 TranscriptEvent::Partial(transcription) => {
+                    // Финализированный сегмент фронт склеивает в итоговый текст и вставляет,
+                    // поэтому он проходит те же команды и пост-обработку, что и финал
+                    let (transcription, source_text) = if transcription.is_final {
+                        let (transcription, retract) = prepare_dictation_final(
+                            transcription,
+                            spoken_commands.as_mut(),
+                            &post_processing,
+                        );
+                        emit_transcription_retract(&app_handle_transcripts, session_id, retract);
+                        let (transcription, source_text) = translate_dictation_final(
+                            &app_handle_transcripts,
+                            session_id,
+                            transcription,
+                            translator.as_ref(),
+                        )
+                        .await;
+                        last_segment_final = Some(DeliveredSegment::of(&transcription));
+                        (transcription, source_text)
+                    } else {
+                        (transcription, None)
+                    };
                     *state_partial.write().await = Some(transcription.text.clone());
 
-                    let payload =
+                    let mut payload =
                         PartialTranscriptionPayload::from_transcription(transcription, session_id);
+                    payload.source_text = source_text;
                     if let Err(e) =
                         app_handle_transcripts.emit(EVENT_TRANSCRIPTION_PARTIAL, payload)
                     {
                         log::error!("Failed to emit partial transcription event: {}", e);
                     }
                 }
-                TranscriptEvent::Final(transcription) => {
+                TranscriptEvent::Final(mut transcription) => {
+                    // Финал по тому же диапазону, что и последний сегмент, — его дубль:
+                    // повторно команды не выполняем ("delete last word" стёр бы два слова)
+                    let (transcription, source_text) = match last_segment_final.take() {
+                        Some(segment) if segment.matches(&transcription) => {
+                            if transcription.text != segment.text {
+                                transcription.text = segment.text;
+                                transcription.words.clear();
+                            }
+                            (transcription, None)
+                        }
+                        _ => {
+                            let (transcription, retract) = prepare_dictation_final(
+                                transcription,
+                                spoken_commands.as_mut(),
+                                &post_processing,
+                            );
+                            emit_transcription_retract(
+                                &app_handle_transcripts,
+                                session_id,
+                                retract,
+                            );
+                            translate_dictation_final(
+                                &app_handle_transcripts,
+                                session_id,
+                                transcription,
+                                translator.as_ref(),
+                            )
+                            .await
+                        }
+                    };
+
                     // Пустой финал — это только сигнал конца utterance (flush от Finalize
                     // или endpointing на тишине); в историю и last-final его не пишем.
                     if !transcription.text.is_empty() {
                         *state_final.write().await = Some(transcription.text.clone());
+                        session_transcripts.update(
+                            SessionTranscriptKind::Dictation,
+                            session_id,
+                            |session| session.push_final(transcription.clone()),
+                        );
 
-                        state_history.write().await.push(transcription.clone());
-
-                        let max_items = state_config.read().await.max_history_items;
-                        let mut history = state_history.write().await;
-                        let len = history.len();
-                        if len > max_items {
-                            history.drain(0..len - max_items);
+                        let (keep_history, max_items) = {
+                            let config = state_config.read().await;
+                            (config.keep_history, config.max_history_items)
+                        };
+                        if keep_history {
+                            let target_app = state_app_target
+                                .read()
+                                .await
+                                .as_ref()
+                                .map(|target| target.bundle_id.clone());
+                            let entry = HistoryEntry::from_transcription(
+                                &transcription,
+                                history_provider.as_str(),
+                            )
+                            .with_session(session_id, Some(recording_started_at_ms))
+                            .with_target_app(target_app);
+                            if let Err(e) = state_history.append(entry, max_items).await {
+                                log::error!("Failed to persist transcription history: {}", e);
+                            }
                         }
                     }
 
-                    let payload =
+                    let mut payload =
                         FinalTranscriptionPayload::from_transcription(transcription, session_id);
+                    payload.source_text = source_text;
+                    if !payload.text.is_empty() {
+                        payload.speaker_turn = speaker_turns.assign(payload.speaker.as_deref());
+                    }
                     if let Err(e) = app_handle_transcripts.emit(EVENT_TRANSCRIPTION_FINAL, payload)
                     {
                         log::error!("Failed to emit final transcription event: {}", e);
@@ -2260,6 +2545,8 @@ pub async fn start_recording(
         });
     });
 
+    sync_openai_dictation_key(state.inner()).await;
+
     // Emit Starting only when a real connection/startup path is expected.
     // In keep-alive resume mode the WebSocket is already open, so emitting Starting
     // creates a false "reconnecting" blink in the UI.
@@ -2316,7 +2603,7 @@ pub async fn start_recording(
         restore_or_clear_failed_start_state_if_current(
             state.inner(),
             session_id,
-            RecordingMode::Dictation,
+            selected_mode,
             displaced_session_id,
             displaced_recording_mode,
         )
@@ -2402,7 +2689,7 @@ pub async fn start_recording(
         restore_or_clear_failed_start_state_if_current(
             state.inner(),
             session_id,
-            RecordingMode::Dictation,
+            selected_mode,
             displaced_session_id,
             displaced_recording_mode,
         )
@@ -2842,14 +3129,16 @@ where
 #[cfg(test)]
 mod snapshot_contract_tests {
     use super::{
-        active_recording_status_payload, auto_paste_text_can_trigger_recording_hotkey,
-        calculate_recording_window_position, configure_incoming_translation_source,
-        hotkey_action_is_stale, incoming_status_requires_controlled_restart,
-        incoming_stop_session_id, incoming_translation_state_payload,
-        is_audio_capture_start_failure, live_translation_health_check_blocks_recording_status,
-        live_translation_health_check_blocks_service_status, point_inside_rect,
-        recording_hotkey_press_intent, recording_hotkey_release_intent, recording_start_is_busy,
-        recording_state_after_failed_start_cleanup, recording_window_size_from_config,
+        active_recording_status_payload, apply_optional_text_update,
+        auto_paste_text_can_trigger_recording_hotkey, calculate_recording_window_position,
+        configure_incoming_translation_source, file_history_entry, hotkey_action_is_stale,
+        incoming_status_requires_controlled_restart, incoming_stop_session_id,
+        incoming_translation_state_payload, is_audio_capture_start_failure,
+        live_translation_health_check_blocks_recording_status,
+        live_translation_health_check_blocks_service_status, openai_dictation_key_update,
+        point_inside_rect, recording_hotkey_press_intent, recording_hotkey_release_intent,
+        recording_start_is_busy, recording_state_after_failed_start_cleanup,
+        recording_window_size_from_config, resolve_dictate_translate_target_language,
         resolve_incoming_delivery, resolve_incoming_translation_source_language,
         resolve_incoming_translation_target_language, resolve_outgoing_translation_target_language,
         resolve_streaming_keyterms_update, should_cancel_hold_to_record_pending_start,
@@ -2870,9 +3159,11 @@ mod snapshot_contract_tests {
         SnapshotEnvelope, SttConfigSnapshotData,
     };
     use crate::domain::{
-        AppConfig, AudioError, BackendStreamingProvider, IncomingTranslationDelivery,
-        RecordingMode, RecordingStatus, RecordingWindowPosition, SttConfig, SttError,
-        SttProviderType,
+        AppConfig, AudioError, BackendStreamingProvider, CaptionTranslationContextConfig,
+        DictateTranslateConfig, DictationRewriteConfig, IncomingTranslationDelivery, RecordingMode,
+        RecordingStatus, RecordingWindowPosition, SpokenCommandsConfig, SttConfig, SttError,
+        SttProviderType, TextTranslationConfig, TranscriptPostProcessingConfig, Transcription,
+        TranslationGlossaryConfig, TranslationMemoryConfig, Word,
     };
     use crate::infrastructure::auto_paste::{AutoPasteTarget, VOICETEXT_BUNDLE_ID};
     use tauri::{PhysicalPosition, PhysicalSize};
@@ -2973,6 +3264,19 @@ mod snapshot_contract_tests {
         assert_eq!(resolve_outgoing_translation_target_language(&config), "ru");
     }
 
+    #[test]
+    fn dictate_translate_target_defaults_to_live_translation_target() {
+        let mut config = AppConfig::default();
+        config.stt.language = "en".to_string();
+        assert_eq!(resolve_dictate_translate_target_language(&config), "ru");
+
+        config.dictate_translate.target_language = Some(" de ".to_string());
+        assert_eq!(resolve_dictate_translate_target_language(&config), "de");
+
+        config.dictate_translate.target_language = Some("auto".to_string());
+        assert_eq!(resolve_dictate_translate_target_language(&config), "ru");
+    }
+
     #[test]
     fn incoming_translation_targets_user_stt_language() {
         let mut config = AppConfig::default();
@@ -2994,6 +3298,60 @@ mod snapshot_contract_tests {
         assert_eq!(resolve_incoming_translation_source_language(&config), "ru");
     }
 
+    #[test]
+    fn openai_dictation_key_is_taken_from_app_config() {
+        let app_config = AppConfig {
+            openai_api_key: Some(" sk-app ".to_string()),
+            ..AppConfig::default()
+        };
+
+        let backend = SttConfig::new(SttProviderType::Backend);
+        assert_eq!(openai_dictation_key_update(&backend, &app_config), None);
+
+        let mut realtime = SttConfig::new(SttProviderType::OpenAIRealtime);
+        assert_eq!(
+            openai_dictation_key_update(&realtime, &app_config),
+            Some(Some("sk-app".to_string()))
+        );
+
+        realtime.openai_api_key = Some("sk-app".to_string());
+        assert_eq!(openai_dictation_key_update(&realtime, &app_config), None);
+
+        let mut failover = SttConfig::new(SttProviderType::Backend);
+        failover.failover_providers = vec![SttProviderType::OpenAIRealtime];
+        assert_eq!(
+            openai_dictation_key_update(&failover, &app_config),
+            Some(Some("sk-app".to_string()))
+        );
+    }
+
+    #[test]
+    fn file_transcription_is_saved_as_one_history_entry() {
+        let segments = vec![
+            Transcription::final_result("first part".to_string())
+                .with_timing(0.0, 2.0)
+                .with_provider("deepgram")
+                .with_words(vec![Word::new("first", 0.0, 0.5)]),
+            Transcription::final_result("second part".to_string())
+                .with_timing(2.0, 3.0)
+                .with_provider("deepgram")
+                .with_words(vec![Word::new("second", 2.0, 2.5)]),
+        ];
+
+        let entry = file_history_entry(&segments, "first part second part", 5.5, "backend");
+
+        assert_eq!(entry.text, "first part second part");
+        assert_eq!(entry.start_secs, 0.0);
+        assert_eq!(entry.duration_secs, 5.5);
+        assert_eq!(entry.provider, "deepgram");
+        assert_eq!(entry.words.len(), 2);
+
+        let mut failover = segments;
+        failover[1].provider = Some("whisperlocal".to_string());
+        let entry = file_history_entry(&failover, "first part second part", 5.5, "backend");
+        assert_eq!(entry.provider, "backend");
+    }
+
     #[test]
     fn incoming_streaming_stt_uses_multilingual_recognition() {
         let mut app_config = AppConfig::default();
@@ -3304,6 +3662,11 @@ mod snapshot_contract_tests {
             11,
             Some(RecordingMode::Dictation)
         ));
+        assert!(should_clear_active_mode_after_dictation_failure(
+            10,
+            10,
+            Some(RecordingMode::DictateTranslate)
+        ));
         assert!(!should_clear_active_mode_after_dictation_failure(
             10,
             10,
@@ -3664,6 +4027,16 @@ mod snapshot_contract_tests {
                 incoming_translation_delivery:
                     crate::domain::IncomingTranslationDelivery::CaptionsOnly,
                 incoming_translation_volume: 100,
+                keep_history: true,
+                max_history_items: 20,
+                post_processing: TranscriptPostProcessingConfig::default(),
+                spoken_commands: SpokenCommandsConfig::default(),
+                dictation_rewrite: DictationRewriteConfig::default(),
+                dictate_translate: DictateTranslateConfig::default(),
+                text_translation: TextTranslationConfig::default(),
+                translation_glossary: TranslationGlossaryConfig::default(),
+                caption_translation_context: CaptionTranslationContextConfig::default(),
+                translation_memory: TranslationMemoryConfig::default(),
             },
         };
 
@@ -3879,6 +4252,18 @@ mod snapshot_contract_tests {
                 keep_connection_alive: true,
                 streaming_keyterms: None,
                 deepgram_keyterms: None,
+                failover_providers: vec![SttProviderType::Deepgram],
+                google_cloud_api_key: None,
+                google_cloud_project_id: None,
+                google_cloud_location: None,
+                azure_speech_key: None,
+                azure_speech_region: None,
+                openai_compatible_base_url: None,
+                openai_compatible_api_key: None,
+                elevenlabs_api_key: None,
+                enable_diarization: false,
+                compress_audio: false,
+                profanity_words: Vec::new(),
             },
         };
 
@@ -3905,6 +4290,22 @@ mod snapshot_contract_tests {
         assert!(data.contains_key("keep_connection_alive"));
         assert!(data.contains_key("streaming_keyterms"));
         assert!(data.contains_key("deepgram_keyterms"));
+        assert!(data.contains_key("failover_providers"));
+        assert!(data.contains_key("azure_speech_region"));
+    }
+
+    #[test]
+    fn optional_text_update_keeps_clears_or_sets_value() {
+        let mut value = Some("old".to_string());
+
+        apply_optional_text_update(&mut value, None);
+        assert_eq!(value.as_deref(), Some("old"));
+
+        apply_optional_text_update(&mut value, Some(" new ".to_string()));
+        assert_eq!(value.as_deref(), Some("new"));
+
+        apply_optional_text_update(&mut value, Some("  ".to_string()));
+        assert_eq!(value, None);
     }
 }
 /// Toggle window visibility
@@ -4308,6 +4709,14 @@ fn resolve_streaming_keyterms_update(
     streaming_keyterms.or(legacy_deepgram_keyterms)
 }
 
+/// Частичное обновление текстового поля: None — не трогаем, пустая строка — очищаем
+fn apply_optional_text_update(target: &mut Option<String>, next: Option<String>) {
+    if let Some(next) = next {
+        let next = next.trim();
+        *target = (!next.is_empty()).then(|| next.to_string());
+    }
+}
+
 #[tauri::command]
 pub async fn update_stt_config(
     state: State<'_, AppState>,
@@ -4325,6 +4734,19 @@ pub async fn update_stt_config(
     streaming_keyterms: Option<Option<String>>,
     // Deprecated IPC alias. Оставляем на один миграционный период для старых окон/сборок.
     deepgram_keyterms: Option<Option<String>>,
+    // Поля ниже тоже частичные: None — не меняем, пустая строка — очищаем.
+    failover_providers: Option<Vec<SttProviderType>>,
+    google_cloud_api_key: Option<String>,
+    google_cloud_project_id: Option<String>,
+    google_cloud_location: Option<String>,
+    azure_speech_key: Option<String>,
+    azure_speech_region: Option<String>,
+    openai_compatible_base_url: Option<String>,
+    openai_compatible_api_key: Option<String>,
+    elevenlabs_api_key: Option<String>,
+    enable_diarization: Option<bool>,
+    compress_audio: Option<bool>,
+    profanity_words: Option<Vec<String>>,
 ) -> Result<(), String> {
     log::info!(
         "Command: update_stt_config - provider: {}, language: {}, model: {:?}",
@@ -4333,12 +4755,9 @@ pub async fn update_stt_config(
         model
     );
 
-    let stt_config_guard = state.stt_config_guard.lock().await;
+    let provider_type = provider.parse::<SttProviderType>()?;
 
-    // Выбор провайдера отключён — всегда используем Backend.
-    // Параметр provider оставлен, чтобы не ломать совместимость API.
-    let _ = provider;
-    let provider_type = SttProviderType::Backend;
+    let stt_config_guard = state.stt_config_guard.lock().await;
 
     // Снимаем текущее состояние для сравнения после сохранения
     let old_stt = {
@@ -4356,9 +4775,7 @@ pub async fn update_stt_config(
         config.backend_streaming_provider = next_provider.parse::<BackendStreamingProvider>()?;
     }
 
-    // Whisper/model больше не используем в backend-only архитектуре.
-    let _ = model;
-    config.model = None;
+    apply_optional_text_update(&mut config.model, model);
 
     crate::application::apply_backend_dictation_keep_alive_policy(&mut config);
 
@@ -4368,11 +4785,39 @@ pub async fn update_stt_config(
         provider_type
     );
 
-    // API ключи больше не используем в настройках (backend-only).
-    let _ = deepgram_api_key;
-    let _ = assemblyai_api_key;
-    config.deepgram_api_key = None;
-    config.assemblyai_api_key = None;
+    apply_optional_text_update(&mut config.deepgram_api_key, deepgram_api_key);
+    apply_optional_text_update(&mut config.assemblyai_api_key, assemblyai_api_key);
+    apply_optional_text_update(&mut config.google_cloud_api_key, google_cloud_api_key);
+    apply_optional_text_update(&mut config.google_cloud_project_id, google_cloud_project_id);
+    apply_optional_text_update(&mut config.google_cloud_location, google_cloud_location);
+    apply_optional_text_update(&mut config.azure_speech_key, azure_speech_key);
+    apply_optional_text_update(&mut config.azure_speech_region, azure_speech_region);
+    apply_optional_text_update(
+        &mut config.openai_compatible_base_url,
+        openai_compatible_base_url,
+    );
+    apply_optional_text_update(
+        &mut config.openai_compatible_api_key,
+        openai_compatible_api_key,
+    );
+    apply_optional_text_update(&mut config.elevenlabs_api_key, elevenlabs_api_key);
+    if let Some(next) = failover_providers {
+        config.failover_providers = next;
+    }
+    if let Some(next) = enable_diarization {
+        config.enable_diarization = next;
+    }
+    if let Some(next) = compress_audio {
+        config.compress_audio = next;
+    }
+    if let Some(next) = profanity_words {
+        config.profanity_words = next
+            .iter()
+            .map(|word| word.trim())
+            .filter(|word| !word.is_empty())
+            .map(str::to_string)
+            .collect();
+    }
 
     // Keyterms для улучшения streaming-распознавания
     // - None: не меняем существующее значение
@@ -4407,7 +4852,22 @@ pub async fn update_stt_config(
     let stt_changed = incoming_language_changed
         || config.streaming_keyterms != old_stt.streaming_keyterms
         || config.backend_streaming_provider != old_stt.backend_streaming_provider
-        || config.provider != old_stt.provider;
+        || config.provider != old_stt.provider
+        || config.failover_providers != old_stt.failover_providers
+        || config.model != old_stt.model
+        || config.deepgram_api_key != old_stt.deepgram_api_key
+        || config.assemblyai_api_key != old_stt.assemblyai_api_key
+        || config.google_cloud_api_key != old_stt.google_cloud_api_key
+        || config.google_cloud_project_id != old_stt.google_cloud_project_id
+        || config.google_cloud_location != old_stt.google_cloud_location
+        || config.azure_speech_key != old_stt.azure_speech_key
+        || config.azure_speech_region != old_stt.azure_speech_region
+        || config.openai_compatible_base_url != old_stt.openai_compatible_base_url
+        || config.openai_compatible_api_key != old_stt.openai_compatible_api_key
+        || config.elevenlabs_api_key != old_stt.elevenlabs_api_key
+        || config.enable_diarization != old_stt.enable_diarization
+        || config.compress_audio != old_stt.compress_audio
+        || config.profanity_words != old_stt.profanity_words;
     if stt_changed {
         let revision = AppState::bump_revision(&state.stt_config_revision).await;
         let _ = app_handle.emit(
@@ -4465,6 +4925,16 @@ pub struct AppConfigSnapshotData {
     pub openai_api_key: Option<String>,
     pub incoming_translation_delivery: IncomingTranslationDelivery,
     pub incoming_translation_volume: u8,
+    pub keep_history: bool,
+    pub max_history_items: usize,
+    pub post_processing: TranscriptPostProcessingConfig,
+    pub spoken_commands: SpokenCommandsConfig,
+    pub dictation_rewrite: DictationRewriteConfig,
+    pub dictate_translate: DictateTranslateConfig,
+    pub text_translation: TextTranslationConfig,
+    pub translation_glossary: TranslationGlossaryConfig,
+    pub caption_translation_context: CaptionTranslationContextConfig,
+    pub translation_memory: TranslationMemoryConfig,
 }
 /// Get current application configuration + revision (for cross-window sync)
 #[tauri::command]
@@ -4489,6 +4959,16 @@ pub async fn get_app_config_snapshot(
         openai_api_key: config.openai_api_key,
         incoming_translation_delivery: config.incoming_translation_delivery,
         incoming_translation_volume: config.incoming_translation_volume,
+        keep_history: config.keep_history,
+        max_history_items: config.max_history_items,
+        post_processing: config.post_processing,
+        spoken_commands: config.spoken_commands,
+        dictation_rewrite: config.dictation_rewrite,
+        dictate_translate: config.dictate_translate,
+        text_translation: config.text_translation,
+        translation_glossary: config.translation_glossary,
+        caption_translation_context: config.caption_translation_context,
+        translation_memory: config.translation_memory,
     };
     let revision = state.app_config_revision.read().await.to_string();
     Ok(SnapshotEnvelope { revision, data })
@@ -4511,6 +4991,18 @@ pub struct SttConfigSnapshotData {
     pub keep_connection_alive: bool,
     pub streaming_keyterms: Option<String>,
     pub deepgram_keyterms: Option<String>,
+    pub failover_providers: Vec<crate::domain::SttProviderType>,
+    pub google_cloud_api_key: Option<String>,
+    pub google_cloud_project_id: Option<String>,
+    pub google_cloud_location: Option<String>,
+    pub azure_speech_key: Option<String>,
+    pub azure_speech_region: Option<String>,
+    pub openai_compatible_base_url: Option<String>,
+    pub openai_compatible_api_key: Option<String>,
+    pub elevenlabs_api_key: Option<String>,
+    pub enable_diarization: bool,
+    pub compress_audio: bool,
+    pub profanity_words: Vec<String>,
 }
 
 /// Get current STT configuration snapshot
@@ -4533,6 +5025,18 @@ pub async fn get_stt_config_snapshot(
         keep_connection_alive: config.keep_connection_alive,
         streaming_keyterms: config.streaming_keyterms.clone(),
         deepgram_keyterms: config.streaming_keyterms,
+        failover_providers: config.failover_providers,
+        google_cloud_api_key: config.google_cloud_api_key,
+        google_cloud_project_id: config.google_cloud_project_id,
+        google_cloud_location: config.google_cloud_location,
+        azure_speech_key: config.azure_speech_key,
+        azure_speech_region: config.azure_speech_region,
+        openai_compatible_base_url: config.openai_compatible_base_url,
+        openai_compatible_api_key: config.openai_compatible_api_key,
+        elevenlabs_api_key: config.elevenlabs_api_key,
+        enable_diarization: config.enable_diarization,
+        compress_audio: config.compress_audio,
+        profanity_words: config.profanity_words,
     };
     let revision = state.stt_config_revision.read().await.to_string();
     Ok(SnapshotEnvelope { revision, data })
@@ -4690,6 +5194,501 @@ pub async fn update_ui_preferences(
     Ok(())
 }
 
+/// Последний финализированный сегмент диктовки в том виде, в каком он ушёл во фронт
+struct DeliveredSegment {
+    start: f64,
+    duration: f64,
+    text: String,
+}
+
+impl DeliveredSegment {
+    fn of(transcription: &Transcription) -> Self {
+        Self {
+            start: transcription.start,
+            duration: transcription.duration,
+            text: transcription.text.clone(),
+        }
+    }
+
+    fn matches(&self, transcription: &Transcription) -> bool {
+        // Без таймингов (start=0, duration=0) дубль не отличить от новой фразы
+        self.duration > 0.0
+            && self.start == transcription.start
+            && self.duration == transcription.duration
+    }
+}
+
+/// Голосовые команды, затем пост-обработка финализированного текста диктовки.
+/// Вторым значением возвращается уже доставленный хвост, который команда убрала.
+fn prepare_dictation_final(
+    mut transcription: Transcription,
+    spoken_commands: Option<&mut SpokenCommandInterpreter>,
+    post_processing: &TranscriptPostProcessingChain,
+) -> (Transcription, Option<String>) {
+    let Some(interpreter) = spoken_commands else {
+        return (post_processing.apply(transcription), None);
+    };
+
+    let outcome = interpreter.interpret(&transcription.text);
+    if outcome.text != transcription.text {
+        transcription.text = outcome.text;
+        transcription.words.clear();
+    }
+    let transcription = post_processing.apply(transcription);
+    interpreter.record_delivered(&transcription.text);
+    (transcription, outcome.retract)
+}
+
+fn emit_transcription_retract(app_handle: &AppHandle, session_id: u64, retract: Option<String>) {
+    let Some(text) = retract else {
+        return;
+    };
+
+    log::info!(
+        "Spoken command retracts {} chars of delivered dictation",
+        text.chars().count()
+    );
+    let payload = TranscriptionRetractPayload { session_id, text };
+    if let Err(e) = app_handle.emit(EVENT_TRANSCRIPTION_RETRACT, payload) {
+        log::error!("Failed to emit transcription retract event: {}", e);
+    }
+}
+
+/// В режиме dictate_translate переводит финализированный текст; вторым значением — оригинал.
+/// Если перевод не удался, вставится оригинал — предупреждаем об этом, не обрывая сессию.
+async fn translate_dictation_final(
+    app_handle: &AppHandle,
+    session_id: u64,
+    transcription: Transcription,
+    translator: Option<&DictationTranslator>,
+) -> (Transcription, Option<String>) {
+    match translator {
+        Some(translator) if !transcription.text.is_empty() => {
+            let (transcription, source_text, error) =
+                translator.translate_final(transcription).await;
+            if let Some(error) = error {
+                let payload = TranscriptionWarningPayload {
+                    session_id,
+                    warning: format!(
+                        "Segment was not translated to {} and was inserted as spoken: {}",
+                        translator.target_language(),
+                        error
+                    ),
+                    warning_type: "translation_failed".to_string(),
+                };
+                if let Err(e) = app_handle.emit(EVENT_TRANSCRIPTION_WARNING, payload) {
+                    log::error!("Failed to emit transcription warning event: {}", e);
+                }
+            }
+            (transcription, Some(source_text))
+        }
+        _ => (transcription, None),
+    }
+}
+
+/// Имя провайдера для истории, если failover-цепочка не пометила сегмент своим label
+fn history_provider_name(provider: SttProviderType) -> String {
+    serde_json::to_value(provider)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", provider))
+}
+
+/// Страница персистентной истории: поиск по тексту + пагинация (новые записи первыми)
+#[tauri::command]
+pub async fn get_history(
+    state: State<'_, AppState>,
+    query: Option<HistoryQuery>,
+) -> Result<HistoryPage, String> {
+    log::debug!("Command: get_history");
+    state
+        .history
+        .query(&query.unwrap_or_default())
+        .await
+        .map_err(|e| format!("Failed to read history: {}", e))
+}
+
+/// Удаляет запись истории; false, если записи с таким id нет
+#[tauri::command]
+pub async fn delete_history_item(state: State<'_, AppState>, id: String) -> Result<bool, String> {
+    log::info!("Command: delete_history_item - id: {}", id);
+    state
+        .history
+        .delete(&id)
+        .await
+        .map_err(|e| format!("Failed to delete history item: {}", e))
+}
+
+/// Полностью очищает историю (и файл на диске)
+#[tauri::command]
+pub async fn clear_history(state: State<'_, AppState>) -> Result<(), String> {
+    log::info!("Command: clear_history");
+    state
+        .history
+        .clear()
+        .await
+        .map_err(|e| format!("Failed to clear history: {}", e))
+}
+
+/// Результат экспорта транскрипта
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptExportResult {
+    pub path: String,
+    pub format: TranscriptExportFormat,
+    pub segments: usize,
+}
+
+/// Явный формат важнее расширения; без обоих экспорт невозможен
+fn resolve_export_format(
+    path: &std::path::Path,
+    format: Option<TranscriptExportFormat>,
+) -> Result<TranscriptExportFormat, String> {
+    format
+        .or_else(|| TranscriptExportFormat::from_path(path))
+        .ok_or_else(|| {
+            format!(
+                "Не удалось определить формат экспорта по пути {:?}: укажите srt, vtt, txt, md или json",
+                path
+            )
+        })
+}
+
+async fn write_transcript_export(
+    state: &AppState,
+    path: String,
+    format: Option<TranscriptExportFormat>,
+    cue_options: Option<SubtitleCueOptions>,
+    segments: Vec<TranscriptSegment>,
+) -> Result<TranscriptExportResult, String> {
+    if segments.is_empty() {
+        return Err("Nothing to export: transcript is empty".to_string());
+    }
+    let path_buf = std::path::PathBuf::from(&path);
+    let format = resolve_export_format(&path_buf, format)?;
+    let cue_options = match cue_options {
+        Some(options) => options,
+        None => state.config.read().await.subtitle_cues.clone(),
+    };
+
+    let contents = render_transcript(&segments, format, &cue_options)
+        .map_err(|e| format!("Failed to render transcript: {}", e))?;
+    tokio::fs::write(&path_buf, contents)
+        .await
+        .map_err(|e| format!("Failed to write transcript to {}: {}", path, e))?;
+
+    log::info!(
+        "Exported {} transcript segments as {:?} to {}",
+        segments.len(),
+        format,
+        path
+    );
+    Ok(TranscriptExportResult {
+        path,
+        format,
+        segments: segments.len(),
+    })
+}
+
+/// Экспорт последней сессии (диктовка / входящие субтитры / live translation) в файл
+#[tauri::command]
+pub async fn export_session_transcript(
+    state: State<'_, AppState>,
+    kind: SessionTranscriptKind,
+    path: String,
+    format: Option<TranscriptExportFormat>,
+    cue_options: Option<SubtitleCueOptions>,
+) -> Result<TranscriptExportResult, String> {
+    log::info!("Command: export_session_transcript - kind: {:?}", kind);
+    let segments = state
+        .session_transcripts
+        .snapshot(kind)
+        .map(|session| session.segments())
+        .unwrap_or_default();
+    write_transcript_export(state.inner(), path, format, cue_options, segments).await
+}
+
+/// Экспорт диапазона истории (по id или интервалу времени) в файл
+#[tauri::command]
+pub async fn export_history(
+    state: State<'_, AppState>,
+    path: String,
+    range: Option<HistoryRange>,
+    format: Option<TranscriptExportFormat>,
+    cue_options: Option<SubtitleCueOptions>,
+) -> Result<TranscriptExportResult, String> {
+    log::info!("Command: export_history");
+    let entries = state
+        .history
+        .range(&range.unwrap_or_default())
+        .await
+        .map_err(|e| format!("Failed to read history: {}", e))?;
+    let segments = segments_from_history(&entries);
+    write_transcript_export(state.inner(), path, format, cue_options, segments).await
+}
+
+fn lock_recovering<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Результат транскрибации аудиофайла
+#[derive(Debug, Clone, Serialize)]
+pub struct FileTranscriptionResult {
+    pub path: String,
+    pub text: String,
+    pub segments: Vec<Transcription>,
+    pub duration_secs: f64,
+}
+
+/// Транскрибирует аудиофайл (WAV/MP3/FLAC/OGG) через обычный STT pipeline текущего провайдера
+/// и сохраняет его одной записью истории. `speed` > 1.0 подаёт аудио быстрее реального времени.
+#[tauri::command]
+pub async fn transcribe_audio_file(
+    state: State<'_, AppState>,
+    path: String,
+    speed: Option<f32>,
+) -> Result<FileTranscriptionResult, String> {
+    log::info!("Command: transcribe_audio_file - path: {}", path);
+
+    let file_path = std::path::PathBuf::from(&path);
+    if !file_path.is_file() {
+        return Err(format!("Файл не найден: {}", path));
+    }
+    if !is_supported_audio_file(&file_path) {
+        return Err(format!(
+            "Неподдерживаемый формат файла: {} (поддерживаются {})",
+            path,
+            SUPPORTED_AUDIO_FILE_EXTENSIONS.join(", ")
+        ));
+    }
+
+    // Отдельный сервис: живая диктовка и её соединение не трогаются
+    let mut stt_config = state.transcription_service.get_config().await;
+    stt_config.keep_connection_alive = false;
+    let provider_name = history_provider_name(stt_config.provider);
+
+    let capture = FileAudioCapture::new(&file_path).with_speed(speed.unwrap_or(1.0));
+    let mut completion = capture.completion();
+    let service = TranscriptionService::new(
+        Box::new(capture),
+        Arc::new(DefaultSttProviderFactory::new()),
+    );
+    service
+        .update_config(stt_config)
+        .await
+        .map_err(|e| format!("Failed to configure STT: {}", e))?;
+    service
+        .initialize_audio(AudioConfig::default())
+        .await
+        .map_err(|e| format!("Failed to open audio file: {}", e))?;
+
+    let finals: Arc<Mutex<Vec<Transcription>>> = Arc::new(Mutex::new(Vec::new()));
+    let failure: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    let failed = Arc::new(tokio::sync::Notify::new());
+
+    let finals_cb = finals.clone();
+    let failure_cb = failure.clone();
+    let failed_cb = failed.clone();
+    let started_at_ms = chrono::Utc::now().timestamp_millis();
+    // Номер из общей последовательности сессий записи, но активной сессией файл не становится
+    let session_id = state
+        .transcription_session_seq
+        .fetch_add(1, Ordering::Relaxed)
+        + 1;
+    let post_processing =
+        TranscriptPostProcessingChain::from_config(&state.config.read().await.post_processing);
+
+    service
+        .start_recording(
+            Arc::new(|_| {}),
+            Arc::new(move |transcription: Transcription| {
+                let transcription = post_processing.apply(transcription);
+                if !transcription.text.trim().is_empty() {
+                    lock_recovering(&finals_cb).push(transcription);
+                }
+            }),
+            Arc::new(|_| {}),
+            Arc::new(|_| {}),
+            Arc::new(move |error: SttError| {
+                log::error!("File transcription error: {}", error);
+                *lock_recovering(&failure_cb) = Some(error.to_string());
+                failed_cb.notify_one();
+            }),
+            Arc::new(|_, _| {}),
+        )
+        .await
+        .map_err(|e| format!("Failed to start transcription: {}", e))?;
+
+    let outcome = tokio::select! {
+        outcome = completion.wait_for(|outcome| outcome.is_some()) => {
+            outcome.ok().and_then(|outcome| outcome.clone())
+        }
+        _ = failed.notified() => None,
+    };
+
+    // stop_recording дожидается финалов провайдера по хвосту файла
+    if let Err(e) = service.stop_recording().await {
+        log::warn!("Failed to stop file transcription cleanly: {}", e);
+    }
+
+    if let Some(error) = lock_recovering(&failure).take() {
+        return Err(format!("Transcription failed: {}", error));
+    }
+    let duration_secs = match outcome {
+        Some(FileCaptureOutcome::Finished { duration_secs }) => duration_secs,
+        Some(FileCaptureOutcome::Failed(error)) => {
+            return Err(format!("Failed to decode audio file: {}", error))
+        }
+        Some(FileCaptureOutcome::Stopped) | None => {
+            return Err("File transcription was interrupted".to_string())
+        }
+    };
+
+    let segments = std::mem::take(&mut *lock_recovering(&finals));
+    let text = segments
+        .iter()
+        .map(|segment| segment.text.trim())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let (keep_history, max_items) = {
+        let config = state.config.read().await;
+        (config.keep_history, config.max_history_items)
+    };
+    if keep_history {
+        let source_file = file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.clone());
+        let entry = file_history_entry(&segments, &text, duration_secs, &provider_name)
+            .with_session(session_id, Some(started_at_ms))
+            .with_source_file(source_file);
+        if let Err(e) = state.history.append(entry, max_items).await {
+            log::error!("Failed to persist file transcription history: {}", e);
+        }
+    }
+
+    log::info!(
+        "File transcription finished: {} segments, {:.1}s of audio",
+        segments.len(),
+        duration_secs
+    );
+    Ok(FileTranscriptionResult {
+        path,
+        text,
+        segments,
+        duration_secs,
+    })
+}
+
+/// Одна запись истории на файл: по сегменту на запись длинный файл вытеснил бы
+/// из истории всё остальное, включая собственное начало
+fn file_history_entry(
+    segments: &[Transcription],
+    text: &str,
+    duration_secs: f64,
+    provider_name: &str,
+) -> HistoryEntry {
+    let mut transcription = Transcription::final_result(text.to_string())
+        .with_timing(0.0, duration_secs)
+        .with_words(
+            segments
+                .iter()
+                .flat_map(|segment| segment.words.iter().cloned())
+                .collect(),
+        );
+    // Failover мог переключить провайдера посреди файла — тогда пишем основной
+    if let Some(first) = segments.first() {
+        if segments
+            .iter()
+            .all(|segment| segment.provider == first.provider)
+        {
+            transcription.provider = first.provider.clone();
+        }
+        transcription.language = first.language.clone();
+    }
+    HistoryEntry::from_transcription(&transcription, provider_name)
+}
+
+/// Состояние локального control API для экрана настроек
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlApiStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    pub url: Option<String>,
+    pub token: Option<String>,
+}
+
+async fn control_api_status(state: &AppState) -> ControlApiStatus {
+    let config = state.config.read().await.control_api.clone();
+    let running_port = state
+        .control_api
+        .lock()
+        .await
+        .as_ref()
+        .map(|server| server.port());
+    ControlApiStatus {
+        enabled: config.enabled,
+        running: running_port.is_some(),
+        port: running_port.unwrap_or(config.port),
+        url: running_port.map(|port| format!("http://127.0.0.1:{}", port)),
+        token: config.token,
+    }
+}
+
+/// Get local control API status (включая токен — показываем его только в настройках)
+#[tauri::command]
+pub async fn get_control_api_status(
+    state: State<'_, AppState>,
+) -> Result<ControlApiStatus, String> {
+    log::debug!("Command: get_control_api_status");
+    Ok(control_api_status(state.inner()).await)
+}
+
+/// Включает/выключает control API, меняет порт или перевыпускает токен и перезапускает сервер
+#[tauri::command]
+pub async fn update_control_api_config(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    enabled: Option<bool>,
+    port: Option<u16>,
+    regenerate_token: Option<bool>,
+) -> Result<ControlApiStatus, String> {
+    log::info!(
+        "Command: update_control_api_config - enabled: {:?}, port: {:?}, regenerate_token: {:?}",
+        enabled,
+        port,
+        regenerate_token
+    );
+    if port == Some(0) {
+        return Err("Control API port must be between 1 and 65535".to_string());
+    }
+
+    let control_api = {
+        let mut config = state.config.write().await;
+        if let Some(enabled) = enabled {
+            config.control_api.enabled = enabled;
+        }
+        if let Some(port) = port {
+            config.control_api.port = port;
+        }
+        if regenerate_token.unwrap_or(false)
+            || (config.control_api.enabled && config.control_api.token.is_none())
+        {
+            config.control_api.token = Some(generate_control_api_token());
+        }
+        ConfigStore::save_app_config(&config)
+            .await
+            .map_err(|e| format!("Failed to save app config: {}", e))?;
+        config.control_api.clone()
+    };
+
+    apply_control_api_config(state.inner(), &app_handle, &control_api).await?;
+    Ok(control_api_status(state.inner()).await)
+}
+
 /// Update application configuration (e.g., microphone sensitivity, recording hotkey, auto-copy/paste)
 #[tauri::command]
 pub async fn update_app_config(
@@ -4711,6 +5710,16 @@ pub async fn update_app_config(
     openai_api_key: Option<String>,
     incoming_translation_delivery: Option<IncomingTranslationDelivery>,
     incoming_translation_volume: Option<u8>,
+    keep_history: Option<bool>,
+    max_history_items: Option<usize>,
+    post_processing: Option<TranscriptPostProcessingConfig>,
+    spoken_commands: Option<SpokenCommandsConfig>,
+    dictation_rewrite: Option<DictationRewriteConfig>,
+    dictate_translate: Option<DictateTranslateConfig>,
+    text_translation: Option<TextTranslationConfig>,
+    translation_glossary: Option<TranslationGlossaryConfig>,
+    caption_translation_context: Option<CaptionTranslationContextConfig>,
+    translation_memory: Option<TranslationMemoryConfig>,
 ) -> Result<(), String> {
     log::info!("Command: update_app_config - sensitivity: {:?}, hotkey: {:?}, auto_copy: {:?}, auto_paste: {:?}, completion_sound: {:?}, hide_window_on_hotkey: {:?}, mini_window: {:?}, manual_stop_only: {:?}, hold_to_record: {:?}, double_space_hotkey: {:?}, device: {:?}, mode: {:?}, openai_key: {}",
         microphone_sensitivity, recording_hotkey, auto_copy_to_clipboard, auto_paste_text, play_completion_sound, hide_recording_window_on_hotkey, show_mini_recording_window, keep_recording_until_manual_stop, hold_to_record, double_space_hotkey_enabled, selected_audio_device, recording_mode, openai_api_key.as_ref().is_some_and(|key| !key.trim().is_empty()));
@@ -4733,8 +5742,18 @@ pub async fn update_app_config(
         && openai_api_key.is_none()
         && incoming_translation_delivery.is_none()
         && incoming_translation_volume.is_none()
+        && keep_history.is_none()
+        && max_history_items.is_none()
+        && post_processing.is_none()
+        && spoken_commands.is_none()
+        && dictation_rewrite.is_none()
+        && dictate_translate.is_none()
+        && text_translation.is_none()
+        && translation_glossary.is_none()
+        && caption_translation_context.is_none()
+        && translation_memory.is_none()
     {
-        return Err("update_app_config: не получены поля для обновления. Проверьте, что фронтенд отправляет args в camelCase (например microphoneSensitivity, recordingHotkey, autoCopyToClipboard, autoPasteText, playCompletionSound, hideRecordingWindowOnHotkey, showMiniRecordingWindow, keepRecordingUntilManualStop, holdToRecord, doubleSpaceHotkeyEnabled, selectedAudioDevice, recordingMode, openaiApiKey, incomingTranslationDelivery, incomingTranslationVolume).".to_string());
+        return Err("update_app_config: не получены поля для обновления. Проверьте, что фронтенд отправляет args в camelCase (например microphoneSensitivity, recordingHotkey, autoCopyToClipboard, autoPasteText, playCompletionSound, hideRecordingWindowOnHotkey, showMiniRecordingWindow, keepRecordingUntilManualStop, holdToRecord, doubleSpaceHotkeyEnabled, selectedAudioDevice, recordingMode, openaiApiKey, incomingTranslationDelivery, incomingTranslationVolume, keepHistory, maxHistoryItems, postProcessing, spokenCommands, dictationRewrite, dictateTranslate, textTranslation, translationGlossary, captionTranslationContext, translationMemory).".to_string());
     }
 
     let requested_double_space_hotkey_enabled = double_space_hotkey_enabled;
@@ -4918,6 +5937,153 @@ pub async fn update_app_config(
         }
     }
 
+    let mut history_limit_changed = false;
+    if let Some(keep) = keep_history {
+        if config.keep_history != keep {
+            log::info!("Updating keep_history: {} -> {}", config.keep_history, keep);
+            config.keep_history = keep;
+            any_changed = true;
+        }
+    }
+
+    if let Some(max_items) = max_history_items {
+        if config.max_history_items != max_items {
+            log::info!(
+                "Updating max_history_items: {} -> {}",
+                config.max_history_items,
+                max_items
+            );
+            config.max_history_items = max_items;
+            history_limit_changed = true;
+            any_changed = true;
+        }
+    }
+
+    if let Some(post_processing) = post_processing {
+        if config.post_processing != post_processing {
+            validate_post_processing_config(&post_processing)?;
+            log::info!(
+                "Updating post_processing: stages {:?} -> {:?}, {} replacement rules",
+                config.post_processing.enabled_stages(),
+                post_processing.enabled_stages(),
+                post_processing.replacements.rules.len()
+            );
+            config.post_processing = post_processing;
+            any_changed = true;
+        }
+    }
+
+    if let Some(spoken_commands) = spoken_commands {
+        if config.spoken_commands != spoken_commands {
+            validate_spoken_commands_config(&spoken_commands)?;
+            log::info!(
+                "Updating spoken_commands: enabled {} -> {}, languages {:?}",
+                config.spoken_commands.enabled,
+                spoken_commands.enabled,
+                spoken_commands.vocabularies.keys().collect::<Vec<_>>()
+            );
+            config.spoken_commands = spoken_commands;
+            any_changed = true;
+        }
+    }
+
+    if let Some(dictation_rewrite) = dictation_rewrite {
+        if config.dictation_rewrite != dictation_rewrite {
+            validate_dictation_rewrite_config(&dictation_rewrite)?;
+            log::info!(
+                "Updating dictation_rewrite: enabled {} -> {}, preset {:?} -> {:?}, custom endpoint: {}",
+                config.dictation_rewrite.enabled,
+                dictation_rewrite.enabled,
+                config.dictation_rewrite.preset,
+                dictation_rewrite.preset,
+                dictation_rewrite.custom_endpoint().is_some()
+            );
+            config.dictation_rewrite = dictation_rewrite;
+            any_changed = true;
+        }
+    }
+
+    if let Some(dictate_translate) = dictate_translate {
+        if config.dictate_translate != dictate_translate {
+            log::info!(
+                "Updating dictate_translate: target {:?} -> {:?}, copy original {} -> {}",
+                config.dictate_translate.target_language,
+                dictate_translate.target_language,
+                config.dictate_translate.copy_original_to_clipboard,
+                dictate_translate.copy_original_to_clipboard
+            );
+            config.dictate_translate = dictate_translate;
+            any_changed = true;
+        }
+    }
+
+    if let Some(text_translation) = text_translation {
+        if config.text_translation != text_translation {
+            validate_text_translation_config(&text_translation)?;
+            log::info!(
+                "Updating text_translation: provider {:?} -> {:?}, custom endpoint: {}, own key: {}",
+                config.text_translation.provider,
+                text_translation.provider,
+                text_translation.custom_endpoint().is_some(),
+                text_translation
+                    .api_key
+                    .as_deref()
+                    .is_some_and(|key| !key.trim().is_empty())
+            );
+            config.text_translation = text_translation;
+            any_changed = true;
+        }
+    }
+
+    if let Some(translation_glossary) = translation_glossary {
+        if config.translation_glossary != translation_glossary {
+            validate_translation_glossary_config(&translation_glossary)?;
+            log::info!(
+                "Updating translation_glossary: languages {} -> {}, do-not-translate {} -> {}",
+                config.translation_glossary.glossaries.len(),
+                translation_glossary.glossaries.len(),
+                config.translation_glossary.do_not_translate.len(),
+                translation_glossary.do_not_translate.len()
+            );
+            config.translation_glossary = translation_glossary;
+            any_changed = true;
+        }
+    }
+
+    if let Some(caption_translation_context) = caption_translation_context {
+        if config.caption_translation_context != caption_translation_context {
+            validate_caption_translation_context_config(&caption_translation_context)?;
+            log::info!(
+                "Updating caption_translation_context: {:?} -> {:?}",
+                config.caption_translation_context,
+                caption_translation_context
+            );
+            config.caption_translation_context = caption_translation_context;
+            any_changed = true;
+        }
+    }
+
+    if let Some(translation_memory) = translation_memory {
+        if config.translation_memory != translation_memory {
+            validate_translation_memory_config(&translation_memory)?;
+            log::info!(
+                "Updating translation_memory: {:?} -> {:?}",
+                config.translation_memory,
+                translation_memory
+            );
+            // Новый лимит применяем сразу, чтобы файл не рос до следующей сессии субтитров
+            if let Err(e) = state
+                .translation_memory
+                .set_capacity(translation_memory.max_entries)
+                .await
+            {
+                log::warn!("Failed to apply translation memory capacity: {}", e);
+            }
+            config.translation_memory = translation_memory;
+            any_changed = true;
+        }
+    }
+
     let mut device_changed = false;
     if let Some(device) = selected_audio_device {
         let normalized = device.trim().to_string();
@@ -4968,6 +6134,13 @@ pub async fn update_app_config(
         .await
         .map_err(|e| format!("Failed to save app config: {}", e))?;
 
+    // Уменьшенный лимит применяем сразу, а не только на следующей диктовке
+    if history_limit_changed {
+        if let Err(e) = state.history.enforce_limit(config.max_history_items).await {
+            log::warn!("Failed to apply new history limit: {}", e);
+        }
+    }
+
     // Если горячая клавиша изменилась - перерегистрируем её
     if hotkey_changed {
         drop(config); // освобождаем lock перед async операцией
@@ -5282,6 +6455,8 @@ const AUTO_PASTE_HOTKEY_SUPPRESSION_TAIL_MS: u64 = 150;
 const AUTO_PASTE_WINDOW_SETTLE_MS: u64 = 80;
 const AUTO_PASTE_FOCUS_VERIFY_TIMEOUT_MS: u64 = 300;
 const AUTO_PASTE_FOCUS_VERIFY_POLL_MS: u64 = 50;
+/// Голосовая команда стирает максимум несколько последних фраз; больше — явно ошибка фронта
+const MAX_DELETE_PASTED_TEXT_CHARS: usize = 4_096;
 const DOUBLE_SPACE_HOTKEY_WINDOW_MS: u64 = 350;
 const DOUBLE_SPACE_HOTKEY_CLEANUP_DELAY_MS: u64 = 35;
 const DOUBLE_SPACE_HOTKEY_BACKSPACE_COUNT: usize = 2;
@@ -7532,6 +8707,145 @@ pub async fn auto_paste_text(
     Ok(())
 }
 
+/// Стирает уже вставленный текст Backspace'ами (голосовые команды "undo that", "delete last word")
+#[tauri::command]
+pub async fn delete_pasted_text(state: State<'_, AppState>, count: usize) -> Result<(), String> {
+    log::info!("Command: delete_pasted_text - count: {}", count);
+
+    if count > MAX_DELETE_PASTED_TEXT_CHARS {
+        return Err(format!(
+            "Refusing to delete {} characters (limit {})",
+            count, MAX_DELETE_PASTED_TEXT_CHARS
+        ));
+    }
+
+    // Та же очередь, что и у вставок: Backspace не должен попасть посреди чужой вставки
+    let _paste_guard = state.auto_paste_guard.lock().await;
+    tokio::task::spawn_blocking(move || crate::infrastructure::auto_paste::send_backspaces(count))
+        .await
+        .map_err(|e| format!("Failed to join blocking task: {}", e))?
+        .map_err(|e| format!("Failed to delete pasted text: {}", e))
+}
+
+/// Переписывает текст диктовки выбранным LLM-пресетом перед вставкой.
+/// Ошибки модели не пробрасываются: возвращается исходный текст и `error`.
+#[tauri::command]
+pub async fn rewrite_dictation_text(
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<DictationRewriteOutcome, String> {
+    let (rewrite, openai_api_key) = {
+        let config = state.config.read().await;
+        (
+            config.dictation_rewrite.clone(),
+            resolve_openai_api_key(&config),
+        )
+    };
+    log::info!(
+        "Command: rewrite_dictation_text - text length: {}, enabled: {}, preset: {:?}, custom endpoint: {}",
+        text.len(),
+        rewrite.enabled,
+        rewrite.preset,
+        rewrite.custom_endpoint().is_some()
+    );
+
+    if !rewrite.enabled {
+        return Ok(DictationRewriteOutcome {
+            text,
+            rewritten: false,
+            error: None,
+        });
+    }
+
+    match DictationRewriteService::from_config(&rewrite, &openai_api_key) {
+        Ok(service) => Ok(service.rewrite_or_raw(&text).await),
+        Err(error) => {
+            log::warn!(
+                "Dictation rewrite client unavailable, keeping raw text: {}",
+                error
+            );
+            Ok(DictationRewriteOutcome {
+                text,
+                rewritten: false,
+                error: Some(error.to_string()),
+            })
+        }
+    }
+}
+
+/// Импортирует глоссарий перевода из CSV (`source,target,language`) и сохраняет конфиг.
+/// `language` — язык для строк без третьей колонки, `replace` — заменить глоссарии целиком.
+#[tauri::command]
+pub async fn import_translation_glossary_csv(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    csv: String,
+    language: Option<String>,
+    replace: Option<bool>,
+) -> Result<GlossaryImportSummary, String> {
+    let replace = replace.unwrap_or(false);
+    log::info!(
+        "Command: import_translation_glossary_csv - csv length: {}, language: {:?}, replace: {}",
+        csv.len(),
+        language,
+        replace
+    );
+
+    let summary = {
+        let mut config = state.config.write().await;
+        let mut glossary = config.translation_glossary.clone();
+        let summary = import_glossary_csv(&mut glossary, &csv, language.as_deref(), replace)?;
+        validate_translation_glossary_config(&glossary)?;
+        config.translation_glossary = glossary;
+        ConfigStore::save_app_config(&config)
+            .await
+            .map_err(|e| format!("Failed to save app config: {}", e))?;
+        summary
+    };
+
+    let revision = AppState::bump_revision(&state.app_config_revision).await;
+    let _ = app_handle.emit(
+        EVENT_STATE_SYNC_INVALIDATION,
+        crate::presentation::StateSyncInvalidationPayload {
+            topic: "app-config".to_string(),
+            revision,
+            source_id: Some(window.label().to_string()),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+
+    log::info!(
+        "Translation glossary imported: {} terms, {} do-not-translate",
+        summary.terms,
+        summary.do_not_translate
+    );
+    Ok(summary)
+}
+
+/// Статистика памяти переводов входящих субтитров (записи, попадания, hit rate)
+#[tauri::command]
+pub async fn get_translation_memory_stats(
+    state: State<'_, AppState>,
+) -> Result<TranslationMemoryStats, String> {
+    log::debug!("Command: get_translation_memory_stats");
+    Ok(state.translation_memory.stats().await)
+}
+
+/// Очищает память переводов вместе со статистикой
+#[tauri::command]
+pub async fn clear_translation_memory(
+    state: State<'_, AppState>,
+) -> Result<TranslationMemoryStats, String> {
+    log::info!("Command: clear_translation_memory");
+    state
+        .translation_memory
+        .clear()
+        .await
+        .map_err(|e| format!("Failed to clear translation memory: {}", e))?;
+    Ok(state.translation_memory.stats().await)
+}
+
 /// Копирует текст в системный clipboard используя arboard (кроссплатформенно)
 /// Работает БЕЗ активации приложения - решает проблему с nonactivating_panel на macOS
 #[tauri::command]
@@ -0,0 +1,480 @@
+//! Локальный control API для сторонних интеграций (Stream Deck, плагины редакторов, скрипты).
+//!
+//! - слушает только 127.0.0.1, включается явно в настройках
+//! - каждый запрос требует Bearer-токен (`Authorization: Bearer <token>` или `?token=`,
+//!   т.к. браузерный WebSocket не умеет ставить заголовки)
+//! - действия вызывают те же Tauri-команды, что и UI, поэтому поведение идентично
+//! - `/v1/events` (WebSocket) пересылает события из `presentation/events.rs` как есть
+
+use std::collections::HashSet;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, Request, State as AxumState};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, EventId, Listener, Manager};
+use tokio::sync::{broadcast, watch};
+
+use crate::domain::{ControlApiConfig, RecordingStatus};
+use crate::presentation::{commands, events::*, AppState};
+
+/// События, которые видит WebSocket-клиент (оконные/state-sync события не пересылаем)
+pub const CONTROL_API_FORWARDED_EVENTS: &[&str] = &[
+    EVENT_TRANSCRIPTION_PARTIAL,
+    EVENT_TRANSCRIPTION_FINAL,
+    EVENT_TRANSCRIPTION_RETRACT,
+    EVENT_TRANSCRIPTION_ERROR,
+    EVENT_RECORDING_STATUS,
+    EVENT_AUDIO_LEVEL,
+    EVENT_AUDIO_SPECTRUM,
+    EVENT_CONNECTION_QUALITY,
+    EVENT_TRANSLATION_DELTA,
+    EVENT_TRANSLATION_ERROR,
+    EVENT_INCOMING_TRANSLATION_STATUS,
+    EVENT_INCOMING_TRANSLATION_SOURCE_FINAL,
+    EVENT_INCOMING_TRANSLATION_DELTA,
+    EVENT_INCOMING_TRANSLATION_REVISION,
+    EVENT_INCOMING_TRANSLATION_ERROR,
+    EVENT_INCOMING_TRANSLATION_PLAYBACK,
+];
+
+/// audio:level / audio:spectrum идут десятками в секунду; медленный клиент пропускает старые
+const EVENT_BUFFER_CAPACITY: usize = 1024;
+
+/// Сообщение WebSocket: имя события Tauri + его payload без изменений
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlApiEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Clone)]
+struct ControlApiContext {
+    app_handle: AppHandle,
+    token: Arc<str>,
+    events: broadcast::Sender<ControlApiEvent>,
+    shutdown: watch::Receiver<bool>,
+}
+
+/// Запущенный сервер; `stop` закрывает соединения и снимает подписки на события
+pub struct ControlApiServer {
+    port: u16,
+    app_handle: AppHandle,
+    listeners: Vec<EventId>,
+    shutdown: watch::Sender<bool>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl ControlApiServer {
+    pub async fn start(app_handle: AppHandle, port: u16, token: String) -> Result<Self, String> {
+        if token.trim().is_empty() {
+            return Err("Control API token is empty".to_string());
+        }
+
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let listener = tokio::net::TcpListener::bind(address)
+            .await
+            .map_err(|e| format!("Failed to bind control API on {}: {}", address, e))?;
+        let port = listener
+            .local_addr()
+            .map(|address| address.port())
+            .unwrap_or(port);
+
+        let (events_tx, _) = broadcast::channel(EVENT_BUFFER_CAPACITY);
+        let listeners = CONTROL_API_FORWARDED_EVENTS
+            .iter()
+            .map(|name| {
+                let tx = events_tx.clone();
+                let event_name = name.to_string();
+                app_handle.listen_any(*name, move |event| {
+                    if tx.receiver_count() == 0 {
+                        return;
+                    }
+                    let payload =
+                        serde_json::from_str(event.payload()).unwrap_or(serde_json::Value::Null);
+                    let _ = tx.send(ControlApiEvent {
+                        event: event_name.clone(),
+                        payload,
+                    });
+                })
+            })
+            .collect();
+
+        let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+        let context = ControlApiContext {
+            app_handle: app_handle.clone(),
+            token: Arc::from(token),
+            events: events_tx,
+            shutdown: shutdown_rx.clone(),
+        };
+        let router = router(context);
+
+        let task = tauri::async_runtime::spawn(async move {
+            let shutdown = async move {
+                let _ = shutdown_rx.wait_for(|stop| *stop).await;
+            };
+            if let Err(e) = axum::serve(listener, router)
+                .with_graceful_shutdown(shutdown)
+                .await
+            {
+                log::error!("Control API server failed: {}", e);
+            }
+        });
+
+        log::info!("Control API listening on http://127.0.0.1:{}", port);
+        Ok(Self {
+            port,
+            app_handle,
+            listeners,
+            shutdown: shutdown_tx,
+            task,
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub async fn stop(self) {
+        for listener in self.listeners {
+            self.app_handle.unlisten(listener);
+        }
+        let _ = self.shutdown.send(true);
+        let _ = self.task.await;
+        log::info!("Control API stopped (port {})", self.port);
+    }
+}
+
+/// Приводит запущенный сервер в соответствие с конфигом: останавливает текущий и,
+/// если API включён, поднимает новый. Возвращает фактический порт.
+pub async fn apply_control_api_config(
+    state: &AppState,
+    app_handle: &AppHandle,
+    config: &ControlApiConfig,
+) -> Result<Option<u16>, String> {
+    let mut server = state.control_api.lock().await;
+    if let Some(running) = server.take() {
+        running.stop().await;
+    }
+    if !config.enabled {
+        return Ok(None);
+    }
+    let token = config
+        .token
+        .clone()
+        .ok_or_else(|| "Control API token is not set".to_string())?;
+    let started = ControlApiServer::start(app_handle.clone(), config.port, token).await?;
+    let port = started.port();
+    *server = Some(started);
+    Ok(Some(port))
+}
+
+/// Новый случайный токен (256 бит)
+pub fn generate_control_api_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn router(context: ControlApiContext) -> Router {
+    Router::new()
+        .route("/v1/status", get(status))
+        .route("/v1/recording/start", post(start_recording))
+        .route("/v1/recording/stop", post(stop_recording))
+        .route("/v1/recording/toggle", post(toggle_recording))
+        .route(
+            "/v1/incoming-translation/toggle",
+            post(toggle_incoming_translation),
+        )
+        .route("/v1/events", get(events))
+        .layer(middleware::from_fn_with_state(
+            context.clone(),
+            require_token,
+        ))
+        .with_state(context)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// Сравнение без раннего выхода, чтобы время ответа не подсказывало префикс токена
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    left.iter()
+        .zip(right)
+        .fold(0u8, |acc, (l, r)| acc | (l ^ r))
+        == 0
+}
+
+fn is_authorized(expected: &str, headers: &HeaderMap, query_token: Option<&str>) -> bool {
+    let header_token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    header_token
+        .or(query_token)
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), expected.as_bytes()))
+}
+
+async fn require_token(
+    AxumState(context): AxumState<ControlApiContext>,
+    Query(query): Query<TokenQuery>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !is_authorized(&context.token, request.headers(), query.token.as_deref()) {
+        return ControlApiError::new(StatusCode::UNAUTHORIZED, "Invalid or missing token")
+            .into_response();
+    }
+    next.run(request).await
+}
+
+struct ControlApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ControlApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Ошибки команд — это отказ в текущем состоянии (не авторизован, занято и т.п.)
+    fn command(message: String) -> Self {
+        Self::new(StatusCode::CONFLICT, message)
+    }
+}
+
+impl IntoResponse for ControlApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(serde_json::json!({ "error": self.message })),
+        )
+            .into_response()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ControlApiStatusResponse {
+    recording: RecordingStatus,
+    incoming_translation: RecordingStatus,
+}
+
+#[derive(Debug, Serialize)]
+struct ControlApiActionResponse {
+    message: String,
+}
+
+async fn current_status(
+    app_handle: &AppHandle,
+) -> Result<ControlApiStatusResponse, ControlApiError> {
+    let recording = commands::get_recording_status(app_handle.state::<AppState>())
+        .await
+        .map_err(ControlApiError::command)?;
+    let incoming_translation =
+        commands::get_incoming_translation_status(app_handle.state::<AppState>())
+            .await
+            .map_err(ControlApiError::command)?;
+    Ok(ControlApiStatusResponse {
+        recording,
+        incoming_translation,
+    })
+}
+
+async fn status(
+    AxumState(context): AxumState<ControlApiContext>,
+) -> Result<Json<ControlApiStatusResponse>, ControlApiError> {
+    current_status(&context.app_handle).await.map(Json)
+}
+
+async fn start_recording(
+    AxumState(context): AxumState<ControlApiContext>,
+) -> Result<Json<ControlApiActionResponse>, ControlApiError> {
+    log::info!("Control API: start recording");
+    let app_handle = context.app_handle;
+    let message = commands::start_recording(app_handle.state::<AppState>(), app_handle.clone())
+        .await
+        .map_err(ControlApiError::command)?;
+    Ok(Json(ControlApiActionResponse { message }))
+}
+
+async fn stop_recording(
+    AxumState(context): AxumState<ControlApiContext>,
+) -> Result<Json<ControlApiActionResponse>, ControlApiError> {
+    log::info!("Control API: stop recording");
+    let app_handle = context.app_handle;
+    let message = commands::stop_recording(app_handle.state::<AppState>(), app_handle.clone())
+        .await
+        .map_err(ControlApiError::command)?;
+    Ok(Json(ControlApiActionResponse { message }))
+}
+
+async fn toggle_recording(
+    AxumState(context): AxumState<ControlApiContext>,
+) -> Result<Json<ControlApiActionResponse>, ControlApiError> {
+    let status = current_status(&context.app_handle).await?.recording;
+    log::info!("Control API: toggle recording (status: {:?})", status);
+    match status {
+        RecordingStatus::Starting | RecordingStatus::Recording => {
+            stop_recording(AxumState(context)).await
+        }
+        RecordingStatus::Idle | RecordingStatus::Error => start_recording(AxumState(context)).await,
+        RecordingStatus::Processing => Err(ControlApiError::command(
+            "Recording is still being processed".to_string(),
+        )),
+    }
+}
+
+async fn toggle_incoming_translation(
+    AxumState(context): AxumState<ControlApiContext>,
+) -> Result<Json<ControlApiActionResponse>, ControlApiError> {
+    log::info!("Control API: toggle incoming translation");
+    let app_handle = context.app_handle;
+    let message =
+        commands::toggle_incoming_translation(app_handle.state::<AppState>(), app_handle.clone())
+            .await
+            .map_err(ControlApiError::command)?;
+    Ok(Json(ControlApiActionResponse { message }))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct EventsQuery {
+    /// Необязательный фильтр: `?events=transcription:final,recording:status`
+    events: Option<String>,
+}
+
+/// None — пересылать все события
+fn parse_event_filter(value: Option<&str>) -> Option<HashSet<String>> {
+    let names: HashSet<String> = value?
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+    (!names.is_empty()).then_some(names)
+}
+
+async fn events(
+    AxumState(context): AxumState<ControlApiContext>,
+    Query(query): Query<EventsQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let filter = parse_event_filter(query.events.as_deref());
+    let receiver = context.events.subscribe();
+    let shutdown = context.shutdown.clone();
+    upgrade.on_upgrade(move |socket| forward_events(socket, receiver, filter, shutdown))
+}
+
+async fn forward_events(
+    mut socket: WebSocket,
+    mut receiver: broadcast::Receiver<ControlApiEvent>,
+    filter: Option<HashSet<String>>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    if !filter.as_ref().map_or(true, |names| names.contains(&event.event)) {
+                        continue;
+                    }
+                    let Ok(text) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Control API client lagged, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            // watch::Ref не Send — не держим его через await в других ветках
+            _ = async { let _ = shutdown.wait_for(|stop| *stop).await; } => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_authorization_accepts_bearer_header_or_query_token() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized("secret", &headers, None));
+        assert!(is_authorized("secret", &headers, Some("secret")));
+        assert!(!is_authorized("secret", &headers, Some("secreT")));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret"),
+        );
+        assert!(is_authorized("secret", &headers, None));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Basic secret"),
+        );
+        assert!(!is_authorized("secret", &headers, None));
+    }
+
+    #[test]
+    fn test_generated_tokens_are_unique_hex() {
+        let token = generate_control_api_token();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, generate_control_api_token());
+    }
+
+    #[test]
+    fn test_constant_time_eq_rejects_different_lengths() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abcd"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+    }
+
+    #[test]
+    fn test_event_filter_parsing() {
+        assert_eq!(parse_event_filter(None), None);
+        assert_eq!(parse_event_filter(Some(" , ")), None);
+
+        let filter = parse_event_filter(Some("transcription:final, recording:status")).unwrap();
+        assert!(filter.contains(EVENT_TRANSCRIPTION_FINAL));
+        assert!(filter.contains(EVENT_RECORDING_STATUS));
+        assert!(!filter.contains(EVENT_AUDIO_LEVEL));
+    }
+
+    #[test]
+    fn test_forwarded_events_exclude_window_events() {
+        assert!(CONTROL_API_FORWARDED_EVENTS.contains(&EVENT_INCOMING_TRANSLATION_DELTA));
+        assert!(!CONTROL_API_FORWARDED_EVENTS.contains(&EVENT_STATE_SYNC_INVALIDATION));
+        assert!(!CONTROL_API_FORWARDED_EVENTS.contains(&EVENT_SETTINGS_WINDOW_OPENED));
+    }
+}
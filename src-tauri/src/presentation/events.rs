@@ -1,17 +1,21 @@
 use serde::Serialize;
 
-use crate::domain::{RecordingMode, RecordingStatus, Transcription};
+use crate::domain::{RecordingMode, RecordingStatus, Transcription, Word};
 use crate::domain::{SttConnectionCategory, SttConnectionDetails};
 
 /// Event names for Tauri event system
 pub const EVENT_TRANSCRIPTION_PARTIAL: &str = "transcription:partial";
 pub const EVENT_TRANSCRIPTION_FINAL: &str = "transcription:final";
+/// Голосовая команда ("undo that", "delete last word") убрала уже доставленный текст
+pub const EVENT_TRANSCRIPTION_RETRACT: &str = "transcription:retract";
 pub const EVENT_RECORDING_STATUS: &str = "recording:status";
 pub const EVENT_AUDIO_LEVEL: &str = "audio:level";
 pub const EVENT_AUDIO_SPECTRUM: &str = "audio:spectrum";
 pub const EVENT_MICROPHONE_TEST_LEVEL: &str = "microphone_test:level";
 
 pub const EVENT_TRANSCRIPTION_ERROR: &str = "transcription:error";
+/// Несмертельная проблема сессии (например, сегмент вставлен без перевода); запись продолжается
+pub const EVENT_TRANSCRIPTION_WARNING: &str = "transcription:warning";
 pub const EVENT_CONNECTION_QUALITY: &str = "connection:quality";
 
 // Live translation (OpenAI realtime translate) events.
@@ -22,6 +26,8 @@ pub const EVENT_TRANSLATION_ERROR: &str = "translation:error";
 pub const EVENT_INCOMING_TRANSLATION_STATUS: &str = "incoming_translation:status";
 pub const EVENT_INCOMING_TRANSLATION_SOURCE_FINAL: &str = "incoming_translation:source-final";
 pub const EVENT_INCOMING_TRANSLATION_DELTA: &str = "incoming_translation:delta";
+/// Поздний сегмент склеен с предыдущим: перевод `previous` заменяется на `text`
+pub const EVENT_INCOMING_TRANSLATION_REVISION: &str = "incoming_translation:revision";
 pub const EVENT_INCOMING_TRANSLATION_ERROR: &str = "incoming_translation:error";
 pub const EVENT_INCOMING_TRANSLATION_PLAYBACK: &str = "incoming_translation:playback";
 
@@ -60,6 +66,15 @@ pub struct PartialTranscriptionPayload {
     pub is_segment_final: bool, // true когда сегмент финализирован (is_final=true в Deepgram)
     pub start: f64,             // start время utterance в секундах (от Deepgram)
     pub duration: f64,          // длительность utterance в секундах (от Deepgram)
+    /// STT провайдер, выдавший сегмент (только при failover-цепочке)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Метка говорящего (только при включённой диаризации)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// Оригинал финализированного сегмента, если `text` — его перевод (dictate_translate)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_text: Option<String>,
 }
 
 impl PartialTranscriptionPayload {
@@ -71,6 +86,9 @@ impl PartialTranscriptionPayload {
             is_segment_final: t.is_final, // передаем флаг финализации сегмента
             start: t.start,
             duration: t.duration,
+            provider: t.provider,
+            speaker: t.speaker,
+            source_text: None,
         }
     }
 }
@@ -87,6 +105,20 @@ pub struct FinalTranscriptionPayload {
     pub timestamp: i64,
     pub start: f64,
     pub duration: f64,
+    /// STT провайдер, выдавший сегмент (только при failover-цепочке)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Пословные тайминги/confidence — UI подсвечивает неуверенные слова
+    pub words: Vec<Word>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// Номер реплики: подряд идущие финалы одного говорящего делят один номер,
+    /// чтобы UI склеивал их в один блок. См. `SpeakerTurnTracker`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker_turn: Option<u64>,
+    /// Оригинал финала, если `text` — его перевод (dictate_translate)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_text: Option<String>,
 }
 
 impl FinalTranscriptionPayload {
@@ -99,10 +131,43 @@ impl FinalTranscriptionPayload {
             timestamp: t.timestamp,
             start: t.start,
             duration: t.duration,
+            provider: t.provider,
+            words: t.words,
+            speaker: t.speaker,
+            speaker_turn: None,
+            source_text: None,
         }
     }
 }
 
+/// Payload for transcription retract event.
+/// Приходит перед финалом с исправленным текстом; фронт убирает `text` с конца
+/// накопленного текста и стирает его Backspace'ами, если он уже вставлен.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptionRetractPayload {
+    pub session_id: u64,
+    pub text: String,
+}
+
+/// Нумерует реплики в пределах сессии: смена говорящего открывает новую реплику.
+#[derive(Debug, Default)]
+pub struct SpeakerTurnTracker {
+    current: Option<String>,
+    turn: u64,
+}
+
+impl SpeakerTurnTracker {
+    /// Возвращает номер реплики для финала; `None`, если сегмент без метки говорящего
+    pub fn assign(&mut self, speaker: Option<&str>) -> Option<u64> {
+        let speaker = speaker?;
+        if self.current.as_deref() != Some(speaker) {
+            self.turn += 1;
+            self.current = Some(speaker.to_string());
+        }
+        Some(self.turn)
+    }
+}
+
 /// Payload for recording status event
 #[derive(Debug, Clone, Serialize)]
 pub struct RecordingStatusPayload {
@@ -148,6 +213,13 @@ pub struct TranscriptionErrorPayload {
     pub error_details: Option<TranscriptionErrorDetailsPayload>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptionWarningPayload {
+    pub session_id: u64,
+    pub warning: String,
+    pub warning_type: String, // "translation_failed"
+}
+
 /// Детали ошибки для UI (сериализуемый формат).
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -250,6 +322,15 @@ pub struct IncomingTranslationTextPayload {
     pub delivery: crate::domain::IncomingTranslationDelivery,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct IncomingTranslationRevisionPayload {
+    pub session_id: u64,
+    pub previous: String,
+    pub text: String,
+    pub timestamp: u64,
+    pub delivery: crate::domain::IncomingTranslationDelivery,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct IncomingTranslationErrorPayload {
     pub session_id: u64,
@@ -276,3 +357,31 @@ pub struct ConnectionQualityPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>, // дополнительная информация о причине
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speaker_turn_tracker_groups_consecutive_segments() {
+        let mut tracker = SpeakerTurnTracker::default();
+
+        assert_eq!(tracker.assign(Some("A")), Some(1));
+        assert_eq!(tracker.assign(Some("A")), Some(1));
+        assert_eq!(tracker.assign(None), None);
+        assert_eq!(tracker.assign(Some("B")), Some(2));
+        assert_eq!(tracker.assign(Some("A")), Some(3));
+    }
+
+    #[test]
+    fn final_payload_omits_speaker_fields_without_diarization() {
+        let payload = FinalTranscriptionPayload::from_transcription(
+            Transcription::final_result("hi".to_string()),
+            1,
+        );
+        let json = serde_json::to_value(&payload).unwrap();
+
+        assert!(json.get("speaker").is_none());
+        assert!(json.get("speaker_turn").is_none());
+    }
+}
@@ -7,12 +7,10 @@ use tokio::sync::RwLock;
 use crate::application::services::IncomingSpokenTranslationPorts;
 use crate::application::services::{
     IncomingTranslationFacade, IncomingTranslationFacadeFactory, LiveTranslationPorts,
-    LiveTranslationService,
+    LiveTranslationService, SessionTranscripts,
 };
 use crate::application::TranscriptionService;
-use crate::domain::{
-    AppConfig, AudioCapture, AudioError, RecordingMode, Transcription, UiPreferences,
-};
+use crate::domain::{AppConfig, AudioCapture, AudioError, RecordingMode, UiPreferences};
 #[cfg(not(all(debug_assertions, feature = "webdriver-e2e")))]
 use crate::infrastructure::audio::{
     DefaultLocalPlaybackOutputFactory, DefaultSpokenTranslationCapability,
@@ -22,7 +20,9 @@ use crate::infrastructure::{
     auto_paste::AutoPasteTarget,
     openai::OpenAIRealtimeTranslationFactory,
     AuthSession, AuthStore, AuthStoreData, AuthUser, ConfigStore, DefaultSttProviderFactory,
+    HistoryStore, TranslationMemoryStore,
 };
+use crate::presentation::control_api::ControlApiServer;
 
 const RECORDING_WINDOW_POSITION_SAVE_SUPPRESSION_MS: i64 = 800;
 const TRANSLATION_APP_EXIT_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(4_500);
@@ -150,8 +150,17 @@ pub struct AppState {
     /// UI-настройки (тема, локаль)
     pub ui_preferences: Arc<RwLock<UiPreferences>>,
 
-    /// Transcription history
-    pub history: Arc<RwLock<Vec<Transcription>>>,
+    /// Персистентная история диктовок
+    pub history: Arc<HistoryStore>,
+
+    /// Память переводов входящих субтитров (переживает сессии и перезапуски)
+    pub translation_memory: Arc<TranslationMemoryStore>,
+
+    /// Финалы последних сессий (диктовка / субтитры / live translation) для экспорта
+    pub session_transcripts: Arc<SessionTranscripts>,
+
+    /// Запущенный локальный control API (None — выключен)
+    pub control_api: Arc<tokio::sync::Mutex<Option<ControlApiServer>>>,
 
     /// Latest partial transcription
     pub partial_transcription: Arc<RwLock<Option<String>>>,
@@ -341,7 +350,10 @@ impl AppState {
                     auth_state_revision: Arc::new(RwLock::new(0)),
                     ui_preferences_revision: Arc::new(RwLock::new(0)),
                     ui_preferences: Arc::new(RwLock::new(UiPreferences::default())),
-                    history: Arc::new(RwLock::new(Vec::new())),
+                    history: Arc::new(HistoryStore::open_default()),
+                    translation_memory: Arc::new(TranslationMemoryStore::open_default()),
+                    session_transcripts: Arc::new(SessionTranscripts::default()),
+                    control_api: Arc::new(tokio::sync::Mutex::new(None)),
                     partial_transcription: Arc::new(RwLock::new(None)),
                     final_transcription: Arc::new(RwLock::new(None)),
                     microphone_test: Arc::new(RwLock::new(MicrophoneTestState::default())),
@@ -419,7 +431,10 @@ impl AppState {
                     auth_state_revision: Arc::new(RwLock::new(0)),
                     ui_preferences_revision: Arc::new(RwLock::new(0)),
                     ui_preferences: Arc::new(RwLock::new(UiPreferences::default())),
-                    history: Arc::new(RwLock::new(Vec::new())),
+                    history: Arc::new(HistoryStore::open_default()),
+                    translation_memory: Arc::new(TranslationMemoryStore::open_default()),
+                    session_transcripts: Arc::new(SessionTranscripts::default()),
+                    control_api: Arc::new(tokio::sync::Mutex::new(None)),
                     partial_transcription: Arc::new(RwLock::new(None)),
                     final_transcription: Arc::new(RwLock::new(None)),
                     microphone_test: Arc::new(RwLock::new(MicrophoneTestState::default())),
@@ -517,7 +532,10 @@ impl AppState {
             auth_state_revision: Arc::new(RwLock::new(0)),
             ui_preferences_revision: Arc::new(RwLock::new(0)),
             ui_preferences: Arc::new(RwLock::new(UiPreferences::default())),
-            history: Arc::new(RwLock::new(Vec::new())),
+            history: Arc::new(HistoryStore::open_default()),
+            translation_memory: Arc::new(TranslationMemoryStore::open_default()),
+            session_transcripts: Arc::new(SessionTranscripts::default()),
+            control_api: Arc::new(tokio::sync::Mutex::new(None)),
             partial_transcription: Arc::new(RwLock::new(None)),
             final_transcription: Arc::new(RwLock::new(None)),
             microphone_test: Arc::new(RwLock::new(MicrophoneTestState::default())),
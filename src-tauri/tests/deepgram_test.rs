@@ -227,6 +227,9 @@ async fn test_deepgram_callbacks() {
         timestamp: 0,
         start: 0.0,
         duration: 0.0,
+        provider: None,
+        words: Vec::new(),
+        speaker: None,
     };
 
     on_partial(test_transcription.clone());
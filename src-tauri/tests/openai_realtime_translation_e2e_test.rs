@@ -1248,6 +1248,7 @@ async fn simultaneous_incoming_and_outgoing_routes_translate_and_stop_independen
             let incoming_text = incoming_text.clone();
             Arc::new(move |text| incoming_text.lock().unwrap().push_str(&text))
         },
+        on_translation_revision: Arc::new(|_| {}),
         on_error: {
             let incoming_errors = incoming_errors.clone();
             Arc::new(move |error| incoming_errors.lock().unwrap().push(error.to_string()))
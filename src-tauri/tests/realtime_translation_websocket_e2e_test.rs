@@ -619,6 +619,7 @@ async fn spoken_facade_runs_synthetic_audio_through_local_websocket_and_playback
             let translated_text = translated_text.clone();
             Arc::new(move |delta| translated_text.lock().unwrap().push_str(&delta))
         },
+        on_translation_revision: Arc::new(|_| {}),
         on_error: {
             let errors = errors.clone();
             Arc::new(move |error| errors.lock().unwrap().push(error.to_string()))
@@ -730,6 +731,7 @@ async fn paid_openai_network_interruption_cleans_incoming_capture_and_output() {
     let callbacks = IncomingTranslationCallbacks {
         on_source_final: Arc::new(|_| {}),
         on_translation_delta: Arc::new(|_| {}),
+        on_translation_revision: Arc::new(|_| {}),
         on_error: {
             let errors = errors.clone();
             Arc::new(move |error| errors.lock().unwrap().push(error.to_string()))
@@ -1293,6 +1295,7 @@ async fn spoken_runtime_repeated_start_stop_stress_releases_every_session_and_ta
                     counters.callback_count.fetch_add(1, Ordering::SeqCst);
                 })
             },
+            on_translation_revision: Arc::new(|_| {}),
             on_error: {
                 let counters = counters.clone();
                 Arc::new(move |_| {
@@ -1578,6 +1581,7 @@ async fn spoken_runtime_long_soak_keeps_audio_flow_bounded_and_stops_cleanly() {
                 translated_text_chars.fetch_add(delta.len(), Ordering::Relaxed);
             })
         },
+        on_translation_revision: Arc::new(|_| {}),
         on_error: {
             let errors = errors.clone();
             Arc::new(move |error| errors.lock().unwrap().push(error.to_string()))
@@ -44,6 +44,11 @@ impl SttConfigTestExt for SttConfig {
         match self.provider {
             SttProviderType::Deepgram => self.deepgram_api_key = Some(api_key),
             SttProviderType::AssemblyAI => self.assemblyai_api_key = Some(api_key),
+            SttProviderType::GoogleCloud => self.google_cloud_api_key = Some(api_key),
+            SttProviderType::Azure => self.azure_speech_key = Some(api_key),
+            SttProviderType::OpenAICompatible => self.openai_compatible_api_key = Some(api_key),
+            SttProviderType::OpenAIRealtime => self.openai_api_key = Some(api_key),
+            SttProviderType::ElevenLabs => self.elevenlabs_api_key = Some(api_key),
             _ => {}
         }
 
@@ -31,8 +31,8 @@ export interface WhisperModelOption {
 // Тема приложения
 export type AppTheme = 'dark' | 'light';
 
-// Режим записи: STT в текст vs realtime translation в virtual mic
-export type RecordingMode = 'dictation' | 'live_translation';
+// Режим записи: STT в текст, realtime translation в virtual mic или STT с переводом текста
+export type RecordingMode = 'dictation' | 'live_translation' | 'dictate_translate';
 export type IncomingTranslationDelivery = 'captions_only' | 'text_and_audio';
 
 // Конфигурация STT (соответствует бэкенду)
@@ -44,6 +44,18 @@ export interface SttConfigData {
   assemblyaiApiKey: string | null;
   model: string | null;
   streamingKeyterms: string | null;
+  failoverProviders: SttProviderType[];
+  googleCloudApiKey: string | null;
+  googleCloudProjectId: string | null;
+  googleCloudLocation: string | null;
+  azureSpeechKey: string | null;
+  azureSpeechRegion: string | null;
+  openaiCompatibleBaseUrl: string | null;
+  openaiCompatibleApiKey: string | null;
+  elevenlabsApiKey: string | null;
+  enableDiarization: boolean;
+  compressAudio: boolean;
+  profanityWords: string[];
 }
 
 // Конфигурация приложения (соответствует бэкенду)
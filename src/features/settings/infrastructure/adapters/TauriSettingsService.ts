@@ -45,6 +45,22 @@ class TauriSettingsService {
     if ('assemblyaiApiKey' in config) args.assemblyaiApiKey = config.assemblyaiApiKey;
     if ('model' in config) args.model = config.model;
     if ('streamingKeyterms' in config) args.streamingKeyterms = config.streamingKeyterms;
+    if ('failoverProviders' in config) args.failoverProviders = config.failoverProviders;
+    if ('googleCloudApiKey' in config) args.googleCloudApiKey = config.googleCloudApiKey;
+    if ('googleCloudProjectId' in config) args.googleCloudProjectId = config.googleCloudProjectId;
+    if ('googleCloudLocation' in config) args.googleCloudLocation = config.googleCloudLocation;
+    if ('azureSpeechKey' in config) args.azureSpeechKey = config.azureSpeechKey;
+    if ('azureSpeechRegion' in config) args.azureSpeechRegion = config.azureSpeechRegion;
+    if ('openaiCompatibleBaseUrl' in config) {
+      args.openaiCompatibleBaseUrl = config.openaiCompatibleBaseUrl;
+    }
+    if ('openaiCompatibleApiKey' in config) {
+      args.openaiCompatibleApiKey = config.openaiCompatibleApiKey;
+    }
+    if ('elevenlabsApiKey' in config) args.elevenlabsApiKey = config.elevenlabsApiKey;
+    if ('enableDiarization' in config) args.enableDiarization = config.enableDiarization;
+    if ('compressAudio' in config) args.compressAudio = config.compressAudio;
+    if ('profanityWords' in config) args.profanityWords = config.profanityWords;
 
     await invokeUpdateSttConfig(args);
   }
@@ -98,7 +114,11 @@ class TauriSettingsService {
     if (typeof config.selected_audio_device === 'string' || config.selected_audio_device === null) {
       args.selectedAudioDevice = config.selected_audio_device ?? '';
     }
-    if (config.recording_mode === 'dictation' || config.recording_mode === 'live_translation') {
+    if (
+      config.recording_mode === 'dictation' ||
+      config.recording_mode === 'live_translation' ||
+      config.recording_mode === 'dictate_translate'
+    ) {
       args.recordingMode = config.recording_mode;
     }
     if (typeof config.openai_api_key === 'string' || config.openai_api_key === null) {
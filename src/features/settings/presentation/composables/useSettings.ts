@@ -259,7 +259,7 @@ export function useSettings() {
       // Загружаем STT конфиг — из sync store если уже загружен, иначе invoke
       const sttConfigStoreInstance = useSttConfigStore();
       if (sttConfigStoreInstance.isLoaded) {
-        store.setProvider(SttProviderType.Backend);
+        store.setProvider(sttConfigStoreInstance.provider);
         store.setBackendStreamingProvider(
           sttConfigStoreInstance.backendStreamingProvider
         );
@@ -269,7 +269,7 @@ export function useSettings() {
         });
       } else {
         const sttConfig = await tauriSettingsService.getSttConfig();
-        store.setProvider(SttProviderType.Backend);
+        store.setProvider(sttConfig.provider ?? SttProviderType.Backend);
         store.setBackendStreamingProvider(
           sttConfig.backend_streaming_provider ??
             BackendStreamingProviderType.Deepgram
@@ -419,7 +419,7 @@ export function useSettings() {
           ? store.backendStreamingProvider
           : latestBackendStreamingProvider;
         const sttConfigData: Partial<SttConfigData> & Pick<SttConfigData, 'provider' | 'language'> = {
-          provider: SttProviderType.Backend,
+          provider: latestStt.provider ?? SttProviderType.Backend,
           language: languageForSave,
           backendStreamingProvider: backendStreamingProviderForSave,
         };
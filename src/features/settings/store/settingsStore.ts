@@ -119,7 +119,7 @@ export const useSettingsStore = defineStore('settings', () => {
   async function persistSttLanguage(next: string): Promise<boolean> {
     try {
       await invokeUpdateSttConfig({
-        provider: SttProviderType.Backend,
+        provider: provider.value,
         language: next,
       });
       lastPersistedSttLanguage = next;
@@ -140,9 +140,8 @@ export const useSettingsStore = defineStore('settings', () => {
     }
   }
 
-  function setProvider(_value: SttProviderType) {
-    // Выбор провайдера выключен: всегда используем Backend.
-    provider.value = SttProviderType.Backend;
+  function setProvider(value: SttProviderType) {
+    provider.value = value;
   }
 
   function setBackendStreamingProvider(value: BackendStreamingProviderType | string) {
@@ -320,7 +319,8 @@ export const useSettingsStore = defineStore('settings', () => {
   }
 
   function setRecordingMode(value: RecordingMode) {
-    recordingMode.value = value === 'live_translation' ? 'live_translation' : 'dictation';
+    recordingMode.value =
+      value === 'live_translation' || value === 'dictate_translate' ? value : 'dictation';
   }
 
   function setIncomingTranslationDelivery(value: IncomingTranslationDelivery) {
@@ -10,6 +10,7 @@ const messages = {
       settings: 'Settings',
       connectionRecovering: 'Reconnecting...',
       connectionPoor: 'Poor connection. Recording continues...',
+      translationSkipped: 'Translation failed; the original text was inserted.',
       connecting: 'Connecting...',
       audioLevel: 'Audio level',
       errorGeneric: 'An error occurred. Please try again.',
@@ -105,6 +106,7 @@ const messages = {
         label: 'Recording mode',
         dictation: 'Voice to text',
         liveTranslation: 'Live translation',
+        dictateTranslate: 'Dictate & translate',
         hintBody: 'Voice to text uses the configured STT provider. Live translation streams to OpenAI realtime translate and writes translated voice to the platform virtual microphone.',
         platformStatusChecking: 'Checking live translation audio setup...',
         platformStatusUnavailable: 'Live translation audio setup status is not available.',
@@ -424,6 +426,7 @@ const messages = {
       settings: 'Настройки',
       connectionRecovering: 'Восстановление связи...',
       connectionPoor: 'Плохая связь. Запись продолжается...',
+      translationSkipped: 'Перевод не удался — вставлен исходный текст.',
       connecting: 'Подключение...',
       audioLevel: 'Уровень громкости',
       errorGeneric: 'Произошла ошибка. Попробуйте снова.',
@@ -519,6 +522,7 @@ const messages = {
         label: 'Режим записи',
         dictation: 'Голос в текст',
         liveTranslation: 'Live-перевод',
+        dictateTranslate: 'Диктовка с переводом',
         hintBody: 'Голос в текст использует выбранный STT-провайдер. Live-перевод стримит звук в OpenAI realtime translate и выводит переведенный голос в виртуальный микрофон платформы.',
         platformStatusChecking: 'Проверяем аудио-настройку live-перевода...',
         platformStatusUnavailable: 'Статус аудио-настройки live-перевода недоступен.',
@@ -838,6 +842,7 @@ const messages = {
       settings: 'Ajustes',
       connectionRecovering: 'Reconectando...',
       connectionPoor: 'Conexión débil. La grabación continúa...',
+      translationSkipped: 'La traducción falló; se insertó el texto original.',
       connecting: 'Conectando...',
       audioLevel: 'Nivel de audio',
       errorGeneric: 'Ocurrió un error. Inténtalo de nuevo.',
@@ -933,6 +938,7 @@ const messages = {
         label: 'Modo de grabación',
         dictation: 'Voz a texto',
         liveTranslation: 'Traducción en directo',
+        dictateTranslate: 'Dictar y traducir',
         hintBody: 'Voz a texto usa el proveedor STT configurado. Traducción en directo transmite audio a OpenAI realtime translate y envía la voz traducida al micrófono virtual de la plataforma.',
         platformStatusChecking: 'Comprobando la configuración de audio de traducción en directo...',
         platformStatusUnavailable: 'El estado de audio de traducción en directo no está disponible.',
@@ -1252,6 +1258,7 @@ const messages = {
       settings: 'Paramètres',
       connectionRecovering: 'Reconnexion...',
       connectionPoor: 'Connexion faible. L’enregistrement continue...',
+      translationSkipped: 'La traduction a échoué ; le texte original a été inséré.',
       connecting: 'Connexion...',
       audioLevel: 'Niveau audio',
       errorGeneric: 'Une erreur s’est produite. Veuillez réessayer.',
@@ -1347,6 +1354,7 @@ const messages = {
         label: 'Mode d’enregistrement',
         dictation: 'Voix vers texte',
         liveTranslation: 'Traduction en direct',
+        dictateTranslate: 'Dicter et traduire',
         hintBody: 'Voix vers texte utilise le fournisseur STT configuré. Traduction en direct envoie l’audio à OpenAI realtime translate et la voix traduite vers le micro virtuel de la plateforme.',
         platformStatusChecking: 'Vérification de la configuration audio de traduction en direct...',
         platformStatusUnavailable: 'Le statut audio de traduction en direct est indisponible.',
@@ -1666,6 +1674,7 @@ const messages = {
       settings: 'Einstellungen',
       connectionRecovering: 'Verbindung wird wiederhergestellt...',
       connectionPoor: 'Schlechte Verbindung. Aufnahme läuft weiter...',
+      translationSkipped: 'Übersetzung fehlgeschlagen; der Originaltext wurde eingefügt.',
       connecting: 'Verbinden...',
       audioLevel: 'Audiopegel',
       errorGeneric: 'Ein Fehler ist aufgetreten. Bitte erneut versuchen.',
@@ -1761,6 +1770,7 @@ const messages = {
         label: 'Aufnahmemodus',
         dictation: 'Sprache zu Text',
         liveTranslation: 'Live-Übersetzung',
+        dictateTranslate: 'Diktieren & übersetzen',
         hintBody: 'Sprache zu Text nutzt den konfigurierten STT-Provider. Live-Übersetzung streamt Audio an OpenAI realtime translate und sendet die übersetzte Stimme an das virtuelle Mikrofon der Plattform.',
         platformStatusChecking: 'Audio-Setup für Live-Übersetzung wird geprüft...',
         platformStatusUnavailable: 'Audio-Setup-Status für Live-Übersetzung ist nicht verfügbar.',
@@ -2080,6 +2090,7 @@ const messages = {
       settings: 'Налаштування',
       connectionRecovering: 'Відновлення зʼєднання...',
       connectionPoor: 'Погане зʼєднання. Запис триває...',
+      translationSkipped: 'Переклад не вдався — вставлено вихідний текст.',
       connecting: 'Підключення...',
       audioLevel: 'Рівень гучності',
       errorGeneric: 'Сталася помилка. Спробуйте ще раз.',
@@ -2175,6 +2186,7 @@ const messages = {
         label: 'Режим запису',
         dictation: 'Голос у текст',
         liveTranslation: 'Жива трансляція',
+        dictateTranslate: 'Диктування з перекладом',
         hintBody: 'Голос у текст використовує налаштований STT-провайдер. Жива трансляція стрімить аудіо в OpenAI realtime translate і виводить перекладений голос у віртуальний мікрофон платформи.',
         platformStatusChecking: 'Перевіряємо аудіо-налаштування живого перекладу...',
         platformStatusUnavailable: 'Статус аудіо-налаштування живого перекладу недоступний.',
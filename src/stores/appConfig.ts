@@ -9,6 +9,7 @@ import {
 import type { RevisionSyncHandle } from '@/windowing/stateSync';
 import type {
   AppConfigSnapshotData,
+  DictationRewritePreset,
   IncomingTranslationDelivery,
   RecordingMode,
   TauriSnapshotEnvelope,
@@ -34,6 +35,9 @@ export const useAppConfigStore = defineStore('appConfig', () => {
   const openaiApiKey = ref('');
   const incomingTranslationDelivery = ref<IncomingTranslationDelivery>('captions_only');
   const incomingTranslationVolume = ref(100);
+  const dictationRewriteEnabled = ref(false);
+  const dictationRewritePreset = ref<DictationRewritePreset>('fix_grammar');
+  const dictateTranslateCopyOriginal = ref(false);
 
   let syncHandle: RevisionSyncHandle | null = null;
   let syncStartPromise: Promise<boolean> | null = null;
@@ -64,6 +68,10 @@ export const useAppConfigStore = defineStore('appConfig', () => {
       0,
       Math.min(100, Math.round(data.incoming_translation_volume ?? 100)),
     );
+    dictationRewriteEnabled.value = data.dictation_rewrite?.enabled ?? false;
+    dictationRewritePreset.value = data.dictation_rewrite?.preset ?? 'fix_grammar';
+    dictateTranslateCopyOriginal.value =
+      data.dictate_translate?.copy_original_to_clipboard ?? false;
     isLoaded.value = true;
   }
 
@@ -143,6 +151,9 @@ export const useAppConfigStore = defineStore('appConfig', () => {
     openaiApiKey,
     incomingTranslationDelivery,
     incomingTranslationVolume,
+    dictationRewriteEnabled,
+    dictationRewritePreset,
+    dictateTranslateCopyOriginal,
 
     hasSelectedAudioDevice: computed(() => Boolean(selectedAudioDevice.value)),
 
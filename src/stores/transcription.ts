@@ -4,7 +4,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { isTauriAvailable } from '../utils/tauri';
 import { i18n } from '../i18n';
-import { appendTranscriptText, mergeTranscriptText } from '../utils/transcriptionText';
+import { appendTranscriptText, mergeTranscriptText, replaceTranscriptTail } from '../utils/transcriptionText';
 import { reconcilePartialAnimation } from './transcriptionReconciliation';
 
 const MAX_STREAMING_TRANSLATION_TEXT_CHARS = 32_000;
@@ -33,6 +33,10 @@ function appendIncomingTranslationText(
   }
   return appendStreamingTranscriptText(current, next);
 }
+
+function reviseIncomingTranslationText(current: string, previous: string, next: string): string {
+  return keepRecentStreamingText(replaceTranscriptTail(current, previous, next));
+}
 import { api } from '../features/auth/infrastructure/api/apiClient';
 import { useAuthStore } from '../features/auth/store/authStore';
 import { useAppConfigStore } from './appConfig';
@@ -44,26 +48,33 @@ import {
   ConnectionQuality,
   PartialTranscriptionPayload,
   FinalTranscriptionPayload,
+  TranscriptionRetractPayload,
+  DictationRewriteOutcome,
   RecordingStatusPayload,
   TranscriptionErrorPayload,
+  TranscriptionWarningPayload,
   ConnectionQualityPayload,
   TranslationDeltaPayload,
   TranslationErrorPayload,
   IncomingTranslationStatusPayload,
   IncomingTranslationTextPayload,
+  IncomingTranslationRevisionPayload,
   IncomingTranslationErrorPayload,
   IncomingTranslationPlaybackPayload,
   LiveTranslationHealthCheck,
   EVENT_TRANSCRIPTION_PARTIAL,
   EVENT_TRANSCRIPTION_FINAL,
+  EVENT_TRANSCRIPTION_RETRACT,
   EVENT_RECORDING_STATUS,
   EVENT_TRANSCRIPTION_ERROR,
+  EVENT_TRANSCRIPTION_WARNING,
   EVENT_CONNECTION_QUALITY,
   EVENT_TRANSLATION_DELTA,
   EVENT_TRANSLATION_ERROR,
   EVENT_INCOMING_TRANSLATION_STATUS,
   EVENT_INCOMING_TRANSLATION_SOURCE_FINAL,
   EVENT_INCOMING_TRANSLATION_DELTA,
+  EVENT_INCOMING_TRANSLATION_REVISION,
   EVENT_INCOMING_TRANSLATION_ERROR,
   EVENT_INCOMING_TRANSLATION_PLAYBACK,
 } from '../types';
@@ -105,6 +116,10 @@ export const useTranscriptionStore = defineStore('transcription', () => {
   // Обновляется из payload.mode у recording:status; live_translation сессии не идут через STT auto-paste.
   const activeRecordingMode = ref<RecordingMode>('dictation');
   const translationText = ref<string>('');
+  // dictate_translate: оригиналы переведённых финалов (для "оригинал в буфер обмена")
+  const dictationSourceText = ref<string>('');
+  // dictate_translate: сегмент не перевёлся и вставлен как есть — запись продолжается
+  const translationWarning = ref<string | null>(null);
 
   // Incoming subtitles: system audio -> STT -> text translation. Separate lifecycle.
   const incomingTranslationStatus = ref<RecordingStatus>(RecordingStatus.Idle);
@@ -155,6 +170,10 @@ export const useTranscriptionStore = defineStore('transcription', () => {
   const autoPasteEnabled = computed(
     () => activeRecordingMode.value !== 'live_translation' && appConfig.autoPasteText
   );
+  // С LLM-переписыванием текст вставляется целиком после остановки, а не по сегментам
+  const dictationRewriteEnabled = computed(
+    () => activeRecordingMode.value !== 'live_translation' && appConfig.dictationRewriteEnabled
+  );
 
   // Auth store — нужен, чтобы корректно сбрасывать ошибки записи после успешной авторизации,
   // если ошибка относилась к предыдущему пользователю/токену.
@@ -340,14 +359,17 @@ export const useTranscriptionStore = defineStore('transcription', () => {
   type UnlistenFn = () => void;
   let unlistenPartial: UnlistenFn | null = null;
   let unlistenFinal: UnlistenFn | null = null;
+  let unlistenRetract: UnlistenFn | null = null;
   let unlistenStatus: UnlistenFn | null = null;
   let unlistenError: UnlistenFn | null = null;
+  let unlistenWarning: UnlistenFn | null = null;
   let unlistenConnectionQuality: UnlistenFn | null = null;
   let unlistenTranslationDelta: UnlistenFn | null = null;
   let unlistenTranslationError: UnlistenFn | null = null;
   let unlistenIncomingStatus: UnlistenFn | null = null;
   let unlistenIncomingSourceFinal: UnlistenFn | null = null;
   let unlistenIncomingDelta: UnlistenFn | null = null;
+  let unlistenIncomingRevision: UnlistenFn | null = null;
   let unlistenIncomingError: UnlistenFn | null = null;
   let unlistenIncomingPlayback: UnlistenFn | null = null;
   let listenerGeneration = 0;
@@ -1056,6 +1078,51 @@ export const useTranscriptionStore = defineStore('transcription', () => {
     }
   }
 
+  // "undo that" / "delete last word": убираем хвост из текста, а если он уже вставлен —
+  // стираем его Backspace'ами в той же очереди, что и вставки. Исправленный текст
+  // backend пришлёт следующим финалом.
+  function retractDeliveredText(retractedText: string): void {
+    const removed = retractedText.trim();
+    if (!removed) return;
+
+    const current = appendTranscriptText(finalText.value, accumulatedText.value);
+    if (!current.endsWith(removed)) {
+      console.warn('[SpokenCommands] Retracted text is not the transcript tail, skipping:', {
+        removed,
+        current,
+      });
+      clientLog('transcription_retract_mismatch', {
+        removedLength: removed.length,
+        currentLength: current.length,
+      }, 'warn');
+      return;
+    }
+
+    const kept = current.slice(0, current.length - removed.length).trimEnd();
+    finalText.value = kept;
+    accumulatedText.value = '';
+    animatedAccumulatedText.value = '';
+
+    const alreadyPasted = lastPastedFinalText.value.trim();
+    if (!autoPasteEnabled.value || alreadyPasted.length <= kept.length || !alreadyPasted.startsWith(kept)) {
+      return;
+    }
+
+    const count = Array.from(alreadyPasted.slice(kept.length)).length;
+    lastPastedFinalText.value = kept;
+    const generation = autoPasteGeneration;
+    autoPasteQueue = autoPasteQueue
+      .catch(() => undefined)
+      .then(async () => {
+        if (generation !== autoPasteGeneration) return;
+        await invoke('delete_pasted_text', { count });
+      })
+      .catch((err) => {
+        console.error('❌ Failed to delete pasted text:', err);
+        clientLog('delete_pasted_text_failed', { count, error: String(err) }, 'error');
+      });
+  }
+
   function autoPasteCurrentText(reason: string, currentText = buildCurrentTranscriptionText()): Promise<boolean> {
     const textSnapshot = currentText.trim();
     const generation = autoPasteGeneration;
@@ -1114,6 +1181,8 @@ export const useTranscriptionStore = defineStore('transcription', () => {
     animatedAccumulatedText.value = '';
     clearTranscriptionAnimationTimers();
     translationText.value = '';
+    dictationSourceText.value = '';
+    translationWarning.value = null;
   }
 
   function suppressPreviousTranscriptionDisplay(reason = 'window_hide'): void {
@@ -1132,9 +1201,32 @@ export const useTranscriptionStore = defineStore('transcription', () => {
     }, 'debug');
   }
 
+  /**
+   * Прогоняет текст через выбранный LLM-пресет. Бэкенд при ошибке модели сам
+   * возвращает исходный текст, здесь страхуемся от сбоя самого invoke.
+   */
+  async function rewriteDictationText(text: string, reason: string): Promise<string> {
+    try {
+      const outcome = await invoke<DictationRewriteOutcome>('rewrite_dictation_text', { text });
+      clientLog('dictation_rewrite_completed', {
+        reason,
+        rewritten: outcome.rewritten,
+        error: outcome.error,
+        textLength: text.length,
+        rewrittenLength: outcome.text.length,
+        preset: appConfig.dictationRewritePreset,
+      }, outcome.error ? 'warn' : 'info');
+      return outcome.text.trim() ? outcome.text : text;
+    } catch (err) {
+      console.error('❌ Failed to rewrite dictation, using raw text:', err);
+      clientLog('dictation_rewrite_failed', { reason, error: String(err) }, 'warn');
+      return text;
+    }
+  }
+
   async function processCurrentTextAfterStop(reason: string): Promise<boolean> {
-    const currentText = buildCurrentTranscriptionText();
-    if (!currentText) {
+    const rawText = buildCurrentTranscriptionText();
+    if (!rawText) {
       console.log('[STT] No transcription text to process after stop:', { reason });
       clientLog('recording_stop_text_empty', {
         reason,
@@ -1146,9 +1238,19 @@ export const useTranscriptionStore = defineStore('transcription', () => {
       return false;
     }
 
+    // Переписываем только если ничего не вставлено по ходу диктовки (режим включили посреди сессии)
+    const currentText =
+      dictationRewriteEnabled.value && !lastPastedFinalText.value.trim()
+        ? await rewriteDictationText(rawText, reason)
+        : rawText;
+
     console.log('📝 Текущий текст для обработки:', currentText);
 
-    if (autoCopyEnabled.value) {
+    // dictate_translate: вставляем перевод, а в буфер кладём оригинал — после вставки,
+    // чтобы clipboard-вставка его не перезаписала
+    const originalText = appConfig.dictateTranslateCopyOriginal ? dictationSourceText.value : '';
+
+    if (autoCopyEnabled.value && !originalText) {
       try {
         await invoke('copy_to_clipboard_native', { text: currentText });
         console.log('📋 Auto-copied full transcription to clipboard');
@@ -1161,6 +1263,16 @@ export const useTranscriptionStore = defineStore('transcription', () => {
       await autoPasteCurrentText(reason, currentText);
     }
 
+    if (originalText) {
+      try {
+        await autoPasteQueue;
+        await invoke('copy_to_clipboard_native', { text: originalText });
+        console.log('📋 Copied original dictation to clipboard');
+      } catch (err) {
+        console.error('❌ Failed to copy original dictation:', err);
+      }
+    }
+
     return true;
   }
 
@@ -1301,6 +1413,12 @@ export const useTranscriptionStore = defineStore('transcription', () => {
           if (!ensureActiveSessionForIncomingEvent(event.payload.session_id, 'transcription:partial')) {
             return;
           }
+          if (event.payload.source_text) {
+            dictationSourceText.value = appendTranscriptText(
+              dictationSourceText.value,
+              event.payload.source_text
+            );
+          }
           // Детальное логирование для отладки
           console.log('📝 PARTIAL EVENT:', {
             text: event.payload.text,
@@ -1367,7 +1485,7 @@ export const useTranscriptionStore = defineStore('transcription', () => {
               partialAnimationTimer = null;
             }
 
-            if (autoPasteEnabled.value && newText.trim()) {
+            if (autoPasteEnabled.value && !dictationRewriteEnabled.value && newText.trim()) {
               await autoPasteCurrentText('segment_final');
             }
 
@@ -1430,6 +1548,12 @@ export const useTranscriptionStore = defineStore('transcription', () => {
           if (!ensureActiveSessionForIncomingEvent(event.payload.session_id, 'transcription:final')) {
             return;
           }
+          if (event.payload.source_text) {
+            dictationSourceText.value = appendTranscriptText(
+              dictationSourceText.value,
+              event.payload.source_text
+            );
+          }
 
           // Детальное логирование для отладки
           console.log('✅ FINAL EVENT (speech_final=true):', {
@@ -1533,7 +1657,11 @@ export const useTranscriptionStore = defineStore('transcription', () => {
             console.log('📋 [AFTER ADD] finalText:', finalText.value);
             console.log('📋 Successfully added utterance to finalText');
 
-            if (autoPasteEnabled.value && currentUtteranceText.trim()) {
+            if (
+              autoPasteEnabled.value &&
+              !dictationRewriteEnabled.value &&
+              currentUtteranceText.trim()
+            ) {
               const pasted = await autoPasteCurrentText('speech_final');
               if (!pasted && autoCopyEnabled.value) {
                 try {
@@ -1554,6 +1682,35 @@ export const useTranscriptionStore = defineStore('transcription', () => {
       if (!finalUnlisten) return;
       unlistenFinal = finalUnlisten;
 
+      // Голосовые команды: backend присылает уже доставленный хвост, который нужно убрать
+      const retractUnlisten = await registerStoreListener<TranscriptionRetractPayload>(
+        generation,
+        EVENT_TRANSCRIPTION_RETRACT,
+        (event) => {
+          if (!ensureActiveSessionForIncomingEvent(event.payload.session_id, 'transcription:retract')) {
+            return;
+          }
+          retractDeliveredText(event.payload.text);
+        }
+      );
+      if (!retractUnlisten) return;
+      unlistenRetract = retractUnlisten;
+
+      // Предупреждения не обрывают сессию: в отличие от transcription:error статус не трогаем
+      const warningUnlisten = await registerStoreListener<TranscriptionWarningPayload>(
+        generation,
+        EVENT_TRANSCRIPTION_WARNING,
+        (event) => {
+          if (!ensureActiveSessionForIncomingEvent(event.payload.session_id, 'transcription:warning')) {
+            return;
+          }
+          console.warn('[transcription:warning]', event.payload);
+          translationWarning.value = event.payload.warning;
+        }
+      );
+      if (!warningUnlisten) return;
+      unlistenWarning = warningUnlisten;
+
       // Listen to recording status events
       const statusUnlisten = await registerStoreListener<RecordingStatusPayload>(
         generation,
@@ -2164,6 +2321,29 @@ export const useTranscriptionStore = defineStore('transcription', () => {
       if (!incomingDeltaUnlisten) return;
       unlistenIncomingDelta = incomingDeltaUnlisten;
 
+      // Поздний сегмент склеен с предыдущим: переписываем последний субтитр
+      const incomingRevisionUnlisten = await registerStoreListener<IncomingTranslationRevisionPayload>(
+        generation,
+        EVENT_INCOMING_TRANSLATION_REVISION,
+        (event) => {
+          const payloadSessionId = event.payload.session_id;
+          if (!isValidIncomingTranslationSessionId(payloadSessionId)) return;
+          if (isIncomingTranslationSessionClosed(payloadSessionId)) return;
+          if (payloadSessionId !== incomingTranslationSessionId.value) return;
+          if (incomingTranslationStatus.value === RecordingStatus.Error) return;
+          if (event.payload.text) {
+            incomingTranslationError.value = null;
+            incomingTranslationText.value = reviseIncomingTranslationText(
+              incomingTranslationText.value,
+              event.payload.previous,
+              event.payload.text
+            );
+          }
+        }
+      );
+      if (!incomingRevisionUnlisten) return;
+      unlistenIncomingRevision = incomingRevisionUnlisten;
+
       const incomingErrorUnlisten = await registerStoreListener<IncomingTranslationErrorPayload>(
         generation,
         EVENT_INCOMING_TRANSLATION_ERROR,
@@ -3077,6 +3257,10 @@ export const useTranscriptionStore = defineStore('transcription', () => {
       unlistenFinal();
       unlistenFinal = null;
     }
+    if (unlistenRetract) {
+      unlistenRetract();
+      unlistenRetract = null;
+    }
     if (unlistenStatus) {
       unlistenStatus();
       unlistenStatus = null;
@@ -3085,6 +3269,10 @@ export const useTranscriptionStore = defineStore('transcription', () => {
       unlistenError();
       unlistenError = null;
     }
+    if (unlistenWarning) {
+      unlistenWarning();
+      unlistenWarning = null;
+    }
     if (unlistenConnectionQuality) {
       unlistenConnectionQuality();
       unlistenConnectionQuality = null;
@@ -3109,6 +3297,10 @@ export const useTranscriptionStore = defineStore('transcription', () => {
       unlistenIncomingDelta();
       unlistenIncomingDelta = null;
     }
+    if (unlistenIncomingRevision) {
+      unlistenIncomingRevision();
+      unlistenIncomingRevision = null;
+    }
     if (unlistenIncomingError) {
       unlistenIncomingError();
       unlistenIncomingError = null;
@@ -3146,6 +3338,7 @@ export const useTranscriptionStore = defineStore('transcription', () => {
     connectionQuality,
     activeRecordingMode,
     translationText,
+    translationWarning,
     incomingTranslationStatus,
     incomingTranslationSessionId,
     incomingSourceText,
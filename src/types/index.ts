@@ -23,6 +23,7 @@ export interface PartialTranscriptionPayload {
   is_segment_final: boolean; // true когда сегмент финализирован (но речь продолжается)
   start: number; // start время utterance в секундах (от Deepgram)
   duration: number; // длительность utterance в секундах (от Deepgram)
+  source_text?: string; // оригинал финализированного сегмента, если text — перевод (dictate_translate)
 }
 
 export interface FinalTranscriptionPayload {
@@ -33,9 +34,23 @@ export interface FinalTranscriptionPayload {
   timestamp: number;
   start?: number;
   duration?: number;
+  source_text?: string; // оригинал финала, если text — перевод (dictate_translate)
 }
 
-export type RecordingMode = 'dictation' | 'live_translation';
+// Голосовая команда ("undo that", "delete last word") убрала уже доставленный хвост текста
+export interface TranscriptionRetractPayload {
+  session_id: number;
+  text: string;
+}
+
+// Ответ `rewrite_dictation_text`: при ошибке модели text = исходный текст, error = причина
+export interface DictationRewriteOutcome {
+  text: string;
+  rewritten: boolean;
+  error: string | null;
+}
+
+export type RecordingMode = 'dictation' | 'live_translation' | 'dictate_translate';
 
 export interface RecordingStatusPayload {
   session_id: number;
@@ -81,6 +96,15 @@ export interface IncomingTranslationTextPayload {
   delivery?: 'captions_only' | 'text_and_audio';
 }
 
+// Перевод сегмента склеен с предыдущим: `text` заменяет уже показанный `previous`
+export interface IncomingTranslationRevisionPayload {
+  session_id: number;
+  previous: string;
+  text: string;
+  timestamp: number;
+  delivery?: 'captions_only' | 'text_and_audio';
+}
+
 export interface IncomingTranslationErrorPayload {
   session_id: number;
   error: string;
@@ -121,6 +145,13 @@ export interface TranscriptionErrorPayload {
   error_details?: TranscriptionErrorDetailsPayload;
 }
 
+// Несмертельная проблема сессии: запись продолжается, пользователя только предупреждаем
+export interface TranscriptionWarningPayload {
+  session_id: number;
+  warning: string;
+  warning_type: 'translation_failed';
+}
+
 export interface TranscriptionErrorDetailsPayload {
   category?:
     | 'offline'
@@ -158,14 +189,17 @@ export interface ConnectionQualityPayload {
 // Event names (must match Rust backend)
 export const EVENT_TRANSCRIPTION_PARTIAL = 'transcription:partial';
 export const EVENT_TRANSCRIPTION_FINAL = 'transcription:final';
+export const EVENT_TRANSCRIPTION_RETRACT = 'transcription:retract';
 export const EVENT_RECORDING_STATUS = 'recording:status';
 export const EVENT_TRANSCRIPTION_ERROR = 'transcription:error';
+export const EVENT_TRANSCRIPTION_WARNING = 'transcription:warning';
 export const EVENT_CONNECTION_QUALITY = 'connection:quality';
 export const EVENT_TRANSLATION_DELTA = 'translation:delta';
 export const EVENT_TRANSLATION_ERROR = 'translation:error';
 export const EVENT_INCOMING_TRANSLATION_STATUS = 'incoming_translation:status';
 export const EVENT_INCOMING_TRANSLATION_SOURCE_FINAL = 'incoming_translation:source-final';
 export const EVENT_INCOMING_TRANSLATION_DELTA = 'incoming_translation:delta';
+export const EVENT_INCOMING_TRANSLATION_REVISION = 'incoming_translation:revision';
 export const EVENT_INCOMING_TRANSLATION_ERROR = 'incoming_translation:error';
 export const EVENT_INCOMING_TRANSLATION_PLAYBACK = 'incoming_translation:playback';
 export const EVENT_ERROR = 'app:error';
@@ -181,6 +215,9 @@ export enum SttProviderType {
   WhisperLocal = 'whisperlocal',
   GoogleCloud = 'googlecloud',
   Azure = 'azure',
+  OpenAICompatible = 'openaicompatible',
+  OpenAIRealtime = 'openairealtime',
+  ElevenLabs = 'elevenlabs',
 }
 
 export enum BackendStreamingProviderType {
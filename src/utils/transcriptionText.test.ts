@@ -1,14 +1,25 @@
 import { describe, expect, it } from 'vitest';
-import { appendTranscriptText, mergeTranscriptText } from './transcriptionText';
+import { appendTranscriptText, mergeTranscriptText, replaceTranscriptTail } from './transcriptionText';
 
 describe('transcription text helpers', () => {
   it('appends finalized chunks without removing boundary repeats', () => {
     expect(appendTranscriptText('two two', 'two two three')).toBe('two two two two three');
   });
 
+  it('keeps line breaks from spoken commands', () => {
+    expect(appendTranscriptText('Dear  team,', 'Hi \n the  release')).toBe('Dear team, Hi\nthe release');
+  });
+
   it('merges overlapping live interim text for display only', () => {
     expect(mergeTranscriptText('Ты слышишь, что', 'Ты слышишь, что я говорю?')).toBe(
       'Ты слышишь, что я говорю?'
     );
   });
+
+  it('replaces the revised caption at the end', () => {
+    expect(replaceTranscriptTail('Привет. Она сказала мне', 'Она сказала мне', 'Она сказала, что устала')).toBe(
+      'Привет. Она сказала, что устала'
+    );
+    expect(replaceTranscriptTail('Привет.', 'Она сказала мне', 'Она устала')).toBe('Привет. Она устала');
+  });
 });
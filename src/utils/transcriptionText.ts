@@ -2,7 +2,11 @@ const ZERO_WIDTH_RE = /[\u200B-\u200D\uFEFF]/g;
 const PUNCT_RE = /[.,!?;:"'`(){}\[\]<>\-–—_\\/|@$%^&*=~]/g;
 
 function normalizeSpaces(text: string): string {
-  return String(text ?? '').replace(/\s+/g, ' ').trim();
+  // Переносы строк приходят от голосовых команд ("new line") — их не схлопываем
+  return String(text ?? '')
+    .replace(/[^\S\n]+/g, ' ')
+    .replace(/ ?\n ?/g, '\n')
+    .trim();
 }
 
 function splitWords(text: string): string[] {
@@ -73,6 +77,16 @@ export function appendTranscriptText(base: string, next: string): string {
   return joinWithSpace(base, next);
 }
 
+/** Заменяет последний показанный кусок `previous` на `next`; если его уже нет в конце — дописывает */
+export function replaceTranscriptTail(base: string, previous: string, next: string): string {
+  const text = normalizeSpaces(base);
+  const tail = normalizeSpaces(previous);
+  if (tail && text.endsWith(tail)) {
+    return joinWithSpace(text.slice(0, text.length - tail.length), next);
+  }
+  return joinWithSpace(text, next);
+}
+
 export function mergeTranscriptText(base: string, next: string): string {
   const a = normalizeSpaces(base);
   const b = normalizeSpaces(next);
@@ -95,9 +95,9 @@ function assertValidUpdateAppConfigArgs(args: Record<string, unknown>): void {
         }
         break;
       case 'recordingMode':
-        if (v !== 'dictation' && v !== 'live_translation') {
+        if (v !== 'dictation' && v !== 'live_translation' && v !== 'dictate_translate') {
           throw new Error(
-            `[update_app_config] "recordingMode" должен быть 'dictation' | 'live_translation', получили: ${String(v)}`,
+            `[update_app_config] "recordingMode" должен быть 'dictation' | 'live_translation' | 'dictate_translate', получили: ${String(v)}`,
           );
         }
         break;
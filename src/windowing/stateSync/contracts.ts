@@ -11,8 +11,73 @@ import type { UiLocale, UiTheme } from '@/i18n.locales';
 import type { SnapshotEnvelope } from '@statesync/core';
 
 /** Режим записи. Соответствует Rust `RecordingMode`. */
-export type RecordingMode = 'dictation' | 'live_translation';
+export type RecordingMode = 'dictation' | 'live_translation' | 'dictate_translate';
 export type IncomingTranslationDelivery = 'captions_only' | 'text_and_audio';
+/** Режим dictate_translate. Соответствует Rust `DictateTranslateConfig`. */
+export type DictateTranslateConfig = {
+  target_language: string | null;
+  copy_original_to_clipboard: boolean;
+};
+
+export type TextTranslationProvider = 'openai' | 'deepl' | 'libretranslate' | 'openaicompatible';
+
+/** Провайдер текстового перевода (субтитры, dictate_translate). Соответствует Rust `TextTranslationConfig`. */
+export type TextTranslationConfig = {
+  provider: TextTranslationProvider;
+  endpoint: string | null;
+  model: string | null;
+  api_key: string | null;
+};
+
+export type GlossaryTerm = {
+  source: string;
+  target: string;
+};
+
+/** Глоссарии по языкам перевода и do-not-translate список. Соответствует Rust `TranslationGlossaryConfig`. */
+export type TranslationGlossaryConfig = {
+  glossaries: Record<string, GlossaryTerm[]>;
+  do_not_translate: string[];
+};
+
+/** Окно предыдущих сегментов для перевода входящих субтитров. Соответствует Rust `CaptionTranslationContextConfig`. */
+export type CaptionTranslationContextConfig = {
+  enabled: boolean;
+  max_segments: number;
+  max_tokens: number;
+};
+
+/** Персистентная память переводов входящих субтитров. Соответствует Rust `TranslationMemoryConfig`. */
+export type TranslationMemoryConfig = {
+  enabled: boolean;
+  max_entries: number;
+};
+
+/** Ответ `get_translation_memory_stats` / `clear_translation_memory`; `hit_rate` от 0 до 1. */
+export type TranslationMemoryStats = {
+  entries: number;
+  capacity: number;
+  hits: number;
+  misses: number;
+  hit_rate: number;
+};
+
+/** Ответ `import_translation_glossary_csv`. */
+export type GlossaryImportSummary = {
+  terms: number;
+  do_not_translate: number;
+};
+
+export type DictationRewritePreset = 'fix_grammar' | 'make_concise' | 'bullet_list' | 'formal_email';
+
+/** LLM-переписывание диктовки перед вставкой. Соответствует Rust `DictationRewriteConfig`. */
+export type DictationRewriteConfig = {
+  enabled: boolean;
+  preset: DictationRewritePreset;
+  endpoint: string | null;
+  model: string | null;
+  api_key: string | null;
+};
 
 /**
  * App-config snapshot for frontend windows.
@@ -35,6 +100,12 @@ export type AppConfigSnapshotData = {
   openai_api_key: string | null;
   incoming_translation_delivery?: IncomingTranslationDelivery;
   incoming_translation_volume?: number;
+  dictation_rewrite?: DictationRewriteConfig;
+  dictate_translate?: DictateTranslateConfig;
+  text_translation?: TextTranslationConfig;
+  translation_glossary?: TranslationGlossaryConfig;
+  caption_translation_context?: CaptionTranslationContextConfig;
+  translation_memory?: TranslationMemoryConfig;
 };
 
 /**
@@ -58,6 +129,19 @@ export type SttConfigSnapshotData = {
   streaming_keyterms?: string | null;
   /** Deprecated snapshot alias kept for one migration period. */
   deepgram_keyterms?: string | null;
+
+  failover_providers?: SttProviderType[];
+  google_cloud_api_key?: string | null;
+  google_cloud_project_id?: string | null;
+  google_cloud_location?: string | null;
+  azure_speech_key?: string | null;
+  azure_speech_region?: string | null;
+  openai_compatible_base_url?: string | null;
+  openai_compatible_api_key?: string | null;
+  elevenlabs_api_key?: string | null;
+  enable_diarization?: boolean;
+  compress_audio?: boolean;
+  profanity_words?: string[];
 };
 
 /** Соответствует Rust `AuthStateData`. */
@@ -50,6 +50,17 @@ export type {
   TauriSnapshotEnvelope,
   RecordingMode,
   IncomingTranslationDelivery,
+  DictationRewriteConfig,
+  DictationRewritePreset,
+  DictateTranslateConfig,
+  TextTranslationConfig,
+  TextTranslationProvider,
+  GlossaryTerm,
+  GlossaryImportSummary,
+  TranslationGlossaryConfig,
+  CaptionTranslationContextConfig,
+  TranslationMemoryConfig,
+  TranslationMemoryStats,
 } from './contracts';
 
 export { createAuthStateSync } from './authStateSync';
@@ -17,6 +17,19 @@ export type UpdateSttConfigInvokeArgs = {
   assemblyaiApiKey?: string | null;
   model?: string | null;
   streamingKeyterms?: string | null;
+  // Пустая строка очищает значение, отсутствие ключа — оставляет как есть
+  failoverProviders?: string[];
+  googleCloudApiKey?: string | null;
+  googleCloudProjectId?: string | null;
+  googleCloudLocation?: string | null;
+  azureSpeechKey?: string | null;
+  azureSpeechRegion?: string | null;
+  openaiCompatibleBaseUrl?: string | null;
+  openaiCompatibleApiKey?: string | null;
+  elevenlabsApiKey?: string | null;
+  enableDiarization?: boolean;
+  compressAudio?: boolean;
+  profanityWords?: string[];
 };
 
 const ALLOWED_KEYS = new Set([
@@ -27,6 +40,18 @@ const ALLOWED_KEYS = new Set([
   'assemblyaiApiKey',
   'model',
   'streamingKeyterms',
+  'failoverProviders',
+  'googleCloudApiKey',
+  'googleCloudProjectId',
+  'googleCloudLocation',
+  'azureSpeechKey',
+  'azureSpeechRegion',
+  'openaiCompatibleBaseUrl',
+  'openaiCompatibleApiKey',
+  'elevenlabsApiKey',
+  'enableDiarization',
+  'compressAudio',
+  'profanityWords',
 ]);
 
 function assertValidUpdateSttConfigArgs(args: Record<string, unknown>): void {